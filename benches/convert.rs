@@ -0,0 +1,51 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use new_page::output::write_pages;
+use new_page::{convert, render, Options};
+
+#[path = "../tests/support/mod.rs"]
+mod support;
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+fn parse_and_convert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_and_convert");
+    for n in SIZES {
+        let input = support::synthetic_bibliography(n);
+        group.bench_with_input(BenchmarkId::from_parameter(n), &input, |b, input| {
+            b.iter(|| convert(input, &Options::default()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn render_to_string(c: &mut Criterion) {
+    let mut group = c.benchmark_group("render");
+    for n in SIZES {
+        let papers = convert(&support::synthetic_bibliography(n), &Options::default()).unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(n), &papers, |b, papers| {
+            b.iter(|| render(papers, &Options::default()))
+        });
+    }
+    group.finish();
+}
+
+fn end_to_end(c: &mut Criterion) {
+    let dir = std::env::temp_dir().join("new_page_bench");
+    let mut group = c.benchmark_group("end_to_end");
+    group.sample_size(10);
+    for n in SIZES {
+        let input = support::synthetic_bibliography(n);
+        group.bench_with_input(BenchmarkId::from_parameter(n), &input, |b, input| {
+            b.iter(|| {
+                let papers = convert(input, &Options::default()).unwrap();
+                write_pages(&papers, &dir, &Options::default()).unwrap()
+            })
+        });
+    }
+    group.finish();
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+criterion_group!(benches, parse_and_convert, render_to_string, end_to_end);
+criterion_main!(benches);
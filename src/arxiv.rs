@@ -0,0 +1,174 @@
+//! Completing preprints from the arXiv API: canonical abstract, primary
+//! category and date of the latest version.
+
+use crate::fetch::{encode_query, Fetcher};
+use crate::provenance::Origin;
+use crate::{progress, Paper, Warning};
+
+const SERVICE: &str = "arxiv";
+
+/// An arXiv identifier, in the old (`math.GT/0309136`) or new
+/// (`2106.01234`) scheme, with its optional version.
+#[derive(Debug, PartialEq)]
+pub struct ArxivId {
+    pub id: String,
+    pub version: Option<u32>,
+}
+
+impl ArxivId {
+    /// Parses an identifier, with or without an `arXiv:` prefix.
+    pub fn parse(s: &str) -> Option<ArxivId> {
+        let s = s.trim();
+        let s = s
+            .get(..6)
+            .filter(|p| p.eq_ignore_ascii_case("arxiv:"))
+            .map_or(s, |_| &s[6..]);
+        let (id, version) = match s.rfind('v') {
+            Some(i) if i + 1 < s.len() && s[i + 1..].bytes().all(|b| b.is_ascii_digit()) => {
+                (&s[..i], s[i + 1..].parse().ok())
+            }
+            _ => (s, None),
+        };
+        let digits =
+            |s: &str, n: &[usize]| n.contains(&s.len()) && s.bytes().all(|b| b.is_ascii_digit());
+        let valid = match id.split_once('/') {
+            Some((archive, number)) => {
+                !archive.is_empty()
+                    && archive
+                        .chars()
+                        .all(|c| c.is_ascii_alphabetic() || c == '-' || c == '.')
+                    && digits(number, &[7])
+            }
+            None => match id.split_once('.') {
+                Some((month, number)) => digits(month, &[4]) && digits(number, &[4, 5]),
+                None => false,
+            },
+        };
+        valid.then(|| ArxivId {
+            id: id.to_string(),
+            version,
+        })
+    }
+
+    /// Abstract page of the work, whatever its version.
+    pub fn abs_url(&self) -> String {
+        format!("https://arxiv.org/abs/{}", self.id)
+    }
+
+    /// The identifier as written, with its version.
+    pub fn versioned(&self) -> String {
+        match self.version {
+            Some(v) => format!("{}v{}", self.id, v),
+            None => self.id.clone(),
+        }
+    }
+}
+
+/// What the arXiv API tells about a work.
+#[derive(Debug, Default, PartialEq)]
+pub struct Metadata {
+    pub summary: Option<String>,
+    pub category: Option<String>,
+    /// Date of the latest version, `YYYY-MM-DD`
+    pub updated: Option<String>,
+}
+
+/// Text of the first `<name>` element of `xml`.
+fn element<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}", name))?;
+    let content = start + xml[start..].find('>')? + 1;
+    let end = content + xml[content..].find(&format!("</{}>", name))?;
+    Some(&xml[content..end])
+}
+
+/// Value of the `attr` attribute of the first `<name>` element of `xml`.
+fn attribute<'a>(xml: &'a str, name: &str, attr: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}", name))?;
+    let tag = &xml[start..start + xml[start..].find('>')?];
+    let value = tag.find(&format!("{}=\"", attr))? + attr.len() + 2;
+    Some(&tag[value..value + tag[value..].find('"')?])
+}
+
+fn unescape(s: &str) -> String {
+    s.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Reads the metadata of the first entry of an Atom answer.
+pub fn parse_atom(answer: &str) -> Option<Metadata> {
+    let entry = element(answer, "entry")?;
+    // The API answers with an entry titled `Error` for unknown identifiers.
+    if element(entry, "title") == Some("Error") {
+        return None;
+    }
+    Some(Metadata {
+        summary: element(entry, "summary").map(unescape),
+        category: attribute(entry, "arxiv:primary_category", "term").map(String::from),
+        updated: element(entry, "updated")
+            .and_then(|d| d.get(..10))
+            .map(String::from),
+    })
+}
+
+/// Merges `meta` into `paper`, only into empty fields unless `overwrite`.
+pub fn apply(paper: &mut Paper, id: &ArxivId, meta: Metadata, overwrite: bool) {
+    let from_arxiv = || Origin::Service(SERVICE.to_string());
+    if let Some(summary) = meta.summary {
+        if overwrite || paper.abs.abs.is_empty() {
+            paper.abs.abs = summary;
+            paper.provenance.set("abstract", None, from_arxiv());
+        }
+    }
+    if let Some(category) = meta.category {
+        if (overwrite || paper.keywords.tags.is_empty()) && !paper.keywords.tags.contains(&category)
+        {
+            paper.keywords.tags.push(category);
+            paper.provenance.set("tags", None, from_arxiv());
+        }
+    }
+    if meta.updated.is_some() && (overwrite || paper.updated.at.is_none()) {
+        paper.updated.at = meta.updated;
+        paper.provenance.set("lastmod", None, from_arxiv());
+    }
+    if overwrite || paper.url.link.is_empty() {
+        paper.url.link = id.abs_url();
+        paper.provenance.set("www", None, from_arxiv());
+    }
+}
+
+/// Completes the papers having an arXiv `eprint`.
+pub fn enrich(papers: &mut [Paper], fetcher: &mut Fetcher, overwrite: bool) {
+    progress::start(
+        "arXiv",
+        papers.iter().filter(|p| p.eprint.is_some()).count(),
+    );
+    for p in papers.iter_mut() {
+        let Some(eprint) = &p.eprint else { continue };
+        progress::tick(&p.key.key);
+        let Some(id) = ArxivId::parse(eprint) else {
+            crate::warn(Warning::invalid(
+                &p.key.key,
+                "eprint",
+                format!("not an arXiv identifier: {}", eprint),
+            ));
+            continue;
+        };
+        let url = format!(
+            "https://export.arxiv.org/api/query?id_list={}",
+            encode_query(&id.versioned())
+        );
+        if let Some(meta) = fetcher
+            .get(SERVICE, &id.versioned(), &url)
+            .and_then(|a| parse_atom(&a))
+        {
+            apply(p, &id, meta, overwrite);
+        }
+    }
+    progress::finish();
+}
@@ -0,0 +1,221 @@
+//! Building conversion options programmatically, with the checks the
+//! command line applies to its flags.
+
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+use crate::check::Checks;
+use crate::fields::VenueKind;
+use crate::ignore::IgnoreList;
+use crate::locale::Locale;
+use crate::mapping::FieldMap;
+use crate::output::LineEnding;
+use crate::target::{Emit, Extension, Target};
+use crate::text::{Punctuation, TitleRules};
+use crate::yaml::Quotes;
+use crate::{AliasFrom, LinkSource, Options, Venue};
+
+/// Options under construction, starting from the command line defaults.
+#[derive(Default)]
+pub struct OptionsBuilder {
+    opts: Options,
+}
+
+/// Setters of the options, `name(value)` setting the option of the same
+/// name. The optional ones take either a value or an `Option`.
+macro_rules! setters {
+    ($($(#[$doc:meta])* $name:ident: $ty:ty,)*) => {
+        $(
+            $(#[$doc])*
+            pub fn $name(mut self, $name: impl Into<$ty>) -> Self {
+                self.opts.$name = $name.into();
+                self
+            }
+        )*
+    };
+}
+
+impl Options {
+    /// Options to build, starting from the command line defaults.
+    ///
+    /// ```
+    /// use new_page::{Options, Target};
+    ///
+    /// let opts = Options::builder()
+    ///     .target(Target::Zola)
+    ///     .bundle(true)
+    ///     .skip_types(vec!["proceedings".to_string()])
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(opts.target, Target::Zola);
+    /// assert_eq!(opts.summary_sentences, 2);
+    ///
+    /// let err = Options::builder().years(2030..=2000).build().err().unwrap();
+    /// assert_eq!(err, "the earliest year, 2030, is after the latest one, 2000");
+    /// ```
+    pub fn builder() -> OptionsBuilder {
+        OptionsBuilder::default()
+    }
+}
+
+impl OptionsBuilder {
+    setters! {
+        /// Prefer `eventtitle` over `booktitle` for the conference name
+        eventtitle: bool,
+        /// Emit the category of the entry type under `categories`
+        categories: bool,
+        /// Category of the entry types, overriding the default ones
+        category_names: BTreeMap<String, String>,
+        /// Words of the conference names marking workshop papers
+        workshop_keywords: Vec<String>,
+        /// Kind of venue of the papers of proceedings, by citation key
+        venue_kinds: BTreeMap<String, VenueKind>,
+        /// Entry types left out of the conversion
+        skip_types: Vec<String>,
+        /// Leave out the entries of unknown types
+        skip_unknown_types: bool,
+        /// Static site generator the pages are written for
+        target: Target,
+        /// Parts of the pages which are rendered
+        emit: Emit,
+        /// Content of the files written one per entry
+        extension: Option<Extension>,
+        /// Character ending the names of authors who contributed equally
+        author_marker: char,
+        /// Cut the given names to their initials
+        initials: bool,
+        /// Languages of the pages, one page per entry and language
+        languages: Vec<String>,
+        /// Suffix of the tags holding the translations, `{}` standing for
+        /// the language
+        language_suffix: String,
+        /// Look for awards in the notes of the entries without `award` tag
+        detect_awards: bool,
+        /// Feature the pages of awarded works
+        feature_awards: bool,
+        /// Typography of the dashes and quotes of abstracts
+        abstract_punctuation: Punctuation,
+        /// Leave the HTML or JATS markup of the abstracts as it is
+        keep_abstract_markup: bool,
+        /// Marker ending a cut abstract
+        ellipsis: String,
+        /// Names, order and omissions of the front matter keys
+        fields: FieldMap,
+        /// Directory of the cache of the parsed inputs
+        cache_dir: Option<PathBuf>,
+        /// Write pages as `<key>/index.md` page bundles
+        bundle: bool,
+        /// Number of sentences of the abstract kept in the summary
+        summary_sentences: usize,
+        /// Name conferences after their cleaned proceedings title
+        clean_proceedings: bool,
+        /// Take the `number` for the series when there is no `series`
+        legacy_series_number: bool,
+        /// Convert the entries which parse when the file as a whole does not
+        recover: bool,
+        /// Plausible publication years
+        years: RangeInclusive<i64>,
+        /// Page ranges spanning more pages are warned about
+        max_page_span: i64,
+        /// Expand the abbreviated end pages
+        fix_pages: bool,
+        /// Length in bytes of the tag values beyond which they are cut
+        max_field_length: usize,
+        /// Clean-ups applied to the titles
+        title: TitleRules,
+        /// Rules of `check::check`
+        checks: Checks,
+        /// Reading speed, in words per minute
+        wpm: usize,
+        /// Citation keys of the entries kept out of the website
+        exclude_keys: Vec<String>,
+        /// Entries never converted, from the ignore file
+        ignore: IgnoreList,
+        /// Citation keys of the only entries to convert, if any
+        select_keys: Vec<String>,
+        /// Generation time written in every page
+        stamp: Option<String>,
+        /// Length of the abstract body in characters, beyond which it is cut
+        max_abstract_chars: Option<usize>,
+        /// Length of the abstract body in words, beyond which it is cut
+        max_abstract_words: Option<usize>,
+        /// Identifiers the `aliases` of the pages are made of
+        alias_from: Option<AliasFrom>,
+        /// Primary venue of entries having both a journal and a booktitle
+        prefer_venue: Option<Venue>,
+        /// Sources of the `www` link, in order of preference
+        url_preference: Vec<LinkSource>,
+        /// List the links not taken for `www` under `links`
+        other_links: bool,
+        /// Name the source of each front matter key in a comment
+        provenance_comments: bool,
+        /// Quotes of the strings of the YAML front matter, data file and venue pages
+        yaml_quotes: Quotes,
+        /// Language of the months and phrases of the citation strings
+        locale: Locale,
+        /// Line ending of the generated files
+        line_ending: LineEnding,
+    }
+
+    /// The options, unless some of them contradict each other or are out
+    /// of range.
+    pub fn build(self) -> Result<Options, String> {
+        let opts = self.opts;
+        if opts.years.is_empty() {
+            return Err(format!(
+                "the earliest year, {}, is after the latest one, {}",
+                opts.years.start(),
+                opts.years.end()
+            ));
+        }
+        if opts.max_field_length == 0 {
+            return Err("the maximum field length must be positive".to_string());
+        }
+        if opts.wpm == 0 {
+            return Err("the reading speed must be positive".to_string());
+        }
+        if opts.max_abstract_chars == Some(0) || opts.max_abstract_words == Some(0) {
+            return Err("the abstract length limits must be positive".to_string());
+        }
+        if opts.author_marker.is_alphanumeric() || opts.author_marker.is_whitespace() {
+            return Err(format!(
+                "the author marker {:?} would be part of the names",
+                opts.author_marker
+            ));
+        }
+        if !opts.languages.is_empty() && !opts.language_suffix.contains("{}") {
+            return Err(format!(
+                "the language suffix {:?} has no {{}} standing for the language",
+                opts.language_suffix
+            ));
+        }
+        if let Some((i, lang)) = opts
+            .languages
+            .iter()
+            .enumerate()
+            .find(|(i, l)| opts.languages[..*i].contains(l))
+        {
+            return Err(format!("language {} is listed twice, at {}", lang, i + 1));
+        }
+        match (opts.extension, opts.emit, opts.target) {
+            (Some(Extension::Yaml | Extension::Json), Emit::Body, _) => {
+                return Err(format!(
+                    "{} files hold the front matter, which the body alone leaves out",
+                    opts.extension.map_or("", Extension::name)
+                ));
+            }
+            (Some(Extension::Yaml), _, Target::Zola) => {
+                return Err("the front matter of Zola pages is TOML, not YAML".to_string());
+            }
+            _ => {}
+        }
+        if opts.yaml_quotes != Quotes::Double && opts.target == Target::Zola {
+            return Err(
+                "YAML quotes do not apply to Zola pages, whose front matter is TOML".to_string(),
+            );
+        }
+        opts.fields.validate()?;
+        Ok(opts)
+    }
+}
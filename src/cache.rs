@@ -0,0 +1,135 @@
+//! Cache of the parsed bibliographies, so that converting an unchanged file
+//! again skips parsing, recovery, merging and the clean-ups of the tags.
+//! Each input has its own file, named after the hash of the input and of
+//! the settings changing its parsing; editing the input changes the hash,
+//! so an entry is never partly stale. The inputs whose parsing gives
+//! warnings are not cached, as reading them back would not give them. The
+//! entries are JSON, with the version of their format.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use nom_bibtex::Bibliography;
+use serde::{Deserialize, Serialize};
+
+use crate::latex::Macros;
+use crate::provenance::Origins;
+
+/// Version of the cache format, to be raised whenever the cached data or
+/// the processing before it change, so that older entries are ignored.
+pub const VERSION: u32 = 3;
+
+/// Subdirectory of the cache directory of the enrichment services holding
+/// the parsed inputs.
+pub const SUBDIRECTORY: &str = "parsed";
+
+/// FNV-1a hash of the `parts`, stable across runs and platforms unlike the
+/// hasher of the standard library.
+pub fn hash(parts: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for part in parts {
+        for byte in part.bytes().chain([0xff]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+#[derive(Deserialize, Serialize)]
+struct CachedEntry {
+    entry_type: String,
+    key: String,
+    tags: BTreeMap<String, String>,
+}
+
+/// What parsing an input gave, as stored.
+#[derive(Deserialize, Serialize)]
+struct Cached {
+    version: u32,
+    /// Version of the crate which wrote it
+    writer: String,
+    entries: Vec<CachedEntry>,
+    macros: HashMap<String, String>,
+    origins: Origins,
+    /// Entries which failed to parse
+    failed: usize,
+    /// Time the parsing took
+    parse_time: Duration,
+}
+
+/// A parse found in the cache.
+pub struct Hit {
+    pub entries: Vec<Bibliography>,
+    pub macros: Macros,
+    pub origins: Origins,
+    pub failed: usize,
+    /// Time the parsing took when it was cached
+    pub parse_time: Duration,
+}
+
+fn path(dir: &Path, hash: u64) -> PathBuf {
+    dir.join(format!("{:016x}.json", hash))
+}
+
+/// The parse cached in `dir` under `hash`, unless it is missing, unreadable
+/// or of another format or crate version.
+pub fn load(dir: &Path, hash: u64) -> Option<Hit> {
+    let content = std::fs::read_to_string(path(dir, hash)).ok()?;
+    let cached: Cached = serde_json::from_str(&content).ok()?;
+    if cached.version != VERSION || cached.writer != env!("CARGO_PKG_VERSION") {
+        return None;
+    }
+    Some(Hit {
+        entries: cached
+            .entries
+            .into_iter()
+            .map(|e| Bibliography::new(e.entry_type, e.key, e.tags.into_iter().collect()))
+            .collect(),
+        macros: Macros {
+            defs: cached.macros,
+        },
+        origins: cached.origins,
+        failed: cached.failed,
+        parse_time: cached.parse_time,
+    })
+}
+
+/// Caches in `dir` under `hash` the `entries`, `macros` and `origins`
+/// parsing took `parse_time` to give, `failed` entries failing to parse.
+pub fn store(
+    dir: &Path,
+    hash: u64,
+    entries: &[Bibliography],
+    macros: &Macros,
+    origins: &Origins,
+    failed: usize,
+    parse_time: Duration,
+) -> Result<(), String> {
+    let cached = Cached {
+        version: VERSION,
+        writer: env!("CARGO_PKG_VERSION").to_string(),
+        entries: entries
+            .iter()
+            .map(|b| CachedEntry {
+                entry_type: b.entry_type().to_string(),
+                key: b.citation_key().to_string(),
+                tags: b.tags().clone().into_iter().collect(),
+            })
+            .collect(),
+        macros: macros.defs.clone(),
+        origins: origins.clone(),
+        failed,
+        parse_time,
+    };
+    let content = serde_json::to_string(&cached).map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    // Written aside then renamed, so that concurrent runs never read half
+    // an entry.
+    let target = path(dir, hash);
+    let partial = target.with_extension(format!("{}.tmp", std::process::id()));
+    std::fs::write(&partial, content)
+        .and_then(|_| std::fs::rename(&partial, &target))
+        .map_err(|e| format!("{}: {}", target.display(), e))
+}
@@ -0,0 +1,241 @@
+//! House conventions of a bibliography, checked with `--check` rather than
+//! converting it: lowercase keys, bare DOIs... Each rule reports its
+//! violations under its own id, so that CI can allow some of them; the
+//! rules are switched on and set under `[check]` in the configuration.
+//!
+//! A new rule is a type implementing `Rule`, a setting of `Checks` and a
+//! line of `Checks::rules`.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::merge::MergeOn;
+use crate::{parse_merged, quietly, warn, Error, Options, Paper, Summary, Warning};
+
+/// What a rule sees of an entry: its tags as written, the overrides and
+/// `@xdata` bundles applied, and the page they give.
+pub struct Entry<'a> {
+    pub key: &'a str,
+    pub tags: &'a HashMap<String, String>,
+    pub paper: &'a Paper,
+}
+
+impl Entry<'_> {
+    /// The tag `name`, unless missing or blank.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .get(name)
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+    }
+}
+
+/// What an entry does against a convention.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violation {
+    /// Tag it is about, if a single one
+    pub field: Option<&'static str>,
+    pub message: String,
+}
+
+impl Violation {
+    fn new(field: Option<&'static str>, message: impl Into<String>) -> Option<Violation> {
+        Some(Violation {
+            field,
+            message: message.into(),
+        })
+    }
+}
+
+/// A convention of the entries.
+pub trait Rule {
+    /// Id the violations are reported under, `key-case`.
+    fn id(&self) -> &'static str;
+
+    /// How `entry` goes against the convention, if it does.
+    fn check(&self, entry: &Entry) -> Option<Violation>;
+}
+
+/// Citation keys in lowercase.
+pub struct KeyCase;
+
+impl Rule for KeyCase {
+    fn id(&self) -> &'static str {
+        "key-case"
+    }
+
+    fn check(&self, entry: &Entry) -> Option<Violation> {
+        if entry.key == entry.key.to_lowercase() {
+            return None;
+        }
+        Violation::new(
+            None,
+            format!("key is not lowercase, {}", entry.key.to_lowercase()),
+        )
+    }
+}
+
+/// DOIs written bare, `10.1145/3385412.3385983`, without resolver nor
+/// `doi:` prefix.
+pub struct DoiFormat;
+
+impl Rule for DoiFormat {
+    fn id(&self) -> &'static str {
+        "doi-format"
+    }
+
+    fn check(&self, entry: &Entry) -> Option<Violation> {
+        let doi = entry.tag("doi")?;
+        let bare =
+            doi.starts_with("10.") && doi.contains('/') && !doi.contains(char::is_whitespace);
+        if bare {
+            return None;
+        }
+        Violation::new(
+            Some("doi"),
+            format!("doi {} is not bare, as 10.1145/3385412", doi),
+        )
+    }
+}
+
+/// No `url` besides a `doi`, the DOI resolver linking to the work.
+pub struct RedundantUrl;
+
+impl Rule for RedundantUrl {
+    fn id(&self) -> &'static str {
+        "redundant-url"
+    }
+
+    fn check(&self, entry: &Entry) -> Option<Violation> {
+        entry.tag("doi")?;
+        entry.tag("url")?;
+        Violation::new(Some("url"), "has a url besides its doi")
+    }
+}
+
+/// An abstract for the works after a year.
+pub struct RequireAbstractAfterYear(pub i64);
+
+impl Rule for RequireAbstractAfterYear {
+    fn id(&self) -> &'static str {
+        "require-abstract-after-year"
+    }
+
+    fn check(&self, entry: &Entry) -> Option<Violation> {
+        let year = &entry.paper.year;
+        if !year.dated || year.year <= self.0 || entry.tag("abstract").is_some() {
+            return None;
+        }
+        Violation::new(
+            Some("abstract"),
+            format!("has no abstract, required after {}", self.0),
+        )
+    }
+}
+
+/// At most this many authors, more hinting at a list which was not split
+/// at its `and`s as it should.
+pub struct MaxAuthorsSanity(pub usize);
+
+impl Rule for MaxAuthorsSanity {
+    fn id(&self) -> &'static str {
+        "max-authors-sanity"
+    }
+
+    fn check(&self, entry: &Entry) -> Option<Violation> {
+        let authors = entry.paper.auth.authors.len();
+        if authors <= self.0 {
+            return None;
+        }
+        Violation::new(
+            Some("author"),
+            format!("lists {} authors, more than {}", authors, self.0),
+        )
+    }
+}
+
+/// The rules of `--check` and their settings, under `[check]` in the
+/// configuration.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Checks {
+    /// See `KeyCase`
+    pub key_case: bool,
+    /// See `DoiFormat`
+    pub doi_format: bool,
+    /// See `RedundantUrl`
+    pub redundant_url: bool,
+    /// Year after which the works need an abstract; none when missing
+    pub require_abstract_after_year: Option<i64>,
+    /// Most authors of an entry; none when missing
+    pub max_authors_sanity: Option<usize>,
+}
+
+impl Default for Checks {
+    fn default() -> Checks {
+        Checks {
+            key_case: true,
+            doi_format: true,
+            redundant_url: true,
+            require_abstract_after_year: None,
+            max_authors_sanity: Some(100),
+        }
+    }
+}
+
+impl Checks {
+    /// The rules switched on.
+    pub fn rules(&self) -> Vec<Box<dyn Rule>> {
+        let mut rules: Vec<Box<dyn Rule>> = Vec::new();
+        if self.key_case {
+            rules.push(Box::new(KeyCase));
+        }
+        if self.doi_format {
+            rules.push(Box::new(DoiFormat));
+        }
+        if self.redundant_url {
+            rules.push(Box::new(RedundantUrl));
+        }
+        if let Some(year) = self.require_abstract_after_year {
+            rules.push(Box::new(RequireAbstractAfterYear(year)));
+        }
+        if let Some(max) = self.max_authors_sanity {
+            rules.push(Box::new(MaxAuthorsSanity(max)));
+        }
+        rules
+    }
+}
+
+/// Checks the entries of `input`, merged with the ones of `overrides`,
+/// against the rules of `opts.checks`, warning about each violation, and
+/// returns their number. The ignored entries are not checked, and the
+/// warnings of the conversion are left out.
+pub fn check(input: &str, overrides: &str, on: MergeOn, opts: &Options) -> Result<usize, Error> {
+    let (entries, macros, _) = parse_merged(input, overrides, on, opts, &mut Summary::default())?;
+    let rules = opts.checks.rules();
+    let mut violations = 0;
+    for b in entries.iter().filter(|b| opts.ignore.reason(b).is_none()) {
+        // The entries which do not convert fail the conversion anyway.
+        let Ok(paper) = quietly(|| Paper::new(b, opts, &macros)) else {
+            continue;
+        };
+        let entry = Entry {
+            key: b.citation_key(),
+            tags: b.tags(),
+            paper: &paper,
+        };
+        for rule in &rules {
+            if let Some(violation) = rule.check(&entry) {
+                warn(Warning::Convention {
+                    rule: rule.id(),
+                    key: entry.key.to_string(),
+                    field: violation.field.map(String::from),
+                    message: violation.message,
+                });
+                violations += 1;
+            }
+        }
+    }
+    Ok(violations)
+}
@@ -0,0 +1,152 @@
+//! Plain-text citation strings, shared by the list-like output formats.
+
+use crate::fields::{Pages, Place};
+use crate::formats::html_escape;
+use crate::Paper;
+
+/// Conventions of the formats showing citations: the journal or conference
+/// in italics, and the rest escaped so that it shows as written.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Style {
+    /// As written, without italics
+    #[default]
+    Plain,
+    /// Italics between asterisks, the asterisks of the text escaped
+    Markdown,
+    /// Italics as `<em>`, the text escaped
+    Html,
+}
+
+impl Style {
+    /// `s` as text of the format.
+    pub fn text(self, s: &str) -> String {
+        match self {
+            Style::Plain => s.to_string(),
+            Style::Markdown => s.replace('*', "\\*"),
+            Style::Html => html_escape(s),
+        }
+    }
+
+    /// `s` as the name of a journal or conference, in italics.
+    pub fn venue(self, s: &str) -> String {
+        match self {
+            Style::Plain => s.to_string(),
+            Style::Markdown => format!("*{}*", self.text(s)),
+            Style::Html => format!("<em>{}</em>", self.text(s)),
+        }
+    }
+}
+
+/// Volume, issue and pages as journal references show them,
+/// `42(3):123–145`, leaving out the missing parts: `42:123–145`, `(3)`...
+/// Pages are shown as written when they are not plain numbers.
+pub fn venue_detail(volume: Option<i64>, issue: Option<&str>, pages: &Pages) -> Option<String> {
+    let mut s = String::new();
+    if let Some(v) = volume {
+        s.push_str(&v.to_string());
+    }
+    if let Some(i) = issue {
+        s.push_str(&format!("({})", i));
+    }
+    if pages.from.is_some() || pages.display.is_some() {
+        if !s.is_empty() {
+            s.push(':');
+        }
+        match (&pages.display, pages.ranges_text(), pages.from) {
+            (Some(display), _, _) => s.push_str(display),
+            (None, Some(ranges), _) => s.push_str(&ranges),
+            (None, None, Some(from)) => {
+                s.push_str(&from.to_string());
+                if let Some(to) = pages.to {
+                    s.push_str(&format!("–{}", to));
+                }
+            }
+            (None, None, None) => {}
+        }
+    }
+    (!s.is_empty()).then_some(s)
+}
+
+impl Paper {
+    /// Authors, or editors when there are none, as `Jane Doe, Bob Smith`.
+    pub fn authors_text(&self) -> String {
+        if self.auth.authors.is_empty() {
+            self.editors.authors.join(", ")
+        } else {
+            self.auth.authors.join(", ")
+        }
+    }
+
+    /// Where the entry appeared: the journal or conference, or the publisher
+    /// for standalone entries.
+    pub fn venue(&self) -> Option<String> {
+        match &self.place {
+            Some(p) => Some(p.name().to_string()),
+            None => self
+                .publi
+                .publi
+                .clone()
+                .or_else(|| self.howpublished.how.clone()),
+        }
+    }
+
+    /// Volume, issue and pages, see `venue_detail`.
+    pub fn venue_detail(&self) -> Option<String> {
+        venue_detail(self.vol.nb, self.issue.issue.as_deref(), &self.pages)
+    }
+
+    /// The entry as `Authors. "Title." Venue, Year.`, the venue being
+    /// followed by the title of its special issue, and the date an online
+    /// resource was accessed, `Accessed: Jan. 15, 2024.` in the locale of
+    /// the page, ending it.
+    pub fn citation(&self) -> String {
+        self.styled_citation(Style::Plain)
+    }
+
+    /// Like `citation`, in the conventions of `style`: the journal or
+    /// conference is in italics, the publisher of standalone entries not.
+    pub fn styled_citation(&self, style: Style) -> String {
+        let mut s = String::new();
+        let authors = self.authors_text();
+        if !authors.is_empty() {
+            s.push_str(&style.text(&authors));
+            s.push_str(". ");
+        }
+        s.push_str(&style.text(&format!("\"{}.\" ", self.title.title.trim_end_matches('.'))));
+        if let Some(v) = self.venue() {
+            match self.place {
+                Some(_) => s.push_str(&style.venue(&v)),
+                None => s.push_str(&style.text(&v)),
+            }
+            s.push_str(", ");
+        }
+        if let Some(Place::Journal(_, Some(t))) = &self.place {
+            s.push_str(&style.text(t));
+            s.push_str(", ");
+        }
+        s.push_str(&format!("{}.", self.year.year));
+        let at = self.accessed.at.as_deref();
+        if let Some(accessed) = at.and_then(|d| self.locale.accessed(d)) {
+            s.push(' ');
+            s.push_str(&accessed);
+        }
+        s
+    }
+
+    /// Link to the entry, preferring the DOI resolver over the `url` tag.
+    pub fn link(&self) -> Option<(&'static str, String)> {
+        if !self.doi.s.is_empty() {
+            Some(("doi", format!("https://doi.org/{}", self.doi.s)))
+        } else if !self.url.link.is_empty() {
+            Some(("url", self.url.link.clone()))
+        } else {
+            None
+        }
+    }
+}
+
+/// Sorts papers from the most recent year to the oldest, then the undated
+/// ones, keeping the file order within a year.
+pub fn sort_newest_first(papers: &mut [Paper]) {
+    papers.sort_by_key(|p| std::cmp::Reverse((p.year.dated, p.year.year)));
+}
@@ -0,0 +1,72 @@
+//! Citation counts from the Semantic Scholar Graph API.
+
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::arxiv::ArxivId;
+use crate::fetch::Fetcher;
+use crate::provenance::Origin;
+use crate::time::today;
+use crate::{progress, Paper};
+
+const SERVICE: &str = "semanticscholar";
+const BATCH_URL: &str = "https://api.semanticscholar.org/graph/v1/paper/batch?fields=citationCount";
+/// Largest number of identifiers the batch endpoint accepts.
+const BATCH_SIZE: usize = 500;
+
+/// Identifier of a paper for the Graph API, from its DOI or arXiv eprint.
+pub fn paper_id(paper: &Paper) -> Option<String> {
+    if !paper.doi.s.is_empty() {
+        Some(format!("DOI:{}", paper.doi.s))
+    } else {
+        let id = ArxivId::parse(paper.eprint.as_deref()?)?;
+        Some(format!("ARXIV:{}", id.id))
+    }
+}
+
+/// Reads a cached count, stored as `<count> <date>`.
+fn parse_cached(cached: &str) -> Option<(u64, String)> {
+    let (count, date) = cached.trim().split_once(' ')?;
+    Some((count.parse().ok()?, date.to_string()))
+}
+
+/// Fills in the citation counts of the papers having a DOI or an arXiv
+/// identifier. Counts cached for less than `max_age` are reused; the others
+/// are fetched in batches. Papers whose count cannot be had get none.
+pub fn enrich(papers: &mut [Paper], fetcher: &mut Fetcher, max_age: Duration) {
+    let ids: Vec<Option<String>> = papers.iter().map(paper_id).collect();
+    let missing: Vec<&String> = ids
+        .iter()
+        .flatten()
+        .filter(|id| fetcher.cached(SERVICE, id, Some(max_age)).is_none())
+        .collect();
+    progress::start("Citation counts", missing.len());
+    for batch in missing.chunks(BATCH_SIZE) {
+        for id in batch {
+            progress::tick(id);
+        }
+        let body = json!({ "ids": batch }).to_string();
+        let Some(answer) = fetcher.post(SERVICE, BATCH_URL, &body) else {
+            continue;
+        };
+        let results: Vec<Value> = serde_json::from_str(&answer).unwrap_or_default();
+        for (id, result) in batch.iter().zip(results) {
+            if let Some(n) = result["citationCount"].as_u64() {
+                fetcher.store(SERVICE, id, &format!("{} {}", n, today()));
+            }
+        }
+    }
+    progress::finish();
+    for (p, id) in papers.iter_mut().zip(&ids) {
+        let cached = id.as_ref().and_then(|id| fetcher.cached(SERVICE, id, None));
+        if let Some((count, date)) = cached.as_deref().and_then(parse_cached) {
+            p.citations.count = Some(count);
+            p.citations.date = Some(date);
+            for field in ["citation_count", "citation_count_date"] {
+                p.provenance
+                    .set(field, None, Origin::Service(SERVICE.to_string()));
+            }
+        }
+    }
+}
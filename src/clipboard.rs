@@ -0,0 +1,59 @@
+//! Reading the entries to convert from the system clipboard, as copied from
+//! a publisher page. The clipboard is only read when the crate is built
+//! with the `clipboard` feature, through the tools of the platform.
+
+use nom_bibtex::{Bibtex, Entry};
+
+/// Commands printing the clipboard, tried in order until one succeeds.
+#[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+const COMMANDS: [&[&str]; 5] = [
+    &["pbpaste"],
+    &["wl-paste", "--no-newline"],
+    &["xclip", "-selection", "clipboard", "-out"],
+    &["xsel", "--clipboard", "--output"],
+    &["powershell", "-NoProfile", "-Command", "Get-Clipboard"],
+];
+
+/// The text of the clipboard. Without a display or a clipboard tool, the
+/// error says so.
+#[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+pub fn read() -> Result<String, String> {
+    use std::process::{Command, Stdio};
+
+    for command in COMMANDS {
+        let output = Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output();
+        if let Some(output) = output.ok().filter(|o| o.status.success()) {
+            return String::from_utf8(output.stdout)
+                .map_err(|_| "the clipboard does not hold text".to_string());
+        }
+    }
+    Err(
+        "no clipboard available: without a display, or without pbpaste, wl-paste, \
+         xclip, xsel or powershell, pipe the entry with --stdin-format entry instead"
+            .to_string(),
+    )
+}
+
+#[cfg(not(all(feature = "clipboard", not(target_arch = "wasm32"))))]
+pub fn read() -> Result<String, String> {
+    Err("built without the `clipboard` feature".to_string())
+}
+
+/// The clipboard `content` if it holds BibTeX entries, possibly surrounded
+/// by blank lines.
+pub fn bibtex(content: &str) -> Result<String, String> {
+    let content = content.trim();
+    let entries = content
+        .starts_with('@')
+        .then(|| Bibtex::raw_parse(content).ok())
+        .flatten()
+        .filter(|entries| entries.iter().any(|e| matches!(e, Entry::Bibliography(..))));
+    match entries {
+        Some(_) => Ok(content.to_string()),
+        None => Err("clipboard does not look like a BibTeX entry".to_string()),
+    }
+}
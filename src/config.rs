@@ -0,0 +1,54 @@
+//! Settings read from a TOML configuration file, completing the command
+//! line flags.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::check::Checks;
+use crate::fields::VenueKind;
+use crate::mapping::FieldMap;
+use crate::text::TitleRules;
+
+/// Name of the configuration file looked for in the current directory.
+pub const DEFAULT_PATH: &str = "new_page.toml";
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Citation keys of the entries kept out of the website
+    pub exclude_keys: Vec<String>,
+    /// Category of the entry types, under `[categories]`, as
+    /// `misc = "software"`
+    pub categories: BTreeMap<String, String>,
+    /// Words of the conference names, or keywords, marking workshop
+    /// papers; `["workshop"]` when missing
+    pub workshop_keywords: Option<Vec<String>>,
+    /// Kind of venue of papers of proceedings, under `[venue_kinds]`, as
+    /// `doe21 = "workshop"`, overriding the detected one
+    pub venue_kinds: BTreeMap<String, VenueKind>,
+    /// Clean-ups applied to the titles, under `[title]`
+    pub title: TitleRules,
+    /// Names, order and omissions of the front matter keys, under `[fields]`
+    pub fields: FieldMap,
+    /// Rules of `--check`, under `[check]`, as `redundant-url = false` or
+    /// `require-abstract-after-year = 2018`
+    pub check: Checks,
+}
+
+impl Config {
+    /// Reads the configuration at `path`, rejecting unknown front matter
+    /// fields. Errors mention the path.
+    pub fn load(path: &Path) -> Result<Config, String> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let config: Config =
+            toml::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))?;
+        config
+            .fields
+            .validate()
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(config)
+    }
+}
@@ -0,0 +1,287 @@
+//! The conventions of the pages already in a content directory, so that the
+//! new pages follow them: the generator their front matter is for, the
+//! name of the authors key, page bundles or single files, and the format
+//! of the dates. A few pages are sampled; a convention is only detected
+//! when they all agree.
+
+use std::path::{Path, PathBuf};
+
+use crate::{info, warn, Options, Target, Warning};
+
+/// Number of existing pages read.
+pub const SAMPLE: usize = 10;
+
+/// Format of the `date` of the pages.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DateFormat {
+    /// `2021-03-01`
+    Day,
+    /// With a time, `2021-03-01T00:00:00Z`
+    Time,
+}
+
+impl DateFormat {
+    fn of(date: &str) -> DateFormat {
+        match date.trim().len() > 10 {
+            true => DateFormat::Time,
+            false => DateFormat::Day,
+        }
+    }
+}
+
+/// What the sampled pages have in common; `None` when they disagree, or
+/// when there are none.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Conventions {
+    /// Number of pages read
+    pub sampled: usize,
+    /// Whether the front matter is TOML rather than YAML
+    pub toml: Option<bool>,
+    /// Generator the front matter is for, when it tells: Zola's `[extra]`
+    /// and `[taxonomies]` tables, Jekyll's `layout`
+    pub target: Option<Target>,
+    /// Key of the authors, `authors` or `author`
+    pub authors_key: Option<String>,
+    pub bundle: Option<bool>,
+    pub date: Option<DateFormat>,
+}
+
+/// What one page tells.
+struct Page {
+    toml: bool,
+    target: Target,
+    authors_key: Option<String>,
+    bundle: bool,
+    date: Option<DateFormat>,
+}
+
+/// The pages of `dir`, `<name>.md` or `<name>/index.md`, in name order,
+/// without the section pages `_index.md`.
+fn pages(dir: &Path) -> Vec<(PathBuf, bool)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut pages: Vec<(PathBuf, bool)> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter_map(|path| {
+            if path.is_dir() {
+                let index = path.join("index.md");
+                return index.is_file().then_some((index, true));
+            }
+            let name = path.file_name()?.to_str()?;
+            (name.ends_with(".md") && !name.starts_with('_')).then_some((path, false))
+        })
+        .collect();
+    pages.sort();
+    pages
+}
+
+/// What the page `content` tells, unless it has no front matter.
+fn read(content: &str, bundle: bool) -> Option<Page> {
+    let content = content.replace("\r\n", "\n");
+    if let Some(rest) = content.strip_prefix("+++\n") {
+        let table: toml::Table = rest.split_once("\n+++")?.0.parse().ok()?;
+        let zola = table.contains_key("extra") || table.contains_key("taxonomies");
+        let authors = table
+            .get("extra")
+            .and_then(|e| e.as_table())
+            .unwrap_or(&table);
+        return Some(Page {
+            toml: true,
+            target: if zola { Target::Zola } else { Target::Hugo },
+            authors_key: ["authors", "author"]
+                .into_iter()
+                .find(|k| authors.contains_key(*k))
+                .map(String::from),
+            bundle,
+            date: table.get("date").map(|d| match d {
+                toml::Value::String(s) => DateFormat::of(s),
+                d => DateFormat::of(&d.to_string()),
+            }),
+        });
+    }
+    let rest = content.strip_prefix("---\n")?;
+    let yaml = match rest.split_once("\n---") {
+        Some((yaml, _)) => yaml,
+        None => rest.strip_prefix("---")?,
+    };
+    let yaml: serde_yaml::Mapping = serde_yaml::from_str(yaml).ok()?;
+    let has = |k: &str| yaml.contains_key(k);
+    Some(Page {
+        toml: false,
+        target: if has("layout") {
+            Target::Jekyll
+        } else {
+            Target::Hugo
+        },
+        authors_key: ["authors", "author"]
+            .into_iter()
+            .find(|k| has(k))
+            .map(String::from),
+        bundle,
+        date: yaml
+            .get("date")
+            .and_then(|d| d.as_str())
+            .map(DateFormat::of),
+    })
+}
+
+/// The value all the `values` share, if they do.
+fn common<T: PartialEq>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    let mut values = values.into_iter();
+    let first = values.next()?;
+    values.all(|v| v == first).then_some(first)
+}
+
+/// The conventions of the first `SAMPLE` pages of `dir`.
+pub fn detect(dir: &Path) -> Conventions {
+    let pages: Vec<Page> = pages(dir)
+        .into_iter()
+        .filter_map(|(path, bundle)| read(&std::fs::read_to_string(path).ok()?, bundle))
+        .take(SAMPLE)
+        .collect();
+    Conventions {
+        sampled: pages.len(),
+        toml: common(pages.iter().map(|p| p.toml)),
+        target: common(pages.iter().map(|p| p.target)),
+        authors_key: common(pages.iter().map(|p| p.authors_key.clone())).flatten(),
+        bundle: common(pages.iter().map(|p| p.bundle)),
+        date: common(pages.iter().map(|p| p.date)).flatten(),
+    }
+}
+
+/// A difference between the existing pages and the ones the options would
+/// write.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mismatch {
+    /// What differs, and what to pass to follow the existing pages
+    pub message: String,
+    /// Whether the options can be changed to follow the existing pages
+    pub adoptable: bool,
+}
+
+/// Name of `target` on the command line.
+fn flag(target: Target) -> &'static str {
+    match target {
+        Target::Hugo => "hugo",
+        Target::Zola => "zola",
+        Target::Jekyll => "jekyll",
+    }
+}
+
+/// The ways the pages written with `opts` would differ from the existing
+/// ones.
+pub fn mismatches(found: &Conventions, opts: &Options) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
+    let mut add = |message: String, adoptable| mismatches.push(Mismatch { message, adoptable });
+    match (found.toml, found.target) {
+        (Some(true), Some(Target::Zola)) if opts.target != Target::Zola => add(
+            "existing pages are written for Zola, with TOML front matter; pass --target zola"
+                .to_string(),
+            true,
+        ),
+        (Some(true), _) if opts.target != Target::Zola => add(
+            "existing pages use TOML front matter, which is only written for Zola (--target zola)"
+                .to_string(),
+            false,
+        ),
+        (Some(false), Some(target)) if opts.target == Target::Zola => add(
+            format!(
+                "existing pages use YAML front matter; pass --target {}",
+                flag(target)
+            ),
+            true,
+        ),
+        (Some(false), Some(Target::Jekyll)) if opts.target != Target::Jekyll => add(
+            "existing pages are written for Jekyll; pass --target jekyll".to_string(),
+            true,
+        ),
+        _ => {}
+    }
+    if let Some(key) = &found.authors_key {
+        if key != opts.fields.name("authors") {
+            add(
+                format!(
+                    "existing pages name the authors `{}`; add `authors = \"{}\"` under \
+                     [fields.rename] in the configuration",
+                    key, key
+                ),
+                true,
+            );
+        }
+    }
+    match found.bundle {
+        Some(true) if !opts.bundle => add(
+            "existing pages are page bundles; pass --bundle".to_string(),
+            true,
+        ),
+        Some(false) if opts.bundle => add(
+            "existing pages are single files; drop --bundle".to_string(),
+            true,
+        ),
+        _ => {}
+    }
+    let written = match opts.target {
+        Target::Jekyll => DateFormat::Time,
+        Target::Hugo | Target::Zola => DateFormat::Day,
+    };
+    match found.date {
+        Some(DateFormat::Time) if written == DateFormat::Day => add(
+            "existing pages give the time of their date, new pages only the day".to_string(),
+            false,
+        ),
+        Some(DateFormat::Day) if written == DateFormat::Time => add(
+            "existing pages give the day of their date only, new pages a time too".to_string(),
+            false,
+        ),
+        _ => {}
+    }
+    mismatches
+}
+
+/// Changes `opts` to follow the conventions `found`, where they can be.
+pub fn adopt(found: &Conventions, opts: &mut Options) {
+    for mismatch in mismatches(found, opts).iter().filter(|m| m.adoptable) {
+        info("conventions", &format!("adopted: {}", mismatch.message));
+    }
+    match (found.toml, found.target) {
+        (Some(true), Some(Target::Zola)) => opts.target = Target::Zola,
+        (Some(false), Some(target)) if opts.target == Target::Zola => opts.target = target,
+        (Some(false), Some(Target::Jekyll)) => opts.target = Target::Jekyll,
+        _ => {}
+    }
+    match found.authors_key.as_deref() {
+        Some("authors") => {
+            opts.fields.rename.remove("authors");
+        }
+        Some(key) => {
+            opts.fields
+                .rename
+                .insert("authors".to_string(), key.to_string());
+        }
+        None => {}
+    }
+    if let Some(bundle) = found.bundle {
+        opts.bundle = bundle;
+    }
+}
+
+/// Compares the pages of `dir` with the ones `opts` would write, warning
+/// about the differences or, with `adopt`, following the existing pages
+/// where they can be.
+pub fn check(dir: &Path, opts: &mut Options, adopt_them: bool) {
+    let found = detect(dir);
+    if found.sampled == 0 {
+        return;
+    }
+    if adopt_them {
+        adopt(&found, opts);
+    }
+    for mismatch in mismatches(&found, opts) {
+        warn(Warning::ConventionMismatch {
+            dir: dir.display().to_string(),
+            message: mismatch.message,
+        });
+    }
+}
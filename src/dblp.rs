@@ -0,0 +1,123 @@
+//! Filling in missing DOIs from the dblp publication search.
+
+use serde_json::Value;
+
+use crate::fetch::{encode_query, Fetcher};
+use crate::fields::Pages;
+use crate::provenance::Origin;
+use crate::{progress, warn, Paper, Warning};
+
+const SERVICE: &str = "dblp";
+
+/// A publication found on dblp.
+#[derive(Debug, Default, PartialEq)]
+pub struct Hit {
+    pub title: String,
+    pub doi: Option<String>,
+    pub url: Option<String>,
+    pub pages: Option<String>,
+}
+
+/// Lowercase letters and digits of a title, which is what titles are
+/// compared on.
+pub fn normalize_title(title: &str) -> String {
+    title
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Reads the hits of a dblp search answer.
+pub fn parse_hits(answer: &str) -> Vec<Hit> {
+    let json: Value = match serde_json::from_str(answer) {
+        Ok(json) => json,
+        Err(_) => return Vec::new(),
+    };
+    let text = |v: &Value| v.as_str().map(String::from);
+    json["result"]["hits"]["hit"]
+        .as_array()
+        .map(|hits| {
+            hits.iter()
+                .map(|h| &h["info"])
+                .map(|info| Hit {
+                    title: text(&info["title"]).unwrap_or_default(),
+                    doi: text(&info["doi"]),
+                    url: text(&info["ee"]),
+                    pages: text(&info["pages"]),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The hit whose title is the one of `paper`, or the closest one as a
+/// suggestion.
+fn best_hit<'a>(paper: &Paper, hits: &'a [Hit]) -> Option<(&'a Hit, bool)> {
+    let title = normalize_title(&paper.title.title);
+    hits.iter()
+        .find(|h| normalize_title(&h.title) == title)
+        .map(|h| (h, true))
+        .or_else(|| hits.iter().find(|h| h.doi.is_some()).map(|h| (h, false)))
+}
+
+/// Fills in the DOI, and the URL and pages when missing, of `paper` from
+/// a dblp hit with the same title.
+pub fn apply(paper: &mut Paper, hits: &[Hit]) {
+    match best_hit(paper, hits) {
+        Some((hit, true)) => {
+            let from_dblp = Origin::Service(SERVICE.to_string());
+            if let Some(doi) = &hit.doi {
+                paper.doi.s = doi.clone();
+                paper.provenance.set("doi", None, from_dblp.clone());
+            }
+            if paper.url.link.is_empty() {
+                if let Some(url) = &hit.url {
+                    paper.url.link = url.clone();
+                    paper.provenance.set("www", None, from_dblp.clone());
+                }
+            }
+            if paper.pages.from.is_none() {
+                if let Some(pages) = &hit.pages {
+                    paper.pages = Pages::from_string(pages);
+                    paper.provenance.set("page", None, from_dblp);
+                }
+            }
+        }
+        Some((hit, false)) => warn(Warning::Suggestion {
+            key: paper.key.key.clone(),
+            field: "doi".to_string(),
+            message: format!(
+                "dblp suggests DOI {} for \"{}\"",
+                hit.doi.as_deref().unwrap_or_default(),
+                hit.title
+            ),
+        }),
+        None => {}
+    }
+}
+
+/// Looks the papers without DOI up on dblp, by title and first author's
+/// family name.
+pub fn enrich(papers: &mut [Paper], fetcher: &mut Fetcher) {
+    let lookups = papers.iter().filter(|p| p.doi.s.is_empty()).count();
+    progress::start("DBLP", lookups);
+    for p in papers.iter_mut().filter(|p| p.doi.s.is_empty()) {
+        progress::tick(&p.key.key);
+        if p.title.title.is_empty() {
+            continue;
+        }
+        let mut query = p.title.title.replace(['{', '}'], "");
+        if let Some(family) = p.auth.authors.first().and_then(|a| a.rsplit(' ').next()) {
+            query = format!("{} {}", query, family);
+        }
+        let url = format!(
+            "https://dblp.org/search/publ/api?format=json&h=5&q={}",
+            encode_query(&query)
+        );
+        if let Some(answer) = fetcher.get(SERVICE, &query, &url) {
+            apply(p, &parse_hits(&answer));
+        }
+    }
+    progress::finish();
+}
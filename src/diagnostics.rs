@@ -0,0 +1,505 @@
+//! Errors and warnings of the conversion, with what they are about: the
+//! entry, the tag and, for the entries which do not parse, where they are
+//! in the input. The command line prints them as they are displayed, or as
+//! JSON, so that embedders see what it does.
+
+use std::cell::RefCell;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use nom_bibtex::error::BibtexError;
+use serde::{Serialize, Serializer};
+
+use crate::progress;
+
+/// A problem with an entry that does not prevent its conversion.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Warning {
+    /// An entry which does not parse, left out in recovery mode
+    Unparsable {
+        entry_type: String,
+        /// Key of the entry, when it can be read
+        key: Option<String>,
+        line: usize,
+        /// Byte offsets of the entry in the input, its line endings made
+        /// `\n`; the overrides come after the input
+        span: Range<usize>,
+    },
+    /// A tag whose value cannot be used as it is; the message says what is
+    /// done instead
+    InvalidField {
+        key: String,
+        field: String,
+        message: String,
+    },
+    /// A tag which is missing, replaced as the message says
+    MissingField {
+        key: String,
+        field: String,
+        message: String,
+    },
+    /// A tag left out because of the type or the other tags of the entry
+    IgnoredField {
+        key: String,
+        field: String,
+        message: String,
+    },
+    /// A tag longer than the limit, cut to it
+    FieldTooLong {
+        key: String,
+        field: String,
+        length: usize,
+        limit: usize,
+    },
+    /// Tags holding control characters, which are removed
+    ControlCharacters { key: String, fields: Vec<String> },
+    /// A person listed twice, the second time being dropped
+    DuplicateName {
+        key: String,
+        field: String,
+        first: String,
+        name: String,
+    },
+    /// An entry with both a journal and a booktitle
+    TwoVenues { key: String },
+    /// `@xdata` references going round in circles, `chain` ending with the
+    /// key seen twice
+    XdataCycle { key: String, chain: Vec<String> },
+    /// A reference to an `@xdata` entry which does not exist
+    MissingXdata { key: String, reference: String },
+    /// An override which matches no entry, converted on its own
+    UnmatchedOverride { key: String },
+    /// An entry of the ignore file converted because it is selected, the
+    /// reason saying why it would have been ignored
+    SelectedIgnored { key: String, reason: String },
+    /// Entries of unknown types converted generically, with their count
+    UnknownTypes { types: Vec<(String, usize)> },
+    /// Versions of a work left unmerged, the reason saying why
+    AmbiguousVersions { keys: Vec<String>, reason: String },
+    /// Pages of a content directory following other conventions than the
+    /// new ones
+    ConventionMismatch { dir: String, message: String },
+    /// A page which does not verify, left out
+    InvalidPage { key: String, reason: String },
+    /// A page whose file name is taken by another one
+    FileNameCollision { key: String, name: String },
+    /// A correction an enrichment service suggests, left to the user
+    Suggestion {
+        key: String,
+        field: String,
+        message: String,
+    },
+    /// A failure of an enrichment service or of a cache
+    Service { service: String, message: String },
+    /// An entry going against a convention of `--check`, reported under
+    /// the id of its rule
+    Convention {
+        rule: &'static str,
+        key: String,
+        /// Tag it is about, if a single one
+        field: Option<String>,
+        message: String,
+    },
+}
+
+impl Warning {
+    pub(crate) fn invalid(key: &str, field: &str, message: impl Into<String>) -> Warning {
+        Warning::InvalidField {
+            key: key.to_string(),
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn missing(key: &str, field: &str, message: impl Into<String>) -> Warning {
+        Warning::MissingField {
+            key: key.to_string(),
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn ignored(key: &str, field: &str, message: impl Into<String>) -> Warning {
+        Warning::IgnoredField {
+            key: key.to_string(),
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn service(service: &str, message: impl Into<String>) -> Warning {
+        Warning::Service {
+            service: service.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Identifier of the kind of warning, stable across versions.
+    pub fn rule(&self) -> &'static str {
+        match self {
+            Warning::Unparsable { .. } => "unparsable-entry",
+            Warning::InvalidField { .. } => "invalid-field",
+            Warning::MissingField { .. } => "missing-field",
+            Warning::IgnoredField { .. } => "ignored-field",
+            Warning::FieldTooLong { .. } => "field-too-long",
+            Warning::ControlCharacters { .. } => "control-characters",
+            Warning::DuplicateName { .. } => "duplicate-name",
+            Warning::TwoVenues { .. } => "two-venues",
+            Warning::XdataCycle { .. } => "xdata-cycle",
+            Warning::MissingXdata { .. } => "missing-xdata",
+            Warning::UnmatchedOverride { .. } => "unmatched-override",
+            Warning::SelectedIgnored { .. } => "selected-ignored",
+            Warning::UnknownTypes { .. } => "unknown-types",
+            Warning::AmbiguousVersions { .. } => "ambiguous-versions",
+            Warning::ConventionMismatch { .. } => "convention-mismatch",
+            Warning::InvalidPage { .. } => "invalid-page",
+            Warning::FileNameCollision { .. } => "file-name-collision",
+            Warning::Suggestion { .. } => "suggestion",
+            Warning::Service { .. } => "service",
+            Warning::Convention { rule, .. } => rule,
+        }
+    }
+
+    /// Citation key of the entry, unless the warning is about several.
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            Warning::Unparsable { key, .. } => key.as_deref(),
+            Warning::InvalidField { key, .. }
+            | Warning::MissingField { key, .. }
+            | Warning::IgnoredField { key, .. }
+            | Warning::FieldTooLong { key, .. }
+            | Warning::ControlCharacters { key, .. }
+            | Warning::DuplicateName { key, .. }
+            | Warning::TwoVenues { key }
+            | Warning::XdataCycle { key, .. }
+            | Warning::MissingXdata { key, .. }
+            | Warning::UnmatchedOverride { key }
+            | Warning::SelectedIgnored { key, .. }
+            | Warning::InvalidPage { key, .. }
+            | Warning::FileNameCollision { key, .. }
+            | Warning::Suggestion { key, .. }
+            | Warning::Convention { key, .. } => Some(key),
+            Warning::UnknownTypes { .. }
+            | Warning::AmbiguousVersions { .. }
+            | Warning::ConventionMismatch { .. }
+            | Warning::Service { .. } => None,
+        }
+    }
+
+    /// Tag the warning is about, if a single one.
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            Warning::InvalidField { field, .. }
+            | Warning::MissingField { field, .. }
+            | Warning::IgnoredField { field, .. }
+            | Warning::FieldTooLong { field, .. }
+            | Warning::DuplicateName { field, .. }
+            | Warning::Suggestion { field, .. } => Some(field),
+            Warning::Convention { field, .. } => field.as_deref(),
+            Warning::ControlCharacters { fields, .. } if fields.len() == 1 => Some(&fields[0]),
+            _ => None,
+        }
+    }
+
+    /// Line of the input the warning is about, when known.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Warning::Unparsable { line, .. } => Some(*line),
+            _ => None,
+        }
+    }
+
+    /// Byte offsets in the input of what the warning is about, when known.
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self {
+            Warning::Unparsable { span, .. } => Some(span.clone()),
+            _ => None,
+        }
+    }
+
+    /// What the warning says, without what it is about.
+    pub fn message(&self) -> String {
+        match self {
+            Warning::Unparsable { entry_type, .. } => {
+                format!("cannot parse this @{}, skipping it", entry_type)
+            }
+            Warning::InvalidField { message, .. }
+            | Warning::MissingField { message, .. }
+            | Warning::IgnoredField { message, .. }
+            | Warning::Suggestion { message, .. }
+            | Warning::ConventionMismatch { message, .. }
+            | Warning::Service { message, .. }
+            | Warning::Convention { message, .. } => message.clone(),
+            Warning::FieldTooLong {
+                field,
+                length,
+                limit,
+                ..
+            } => format!(
+                "{} is {} bytes long, cut to the limit of {}; raise it with --max-field-length",
+                field, length, limit
+            ),
+            Warning::ControlCharacters { fields, .. } => {
+                format!("removed control characters from {}", fields.join(", "))
+            }
+            Warning::DuplicateName {
+                field, first, name, ..
+            } => format!("{} lists {} twice, as {}", field, first, name),
+            Warning::TwoVenues { .. } => {
+                "has both a journal and a booktitle, keeping the second as also_appeared_in"
+                    .to_string()
+            }
+            Warning::XdataCycle { chain, .. } => {
+                format!("xdata cycle {}, left unresolved", chain.join(" -> "))
+            }
+            Warning::MissingXdata { reference, .. } => {
+                format!("xdata {} does not exist", reference)
+            }
+            Warning::UnmatchedOverride { .. } => {
+                "the override matches no entry, converting it on its own".to_string()
+            }
+            Warning::SelectedIgnored { reason, .. } => {
+                format!("{}, converting it as it is selected", reason)
+            }
+            Warning::UnknownTypes { types } => {
+                let counts: Vec<String> = types
+                    .iter()
+                    .map(|(kind, n)| format!("@{} ({})", kind, n))
+                    .collect();
+                format!("converted generically {}", counts.join(", "))
+            }
+            Warning::AmbiguousVersions { reason, .. } => {
+                format!("not merged as versions of one work, {}", reason)
+            }
+            Warning::InvalidPage { reason, .. } => format!("invalid page, left out: {}", reason),
+            Warning::FileNameCollision { name, .. } => {
+                format!("file name collides, using {}", name)
+            }
+        }
+    }
+
+    /// What the warning is about, as printed before its message.
+    fn subject(&self) -> String {
+        match self {
+            Warning::Unparsable {
+                key: Some(key),
+                line,
+                ..
+            } => format!("line {} ({})", line, key),
+            Warning::Unparsable { line, .. } => format!("line {}", line),
+            Warning::UnknownTypes { .. } => "entry types".to_string(),
+            Warning::AmbiguousVersions { keys, .. } => keys.join(", "),
+            Warning::ConventionMismatch { dir, .. } => dir.clone(),
+            Warning::Service { service, .. } => service.clone(),
+            _ => self.key().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.subject(), self.message())
+    }
+}
+
+impl Serialize for Warning {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Record {
+            severity: "warning",
+            rule: self.rule(),
+            key: self.key(),
+            field: self.field(),
+            line: self.line(),
+            span: self.span(),
+            message: self.message(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// A failure to read the input, which stops the conversion, or to convert
+/// one of its entries, which is left out.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The input is not BibTeX; its first entry which does not parse on
+    /// its own, if any, locates the error
+    Parse {
+        message: String,
+        line: Option<usize>,
+        /// Byte offsets of the entry in the input
+        span: Option<Range<usize>>,
+    },
+    /// A `@string` macro used without being defined
+    UndefinedMacro {
+        name: String,
+        line: Option<usize>,
+        span: Option<Range<usize>>,
+    },
+    /// An entry without the author its type asks for
+    MissingAuthor { key: String },
+    /// An entry without the journal or the booktitle its type asks for
+    MissingVenue { key: String },
+}
+
+impl Error {
+    /// The error `e` of parsing `input`, located by `failure`, the first
+    /// entry of the input which does not parse.
+    pub(crate) fn located(e: BibtexError, failure: Option<Warning>) -> Error {
+        let line = failure.as_ref().and_then(Warning::line);
+        let span = failure.as_ref().and_then(Warning::span);
+        match e {
+            BibtexError::StringVariableNotFound(name) => Error::UndefinedMacro { name, line, span },
+            BibtexError::Parsing(message) => Error::Parse {
+                message,
+                line,
+                span,
+            },
+        }
+    }
+
+    /// Identifier of the kind of error, stable across versions.
+    pub fn rule(&self) -> &'static str {
+        match self {
+            Error::Parse { .. } => "parse",
+            Error::UndefinedMacro { .. } => "undefined-macro",
+            Error::MissingAuthor { .. } => "missing-author",
+            Error::MissingVenue { .. } => "missing-venue",
+        }
+    }
+
+    /// Citation key of the entry which does not convert, if the error is
+    /// about one.
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            Error::MissingAuthor { key } | Error::MissingVenue { key } => Some(key),
+            Error::Parse { .. } | Error::UndefinedMacro { .. } => None,
+        }
+    }
+
+    /// Line of the input where the error is, when known.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Error::Parse { line, .. } | Error::UndefinedMacro { line, .. } => *line,
+            Error::MissingAuthor { .. } | Error::MissingVenue { .. } => None,
+        }
+    }
+
+    /// Byte offsets in the input of the entry in error, when known.
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self {
+            Error::Parse { span, .. } | Error::UndefinedMacro { span, .. } => span.clone(),
+            Error::MissingAuthor { .. } | Error::MissingVenue { .. } => None,
+        }
+    }
+
+    /// What the error says, without its location.
+    pub fn message(&self) -> String {
+        match self {
+            Error::Parse { message, .. } => message.trim().to_string(),
+            Error::UndefinedMacro { name, .. } => format!("string macro {} is not defined", name),
+            Error::MissingAuthor { .. } => "no author, leaving the entry out".to_string(),
+            Error::MissingVenue { .. } => {
+                "no journal nor booktitle, leaving the entry out".to_string()
+            }
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (self.line(), self.key()) {
+            (Some(line), _) => write!(f, "line {}: {}", line, self.message()),
+            (None, Some(key)) => write!(f, "{}: {}", key, self.message()),
+            (None, None) => write!(f, "{}", self.message()),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Record {
+            severity: "error",
+            rule: self.rule(),
+            key: self.key(),
+            field: None,
+            line: self.line(),
+            span: self.span(),
+            message: self.message(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// How errors and warnings are serialized.
+#[derive(Serialize)]
+struct Record<'a> {
+    severity: &'static str,
+    rule: &'static str,
+    key: Option<&'a str>,
+    field: Option<&'a str>,
+    line: Option<usize>,
+    span: Option<Range<usize>>,
+    message: String,
+}
+
+/// Whether the warnings are printed as JSON lines, see `print_json`.
+static JSON: AtomicBool = AtomicBool::new(false);
+
+/// Prints the warnings as JSON lines from now on, rather than as text.
+pub fn print_json() {
+    JSON.store(true, Ordering::Relaxed);
+}
+
+thread_local! {
+    /// Warnings collected by `collect`, rather than printed.
+    static COLLECTED: RefCell<Option<Vec<Warning>>> = const { RefCell::new(None) };
+}
+
+/// Runs `f`, returning the warnings it gives instead of printing them.
+///
+/// ```
+/// use new_page::diagnostics::{collect, Warning};
+/// use new_page::{convert, Options};
+///
+/// let bib = "@misc{k, title = {T}, author = {Roe, R}, year = {soon}}";
+/// let (papers, warnings) = collect(|| convert(bib, &Options::default()));
+/// assert_eq!(papers.unwrap().len(), 1);
+/// assert!(matches!(&warnings[0], Warning::InvalidField { field, .. } if field == "year"));
+/// ```
+pub fn collect<T>(f: impl FnOnce() -> T) -> (T, Vec<Warning>) {
+    let outer = COLLECTED.replace(Some(Vec::new()));
+    let result = f();
+    let collected = COLLECTED.replace(outer).unwrap_or_default();
+    (result, collected)
+}
+
+/// Prints `error`, about an entry left out of the conversion.
+pub(crate) fn report_error(error: &Error) {
+    match JSON.load(Ordering::Relaxed) {
+        true => progress::report(&serde_json::to_string(error).unwrap()),
+        false => progress::report(&format!("Error: {}", error)),
+    }
+}
+
+/// Hands `warning` to `collect`, or else prints it.
+pub(crate) fn report(warning: Warning) {
+    let unclaimed = COLLECTED.with_borrow_mut(|collected| match collected {
+        Some(collected) => {
+            collected.push(warning);
+            None
+        }
+        None => Some(warning),
+    });
+    let Some(warning) = unclaimed else {
+        return;
+    };
+    match JSON.load(Ordering::Relaxed) {
+        true => progress::report(&serde_json::to_string(&warning).unwrap()),
+        false => progress::report(&format!("Warning: {}", warning)),
+    }
+}
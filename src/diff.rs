@@ -0,0 +1,107 @@
+//! Comparing a bibliography with the pages already generated from it.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Error, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::output::normalize;
+use crate::{Options, Paper};
+
+/// What has to change in a content directory to match the bibliography.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Diff {
+    /// Keys of the entries which have no page
+    pub new: Vec<String>,
+    /// Pages whose key is not in the bibliography
+    pub stale: Vec<PathBuf>,
+    /// Keys of the entries whose page would be regenerated differently
+    pub changed: Vec<String>,
+}
+
+impl Diff {
+    /// Whether the pages match the bibliography.
+    pub fn is_clean(&self) -> bool {
+        self.new.is_empty() && self.stale.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl Display for Diff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        writeln!(f, "new: {}", self.new.len())?;
+        for k in &self.new {
+            writeln!(f, "  {}", k)?;
+        }
+        writeln!(f, "stale: {}", self.stale.len())?;
+        for p in &self.stale {
+            writeln!(f, "  {}", p.display())?;
+        }
+        writeln!(f, "changed: {}", self.changed.len())?;
+        for k in &self.changed {
+            writeln!(f, "  {}", k)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads the citation key of a page, from YAML or TOML front matter, under
+/// `field`: `bibtex_key` unless renamed.
+pub fn page_key(page: &str, field: &str) -> Option<String> {
+    let mut lines = page.lines();
+    let delimiter = lines.next()?.trim_end();
+    if delimiter != "---" && delimiter != "+++" {
+        return None;
+    }
+    lines
+        .take_while(|l| l.trim_end() != delimiter)
+        .find_map(|l| {
+            let rest = l.trim_start().strip_prefix(field)?;
+            let value = rest.trim_start().strip_prefix([':', '='])?.trim();
+            Some(value.trim_matches('"').to_string())
+        })
+}
+
+/// Lists the Markdown pages under `dir` which carry a citation key under
+/// `field`, with their content.
+pub fn scan(dir: &Path, field: &str) -> io::Result<Vec<(PathBuf, String, String)>> {
+    let mut pages = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(d) = dirs.pop() {
+        for entry in std::fs::read_dir(&d)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|e| e == "md") {
+                let content = std::fs::read_to_string(&path)?;
+                if let Some(key) = page_key(&content, field) {
+                    pages.push((path, key, content));
+                }
+            }
+        }
+    }
+    pages.sort();
+    Ok(pages)
+}
+
+/// Compares the pages under `dir` with the ones `papers` would produce.
+pub fn compare(papers: &[Paper], dir: &Path, opts: &Options) -> io::Result<Diff> {
+    let mut pages: HashMap<String, (PathBuf, String)> = scan(dir, opts.fields.name("bibtex_key"))?
+        .into_iter()
+        .map(|(path, key, content)| (key, (path, content)))
+        .collect();
+    let mut diff = Diff::default();
+    for p in papers {
+        match pages.remove(&p.key.key) {
+            None => diff.new.push(p.key.key.clone()),
+            Some((_, content)) if content != normalize(&p.render_with(opts), opts.line_ending) => {
+                diff.changed.push(p.key.key.clone())
+            }
+            Some(_) => {}
+        }
+    }
+    diff.stale = pages.into_values().map(|(path, _)| path).collect();
+    diff.stale.sort();
+    Ok(diff)
+}
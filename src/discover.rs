@@ -0,0 +1,82 @@
+//! Finding the bibtex files of the directories given as input, as the
+//! `refs.bib` of each project of `~/papers`.
+
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::{ignore, info};
+
+/// How directories are searched for `.bib` files.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Discovery {
+    /// Search the subdirectories too
+    pub recursive: bool,
+    /// Depth of the subdirectories searched, the directory given being at 0
+    pub max_depth: usize,
+    /// Names of the files and directories left out, `*` standing for any
+    /// text and `?` for any character
+    pub skip: Vec<String>,
+}
+
+impl Default for Discovery {
+    fn default() -> Discovery {
+        Discovery {
+            recursive: false,
+            max_depth: 16,
+            skip: vec![".*".to_string(), "node_modules".to_string()],
+        }
+    }
+}
+
+/// The `.bib` files of `dir`, and of its subdirectories when searching
+/// recursively, sorted by path. A directory reached again through a
+/// symbolic link is not searched twice. Errors mention the path which could
+/// not be read.
+pub fn bib_files(dir: &Path, discovery: &Discovery) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    walk(dir, 0, discovery, &mut HashSet::new(), &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+/// Adds the `.bib` files of `dir`, at `depth`, to `files`, unless it is one
+/// of the `visited` directories.
+fn walk(
+    dir: &Path,
+    depth: usize,
+    discovery: &Discovery,
+    visited: &mut HashSet<PathBuf>,
+    files: &mut Vec<PathBuf>,
+) -> io::Result<()> {
+    let with_path = |e: io::Error| io::Error::new(e.kind(), format!("{}: {}", dir.display(), e));
+    // Canonical paths tell the loops of symbolic links.
+    if !visited.insert(dir.canonicalize().map_err(with_path)?) {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(dir).map_err(with_path)? {
+        let path = entry.map_err(with_path)?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if discovery.skip.iter().any(|p| ignore::matches(p, &name)) {
+            continue;
+        }
+        if !path.is_dir() {
+            if path
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("bib"))
+            {
+                files.push(path);
+            }
+        } else if !discovery.recursive {
+            continue;
+        } else if depth < discovery.max_depth {
+            walk(&path, depth + 1, discovery, visited, files)?;
+        } else {
+            info(
+                &path.display().to_string(),
+                &format!("not searched, deeper than {}", discovery.max_depth),
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,260 @@
+//! Finding the entries which are likely versions of the same work, such as
+//! a preprint and its conference version, from their titles.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Error, Formatter};
+
+use crate::latex::decode;
+use crate::{warn, Paper, Warning};
+
+/// Similarity above which two titles are taken for the same, by default.
+pub const THRESHOLD: f64 = 0.9;
+
+/// Title reduced to its lowercase words, without LaTeX or punctuation.
+pub fn comparable(title: &str) -> String {
+    decode(title)
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .flat_map(char::to_lowercase)
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Edit distance between `a` and `b`, in characters.
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Similarity of two comparable titles, from 0 for nothing in common to 1
+/// for the same: one minus their edit distance relative to the longest.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    chars_similarity(&a, &b)
+}
+
+fn chars_similarity(a: &[char], b: &[char]) -> f64 {
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(a, b) as f64 / longest as f64
+}
+
+/// Entries whose titles are the same once made comparable, or similar
+/// above `threshold`, as indices in `papers`, in order. Of the pages of an
+/// entry in several languages, only the first is compared.
+pub fn find(papers: &[Paper], threshold: f64) -> Vec<Vec<usize>> {
+    let mut keys = HashSet::new();
+    let titles: Vec<String> = papers
+        .iter()
+        .map(|p| match keys.insert(&p.key.key) {
+            true => comparable(&p.title.title),
+            false => String::new(),
+        })
+        .collect();
+    // Entries are grouped with the first entry of their group.
+    let mut first: Vec<usize> = (0..papers.len()).collect();
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for (i, t) in titles.iter().enumerate().filter(|(_, t)| !t.is_empty()) {
+        match seen.get(t.as_str()) {
+            Some(&j) => first[i] = j,
+            None => {
+                seen.insert(t, i);
+            }
+        }
+    }
+    let distinct: Vec<(usize, Vec<char>)> = seen
+        .values()
+        .map(|&i| (i, titles[i].chars().collect()))
+        .collect();
+    for (n, (i, a)) in distinct.iter().enumerate() {
+        for (j, b) in &distinct[n + 1..] {
+            // The edit distance is at least the difference of lengths.
+            let longest = a.len().max(b.len()) as f64;
+            if (a.len().abs_diff(b.len()) as f64) > (1.0 - threshold) * longest {
+                continue;
+            }
+            if chars_similarity(a, b) >= threshold {
+                join(&mut first, *i, *j);
+            }
+        }
+    }
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..papers.len() {
+        groups.entry(root(&first, i)).or_default().push(i);
+    }
+    let mut groups: Vec<Vec<usize>> = groups.into_values().filter(|g| g.len() > 1).collect();
+    groups.sort();
+    groups
+}
+
+fn root(first: &[usize], mut i: usize) -> usize {
+    while first[i] != i {
+        i = first[i];
+    }
+    i
+}
+
+/// Puts `i` and `j` in the same group, the one of the earliest entry.
+fn join(first: &mut [usize], i: usize, j: usize) {
+    let (root_i, root_j) = (root(first, i), root(first, j));
+    first[root_i.max(root_j)] = root_i.min(root_j);
+}
+
+/// Fills the `other_versions` of the pages of each entry of the groups with
+/// the keys of the other entries.
+pub fn link_versions(papers: &mut [Paper], groups: &[Vec<usize>]) {
+    for group in groups {
+        let keys: Vec<String> = group.iter().map(|&i| papers[i].key.key.clone()).collect();
+        for p in papers.iter_mut().filter(|p| keys.contains(&p.key.key)) {
+            p.other_versions = keys.iter().filter(|k| **k != p.key.key).cloned().collect();
+        }
+    }
+}
+
+/// Difference of years beyond which two entries are not taken for versions
+/// of the same work.
+pub const MAX_YEARS_APART: i64 = 2;
+
+/// Whether the page is of a preprint: an arXiv eprint or link, without
+/// venue.
+fn is_preprint(p: &Paper) -> bool {
+    p.place.is_none() && (p.eprint.is_some() || p.url.link.contains("arxiv.org"))
+}
+
+/// Entries which are versions of the same work, by their titles once made
+/// comparable or by the keys of their `relatedversion` tag, as indices in
+/// `papers`. Pages in different languages are never grouped.
+fn versions(papers: &[Paper]) -> Vec<Vec<usize>> {
+    let index: HashMap<(Option<&str>, &str), usize> = papers
+        .iter()
+        .enumerate()
+        .map(|(i, p)| ((p.lang.as_deref(), p.key.key.as_str()), i))
+        .collect();
+    let mut first: Vec<usize> = (0..papers.len()).collect();
+    let mut titles: HashMap<(Option<&str>, String), usize> = HashMap::new();
+    for (i, p) in papers.iter().enumerate() {
+        let lang = p.lang.as_deref();
+        let title = comparable(&p.title.title);
+        if !title.is_empty() {
+            match titles.get(&(lang, title.clone())) {
+                Some(&j) => join(&mut first, i, j),
+                None => {
+                    titles.insert((lang, title), i);
+                }
+            }
+        }
+        for related in &p.related_versions {
+            if let Some(&j) = index.get(&(lang, related.as_str())) {
+                join(&mut first, i, j);
+            }
+        }
+    }
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..papers.len() {
+        groups.entry(root(&first, i)).or_default().push(i);
+    }
+    let mut groups: Vec<Vec<usize>> = groups.into_values().filter(|g| g.len() > 1).collect();
+    groups.sort();
+    groups
+}
+
+/// Merges each preprint into the page of its published version, found as
+/// `versions` says: the published page gains the links of the preprint
+/// and lists its key under `earlier_versions`, and the preprint has no
+/// page of its own. Groups of more than two entries, or whose years are
+/// more than `MAX_YEARS_APART` apart, are warned about and left alone, as
+/// are the pairs which are not a preprint and a published version.
+pub fn merge_versions(papers: Vec<Paper>) -> Vec<Paper> {
+    let mut merges = Vec::new();
+    for group in versions(&papers) {
+        let keys = || group.iter().map(|&i| papers[i].key.key.clone()).collect();
+        let years = group.iter().map(|&i| papers[i].year.year);
+        let (earliest, latest) = (years.clone().min().unwrap(), years.max().unwrap());
+        if group.len() > 2 {
+            warn(Warning::AmbiguousVersions {
+                keys: keys(),
+                reason: format!("{} candidates", group.len()),
+            });
+            continue;
+        }
+        if latest - earliest > MAX_YEARS_APART {
+            warn(Warning::AmbiguousVersions {
+                keys: keys(),
+                reason: format!(
+                    "years {} and {} are more than {} apart",
+                    earliest, latest, MAX_YEARS_APART
+                ),
+            });
+            continue;
+        }
+        let (a, b) = (group[0], group[1]);
+        match (is_preprint(&papers[a]), is_preprint(&papers[b])) {
+            (true, false) if papers[b].place.is_some() => merges.push((b, a)),
+            (false, true) if papers[a].place.is_some() => merges.push((a, b)),
+            _ => {}
+        }
+    }
+    let mut papers: Vec<Option<Paper>> = papers.into_iter().map(Some).collect();
+    for (published, preprint) in merges {
+        let preprint = papers[preprint].take().unwrap();
+        let published = papers[published].as_mut().unwrap();
+        let links = preprint
+            .eprint
+            .as_ref()
+            .map(|e| format!("https://arxiv.org/abs/{}", e))
+            .into_iter()
+            .chain(Some(preprint.url.link).filter(|l| !l.is_empty()));
+        for link in links {
+            if link != published.url.link && !published.links.contains(&link) {
+                published.links.push(link);
+            }
+        }
+        if published.eprint.is_none() {
+            published.eprint = preprint.eprint;
+        }
+        published.earlier_versions.push(preprint.key.key);
+    }
+    papers.into_iter().flatten().collect()
+}
+
+/// Report of the groups of likely duplicates, one entry per line with its
+/// key, year and venue.
+pub struct Report<'a> {
+    pub papers: &'a [Paper],
+    pub groups: &'a [Vec<usize>],
+}
+
+impl Display for Report<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        for (n, group) in self.groups.iter().enumerate() {
+            writeln!(f, "Possible duplicates, group {}:", n + 1)?;
+            for &i in group {
+                let p = &self.papers[i];
+                let venue = p.place.as_ref().map_or("no venue", |v| v.name());
+                writeln!(
+                    f,
+                    "  {} ({}, {}): {}",
+                    p.key.key, p.year.year, venue, p.title.title
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
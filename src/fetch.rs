@@ -0,0 +1,162 @@
+//! Cached, rate limited access to the web services used to enrich entries.
+//!
+//! Answers are kept in a cache directory, one file per request, so that
+//! regenerating a site does not query the services again. Requests are only
+//! made when the crate is built with the `network` feature and `offline` is
+//! not set; otherwise only the cache is used.
+
+use std::path::PathBuf;
+use std::thread::sleep;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::output::{sanitize_filename, write_file};
+use crate::{warn, Warning};
+
+/// Fetches web resources through an on-disk cache.
+pub struct Fetcher {
+    /// Directory holding the cached answers, one subdirectory per service
+    pub cache_dir: PathBuf,
+    /// Only use the cache
+    pub offline: bool,
+    /// Minimal delay between two requests
+    pub interval: Duration,
+    last: Option<Instant>,
+    warned: bool,
+}
+
+impl Fetcher {
+    pub fn new(cache_dir: PathBuf, offline: bool) -> Fetcher {
+        Fetcher {
+            cache_dir,
+            offline,
+            interval: Duration::from_secs(1),
+            last: None,
+            warned: false,
+        }
+    }
+
+    /// Default cache directory, `$XDG_CACHE_HOME/new_page` or
+    /// `~/.cache/new_page`.
+    pub fn default_cache_dir() -> PathBuf {
+        std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))
+            .unwrap_or_else(std::env::temp_dir)
+            .join("new_page")
+    }
+
+    fn cache_path(&self, service: &str, key: &str) -> PathBuf {
+        self.cache_dir.join(service).join(sanitize_filename(key))
+    }
+
+    /// Cached answer of `service` for `key`, if younger than `max_age`.
+    pub fn cached(&self, service: &str, key: &str, max_age: Option<Duration>) -> Option<String> {
+        let path = self.cache_path(service, key);
+        if let Some(max_age) = max_age {
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            let age = SystemTime::now()
+                .duration_since(modified)
+                .unwrap_or_default();
+            if age > max_age {
+                return None;
+            }
+        }
+        std::fs::read_to_string(path).ok()
+    }
+
+    /// Stores the answer of `service` for `key`.
+    pub fn store(&self, service: &str, key: &str, answer: &str) {
+        if let Err(e) = write_file(&self.cache_path(service, key), answer) {
+            warn(Warning::service(
+                service,
+                format!("cannot cache the answer: {}", e),
+            ));
+        }
+    }
+
+    /// Answer of `service` for `key`, from the cache or by querying `url`.
+    /// Failures are reported as warnings.
+    pub fn get(&mut self, service: &str, key: &str, url: &str) -> Option<String> {
+        if let Some(answer) = self.cached(service, key, None) {
+            return Some(answer);
+        }
+        let answer = self.send(service, || http_get(url))?;
+        self.store(service, key, &answer);
+        Some(answer)
+    }
+
+    /// Posts the JSON `body` to `url`, bypassing the cache: batched answers
+    /// are cached by the caller, one entry at a time.
+    pub fn post(&mut self, service: &str, url: &str, body: &str) -> Option<String> {
+        self.send(service, || http_post(url, body))
+    }
+
+    fn send<F>(&mut self, service: &str, request: F) -> Option<String>
+    where
+        F: FnOnce() -> Result<String, String>,
+    {
+        if self.offline {
+            return None;
+        }
+        if let Some(last) = self.last {
+            sleep(self.interval.saturating_sub(last.elapsed()));
+        }
+        self.last = Some(Instant::now());
+        request()
+            .map_err(|e| {
+                if !self.warned || cfg!(all(feature = "network", not(target_arch = "wasm32"))) {
+                    warn(Warning::service(service, e));
+                }
+                self.warned = true;
+            })
+            .ok()
+    }
+}
+
+/// Percent-encodes `s` for use in a query string.
+pub fn encode_query(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            b' ' => "+".to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(all(feature = "network", not(target_arch = "wasm32")))]
+fn http_get(url: &str) -> Result<String, String> {
+    ureq::get(url)
+        .header(
+            "User-Agent",
+            concat!("new_page/", env!("CARGO_PKG_VERSION")),
+        )
+        .call()
+        .and_then(|mut r| r.body_mut().read_to_string())
+        .map_err(|e| format!("{}: {}", url, e))
+}
+
+#[cfg(all(feature = "network", not(target_arch = "wasm32")))]
+fn http_post(url: &str, body: &str) -> Result<String, String> {
+    ureq::post(url)
+        .header(
+            "User-Agent",
+            concat!("new_page/", env!("CARGO_PKG_VERSION")),
+        )
+        .header("Content-Type", "application/json")
+        .send(body)
+        .and_then(|mut r| r.body_mut().read_to_string())
+        .map_err(|e| format!("{}: {}", url, e))
+}
+
+#[cfg(not(all(feature = "network", not(target_arch = "wasm32"))))]
+fn http_get(_url: &str) -> Result<String, String> {
+    Err("built without the `network` feature, only cached answers are used".into())
+}
+
+#[cfg(not(all(feature = "network", not(target_arch = "wasm32"))))]
+fn http_post(_url: &str, _body: &str) -> Result<String, String> {
+    http_get(_url)
+}
@@ -0,0 +1,2215 @@
+//! Front matter fields, each parsed from the bibtex tags and rendered as
+//! YAML by its `Display` implementation. The `Serialize` implementations give
+//! the same structure to the data exports.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Error, Formatter};
+
+use nom_bibtex::Bibliography;
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::latex::braced;
+use crate::time::{current_year, parse_date, parse_lenient_date, parse_month};
+use crate::yaml::{string, Quotes, ToYaml, Yaml};
+use crate::{warn, Warning};
+
+/// Pages of an entry: `from` and `to` span all its ranges, which are only
+/// kept when there are several, as in `11--17, 23--25`. Pages numbered in
+/// roman numerals or with a letter prefix, and pages which cannot be read
+/// as numbers, are also written as given, in `display`.
+#[derive(Default)]
+pub struct Pages {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub ranges: Vec<(i64, Option<i64>)>,
+    pub display: Option<String>,
+    /// Number of pages, see `with_count`
+    pub count: Option<i64>,
+}
+
+/// Value of a roman numeral, in either case, if it is written the usual
+/// way.
+pub fn roman(s: &str) -> Option<i64> {
+    const NUMERALS: [(i64, &str); 13] = [
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+    let lower = s.to_lowercase();
+    let mut rest = lower.as_str();
+    let mut value = 0;
+    for (n, numeral) in NUMERALS {
+        while let Some(r) = rest.strip_prefix(numeral) {
+            value += n;
+            rest = r;
+        }
+    }
+    // Only the usual way of writing the value, as `xix` but not `xviiii`.
+    let mut usual = String::new();
+    let mut left = value;
+    for (n, numeral) in NUMERALS {
+        while left >= n {
+            usual.push_str(numeral);
+            left -= n;
+        }
+    }
+    (rest.is_empty() && value > 0 && usual == lower).then_some(value)
+}
+
+/// Number of a page, and whether it is written otherwise than in digits:
+/// plain digits, a letter followed by digits (`S45`, `e12`), a roman
+/// numeral, or else the first digits found.
+fn page_number(s: &str) -> Option<(i64, bool)> {
+    let mut chars = s.chars();
+    let first = chars.next()?;
+    if s.chars().all(|c| c.is_ascii_digit()) {
+        return Some((s.parse().ok()?, false));
+    }
+    let rest = chars.as_str();
+    if first.is_ascii_alphabetic() && !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit()) {
+        return Some((rest.parse().ok()?, true));
+    }
+    if let Some(n) = roman(s) {
+        return Some((n, true));
+    }
+    s.split(|c: char| !c.is_ascii_digit())
+        .find_map(|i| i.parse().ok())
+        .map(|n| (n, false))
+}
+
+/// A range of pages, its text with an en dash, and whether it is written
+/// otherwise than in digits.
+fn page_range(range: &str) -> Option<((i64, Option<i64>), String, bool)> {
+    let ends: Vec<&str> = range
+        .split(['-', '–', '—'])
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .collect();
+    match ends.as_slice() {
+        [from, to, ..] => {
+            let (from_n, from_special) = page_number(from)?;
+            let (to_n, to_special) = page_number(to)?;
+            let text = format!("{}–{}", from, to);
+            Some(((from_n, Some(to_n)), text, from_special || to_special))
+        }
+        [page] => match page_number(page) {
+            Some((n, true)) => Some(((n, None), page.to_string(), true)),
+            _ => {
+                // Whatever separates them, the first two numbers.
+                let mut pages = page
+                    .split(|c: char| !c.is_ascii_digit())
+                    .filter_map(|i| i.parse::<i64>().ok());
+                let (from, to) = (pages.next()?, pages.next());
+                Some(((from, to), page.to_string(), false))
+            }
+        },
+        [] => None,
+    }
+}
+
+impl Pages {
+    /// Parses ranges separated by commas or semicolons, their ends separated
+    /// by dashes. When a range cannot be read as numbers, or ends before it
+    /// starts, the pages are only kept as given, in `display`. Free of I/O,
+    /// and never panics; see `checked` for the warnings.
+    pub fn from_string(s: &str) -> Self {
+        Pages::parse(s).ordered(s)
+    }
+
+    /// The ranges of `s`, their ends as written, even when a range ends
+    /// before it starts.
+    fn parse(s: &str) -> Self {
+        let mut pages = Pages::default();
+        let mut texts = Vec::new();
+        let mut special = false;
+        for range in s.split([',', ';']).map(str::trim).filter(|r| !r.is_empty()) {
+            let Some((numbers, text, written)) = page_range(range) else {
+                return Pages {
+                    display: Some(s.trim().to_string()),
+                    ..Pages::default()
+                };
+            };
+            pages.ranges.push(numbers);
+            texts.push(text);
+            special |= written;
+        }
+        if special {
+            pages.display = Some(texts.join(", "));
+        }
+        pages.span();
+        pages
+    }
+
+    /// The pages, or else `s` as given when one of the ranges ends before it
+    /// starts.
+    fn ordered(self, s: &str) -> Pages {
+        let single = self.from.map(|from| (from, self.to));
+        let reversed = single
+            .iter()
+            .chain(&self.ranges)
+            .any(|(from, to)| to.is_some_and(|to| to < *from));
+        match reversed {
+            true => Pages {
+                display: Some(s.trim().to_string()),
+                ..Pages::default()
+            },
+            false => self,
+        }
+    }
+
+    /// Sets `from` and `to` from the ranges, dropping them unless there are
+    /// several.
+    fn span(&mut self) {
+        match self.ranges.as_slice() {
+            [] => {}
+            [(from, to)] => {
+                (self.from, self.to) = (Some(*from), *to);
+                self.ranges.clear();
+            }
+            ranges => {
+                let ends = ranges.iter().flat_map(|(from, to)| [Some(*from), *to]);
+                self.from = ends.clone().flatten().min();
+                self.to = ends.flatten().max();
+            }
+        }
+    }
+
+    /// Number of pages from the bounds, the sum of the lengths of the
+    /// ranges when there are several, a range without end counting for one
+    /// page. A single page without end is not counted.
+    pub fn page_count(&self) -> Option<i64> {
+        if self.ranges.is_empty() {
+            return Some(self.to? - self.from? + 1);
+        }
+        Some(
+            self.ranges
+                .iter()
+                .map(|(from, to)| to.map_or(1, |to| to - from + 1))
+                .sum(),
+        )
+    }
+
+    /// Sets the `count` of pages, from the bounds or else from the
+    /// `numpages` tag, leaving it out with a warning when it is not between
+    /// 1 and `max_span`.
+    pub fn with_count(mut self, key: &str, numpages: Option<&String>, max_span: i64) -> Pages {
+        let count = self
+            .page_count()
+            .or_else(|| numpages.and_then(|n| n.trim().parse().ok()));
+        self.count = match count {
+            Some(n) if !(1..=max_span).contains(&n) => {
+                warn(Warning::invalid(
+                    key,
+                    "pages",
+                    format!("leaving out the page count {}", n),
+                ));
+                None
+            }
+            count => count,
+        };
+        self
+    }
+
+    /// The ranges as written in the page, `11–17, 23–25`, when there are
+    /// several.
+    pub fn ranges_text(&self) -> Option<String> {
+        let ranges: Vec<String> = self
+            .ranges
+            .iter()
+            .map(|(from, to)| match to {
+                Some(to) => format!("{}–{}", from, to),
+                None => from.to_string(),
+            })
+            .collect();
+        (!ranges.is_empty()).then(|| ranges.join(", "))
+    }
+}
+
+impl Serialize for Pages {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let mut m = s.serialize_map(None)?;
+        m.serialize_entry("from", &self.from)?;
+        m.serialize_entry("to", &self.to)?;
+        if let Some(ranges) = self.ranges_text() {
+            m.serialize_entry("ranges", &ranges)?;
+        }
+        if let Some(count) = self.count {
+            m.serialize_entry("count", &count)?;
+        }
+        m.end()
+    }
+}
+
+/// Expands a BibTeX-style abbreviated end page, `1153--67` ending on 1167,
+/// when there is only one way to read it: the end page has fewer digits
+/// than the start page and, completed with the leading digits of the start
+/// page, comes after it.
+pub fn expand_abbreviated_page(from: i64, to: i64) -> Option<i64> {
+    let (from_digits, to_digits) = (from.to_string(), to.to_string());
+    if to >= from || to < 0 || to_digits.len() >= from_digits.len() {
+        return None;
+    }
+    let prefix = &from_digits[..from_digits.len() - to_digits.len()];
+    let expanded: i64 = format!("{}{}", prefix, to_digits).parse().ok()?;
+    (expanded > from).then_some(expanded)
+}
+
+impl Pages {
+    /// Parses a page range and warns when it looks wrong: ending before it
+    /// starts, or spanning more than `max_span` pages. With `fix`, an
+    /// abbreviated end page is expanded when unambiguous.
+    /// Several ranges are checked one by one.
+    pub fn checked(key: &str, s: &str, fix: bool, max_span: i64) -> Pages {
+        let mut pages = Pages::parse(s);
+        if pages.ranges.is_empty() {
+            if let (Some(from), Some(to)) = (pages.from, pages.to) {
+                pages.to = Some(checked_end(key, s, from, to, fix, max_span));
+            }
+            return pages;
+        }
+        for range in pages.ranges.iter_mut() {
+            if let (from, Some(to)) = *range {
+                let s = format!("{}--{}", from, to);
+                range.1 = Some(checked_end(key, &s, from, to, fix, max_span));
+            }
+        }
+        pages.span();
+        pages
+    }
+}
+
+/// End page of the range `s`, from `from` to `to`, expanded when
+/// abbreviated and asked to `fix`.
+fn checked_end(key: &str, s: &str, from: i64, to: i64, fix: bool, max_span: i64) -> i64 {
+    if to < from {
+        match expand_abbreviated_page(from, to) {
+            Some(expanded) if fix => return expanded,
+            Some(expanded) => warn(Warning::invalid(
+                key,
+                "pages",
+                format!(
+                    "pages {} end before they start, maybe {}--{} (see --fix-pages)",
+                    s, from, expanded
+                ),
+            )),
+            None => warn(Warning::invalid(
+                key,
+                "pages",
+                format!("pages {} end before they start", s),
+            )),
+        }
+    } else if to - from > max_span {
+        warn(Warning::invalid(
+            key,
+            "pages",
+            format!("pages {} span more than {} pages", s, max_span),
+        ));
+    }
+    to
+}
+
+impl ToYaml for Pages {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        let to_str: String = self.to.map(|p| p.to_string()).unwrap_or_default();
+        let from_str: String = self.from.map(|p| p.to_string()).unwrap_or_default();
+        // Pages which cannot be read as numbers have no numeric keys.
+        if self.from.is_some() || self.display.is_none() {
+            writeln!(f, "page:\n  from: {}\n  to: {}", from_str, to_str)?;
+            if let Some(ranges) = self.ranges_text() {
+                writeln!(f, "  ranges: {}", string(ranges, quotes))?;
+            }
+            if let Some(count) = self.count {
+                writeln!(f, "  count: {}", count)?;
+            }
+        }
+        if let Some(display) = &self.display {
+            writeln!(f, "page_display: {}", string(display, quotes))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Series {
+    pub series: Option<i64>,
+    pub name: Option<String>,
+    /// Acronym of the conference an ACM-style series names, as `CCS '21`
+    pub acronym: Option<String>,
+    /// Year such a series names, of two digits when written so
+    pub year: Option<i64>,
+}
+
+/// Acronym and year of an ACM-style series, `CCS '21` or `PLDI 2023`: a
+/// single word with a capital, then a four-digit year or an apostrophe and
+/// a two-digit one.
+pub fn dated_acronym(s: &str) -> Option<(String, i64)> {
+    let s = s.trim();
+    let digits = s.len() - s.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let (head, year) = s.split_at(s.len() - digits);
+    let head = match digits {
+        2 => head.strip_suffix(['\'', '’'])?,
+        4 => head.strip_suffix(['\'', '’']).unwrap_or(head),
+        _ => return None,
+    };
+    let acronym = head.trim_end();
+    let valid = acronym.starts_with(|c: char| c.is_ascii_alphabetic())
+        && acronym.chars().any(|c| c.is_ascii_uppercase())
+        && acronym
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/&-".contains(c));
+    valid.then(|| (acronym.to_string(), year.parse().unwrap()))
+}
+
+impl From<Option<&String>> for Series {
+    /// A number, or else an ACM-style series kept as written; the other
+    /// names are dropped.
+    fn from(s: Option<&String>) -> Series {
+        let series = s.and_then(|i| i.parse::<i64>().ok());
+        let dated = s
+            .filter(|_| series.is_none())
+            .and_then(|s| dated_acronym(s));
+        Series {
+            series,
+            name: dated.as_ref().and(s.cloned()),
+            acronym: dated.as_ref().map(|(a, _)| a.clone()),
+            year: dated.map(|(_, y)| y),
+        }
+    }
+}
+
+impl Series {
+    /// Series of a standalone entry, which is usually a name ("Lecture Notes
+    /// in Computer Science") rather than a number.
+    pub fn named(s: Option<&String>) -> Series {
+        let mut series = Series::from(s);
+        if series.series.is_none() {
+            series.name = s.cloned();
+        }
+        series
+    }
+
+    /// Whether the year the series names, if any, is `year`.
+    pub fn is_of(&self, year: i64) -> bool {
+        match self.year {
+            Some(y) if y < 100 => year.rem_euclid(100) == y,
+            Some(y) => year == y,
+            None => true,
+        }
+    }
+}
+
+impl ToYaml for Series {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        match (&self.series, &self.name) {
+            (Some(i), _) => writeln!(f, "series: {}", i),
+            (None, Some(n)) => writeln!(f, "series: {}", string(n, quotes)),
+            (None, None) => writeln!(f, "series: "),
+        }
+    }
+}
+
+impl Serialize for Series {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match (&self.series, &self.name) {
+            (Some(i), _) => s.serialize_i64(*i),
+            (None, Some(n)) => s.serialize_str(n),
+            (None, None) => s.serialize_none(),
+        }
+    }
+}
+
+/// Splits a bibtex name list at its `and`s, written in any case, as whole
+/// words and outside braces: `Doe, Jane AND {Food and Drug Administration}`
+/// holds two names.
+pub fn split_names(s: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            c if depth == 0 && c.is_whitespace() => {
+                let rest = &s[i + c.len_utf8()..];
+                let separator = rest.get(..3).is_some_and(|w| w.eq_ignore_ascii_case("and"))
+                    && rest[3..].starts_with(char::is_whitespace);
+                if separator {
+                    names.push(s[start..i].trim());
+                    start = i + c.len_utf8() + 3;
+                    // Skips the `and`, its whitespace being skipped next.
+                    for _ in 0..3 {
+                        chars.next();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    names.push(s[start..].trim());
+    names.retain(|n| !n.is_empty());
+    names
+}
+
+/// Splits a bibtex name list (`Doe, Jane and Smith, Bob`) into display names
+/// (`Jane Doe`, `Bob Smith`). A name within braces is an organization,
+/// written as is without them. Free of I/O, and never panics.
+pub fn parse_name_list(s: &str) -> Vec<String> {
+    split_names(s)
+        .into_iter()
+        .map(|name| {
+            if braced(name) {
+                return name[1..name.len() - 1].trim().to_string();
+            }
+            split_outside_braces(name, |c| c == ',')
+                .into_iter()
+                .rev()
+                .fold("".to_string(), |mut acc, s| {
+                    acc.push(' ');
+                    acc.push_str(s);
+                    acc
+                })
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+pub struct Authors {
+    pub label: &'static str,
+    pub authors: Vec<String>,
+}
+
+/// Family name and given names of a bibtex name, `Doe, Jane` or
+/// `Jane Doe`, lowercased and without braces or periods.
+fn name_parts(name: &str) -> (String, Vec<String>) {
+    let clean = |s: &str| -> String {
+        s.chars()
+            .filter(|c| !matches!(c, '{' | '}' | '.'))
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    let (family, given) = match name.split_once(',') {
+        Some((family, rest)) => (family, rest.rsplit(',').next().unwrap_or_default()),
+        None => {
+            let name = name.trim();
+            match name.rsplit_once(' ') {
+                Some((given, family)) => (family, given),
+                None => (name, ""),
+            }
+        }
+    };
+    let given = given
+        .split([' ', '-', '.'])
+        .map(clean)
+        .filter(|g| !g.is_empty())
+        .collect();
+    (clean(family).trim().to_string(), given)
+}
+
+/// Whether two bibtex names are the same person: same family name, and
+/// first given names which are equal or, when one is an initial, start
+/// alike.
+pub fn same_person(a: &str, b: &str) -> bool {
+    let (family_a, given_a) = name_parts(a);
+    let (family_b, given_b) = name_parts(b);
+    if family_a != family_b {
+        return false;
+    }
+    match (given_a.first(), given_b.first()) {
+        (Some(x), Some(y)) if x.chars().count() > 1 && y.chars().count() > 1 => x == y,
+        (Some(x), Some(y)) => x.chars().next() == y.chars().next(),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// The bibtex name list without the later occurrences of a person listed
+/// twice, with a warning naming the entry.
+fn distinct_names(label: &str, key: &str, s: &str) -> String {
+    let mut kept: Vec<&str> = Vec::new();
+    for name in split_names(s) {
+        match kept.iter().find(|k| same_person(k, name)) {
+            Some(first) => warn(Warning::DuplicateName {
+                key: key.to_string(),
+                field: label.to_string(),
+                first: first.trim().to_string(),
+                name: name.trim().to_string(),
+            }),
+            None => kept.push(name),
+        }
+    }
+    kept.join(" and ")
+}
+
+impl Authors {
+    /// Like `with_label`, dropping the later occurrences of a person listed
+    /// twice, with a warning naming the entry.
+    pub fn deduplicated(label: &'static str, key: &str, s: &str) -> Self {
+        Self::with_label(label, &distinct_names(label, key, s))
+    }
+
+    /// Like `deduplicated`, the given names cut to their initials once the
+    /// people listed twice are dropped.
+    pub fn abbreviated(label: &'static str, key: &str, s: &str) -> Self {
+        Self::with_label(label, &abbreviate_names(&distinct_names(label, key, s)))
+    }
+}
+
+/// Splits `s` at the `separator`s which are not between braces.
+fn split_outside_braces(s: &str, separator: impl Fn(char) -> bool) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            c if depth == 0 && separator(c) => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Initials of a given name, by grapheme: `Jean-Paul` is `J.-P.`, `J.R.R.`
+/// is `J. R. R.`, and a braced first letter is kept whole, as `{\"O}.`.
+fn given_initials(given: &str) -> String {
+    let initial = |part: &str| -> String {
+        let letter = match part.strip_prefix('{') {
+            Some(rest) => match rest.find('}') {
+                Some(end) => &part[..end + 2],
+                None => part,
+            },
+            None => part.graphemes(true).next().unwrap_or_default(),
+        };
+        format!("{}.", letter)
+    };
+    given
+        .split('-')
+        .map(|piece| {
+            piece
+                .split('.')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(initial)
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// A bibtex name with its given names cut to their initials, in the same
+/// form: `Doe, Jane` becomes `Doe, J.` and `Jane van Doe` `J. van Doe`. The
+/// family name, its particles and the suffix are kept, as is a name
+/// without given names.
+pub fn initials(name: &str) -> String {
+    let parts = split_outside_braces(name, |c| c == ',');
+    if let [family @ .., given] = parts.as_slice() {
+        if !family.is_empty() {
+            let given: Vec<String> = split_outside_braces(given, char::is_whitespace)
+                .into_iter()
+                .filter(|w| !w.is_empty())
+                .map(given_initials)
+                .collect();
+            return format!("{}, {}", family.join(","), given.join(" "))
+                .trim_end_matches([',', ' '])
+                .to_string();
+        }
+    }
+    let words: Vec<&str> = split_outside_braces(name.trim(), char::is_whitespace)
+        .into_iter()
+        .filter(|w| !w.is_empty())
+        .collect();
+    let Some((_, first)) = words.split_last() else {
+        return name.to_string();
+    };
+    // The particles start at the first lowercase word, `van` or `de`.
+    let given = first
+        .iter()
+        .position(|w| w.starts_with(char::is_lowercase))
+        .unwrap_or(first.len());
+    words[..given]
+        .iter()
+        .map(|w| given_initials(w))
+        .chain(words[given..].iter().map(|w| w.to_string()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A bibtex name list with the given names of each name cut to their
+/// initials.
+pub fn abbreviate_names(s: &str) -> String {
+    split_names(s)
+        .into_iter()
+        .map(initials)
+        .collect::<Vec<_>>()
+        .join(" and ")
+}
+
+/// Removes the `marker` ending some names of a bibtex name list, `Doe,
+/// Jane*` or `Doe*, Jane`, returning the list without markers and the marked
+/// names.
+pub fn strip_markers(s: &str, marker: char) -> (String, Vec<String>) {
+    let mut marked = Vec::new();
+    let names: Vec<String> = split_names(s)
+        .into_iter()
+        .map(|name| {
+            let (family, rest) = name.split_once(',').unwrap_or((name, ""));
+            let stripped = if let Some(n) = name.trim_end().strip_suffix(marker) {
+                n.to_string()
+            } else if let Some(f) = family.trim_end().strip_suffix(marker) {
+                format!("{},{}", f, rest)
+            } else {
+                return name.to_string();
+            };
+            marked.push(stripped.clone());
+            stripped
+        })
+        .collect();
+    (names.join(" and "), marked)
+}
+
+/// Authors who contributed equally and corresponding authors, flags aligned
+/// with the author list.
+#[derive(Default)]
+pub struct Contributions {
+    pub equal: Vec<bool>,
+    pub corresponding: Vec<bool>,
+}
+
+impl Contributions {
+    /// Flags the `authors` who are among the `marked` names or the names of
+    /// the `corresponding` tag, warning about the corresponding authors who
+    /// are not authors.
+    pub fn of(
+        key: &str,
+        authors: &Authors,
+        marked: &[String],
+        corresponding: Option<&String>,
+    ) -> Contributions {
+        let among = |names: &[&str]| -> Vec<bool> {
+            authors
+                .authors
+                .iter()
+                .map(|a| names.iter().any(|n| same_person(n, a)))
+                .collect()
+        };
+        let corresponding: Vec<&str> = corresponding.map(|c| split_names(c)).unwrap_or_default();
+        for c in &corresponding {
+            if !authors.authors.iter().any(|a| same_person(c, a)) {
+                warn(Warning::invalid(
+                    key,
+                    "corresponding",
+                    format!("corresponding author {} is not an author", c.trim()),
+                ));
+            }
+        }
+        let marked: Vec<&str> = marked.iter().map(String::as_str).collect();
+        Contributions {
+            equal: among(&marked),
+            corresponding: among(&corresponding),
+        }
+    }
+}
+
+impl Display for Contributions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        for (label, flags) in [
+            ("authors_equal_contribution", &self.equal),
+            ("authors_corresponding", &self.corresponding),
+        ] {
+            if flags.contains(&true) {
+                writeln!(f, "{}:", label)?;
+                for flag in flags {
+                    writeln!(f, "  - {}", flag)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Authors {
+    pub fn from_string(s: &str) -> Self {
+        Self::with_label("authors", s)
+    }
+
+    pub fn with_label(label: &'static str, s: &str) -> Self {
+        Authors {
+            label,
+            authors: parse_name_list(s),
+        }
+    }
+}
+
+impl ToYaml for Authors {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        if self.authors.is_empty() {
+            return writeln!(f, "{}: []", self.label);
+        }
+        writeln!(f, "{}:", self.label)?;
+        for a in &self.authors {
+            writeln!(f, "  - {}", string(a, quotes))?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Authors {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        self.authors.serialize(s)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct Volume {
+    pub nb: Option<i64>,
+}
+
+impl From<Option<&String>> for Volume {
+    fn from(s: Option<&String>) -> Self {
+        Volume {
+            nb: s.and_then(|s| s.parse::<i64>().ok()),
+        }
+    }
+}
+
+impl Display for Volume {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "volume: ")?;
+        match self.nb {
+            Some(n) => writeln!(f, "{}", n),
+            None => writeln!(f),
+        }
+    }
+}
+
+/// Issue of a journal volume, from `issue` or, for articles, `number`.
+pub struct Issue {
+    pub issue: Option<String>,
+}
+
+impl ToYaml for Issue {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        match &self.issue {
+            Some(i) if i.parse::<i64>().is_ok() => writeln!(f, "issue: {}", i),
+            Some(i) => writeln!(f, "issue: {}", string(i, quotes)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Serialize for Issue {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match self.issue.as_deref().map(|i| (i, i.parse::<i64>())) {
+            Some((_, Ok(n))) => s.serialize_i64(n),
+            Some((i, Err(_))) => s.serialize_str(i),
+            None => s.serialize_none(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct Doi {
+    pub s: String,
+}
+
+impl From<Option<&String>> for Doi {
+    fn from(s: Option<&String>) -> Doi {
+        Doi {
+            s: s.cloned().unwrap_or_default(),
+        }
+    }
+}
+
+impl ToYaml for Doi {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        writeln!(f, "doi: {}", string(&self.s, quotes))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct Year {
+    pub year: i64,
+    /// Whether the entry gives a numeric year, otherwise `year` is only a
+    /// stand-in
+    #[serde(skip)]
+    pub dated: bool,
+}
+
+impl Year {
+    /// Year of an entry whose year may be textual ("to appear"), in which
+    /// case the current year is used so that the page still sorts sensibly.
+    pub fn lenient(key: &str, s: &str) -> Year {
+        match s.trim().parse::<i64>() {
+            Ok(year) => Year { year, dated: true },
+            Err(_) => {
+                let year = current_year();
+                warn(Warning::invalid(
+                    key,
+                    "year",
+                    format!("year \"{}\" is not a number, using {}", s, year),
+                ));
+                Year { year, dated: false }
+            }
+        }
+    }
+
+    /// Year of an entry giving none, read from the title of its proceedings.
+    pub fn from_booktitle(key: &str, year: i64) -> Year {
+        warn(Warning::missing(
+            key,
+            "year",
+            format!("no year, using {} from the booktitle", year),
+        ));
+        Year { year, dated: true }
+    }
+
+    /// Year of an entry giving none, the current year as for textual ones.
+    pub fn missing(key: &str) -> Year {
+        let year = current_year();
+        warn(Warning::missing(
+            key,
+            "year",
+            format!("no year, using {}", year),
+        ));
+        Year { year, dated: false }
+    }
+
+    /// Heading of the group of the entry when grouping by year.
+    pub fn heading(&self) -> String {
+        if self.dated {
+            self.year.to_string()
+        } else {
+            "Undated".to_string()
+        }
+    }
+}
+
+/// Generation time of the page, only emitted when explicitly requested so
+/// that regenerating unchanged entries gives identical output.
+pub struct Stamp {
+    pub at: Option<String>,
+}
+
+impl ToYaml for Stamp {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        match &self.at {
+            Some(t) => writeln!(f, "generated: {}", string(t, quotes)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Date an online resource was last accessed, from its `urldate`.
+pub struct Accessed {
+    pub at: Option<String>,
+}
+
+impl Accessed {
+    /// Reads the `urldate` leniently, warning when it is not a date.
+    pub fn checked(key: &str, s: Option<&String>) -> Accessed {
+        let at = s.and_then(|s| {
+            let date = parse_lenient_date(s);
+            if date.is_none() {
+                warn(Warning::invalid(
+                    key,
+                    "urldate",
+                    format!("urldate \"{}\" is not a date, leaving it out", s),
+                ));
+            }
+            date
+        });
+        Accessed { at }
+    }
+}
+
+impl ToYaml for Accessed {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        match &self.at {
+            Some(d) => writeln!(f, "accessed: {}", string(d, quotes)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Date of the latest revision of the work, as Hugo's `lastmod`.
+pub struct Updated {
+    pub at: Option<String>,
+}
+
+impl ToYaml for Updated {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        match &self.at {
+            Some(d) => writeln!(f, "lastmod: {}", string(d, quotes)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Publication date, `YYYY-MM-DD`, and the date the work became available
+/// when the entry tells it apart, from its `urldate` or `issue_date`.
+pub struct Dates {
+    pub date: String,
+    pub publish: Option<String>,
+}
+
+impl Dates {
+    /// Dates of an entry of year `year`, the month and day coming from its
+    /// `date`, or from its `month` tag.
+    pub fn of(key: &str, year: &Year, tags: &HashMap<String, String>) -> Dates {
+        let date = tags
+            .get("date")
+            .and_then(|d| parse_date(d))
+            .filter(|d| year.dated && d.starts_with(&format!("{:04}-", year.year)))
+            .unwrap_or_else(|| {
+                let month = tags.get("month").and_then(|m| parse_month(m)).unwrap_or(1);
+                format!("{:04}-{:02}-01", year.year, month)
+            });
+        let publish = ["urldate", "issue_date"]
+            .iter()
+            .find_map(|t| tags.get(*t).map(|v| (t, v)))
+            .and_then(|(t, v)| {
+                let parsed = parse_date(v);
+                if parsed.is_none() {
+                    warn(Warning::invalid(
+                        key,
+                        t,
+                        format!("{} \"{}\" is not a date, ignoring it", t, v),
+                    ));
+                }
+                parsed
+            });
+        Dates { date, publish }
+    }
+}
+
+impl Display for Dates {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        writeln!(f, "date: {}", self.date)?;
+        if let Some(p) = &self.publish {
+            writeln!(f, "publishDate: {}", p)?;
+        }
+        Ok(())
+    }
+}
+
+/// Number of citations of the work and the date it was counted on.
+#[derive(Default)]
+pub struct Citations {
+    pub count: Option<u64>,
+    pub date: Option<String>,
+}
+
+impl ToYaml for Citations {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        if let Some(n) = self.count {
+            writeln!(f, "citation_count: {}", n)?;
+            writeln!(
+                f,
+                "citation_count_date: {}",
+                string(self.date.as_deref().unwrap_or_default(), quotes)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// arXiv identifier of an entry, from `eprint` when the archive is arXiv or
+/// is not given.
+pub fn arxiv_eprint(tags: &HashMap<String, String>) -> Option<String> {
+    let archive = tags.get("archiveprefix").or(tags.get("eprinttype"));
+    match archive {
+        Some(a) if !a.eq_ignore_ascii_case("arxiv") => None,
+        _ => tags.get("eprint").map(|e| e.trim().to_string()),
+    }
+}
+
+/// Publication status, for entries accepted but not yet published.
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PubState {
+    Forthcoming,
+    InPress,
+    Published,
+}
+
+impl PubState {
+    pub fn from_text(s: &str) -> Option<PubState> {
+        let s = s.to_lowercase();
+        if s.contains("in press") || s.contains("inpress") {
+            Some(PubState::InPress)
+        } else if ["to appear", "forthcoming", "accepted"]
+            .iter()
+            .any(|p| s.contains(p))
+        {
+            Some(PubState::Forthcoming)
+        } else {
+            None
+        }
+    }
+
+    /// Looks for the `pubstate` tag, a textual year or a note saying the
+    /// entry is accepted.
+    pub fn detect(tags: &HashMap<String, String>) -> PubState {
+        if let Some(p) = tags.get("pubstate") {
+            return PubState::from_text(p).unwrap_or(PubState::Forthcoming);
+        }
+        tags.get("year")
+            .filter(|y| y.trim().parse::<i64>().is_err())
+            .or(tags.get("note"))
+            .and_then(|s| PubState::from_text(s))
+            .unwrap_or(PubState::Published)
+    }
+}
+
+impl Display for PubState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let state = match self {
+            PubState::Forthcoming => "forthcoming",
+            PubState::InPress => "inpress",
+            PubState::Published => "published",
+        };
+        writeln!(f, "publication_status: {}", state)
+    }
+}
+
+impl Display for Year {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        writeln!(f, "year: {}", self.year)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct Title {
+    pub title: String,
+    /// Whether the entry has no title, the title being made of its key
+    #[serde(skip)]
+    pub missing: bool,
+}
+
+impl From<&String> for Title {
+    fn from(s: &String) -> Title {
+        Title {
+            title: s.clone(),
+            missing: false,
+        }
+    }
+}
+
+impl Title {
+    /// Title of an entry without one, made of its citation key: the words
+    /// up to the year capitalized, the ones after it in parentheses, so
+    /// that `doe2023fast` is named `Doe 2023 (fast)`.
+    pub fn placeholder(key: &str) -> Title {
+        let mut words: Vec<String> = Vec::new();
+        for c in key.chars() {
+            let same_run = words
+                .last()
+                .and_then(|w| w.chars().last())
+                .is_some_and(|l| l.is_alphanumeric() && l.is_ascii_digit() == c.is_ascii_digit());
+            if !c.is_alphanumeric() {
+                words.push(String::new());
+            } else if same_run {
+                words.last_mut().unwrap().push(c);
+            } else {
+                words.push(c.to_string());
+            }
+        }
+        words.retain(|w| !w.is_empty());
+        let year = words
+            .iter()
+            .position(|w| w.len() == 4 && w.chars().all(|c| c.is_ascii_digit()));
+        let (named, rest) = words.split_at(year.map_or(words.len(), |y| y + 1));
+        let mut title: Vec<String> = named
+            .iter()
+            .map(|w| {
+                let mut chars = w.chars();
+                chars
+                    .next()
+                    .into_iter()
+                    .flat_map(char::to_uppercase)
+                    .chain(chars)
+                    .collect()
+            })
+            .collect();
+        if !rest.is_empty() {
+            title.push(format!("({})", rest.join(" ")));
+        }
+        Title {
+            title: match title.is_empty() {
+                true => key.to_string(),
+                false => title.join(" "),
+            },
+            missing: true,
+        }
+    }
+}
+
+impl ToYaml for Title {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        writeln!(f, "title: {}", string(&self.title, quotes))?;
+        if self.missing {
+            writeln!(f, "title_missing: true")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct Abstract {
+    pub abs: String,
+    /// Whether the abstract was cut to the length limits
+    #[serde(skip)]
+    pub truncated: bool,
+}
+
+impl From<&String> for Abstract {
+    fn from(s: &String) -> Abstract {
+        Abstract {
+            abs: s.clone(),
+            truncated: false,
+        }
+    }
+}
+
+impl Abstract {
+    /// The abstract with a backslash before the lines starting with the
+    /// front matter `delimiter`, which the generator would take for the end
+    /// of the front matter; Markdown renders `\---` as `---`.
+    pub fn escaped(&self, delimiter: &str) -> String {
+        self.abs
+            .split('\n')
+            .map(|line| match line.starts_with(delimiter) {
+                true => format!("\\{}", line),
+                false => line.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Display for Abstract {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        writeln!(f, "{}", self.escaped("---"))
+    }
+}
+
+pub enum Place {
+    /// Journal, with the title of the special issue holding the entry
+    Journal(Name, Option<String>),
+    /// Conference, with the edition its proceedings title gives
+    Conference(Name, Location, Option<i64>),
+    /// Whatever the entry of an unknown type appeared in
+    Other(Name),
+}
+
+impl Place {
+    /// Front matter key of the venue block.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Place::Journal(..) => "journal",
+            Place::Conference(..) => "conference",
+            Place::Other(_) => "venue",
+        }
+    }
+
+    /// Name of the journal or conference, as written in the entry.
+    pub fn name(&self) -> &str {
+        match self {
+            Place::Journal(n, _) | Place::Conference(n, ..) | Place::Other(n) => &n.name,
+        }
+    }
+}
+
+impl Serialize for Place {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let mut m = s.serialize_map(None)?;
+        match self {
+            Place::Journal(n, issue_title) => {
+                m.serialize_entry("name", n)?;
+                m.serialize_entry("shortname", n.short.as_deref().unwrap_or_default())?;
+                if let Some(t) = issue_title {
+                    m.serialize_entry("issue_title", t)?;
+                }
+            }
+            Place::Conference(n, l, edition) => {
+                m.serialize_entry("name", n)?;
+                m.serialize_entry("shortname", n.short.as_deref().unwrap_or_default())?;
+                if let Some(e) = edition {
+                    m.serialize_entry("edition", e)?;
+                }
+                if let Some(l) = &l.loc {
+                    m.serialize_entry("location", l)?;
+                }
+            }
+            Place::Other(n) => m.serialize_entry("name", n)?,
+        }
+        m.end()
+    }
+}
+
+impl ToYaml for Place {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        match self {
+            Place::Journal(n, issue_title) => {
+                writeln!(
+                    f,
+                    "journal:\n  name: {}\n  shortname: {}",
+                    string(n, quotes),
+                    string(n.short.as_deref().unwrap_or_default(), quotes)
+                )?;
+                match issue_title {
+                    Some(t) => writeln!(f, "  issue_title: {}", string(t, quotes)),
+                    None => Ok(()),
+                }
+            }
+            Place::Conference(n, l, edition) => {
+                writeln!(
+                    f,
+                    "conference:\n  name: {}\n  shortname: {}",
+                    string(n, quotes),
+                    string(n.short.as_deref().unwrap_or_default(), quotes)
+                )?;
+                if let Some(e) = edition {
+                    writeln!(f, "  edition: {}", e)?;
+                }
+                write!(f, "{}", Yaml(l, quotes))
+            }
+            Place::Other(n) => writeln!(f, "venue:\n  name: {}", string(n, quotes)),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct Location {
+    pub loc: Option<String>,
+}
+
+impl From<Option<&String>> for Location {
+    fn from(s: Option<&String>) -> Location {
+        Location {
+            loc: s.map(String::from),
+        }
+    }
+}
+
+impl ToYaml for Location {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        match &self.loc {
+            Some(l) => writeln!(f, "  location: {}", string(l, quotes)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The conference an `@inproceedings` entry was presented at, for themes
+/// showing where and when it took place. Every part is optional.
+#[derive(Default)]
+pub struct Event {
+    pub name: Option<String>,
+    /// From the `venue`, or else the `address`
+    pub location: Option<String>,
+    /// First and last day, from the biblatex `eventdate`, `2023-05-14/2023-05-20`
+    pub start: Option<String>,
+    pub end: Option<String>,
+}
+
+impl Event {
+    /// The event named `name`, reading the `eventdate` range, possibly open
+    /// or of a single day, and warning when it does not hold dates.
+    pub fn of(key: &str, name: Option<&String>, tags: &HashMap<String, String>) -> Event {
+        let (start, end) = match tags.get("eventdate") {
+            Some(range) => {
+                let (start, end) = range.split_once('/').unwrap_or((range, ""));
+                let (start, end) = (parse_date(start), parse_date(end));
+                if start.is_none() && end.is_none() {
+                    warn(Warning::invalid(
+                        key,
+                        "eventdate",
+                        format!(
+                            "eventdate \"{}\" is not a date range, leaving it out",
+                            range
+                        ),
+                    ));
+                }
+                (start, end)
+            }
+            None => (None, None),
+        };
+        Event {
+            name: name.cloned(),
+            location: tags.get("venue").or(tags.get("address")).cloned(),
+            start,
+            end,
+        }
+    }
+
+    /// Whether nothing is known of the event, so that it is left out.
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.location.is_none() && self.start.is_none() && self.end.is_none()
+    }
+}
+
+impl Serialize for Event {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let mut m = s.serialize_map(None)?;
+        for (label, value) in [
+            ("name", &self.name),
+            ("location", &self.location),
+            ("start", &self.start),
+            ("end", &self.end),
+        ] {
+            if let Some(v) = value {
+                m.serialize_entry(label, v)?;
+            }
+        }
+        m.end()
+    }
+}
+
+impl ToYaml for Event {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        writeln!(f, "event:")?;
+        if let Some(n) = &self.name {
+            writeln!(f, "  name: {}", string(n, quotes))?;
+        }
+        if let Some(l) = &self.location {
+            writeln!(f, "  location: {}", string(l, quotes))?;
+        }
+        if let Some(d) = &self.start {
+            writeln!(f, "  start: {}", d)?;
+        }
+        if let Some(d) = &self.end {
+            writeln!(f, "  end: {}", d)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether a paper of proceedings was presented at a conference or at a
+/// workshop, which some sites list apart.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VenueKind {
+    Conference,
+    Workshop,
+}
+
+impl VenueKind {
+    /// The kind of venue of the entry `key`: its `venue_kind` tag, else its
+    /// kind in `by_key`, else `Workshop` when one of the `keywords` is in
+    /// its `eventtitle` or `booktitle`, or is one of its keywords, compared
+    /// case insensitively.
+    pub fn detect(
+        key: &str,
+        tags: &HashMap<String, String>,
+        keywords: &[String],
+        by_key: &BTreeMap<String, VenueKind>,
+    ) -> VenueKind {
+        let tagged = tags
+            .get("venue_kind")
+            .and_then(|v| match v.trim().to_lowercase().as_str() {
+                "conference" => Some(VenueKind::Conference),
+                "workshop" => Some(VenueKind::Workshop),
+                _ => {
+                    warn(Warning::invalid(
+                        key,
+                        "venue_kind",
+                        format!(
+                            "venue_kind \"{}\" is neither conference nor workshop, ignoring it",
+                            v
+                        ),
+                    ));
+                    None
+                }
+            });
+        if let Some(kind) = tagged.or(by_key.get(key).copied()) {
+            return kind;
+        }
+        let keywords: Vec<String> = keywords.iter().map(|k| k.to_lowercase()).collect();
+        let named = ["eventtitle", "booktitle"]
+            .iter()
+            .filter_map(|t| tags.get(*t))
+            .any(|name| {
+                let name = name.to_lowercase();
+                keywords.iter().any(|k| name.contains(k.as_str()))
+            });
+        let tagged = Keywords::from(tags.get("keywords"))
+            .tags
+            .iter()
+            .any(|t| keywords.contains(&t.to_lowercase()));
+        match named || tagged {
+            true => VenueKind::Workshop,
+            false => VenueKind::Conference,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            VenueKind::Conference => "conference",
+            VenueKind::Workshop => "workshop",
+        }
+    }
+}
+
+impl Display for VenueKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        writeln!(f, "venue_kind: {}", self.name())
+    }
+}
+
+/// Full title of the proceedings, emitted only when the conference is named
+/// after its `eventtitle`.
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct Proceedings {
+    pub title: Option<String>,
+}
+
+impl From<Option<&String>> for Proceedings {
+    fn from(s: Option<&String>) -> Proceedings {
+        Proceedings {
+            title: s.map(String::from),
+        }
+    }
+}
+
+impl ToYaml for Proceedings {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        match &self.title {
+            Some(t) => writeln!(f, "proceedings: {}", string(t, quotes)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct Name {
+    pub name: String,
+    /// Acronym of the venue
+    #[serde(skip)]
+    pub short: Option<String>,
+}
+
+impl From<&String> for Name {
+    fn from(s: &String) -> Name {
+        Name {
+            name: s.clone(),
+            short: None,
+        }
+    }
+}
+
+impl Display for Name {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct Url {
+    pub link: String,
+}
+
+impl From<Option<&String>> for Url {
+    fn from(s: Option<&String>) -> Url {
+        Url {
+            link: s.cloned().unwrap_or_default(),
+        }
+    }
+}
+
+impl Url {
+    /// Normalizes a link given in a .bib file: strips `\url{}` and LaTeX
+    /// escapes, keeps the first of several links and percent-encodes what
+    /// YAML or Hugo would choke on. Values which are not http(s) or ftp URLs
+    /// are warned about and dropped.
+    pub fn checked(key: &str, s: Option<&String>) -> Url {
+        let Some(s) = s else {
+            return Url::from(None);
+        };
+        let s = strip_url_macro(s)
+            .replace("\\_", "_")
+            .replace("\\%", "%")
+            .replace("\\#", "#")
+            .replace("\\&", "&")
+            .replace("\\~", "~");
+        let mut links = s
+            .split(|c: char| c.is_whitespace() || c == ';')
+            .filter(|l| !l.is_empty());
+        let first = links.next().unwrap_or_default();
+        let rest: Vec<&str> = links.collect();
+        let link = match first.strip_prefix("www.") {
+            Some(_) => format!("https://{}", first),
+            None => first.to_string(),
+        };
+        let link = match url::Url::parse(&link) {
+            Ok(u) if ["http", "https", "ftp"].contains(&u.scheme()) && u.has_host() => {
+                if !rest.is_empty() {
+                    warn(Warning::invalid(
+                        key,
+                        "url",
+                        format!("keeping only the first URL, dropping {}", rest.join(" ")),
+                    ));
+                }
+                let mut link = u.to_string();
+                // The parser adds a path to bare hosts; keep them as written.
+                if u.path() == "/" && !first.ends_with('/') && link.ends_with('/') {
+                    link.pop();
+                }
+                link
+            }
+            _ => {
+                warn(Warning::invalid(
+                    key,
+                    "url",
+                    format!("not a URL, ignored: {}", s.trim()),
+                ));
+                String::new()
+            }
+        };
+        Url { link }
+    }
+}
+
+impl ToYaml for Url {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        writeln!(f, "www: {}", string(&self.link, quotes))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct Publisher {
+    pub publi: Option<String>,
+}
+
+impl From<Option<&String>> for Publisher {
+    fn from(s: Option<&String>) -> Publisher {
+        Publisher {
+            publi: s.map(String::from),
+        }
+    }
+}
+
+impl ToYaml for Publisher {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        write!(f, "publisher: ")?;
+        match &self.publi {
+            Some(n) => writeln!(f, "{}", string(n, quotes)),
+            None => writeln!(f),
+        }
+    }
+}
+
+/// Free-form publication note (`howpublished`) which is not a link.
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct HowPublished {
+    pub how: Option<String>,
+}
+
+impl From<Option<&String>> for HowPublished {
+    fn from(s: Option<&String>) -> HowPublished {
+        HowPublished {
+            how: s.map(String::from),
+        }
+    }
+}
+
+impl ToYaml for HowPublished {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        match &self.how {
+            Some(h) => writeln!(f, "howpublished: {}", string(h, quotes)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Removes a surrounding `\url{...}`, as commonly found in `howpublished`.
+pub fn strip_url_macro(s: &str) -> &str {
+    let s = s.trim();
+    s.strip_prefix("\\url{")
+        .and_then(|s| s.strip_suffix('}'))
+        .unwrap_or(s)
+}
+
+pub fn is_link(s: &str) -> bool {
+    ["http://", "https://", "ftp://", "www."]
+        .iter()
+        .any(|p| s.starts_with(p))
+}
+
+/// Number identifying a patent or a standard.
+pub struct DocumentNumber {
+    pub label: Option<&'static str>,
+    pub number: Option<String>,
+}
+
+impl ToYaml for DocumentNumber {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        match (self.label, &self.number) {
+            (Some(l), Some(n)) => writeln!(f, "{}: {}", l, string(n, quotes)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Language and year of the original work, for translations.
+pub struct Original {
+    pub language: Option<String>,
+    pub year: Option<i64>,
+}
+
+impl ToYaml for Original {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        if let Some(l) = &self.language {
+            writeln!(f, "original_language: {}", string(l, quotes))?;
+        }
+        if let Some(y) = self.year {
+            writeln!(f, "original_year: {}", y)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct Isbn {
+    pub isbn: Option<String>,
+}
+
+impl From<Option<&String>> for Isbn {
+    fn from(s: Option<&String>) -> Isbn {
+        Isbn {
+            isbn: s.map(String::from),
+        }
+    }
+}
+
+impl ToYaml for Isbn {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        write!(f, "isbn: ")?;
+        match &self.isbn {
+            Some(n) => writeln!(f, "{}", string(n, quotes)),
+            None => writeln!(f),
+        }
+    }
+}
+
+pub(crate) const ORDINALS: [&str; 10] = [
+    "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth", "tenth",
+];
+
+/// Reads an edition number written as `2`, `2nd` or `Second`.
+pub fn parse_ordinal(s: &str) -> Option<i64> {
+    let word = s.split_whitespace().next()?.to_lowercase();
+    let digits = word.trim_end_matches(|c: char| c.is_ascii_alphabetic() || c == '.');
+    if let Ok(n) = digits.parse::<i64>() {
+        return Some(n);
+    }
+    ORDINALS
+        .iter()
+        .position(|o| *o == word)
+        .map(|i| i as i64 + 1)
+}
+
+pub struct Edition {
+    pub nb: Option<i64>,
+    pub raw: Option<String>,
+}
+
+impl From<Option<&String>> for Edition {
+    fn from(s: Option<&String>) -> Edition {
+        let raw = s.map(|s| s.trim().to_string());
+        Edition {
+            nb: raw.as_deref().and_then(parse_ordinal),
+            raw,
+        }
+    }
+}
+
+impl ToYaml for Edition {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        match (self.nb, &self.raw) {
+            (Some(n), _) => writeln!(f, "edition: {}", n),
+            (None, Some(r)) => writeln!(f, "edition: {}", string(r, quotes)),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+impl Serialize for Edition {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match (self.nb, &self.raw) {
+            (Some(n), _) => s.serialize_i64(n),
+            (None, r) => r.serialize(s),
+        }
+    }
+}
+
+pub struct Chapter {
+    pub chapter: Option<String>,
+}
+
+impl From<Option<&String>> for Chapter {
+    fn from(s: Option<&String>) -> Chapter {
+        Chapter {
+            chapter: s.map(|s| s.trim().to_string()),
+        }
+    }
+}
+
+impl ToYaml for Chapter {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        match &self.chapter {
+            Some(c) if c.parse::<i64>().is_ok() => writeln!(f, "chapter: {}", c),
+            Some(c) => writeln!(f, "chapter: {}", string(c, quotes)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Serialize for Chapter {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match self.chapter.as_deref().map(|c| (c, c.parse::<i64>())) {
+            Some((_, Ok(n))) => s.serialize_i64(n),
+            Some((c, Err(_))) => s.serialize_str(c),
+            None => s.serialize_none(),
+        }
+    }
+}
+
+/// Bodies whose technical reports are standards rather than plain reports.
+const STANDARD_BODIES: [&str; 8] = ["ISO", "IEC", "IEEE", "ANSI", "DIN", "BSI", "CEN", "NIST"];
+
+/// The bibtex entry type (`article`, `book`, ...), lowercased.
+///
+/// Patents and standards are recognized even when they come as plain BibTeX
+/// `@misc` or `@techreport` entries.
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct EntryType {
+    pub kind: String,
+}
+
+impl From<&Bibliography> for EntryType {
+    fn from(b: &Bibliography) -> EntryType {
+        let tags = b.tags();
+        let kind = b.entry_type().to_lowercase();
+        let kind = match kind.as_str() {
+            "misc" if tags.contains_key("number") && tags.contains_key("nationality") => {
+                "patent".to_string()
+            }
+            "techreport"
+                if tags
+                    .get("institution")
+                    .or(tags.get("organization"))
+                    .or(tags.get("number"))
+                    .is_some_and(|i| STANDARD_BODIES.iter().any(|b| i.starts_with(b))) =>
+            {
+                "standard".to_string()
+            }
+            _ => kind,
+        };
+        EntryType { kind }
+    }
+}
+
+/// Entry types converted by their own rules, the others being converted
+/// generically, as articles without a known venue.
+pub const KNOWN_TYPES: [&str; 18] = [
+    "article",
+    "book",
+    "booklet",
+    "conference",
+    "inbook",
+    "incollection",
+    "inproceedings",
+    "manual",
+    "mastersthesis",
+    "misc",
+    "online",
+    "patent",
+    "phdthesis",
+    "proceedings",
+    "standard",
+    "techreport",
+    "thesis",
+    "unpublished",
+];
+
+impl EntryType {
+    /// Books, proceedings, manuals or theses are publications on their own
+    /// rather than parts of a journal or a conference.
+    pub fn is_standalone(&self) -> bool {
+        matches!(
+            self.kind.as_str(),
+            "book"
+                | "proceedings"
+                | "manual"
+                | "misc"
+                | "online"
+                | "patent"
+                | "standard"
+                | "thesis"
+                | "phdthesis"
+                | "mastersthesis"
+        )
+    }
+
+    /// Label of the front matter key holding the document number, for the
+    /// entry types which have one.
+    pub fn number_label(&self) -> Option<&'static str> {
+        match self.kind.as_str() {
+            "patent" => Some("patent_number"),
+            "standard" => Some("standard_number"),
+            _ => None,
+        }
+    }
+
+    /// Entry types for which an edition and a chapter are meaningful.
+    pub fn has_chapters(&self) -> bool {
+        matches!(
+            self.kind.as_str(),
+            "book" | "inbook" | "incollection" | "manual"
+        )
+    }
+
+    /// Whether the type is one of the `KNOWN_TYPES`.
+    pub fn is_known(&self) -> bool {
+        KNOWN_TYPES.contains(&self.kind.as_str())
+    }
+}
+
+impl ToYaml for EntryType {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        writeln!(f, "entry_type: {}", string(&self.kind, quotes))
+    }
+}
+
+/// Short summary of the abstract, shown by list pages instead of a
+/// truncation of the body.
+#[derive(Default)]
+pub struct Teaser {
+    pub text: Option<String>,
+}
+
+impl ToYaml for Teaser {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        match &self.text {
+            Some(t) => writeln!(f, "summary: {}", string(t, quotes)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Length of the abstract, and the time it takes to read it.
+#[derive(Default)]
+pub struct Reading {
+    pub words: Option<usize>,
+    pub minutes: usize,
+}
+
+impl Reading {
+    /// Counts the Unicode words of `text`, read at `wpm` words per minute.
+    /// Nothing is counted for an empty text.
+    pub fn of(text: &str, wpm: usize) -> Reading {
+        let words = text.unicode_words().count();
+        if words == 0 {
+            return Reading::default();
+        }
+        Reading {
+            words: Some(words),
+            minutes: words.div_ceil(wpm.max(1)),
+        }
+    }
+}
+
+impl Display for Reading {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self.words {
+            Some(n) => writeln!(f, "word_count: {}\nreading_time: {}", n, self.minutes),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Characters allowed as is in a URL path, besides ASCII alphanumerics.
+const PATH_CHARS: &str = "-._~!$&'()*+,;=:@";
+
+/// Percent-encodes the characters which may not appear in a URL path
+/// segment.
+pub fn encode_path_segment(s: &str) -> String {
+    s.bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || PATH_CHARS.contains(b as char) {
+                (b as char).to_string()
+            } else {
+                format!("%{:02X}", b)
+            }
+        })
+        .collect()
+}
+
+/// Stable paths redirecting to the page, as Hugo's `aliases`.
+#[derive(Default)]
+pub struct Aliases {
+    pub paths: Vec<String>,
+}
+
+impl Aliases {
+    /// `/doi/<doi>`, the slashes of the DOI kept as path separators.
+    pub fn doi(doi: &str) -> String {
+        let segments: Vec<String> = doi.split('/').map(encode_path_segment).collect();
+        format!("/doi/{}", segments.join("/"))
+    }
+
+    /// `/bib/<key>`.
+    pub fn key(key: &str) -> String {
+        format!("/bib/{}", encode_path_segment(key))
+    }
+}
+
+impl ToYaml for Aliases {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        if self.paths.is_empty() {
+            return Ok(());
+        }
+        writeln!(f, "aliases:")?;
+        for p in &self.paths {
+            writeln!(f, "  - {}", string(p, quotes))?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Aliases {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        self.paths.serialize(s)
+    }
+}
+
+/// Phrases of a note telling that the work was awarded.
+const AWARD_PHRASES: [&str; 4] = [
+    "award",
+    "best paper",
+    "best student paper",
+    "honorable mention",
+];
+
+/// Awards of the work, from its `award` tag, `;` separated, or from the
+/// parts of its `note` naming one.
+#[derive(Default)]
+pub struct Awards {
+    pub names: Vec<String>,
+    /// Whether the page is featured for its awards
+    pub featured: bool,
+}
+
+impl Awards {
+    /// Awards of an `award` tag, or of a `note` when `detect` is set, given
+    /// LaTeX-decoded.
+    pub fn of(award: Option<&str>, note: Option<&str>, detect: bool, feature: bool) -> Awards {
+        let names: Vec<String> = match (award, note) {
+            (Some(a), _) => a
+                .split(';')
+                .map(str::trim)
+                .filter(|a| !a.is_empty())
+                .map(String::from)
+                .collect(),
+            (None, Some(n)) if detect => n
+                .split([';', ',', '.'])
+                .map(str::trim)
+                .filter(|part| {
+                    let part = part.to_lowercase();
+                    AWARD_PHRASES.iter().any(|p| part.contains(p))
+                })
+                .map(String::from)
+                .collect(),
+            _ => Vec::new(),
+        };
+        Awards {
+            featured: feature && !names.is_empty(),
+            names,
+        }
+    }
+}
+
+impl ToYaml for Awards {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        match self.names.as_slice() {
+            [] => {}
+            [award] => writeln!(f, "award: {}", string(award, quotes))?,
+            awards => {
+                writeln!(f, "award:")?;
+                for a in awards {
+                    writeln!(f, "  - {}", string(a, quotes))?;
+                }
+            }
+        }
+        if self.featured {
+            writeln!(f, "featured: true")?;
+        }
+        Ok(())
+    }
+}
+
+/// Keywords of the entry, emitted as taxonomy tags.
+pub struct Keywords {
+    pub tags: Vec<String>,
+}
+
+impl From<Option<&String>> for Keywords {
+    fn from(s: Option<&String>) -> Keywords {
+        Keywords {
+            tags: s
+                .map(|s| {
+                    s.split([',', ';'])
+                        .map(str::trim)
+                        .filter(|k| !k.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl Keywords {
+    /// Keywords of the entry but `skip`, compared case insensitively.
+    pub fn without(s: Option<&String>, skip: &str) -> Keywords {
+        let mut k = Keywords::from(s);
+        k.tags.retain(|t| !t.eq_ignore_ascii_case(skip));
+        k
+    }
+}
+
+impl ToYaml for Keywords {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        if self.tags.is_empty() {
+            return Ok(());
+        }
+        writeln!(f, "tags:")?;
+        for t in &self.tags {
+            writeln!(f, "  - {}", string(t, quotes))?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Keywords {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        self.tags.serialize(s)
+    }
+}
+
+/// Category of each entry type, emitted as the `categories` taxonomy; the
+/// other types are their own category.
+pub const CATEGORIES: [(&str, &str); 13] = [
+    ("article", "journal"),
+    ("book", "book"),
+    ("conference", "conference"),
+    ("inbook", "chapter"),
+    ("incollection", "chapter"),
+    ("inproceedings", "conference"),
+    ("mastersthesis", "thesis"),
+    ("patent", "patent"),
+    ("phdthesis", "thesis"),
+    ("proceedings", "proceedings"),
+    ("techreport", "report"),
+    ("thesis", "thesis"),
+    ("workshop", "workshop"),
+];
+
+/// Category of the entry, from its type.
+#[derive(Default)]
+pub struct Categories {
+    pub names: Vec<String>,
+}
+
+impl Categories {
+    /// The category of `kind` in `overrides`, else in `CATEGORIES`, else
+    /// the type itself. Workshop papers have the `workshop` kind rather
+    /// than their type.
+    pub fn of(kind: &str, overrides: &BTreeMap<String, String>) -> Categories {
+        let name = overrides
+            .get(kind)
+            .map(String::as_str)
+            .or_else(|| CATEGORIES.iter().find(|(t, _)| *t == kind).map(|(_, c)| *c))
+            .unwrap_or(kind);
+        Categories {
+            names: vec![name.to_string()],
+        }
+    }
+}
+
+impl ToYaml for Categories {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        if self.names.is_empty() {
+            return Ok(());
+        }
+        writeln!(f, "categories:")?;
+        for c in &self.names {
+            writeln!(f, "  - {}", string(c, quotes))?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Categories {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        self.names.serialize(s)
+    }
+}
+
+/// The citation key, kept verbatim even when the output file name needs to
+/// be sanitized.
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct BibtexKey {
+    pub key: String,
+}
+
+impl From<&str> for BibtexKey {
+    fn from(s: &str) -> BibtexKey {
+        BibtexKey { key: s.to_string() }
+    }
+}
+
+impl ToYaml for BibtexKey {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        writeln!(f, "bibtex_key: {}", string(&self.key, quotes))
+    }
+}
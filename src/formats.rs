@@ -0,0 +1,185 @@
+//! Output formats rendering the whole bibliography as a single document.
+
+use crate::citation::Style;
+use crate::schema::Document;
+use crate::yaml::{self, string, Quotes};
+use crate::Paper;
+
+/// Renders all the papers as a single YAML document, for Hugo's `data/`
+/// directory: the `schema_version`, the `generator`, and the `papers`, a
+/// list with the same structure as the front matter. The strings are quoted
+/// as `quotes` says.
+pub fn data_yaml(papers: &[Paper], quotes: Quotes) -> Result<String, serde_yaml::Error> {
+    serde_yaml::to_value(Document::new(papers)).map(|doc| yaml::render(&doc, quotes))
+}
+
+/// Renders the papers, in the given order, as a Markdown bulleted list with
+/// one citation per item, the venues in italics, optionally under `## <year>` headings, the
+/// undated entries under `## Undated`.
+pub fn markdown_list(papers: &[Paper], group_by_year: bool) -> String {
+    let mut out = String::new();
+    let mut heading = None;
+    for p in papers {
+        if group_by_year && heading.as_ref() != Some(&p.year.heading()) {
+            if heading.is_some() {
+                out.push('\n');
+            }
+            out.push_str(&format!("## {}\n\n", p.year.heading()));
+            heading = Some(p.year.heading());
+        }
+        out.push_str("- ");
+        out.push_str(&p.styled_citation(Style::Markdown));
+        if let Some((label, link)) = p.link() {
+            out.push_str(&format!(" [{}]({})", label, link));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Anchor of a Markdown heading, as Hugo makes it: lowercase, spaces
+/// turned into hyphens, punctuation dropped.
+fn anchor(heading: &str) -> String {
+    heading
+        .chars()
+        .filter_map(|c| match c {
+            c if c.is_whitespace() => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Renders the papers, in the given order, as a single Markdown document
+/// with a front matter of its own: the papers of each year under a
+/// `# <year>` heading, each in a `## <title>` section holding its citation,
+/// its link and its abstract. The table of contents, if asked for, links
+/// to the years and the papers. Its title is quoted as `quotes` says.
+pub fn document(papers: &[Paper], title: &str, date: &str, toc: bool, quotes: Quotes) -> String {
+    let mut contents = String::new();
+    let mut body = String::new();
+    let mut heading = None;
+    let mut anchors: Vec<String> = Vec::new();
+    // Anchors are numbered from the second heading of the same name on.
+    let mut unique = |heading: &str| {
+        let base = anchor(heading);
+        let n = anchors.iter().filter(|a| **a == base).count();
+        anchors.push(base.clone());
+        match n {
+            0 => base,
+            n => format!("{}-{}", base, n),
+        }
+    };
+    for p in papers {
+        if heading.as_ref() != Some(&p.year.heading()) {
+            let year = p.year.heading();
+            contents.push_str(&format!("- [{}](#{})\n", year, unique(&year)));
+            body.push_str(&format!("# {}\n\n", year));
+            heading = Some(year);
+        }
+        let name = p.title.title.trim_end_matches('.');
+        contents.push_str(&format!("  - [{}](#{})\n", name, unique(name)));
+        body.push_str(&format!(
+            "## {}\n\n{}\n\n",
+            name,
+            p.styled_citation(Style::Markdown)
+        ));
+        if let Some((label, link)) = p.link() {
+            body.push_str(&format!("[{}]({})\n\n", label, link));
+        }
+        let abs = p.abs.abs.trim();
+        if !abs.is_empty() {
+            body.push_str(abs);
+            body.push_str("\n\n");
+        }
+    }
+    let mut out = format!(
+        "---\ntitle: {}\ndate: {}\n---\n\n",
+        string(title, quotes),
+        date
+    );
+    if toc && !contents.is_empty() {
+        out.push_str(&contents);
+        out.push('\n');
+    }
+    out.push_str(body.trim_end());
+    out.push('\n');
+    out
+}
+
+/// Escapes text for use in HTML content and attribute values.
+pub fn html_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn html_item(p: &Paper) -> String {
+    let mut li = format!(
+        "  <li data-key=\"{}\" data-year=\"{}\" data-type=\"{}\">\n    <span class=\"citation\">{}</span>\n",
+        html_escape(&p.key.key),
+        p.year.year,
+        html_escape(&p.kind.kind),
+        p.styled_citation(Style::Html)
+    );
+    if !p.doi.s.is_empty() {
+        let doi = format!("https://doi.org/{}", p.doi.s);
+        li.push_str(&format!("    <a href=\"{}\">doi</a>\n", html_escape(&doi)));
+    }
+    if !p.url.link.is_empty() {
+        li.push_str(&format!(
+            "    <a href=\"{}\">url</a>\n",
+            html_escape(&p.url.link)
+        ));
+    }
+    let abs = p.abs.abs.trim();
+    if !abs.is_empty() {
+        li.push_str(&format!(
+            "    <p class=\"abstract\">{}</p>\n",
+            html_escape(abs)
+        ));
+    }
+    li.push_str("  </li>\n");
+    li
+}
+
+/// Renders the papers, in the given order, as an HTML ordered list, with one
+/// `<ol>` per year under `<h2>` headings when grouping by year. A standalone
+/// document wraps the fragment in a complete HTML page.
+pub fn html(papers: &[Paper], group_by_year: bool, standalone: bool) -> String {
+    let mut out = String::new();
+    if standalone {
+        out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Bibliography</title>\n</head>\n<body>\n");
+    }
+    let mut heading = None;
+    for p in papers {
+        if heading.is_none() || (group_by_year && heading.as_ref() != Some(&p.year.heading())) {
+            if heading.is_some() {
+                out.push_str("</ol>\n");
+            }
+            if group_by_year {
+                out.push_str(&format!("<h2>{}</h2>\n", p.year.heading()));
+            }
+            out.push_str("<ol class=\"bibliography\">\n");
+            heading = Some(p.year.heading());
+        }
+        out.push_str(&html_item(p));
+    }
+    if heading.is_some() {
+        out.push_str("</ol>\n");
+    }
+    if standalone {
+        out.push_str("</body>\n</html>\n");
+    }
+    out
+}
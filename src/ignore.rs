@@ -0,0 +1,142 @@
+//! Entries never to convert, listed in an ignore file, for the internal
+//! documents of a shared bibliography.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use nom_bibtex::Bibliography;
+use serde::Deserialize;
+
+use crate::provenance;
+
+/// Name of the ignore file looked for next to the input files.
+pub const FILE_NAME: &str = ".newpageignore";
+
+/// Citation keys, key patterns and DOI prefixes of the entries to ignore.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct IgnoreList {
+    pub keys: Vec<String>,
+    /// Key patterns, `*` standing for any text and `?` for any character
+    pub patterns: Vec<String>,
+    /// DOI prefixes, lowercased, as `10.5555/memo`
+    pub doi_prefixes: Vec<String>,
+    /// Lists applying only to the entries of one input file
+    #[serde(skip)]
+    pub scoped: Vec<Scoped>,
+}
+
+/// The ignore list of an input file, with the citation keys of its entries.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Scoped {
+    pub keys: HashSet<String>,
+    pub list: IgnoreList,
+}
+
+impl IgnoreList {
+    /// Reads an ignore file, one entry per line: a DOI prefix when it starts
+    /// with `10.` or `doi:`, a pattern when it has a `*` or a `?`, a key
+    /// otherwise. Blank lines and the text after a `#` are skipped.
+    pub fn parse(content: &str) -> IgnoreList {
+        let mut list = IgnoreList::default();
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let lower = line.to_lowercase();
+            if let Some(doi) = lower.strip_prefix("doi:") {
+                list.doi_prefixes.push(doi.trim().to_string());
+            } else if lower.starts_with("10.") {
+                list.doi_prefixes.push(lower);
+            } else if line.contains(['*', '?']) {
+                list.patterns.push(line.to_string());
+            } else {
+                list.keys.push(line.to_string());
+            }
+        }
+        list
+    }
+
+    /// Reads the ignore file at `path`. Errors mention the path.
+    pub fn load(path: &Path) -> Result<IgnoreList, String> {
+        std::fs::read_to_string(path)
+            .map(|content| IgnoreList::parse(&content))
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Adds the entries of `other`.
+    pub fn extend(&mut self, other: IgnoreList) {
+        self.keys.extend(other.keys);
+        self.patterns.extend(other.patterns);
+        self.doi_prefixes.extend(other.doi_prefixes);
+        self.scoped.extend(other.scoped);
+    }
+
+    /// Adds `list`, applying only to the entries of `content`, a bibtex
+    /// file, as the ignore file next to an input applies to it alone.
+    pub fn scope(&mut self, content: &str, list: IgnoreList) {
+        let keys = provenance::citation_keys(content).into_iter().collect();
+        self.scoped.push(Scoped { keys, list });
+    }
+
+    /// Why the entry is ignored, if it is.
+    pub fn reason(&self, b: &Bibliography) -> Option<String> {
+        self.listed(b).or_else(|| {
+            self.scoped
+                .iter()
+                .filter(|s| s.keys.contains(b.citation_key()))
+                .find_map(|s| s.list.reason(b))
+        })
+    }
+
+    /// Why the entry is ignored by the entries of this list itself.
+    fn listed(&self, b: &Bibliography) -> Option<String> {
+        let key = b.citation_key();
+        if self.keys.iter().any(|k| k == key) {
+            return Some("key is in the ignore file".to_string());
+        }
+        if let Some(p) = self.patterns.iter().find(|p| matches(p, key)) {
+            return Some(format!("key matches {} in the ignore file", p));
+        }
+        let doi = b.tags().get("doi")?.trim().to_lowercase();
+        self.doi_prefixes
+            .iter()
+            .find(|p| doi.starts_with(p.as_str()))
+            .map(|p| format!("DOI starts with {} in the ignore file", p))
+    }
+}
+
+/// The ignore file next to `input`, if there is one.
+pub fn discover(input: &Path) -> Option<PathBuf> {
+    let path = input.parent()?.join(FILE_NAME);
+    path.is_file().then_some(path)
+}
+
+/// Whether `key` matches `pattern`, `*` standing for any text and `?` for
+/// any character.
+pub(crate) fn matches(pattern: &str, key: &str) -> bool {
+    let (pattern, key): (Vec<char>, Vec<char>) = (pattern.chars().collect(), key.chars().collect());
+    // Positions in the pattern and the key after the last `*`, to backtrack.
+    let (mut p, mut k, mut star) = (0, 0, None);
+    while k < key.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, k));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == key[k] => {
+                p += 1;
+                k += 1;
+            }
+            _ => match star {
+                Some((sp, sk)) => {
+                    (p, k) = (sp + 1, sk + 1);
+                    star = Some((sp, sk + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
@@ -0,0 +1,370 @@
+//! Reading references given as RIS or CSL-JSON, by rewriting them as
+//! BibTeX entries which then take the usual conversion path. What a format
+//! cannot express is simply left out of the entries.
+
+use std::path::Path;
+
+use serde_json::Value;
+
+/// Format of an input file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InputFormat {
+    Bibtex,
+    /// An array of CSL-JSON items, as exported by Zotero or citeproc tools
+    CslJson,
+    /// RIS records, `TY  - ` to `ER  - `
+    Ris,
+}
+
+/// Length of the start of a file looked at to tell its format, in bytes.
+pub const SNIFF_LEN: usize = 4096;
+
+/// The first `SNIFF_LEN` bytes of `content`, or fewer to end on a
+/// character, without byte order mark or leading whitespace.
+fn start(content: &str) -> &str {
+    let end = (0..=SNIFF_LEN.min(content.len()))
+        .rev()
+        .find(|i| content.is_char_boundary(*i))
+        .unwrap_or(0);
+    content[..end].trim_start_matches('\u{feff}').trim_start()
+}
+
+/// Why `content` is not BibTeX, for the files mistaken for bibliographies:
+/// the `.bbl` LaTeX compiles from them, and EndNote XML exports.
+fn not_bibtex(content: &str) -> Option<&'static str> {
+    let start = start(content);
+    if start.starts_with("<?xml") || start.starts_with("<xml") {
+        Some(
+            "this looks like an EndNote XML file; new_page needs BibTeX source \
+             (or export RIS from EndNote and use --input-format ris)",
+        )
+    } else if (start.contains("\\bibitem") || start.contains("\\begin{thebibliography}"))
+        && !start.lines().any(|l| l.trim_start().starts_with('@'))
+    {
+        Some(
+            "this looks like a LaTeX .bbl file; new_page needs BibTeX source \
+             (the .bib file it was compiled from)",
+        )
+    } else {
+        None
+    }
+}
+
+impl InputFormat {
+    /// Format of the file at `path`, from the start of its content, RIS
+    /// records or JSON, or else from its extension.
+    pub fn detect(path: &Path, content: &str) -> InputFormat {
+        let start = start(content);
+        if start.starts_with('[') || start.starts_with('{') {
+            return InputFormat::CslJson;
+        }
+        if start.lines().any(|l| l.starts_with("TY  -")) {
+            return InputFormat::Ris;
+        }
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(e) if e.eq_ignore_ascii_case("json") => InputFormat::CslJson,
+            Some(e) if e.eq_ignore_ascii_case("ris") => InputFormat::Ris,
+            _ => InputFormat::Bibtex,
+        }
+    }
+
+    /// The content of a file of this format as BibTeX entries. BibTeX
+    /// content which turns out to be a `.bbl` or an EndNote XML file is an
+    /// error saying so.
+    pub fn to_bibtex(self, content: &str) -> Result<String, String> {
+        match self {
+            InputFormat::Bibtex => match not_bibtex(content) {
+                Some(reason) => Err(reason.to_string()),
+                None => Ok(content.to_string()),
+            },
+            InputFormat::CslJson => csl_to_bibtex(content),
+            InputFormat::Ris => Ok(ris_to_bibtex(content)),
+        }
+    }
+}
+
+/// A single BibTeX entry, as an editor selects it: possibly surrounded by
+/// blank lines and followed by a comma. Anything else than one entry is an
+/// error, on a single line.
+pub fn entry_fragment(fragment: &str) -> Result<String, String> {
+    let entry = fragment.trim().trim_end_matches(',').trim_end();
+    if !entry.starts_with('@') {
+        return Err("expected a single entry starting with @".to_string());
+    }
+    let parsed = nom_bibtex::Bibtex::raw_parse(entry).map_err(|_| "malformed entry".to_string())?;
+    match parsed.as_slice() {
+        [nom_bibtex::Entry::Bibliography(..)] => Ok(entry.to_string()),
+        [_] => Err("expected a bibliography entry".to_string()),
+        entries => Err(format!("expected a single entry, found {}", entries.len())),
+    }
+}
+
+/// A BibTeX entry being assembled.
+struct Entry {
+    kind: &'static str,
+    key: Option<String>,
+    fields: Vec<(&'static str, String)>,
+    authors: Vec<String>,
+    editors: Vec<String>,
+}
+
+impl Entry {
+    fn new(kind: &'static str) -> Entry {
+        Entry {
+            kind,
+            key: None,
+            fields: Vec::new(),
+            authors: Vec::new(),
+            editors: Vec::new(),
+        }
+    }
+
+    /// Sets `name`, unless it is already set or `value` is blank.
+    fn set(&mut self, name: &'static str, value: &str) {
+        let value = value.trim();
+        if !value.is_empty() && !self.fields.iter().any(|(n, _)| *n == name) {
+            self.fields.push((name, value.to_string()));
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The entry in BibTeX, keyed `key` when it has no key of its own.
+    fn to_bibtex(&self, key: &str) -> String {
+        let mut out = format!("@{}{{{},\n", self.kind, self.key.as_deref().unwrap_or(key));
+        let names = [("author", &self.authors), ("editor", &self.editors)];
+        for (name, list) in names.iter().filter(|(_, l)| !l.is_empty()) {
+            out.push_str(&format!(
+                "  {} = {{{}}},\n",
+                name,
+                escape(&list.join(" and "))
+            ));
+        }
+        for (name, value) in &self.fields {
+            out.push_str(&format!("  {} = {{{}}},\n", name, escape(value)));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Key made of the family name of the first author and the year, as
+    /// `doe2021`.
+    fn generated_key(&self) -> String {
+        let family = self
+            .authors
+            .first()
+            .and_then(|a| a.split(',').next())
+            .unwrap_or("anonymous");
+        let family: String = family
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect();
+        format!(
+            "{}{}",
+            family.to_lowercase(),
+            self.get("year").unwrap_or_default()
+        )
+    }
+}
+
+/// Drops the braces and backslashes, which BibTeX would take for groups
+/// and macros; the other characters are kept as they are.
+fn escape(value: &str) -> String {
+    value.replace(['{', '}', '\\'], "")
+}
+
+/// Writes the entries, giving keys to the ones without, distinct from the
+/// others.
+fn write(entries: &[Entry]) -> String {
+    let mut used: Vec<String> = entries.iter().filter_map(|e| e.key.clone()).collect();
+    let mut out = String::new();
+    for e in entries {
+        let mut key = e.generated_key();
+        if e.key.is_none() {
+            let base = key.clone();
+            let mut suffix = b'a';
+            while used.contains(&key) {
+                suffix += 1;
+                key = format!("{}{}", base, suffix as char);
+            }
+            used.push(key.clone());
+        }
+        out.push_str(&e.to_bibtex(&key));
+        out.push('\n');
+    }
+    out
+}
+
+/// The first four digit year of `s`.
+fn year(s: &str) -> Option<String> {
+    s.split(|c: char| !c.is_ascii_digit())
+        .find(|part| part.len() == 4)
+        .map(str::to_string)
+}
+
+/// BibTeX entry type of a RIS reference type.
+fn ris_type(ty: &str) -> &'static str {
+    match ty {
+        "JOUR" | "JFULL" | "MGZN" | "NEWS" => "article",
+        "CONF" | "CPAPER" => "inproceedings",
+        "BOOK" | "EBOOK" => "book",
+        "CHAP" | "ECHAP" => "incollection",
+        "THES" => "phdthesis",
+        "RPRT" => "techreport",
+        "UNPB" => "unpublished",
+        _ => "misc",
+    }
+}
+
+/// RIS records as BibTeX entries: `TY` gives the type, `AU`/`A1` the
+/// authors, `ED`/`A2` the editors, `TI`/`T1` the title, `T2`/`JO`/`JF`/`BT`
+/// the journal or book, `PY`/`Y1`/`DA` the year, `SP`/`EP` the pages, `VL`,
+/// `IS`, `DO`, `UR`, `AB`/`N2`, `PB`, `KW` and `ID` the rest.
+pub fn ris_to_bibtex(content: &str) -> String {
+    let mut entries = Vec::new();
+    let mut entry: Option<Entry> = None;
+    let (mut start_page, mut end_page) = (None, None);
+    let mut keywords = Vec::new();
+    for line in content.lines() {
+        let Some((tag, value)) = line
+            .split_once("  -")
+            .filter(|(tag, _)| tag.len() == 2)
+            .map(|(tag, value)| (tag, value.trim()))
+        else {
+            continue;
+        };
+        if tag == "TY" {
+            entry = Some(Entry::new(ris_type(value)));
+            continue;
+        }
+        let Some(e) = entry.as_mut() else {
+            continue;
+        };
+        match tag {
+            "AU" | "A1" => e.authors.push(value.to_string()),
+            "ED" | "A2" => e.editors.push(value.to_string()),
+            "TI" | "T1" => e.set("title", value),
+            "T2" | "JO" | "JF" | "BT" if e.kind == "article" => e.set("journal", value),
+            "T2" | "BT" => e.set("booktitle", value),
+            "PY" | "Y1" | "DA" => e.set("year", &year(value).unwrap_or_default()),
+            "SP" => start_page = Some(value.to_string()),
+            "EP" => end_page = Some(value.to_string()),
+            "VL" => e.set("volume", value),
+            "IS" => e.set("number", value),
+            "DO" => e.set("doi", value),
+            "UR" => e.set("url", value),
+            "AB" | "N2" => e.set("abstract", value),
+            "PB" => e.set("publisher", value),
+            "KW" => keywords.push(value.to_string()),
+            "ID" if !value.is_empty() => e.key = Some(value.to_string()),
+            "ER" => {
+                let mut e = entry.take().unwrap_or_else(|| Entry::new("misc"));
+                match (start_page.take(), end_page.take()) {
+                    (Some(s), Some(end)) => e.set("pages", &format!("{}--{}", s, end)),
+                    (Some(s), None) => e.set("pages", &s),
+                    _ => {}
+                }
+                e.set("keywords", &keywords.join(", "));
+                keywords.clear();
+                entries.push(e);
+            }
+            _ => {}
+        }
+    }
+    write(&entries)
+}
+
+/// BibTeX entry type of a CSL item type.
+fn csl_type(ty: &str) -> &'static str {
+    match ty {
+        "article-journal" | "article-magazine" | "article-newspaper" | "article" => "article",
+        "paper-conference" => "inproceedings",
+        "book" => "book",
+        "chapter" => "incollection",
+        "thesis" => "phdthesis",
+        "report" => "techreport",
+        "manuscript" => "unpublished",
+        _ => "misc",
+    }
+}
+
+/// Text of a CSL value, numbers included.
+fn csl_text(v: &Value) -> Option<String> {
+    match v {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// CSL names, `family, given`, or their literal form.
+fn csl_names(v: &Value) -> Vec<String> {
+    let Some(names) = v.as_array() else {
+        return Vec::new();
+    };
+    names
+        .iter()
+        .filter_map(|n| match (n["family"].as_str(), n["given"].as_str()) {
+            (Some(family), Some(given)) => Some(format!("{}, {}", family, given)),
+            (Some(family), None) => Some(family.to_string()),
+            _ => n["literal"].as_str().map(str::to_string),
+        })
+        .collect()
+}
+
+/// CSL-JSON items, an array or a single object, as BibTeX entries. The
+/// `container-title` is the journal of articles and the book of the other
+/// types; `issued` gives the year and month.
+pub fn csl_to_bibtex(content: &str) -> Result<String, String> {
+    let items = match serde_json::from_str(content).map_err(|e| e.to_string())? {
+        Value::Array(items) => items,
+        item @ Value::Object(_) => vec![item],
+        _ => return Err("expected an array of CSL items".to_string()),
+    };
+    let mut entries = Vec::new();
+    for item in &items {
+        let mut e = Entry::new(csl_type(item["type"].as_str().unwrap_or_default()));
+        e.key = csl_text(&item["id"]).filter(|k| !k.is_empty());
+        e.authors = csl_names(&item["author"]);
+        e.editors = csl_names(&item["editor"]);
+        let container = if e.kind == "article" {
+            "journal"
+        } else {
+            "booktitle"
+        };
+        let fields = [
+            ("title", "title"),
+            ("container-title", container),
+            ("volume", "volume"),
+            ("issue", "number"),
+            ("DOI", "doi"),
+            ("URL", "url"),
+            ("abstract", "abstract"),
+            ("publisher", "publisher"),
+            ("keyword", "keywords"),
+            ("ISBN", "isbn"),
+        ];
+        for (csl, bibtex) in fields {
+            if let Some(value) = csl_text(&item[csl]) {
+                e.set(bibtex, &value);
+            }
+        }
+        if let Some(pages) = csl_text(&item["page"]) {
+            e.set("pages", &pages.replacen('-', "--", 1));
+        }
+        let date = &item["issued"]["date-parts"][0];
+        if let Some(year) = csl_text(&date[0]) {
+            e.set("year", &year);
+        }
+        if let Some(month) = date[1].as_u64() {
+            e.set("month", &month.to_string());
+        }
+        entries.push(e);
+    }
+    Ok(write(&entries))
+}
@@ -0,0 +1,125 @@
+//! The successive representations of a single entry, from the tags as
+//! written to the rendered page, to see where a page comes out wrong.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Error, Formatter};
+
+use nom_bibtex::model::StringValueType;
+use nom_bibtex::{Bibtex, Entry};
+use serde::Serialize;
+
+use crate::merge::MergeOn;
+use crate::provenance::Provenance;
+use crate::{parse_merged, recover, Options, Paper, Summary};
+
+/// An entry, as written, as converted and as rendered.
+#[derive(Debug, Serialize)]
+pub struct Inspection {
+    pub key: String,
+    pub entry_type: String,
+    /// Tags as written in each entry of the key, overrides included, in
+    /// order: names unchanged, braced strings and macros joined with `#`
+    pub raw: Vec<Vec<(String, String)>>,
+    /// Tags the page is made of: names lowercased, string macros expanded,
+    /// overrides merged
+    pub tags: BTreeMap<String, String>,
+    /// Values of the page fields, as in the structured outputs
+    pub fields: serde_json::Value,
+    /// Tags and origins of the values of the fields
+    pub provenance: Provenance,
+    /// The page
+    pub rendered: String,
+}
+
+impl Display for Inspection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        writeln!(f, "== Raw tags of @{}{{{}}} ==", self.entry_type, self.key)?;
+        for (i, tags) in self.raw.iter().enumerate() {
+            if i > 0 {
+                writeln!(f, "-- override --")?;
+            }
+            for (name, value) in tags {
+                writeln!(f, "{} = {}", name, value)?;
+            }
+        }
+        writeln!(f, "\n== Normalized tags ==")?;
+        for (name, value) in &self.tags {
+            writeln!(f, "{} = {}", name, value)?;
+        }
+        writeln!(f, "\n== Fields ==")?;
+        if let serde_json::Value::Object(fields) = &self.fields {
+            for (name, value) in fields {
+                writeln!(f, "{}: {}", name, value)?;
+            }
+        }
+        writeln!(f, "\n== Provenance ==")?;
+        for (field, source) in self.provenance.iter() {
+            writeln!(f, "{}: {}", field, source)?;
+        }
+        writeln!(f, "\n== Rendered ==")?;
+        write!(f, "{}", self.rendered)
+    }
+}
+
+/// A value as written: braced strings and macro names joined with `#`.
+fn raw_value(value: &[StringValueType]) -> String {
+    value
+        .iter()
+        .map(|v| match v {
+            StringValueType::Str(s) => format!("{{{}}}", s),
+            StringValueType::Abbreviation(a) => a.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(" # ")
+}
+
+/// Tags of the entries of `input` whose key is `key`, as written.
+fn raw_tags(input: &str, key: &str, opts: &Options) -> Vec<Vec<(String, String)>> {
+    let input = input.replace("\r\n", "\n");
+    let entries = match Bibtex::raw_parse(&input) {
+        Err(_) if opts.recover => Bibtex::raw_parse(&recover::recover(&input).0),
+        entries => entries,
+    };
+    entries
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|e| match e {
+            Entry::Bibliography(_, k, tags) if k == key => Some(
+                tags.iter()
+                    .map(|t| (t.key.clone(), raw_value(&t.value)))
+                    .collect(),
+            ),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The entry `key` of `input`, merged with `overrides` as
+/// `convert_with_overrides` does, and converted whether or not the options
+/// would leave it out. `None` when there is no such entry.
+pub fn inspect(
+    input: &str,
+    overrides: &str,
+    on: MergeOn,
+    key: &str,
+    opts: &Options,
+) -> Result<Option<Inspection>, crate::Error> {
+    let (entries, macros, origins) =
+        parse_merged(input, overrides, on, opts, &mut Summary::default())?;
+    let Some(b) = entries.iter().find(|b| b.citation_key() == key) else {
+        return Ok(None);
+    };
+    let mut paper = Paper::new(b, opts, &macros)?;
+    paper.provenance.locate(key, &origins);
+    let mut raw = raw_tags(input, key, opts);
+    raw.extend(raw_tags(overrides, key, opts));
+    Ok(Some(Inspection {
+        key: key.to_string(),
+        entry_type: b.entry_type().to_string(),
+        raw,
+        tags: b.tags().clone().into_iter().collect(),
+        fields: serde_json::to_value(&paper).unwrap_or_default(),
+        provenance: paper.provenance.clone(),
+        rendered: paper.render_with(opts),
+    }))
+}
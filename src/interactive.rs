@@ -0,0 +1,53 @@
+//! Picking the entries to convert from a searchable list.
+
+use std::io::{self, IsTerminal};
+
+use inquire::MultiSelect;
+
+use crate::Paper;
+
+/// Line describing a paper in the selection list, `key — year — title`,
+/// marked when its page has already been generated.
+pub fn choice_label(paper: &Paper, generated: bool) -> String {
+    format!(
+        "{} — {} — {}{}",
+        paper.key.key,
+        paper.year.year,
+        paper.title.title,
+        if generated { " [generated]" } else { "" }
+    )
+}
+
+/// Fails when the standard input or output is not a terminal, the selection
+/// having no one to answer it.
+pub fn check_terminal() -> io::Result<()> {
+    if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+        return Err(io::Error::other("--interactive needs a terminal"));
+    }
+    Ok(())
+}
+
+/// Asks which papers to convert and keeps only the chosen ones. `generated`
+/// tells which papers already have a page. Fails when the standard input or
+/// output is not a terminal rather than waiting for an answer.
+pub fn select(papers: Vec<Paper>, generated: &[bool]) -> io::Result<Vec<Paper>> {
+    check_terminal()?;
+    let labels: Vec<String> = papers
+        .iter()
+        .enumerate()
+        .map(|(i, p)| choice_label(p, generated.get(i).copied().unwrap_or(false)))
+        .collect();
+    let chosen = MultiSelect::new("Entries to convert:", labels)
+        .with_page_size(15)
+        .raw_prompt()
+        .map_err(io::Error::other)?;
+    let mut keep = vec![false; papers.len()];
+    for c in chosen {
+        keep[c.index] = true;
+    }
+    Ok(papers
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(p, k)| k.then_some(p))
+        .collect())
+}
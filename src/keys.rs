@@ -0,0 +1,162 @@
+//! Regenerating citation keys from a pattern, for files whose keys are
+//! meaningless or repeated.
+
+use std::fmt::{Display, Error, Formatter};
+
+use crate::text::transliterate;
+use crate::Paper;
+
+/// Title words skipped when looking for the first significant one.
+const STOP_WORDS: [&str; 18] = [
+    "a", "an", "the", "on", "of", "for", "in", "and", "to", "with", "from", "by", "at", "via",
+    "is", "are", "towards", "toward",
+];
+
+/// Part of a key pattern.
+#[derive(Clone, Debug, PartialEq)]
+enum Part {
+    Text(String),
+    /// Family name of the first author, lowercased
+    Author,
+    Year,
+    /// First significant title word, lowercased
+    Word,
+}
+
+/// Pattern of the keys, such as `{author}{year}{word}`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyPattern {
+    parts: Vec<Part>,
+}
+
+impl KeyPattern {
+    /// Reads a pattern made of text and of the `{author}`, `{year}` and
+    /// `{word}` placeholders.
+    pub fn parse(pattern: &str) -> Result<KeyPattern, String> {
+        let mut parts = Vec::new();
+        let mut rest = pattern;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                parts.push(Part::Text(rest[..start].to_string()));
+            }
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| format!("unclosed placeholder in {}", pattern))?;
+            parts.push(match &rest[start + 1..start + end] {
+                "author" => Part::Author,
+                "year" => Part::Year,
+                "word" => Part::Word,
+                other => {
+                    return Err(format!(
+                        "unknown placeholder {{{}}}, expected {{author}}, {{year}} or {{word}}",
+                        other
+                    ))
+                }
+            });
+            rest = &rest[start + end + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Text(rest.to_string()));
+        }
+        if parts.is_empty() {
+            return Err("the key pattern is empty".to_string());
+        }
+        Ok(KeyPattern { parts })
+    }
+
+    /// Key of `paper`, before making it unique.
+    pub fn key(&self, paper: &Paper) -> String {
+        let ascii_lower = |s: &str| -> String {
+            transliterate(s)
+                .chars()
+                .filter(char::is_ascii_alphanumeric)
+                .flat_map(|c| c.to_lowercase())
+                .collect()
+        };
+        let mut key = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(t) => key.push_str(t),
+                Part::Author => {
+                    let family = paper
+                        .auth
+                        .authors
+                        .first()
+                        .and_then(|a| a.rsplit(' ').next())
+                        .unwrap_or("anonymous");
+                    key.push_str(&ascii_lower(family));
+                }
+                Part::Year if paper.year.dated => key.push_str(&paper.year.year.to_string()),
+                Part::Year => {}
+                Part::Word => {
+                    let word = paper
+                        .title
+                        .title
+                        .split(|c: char| !c.is_alphanumeric())
+                        .map(ascii_lower)
+                        .find(|w| !w.is_empty() && !STOP_WORDS.contains(&w.as_str()));
+                    key.push_str(&word.unwrap_or_default());
+                }
+            }
+        }
+        key
+    }
+}
+
+/// Old and new keys of the entries whose keys were regenerated.
+#[derive(Debug, Default, PartialEq)]
+pub struct KeyMap {
+    pub keys: Vec<(String, String)>,
+}
+
+/// `old -> new`, one entry per line.
+impl Display for KeyMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        for (old, new) in &self.keys {
+            writeln!(f, "{} -> {}", old, new)?;
+        }
+        Ok(())
+    }
+}
+
+/// Letter suffix number `n`, from 0: `a`, ..., `z`, `aa`, `ab`...
+fn suffix(mut n: usize) -> String {
+    let mut s = String::new();
+    loop {
+        s.insert(0, (b'a' + (n % 26) as u8) as char);
+        if n < 26 {
+            return s;
+        }
+        n = n / 26 - 1;
+    }
+}
+
+/// Gives the papers keys following `pattern`, suffixed with letters to keep
+/// them unique. The pages of an entry in several languages keep sharing
+/// their key.
+pub fn regenerate(papers: &mut [Paper], pattern: &KeyPattern) -> KeyMap {
+    let mut map = KeyMap::default();
+    let mut used: Vec<String> = Vec::new();
+    // Old key, language of the first page and new key of the last entry.
+    let mut last: Option<(String, Option<String>, String)> = None;
+    for p in papers.iter_mut() {
+        if let Some((old, lang, new)) = &last {
+            if *old == p.key.key && p.lang.is_some() && p.lang != *lang {
+                p.key.key = new.clone();
+                continue;
+            }
+        }
+        let base = pattern.key(p);
+        let mut key = base.clone();
+        let mut n = 0;
+        while key.is_empty() || used.contains(&key) {
+            key = format!("{}{}", base, suffix(n));
+            n += 1;
+        }
+        used.push(key.clone());
+        let old = std::mem::replace(&mut p.key.key, key.clone());
+        map.keys.push((old.clone(), key.clone()));
+        last = Some((old, p.lang.clone(), key));
+    }
+    map
+}
@@ -0,0 +1,309 @@
+//! Turning the LaTeX found in .bib fields into plain Unicode text.
+
+use std::collections::HashMap;
+
+use crate::debug;
+
+/// Accent macros, with the letters they have a precomposed form for, as
+/// `base, accented` pairs, and the combining character used for the others.
+const ACCENTS: [(char, &str, char); 12] = [
+    ('\'', "aáeéiíoóuúyýAÁEÉIÍOÓUÚYÝcćCĆnńNŃsśSŚzźZŹ", '\u{301}'),
+    ('`', "aàeèiìoòuùAÀEÈIÌOÒUÙ", '\u{300}'),
+    ('^', "aâeêiîoôuûAÂEÊIÎOÔUÛ", '\u{302}'),
+    ('"', "aäeëiïoöuüyÿAÄEËIÏOÖUÜ", '\u{308}'),
+    ('~', "aãoõnñAÃOÕNÑ", '\u{303}'),
+    ('=', "aāeēiīoōuūAĀEĒIĪOŌUŪ", '\u{304}'),
+    ('.', "zżZŻeėEĖ", '\u{307}'),
+    ('u', "aăgğAĂGĞ", '\u{306}'),
+    ('v', "cčCČsšSŠzžZŽrřRŘeěEĚnňNŇ", '\u{30C}'),
+    ('H', "oőuűOŐUŰ", '\u{30B}'),
+    ('c', "cçCÇsşSŞ", '\u{327}'),
+    ('k', "aąeęAĄEĘ", '\u{328}'),
+];
+
+/// Letter macros, without argument.
+const LETTERS: [(&str, &str); 13] = [
+    ("ss", "ß"),
+    ("o", "ø"),
+    ("O", "Ø"),
+    ("ae", "æ"),
+    ("AE", "Æ"),
+    ("oe", "œ"),
+    ("OE", "Œ"),
+    ("aa", "å"),
+    ("AA", "Å"),
+    ("l", "ł"),
+    ("L", "Ł"),
+    ("i", "ı"),
+    ("dots", "…"),
+];
+
+/// Applies an accent macro to the first letter of `arg`.
+fn accent(out: &mut String, (_, precomposed, combining): &(char, &str, char), arg: &str) {
+    let mut arg = arg.chars();
+    let Some(base) = arg.next() else {
+        return;
+    };
+    let letters: Vec<char> = precomposed.chars().collect();
+    match letters.chunks(2).find(|pair| pair[0] == base) {
+        Some(pair) => out.push(pair[1]),
+        None => {
+            out.push(base);
+            out.push(*combining);
+        }
+    }
+    out.extend(arg);
+}
+
+/// Reads a macro argument, braced or a single character, after skipping
+/// spaces.
+fn argument(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    while chars.peek() == Some(&' ') {
+        chars.next();
+    }
+    match chars.next() {
+        Some('{') => {
+            let mut depth = 1;
+            let mut arg = String::new();
+            for c in chars.by_ref() {
+                match c {
+                    '{' => depth += 1,
+                    '}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+                arg.push(c);
+            }
+            arg
+        }
+        Some(c) => c.to_string(),
+        None => String::new(),
+    }
+}
+
+/// Whether the brace opening `s` is closed by its last character, so that
+/// the braces group the whole value.
+pub(crate) fn braced(s: &str) -> bool {
+    if !(s.starts_with('{') && s.ends_with('}')) {
+        return false;
+    }
+    let mut depth = 0;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i == s.len() - 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Whether `s` is quoted as a whole, `"A Title"`, the accents as `\"o` not
+/// counting as quotes.
+fn quoted(s: &str) -> bool {
+    let Some(inner) = s
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .filter(|inner| !inner.ends_with('\\'))
+    else {
+        return false;
+    };
+    let mut escaped = false;
+    inner.chars().all(|c| {
+        let ok = escaped || c != '"';
+        escaped = !escaped && c == '\\';
+        ok
+    })
+}
+
+/// The value without the braces grouping it as a whole, `{{A Title}}`, and
+/// then without one layer of surrounding quotes, as some exporters leave
+/// them. Braces protecting part of the value, `{A} and {B}`, are kept.
+pub fn unwrap_value(s: &str) -> &str {
+    let mut s = s.trim();
+    while braced(s) {
+        s = s[1..s.len() - 1].trim();
+    }
+    unquote_value(s)
+}
+
+/// The value without one layer of surrounding quotes, `"A Title"`.
+pub fn unquote_value(s: &str) -> &str {
+    let s = s.trim();
+    match quoted(s) {
+        true => s[1..s.len() - 1].trim(),
+        false => s,
+    }
+}
+
+/// Decodes accents, escaped characters, dashes and text formatting macros,
+/// and drops the grouping braces and math dollars. Unknown macros keep their
+/// arguments. Free of I/O, and never panics.
+pub fn decode(s: &str) -> String {
+    let mut out = String::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let Some(&next) = chars.peek() else {
+                    break;
+                };
+                if next.is_ascii_alphabetic() {
+                    let mut name = String::new();
+                    while let Some(&c) = chars.peek().filter(|c| c.is_ascii_alphabetic()) {
+                        name.push(c);
+                        chars.next();
+                    }
+                    let macro_accent = ACCENTS
+                        .iter()
+                        .find(|(m, _, _)| name.len() == 1 && name.starts_with(*m));
+                    if let Some(a) = macro_accent {
+                        accent(&mut out, a, &decode(&argument(&mut chars)));
+                    } else if let Some((_, letter)) = LETTERS.iter().find(|(m, _)| *m == name) {
+                        out.push_str(letter);
+                        if chars.peek() == Some(&'{') {
+                            argument(&mut chars);
+                        } else if chars.peek() == Some(&' ') {
+                            chars.next();
+                        }
+                    } else if chars.peek() == Some(&'{') {
+                        out.push_str(&decode(&argument(&mut chars)));
+                    } else if chars.peek() == Some(&' ') {
+                        chars.next();
+                    }
+                } else {
+                    chars.next();
+                    match ACCENTS.iter().find(|(m, _, _)| *m == next) {
+                        Some(a) => accent(&mut out, a, &decode(&argument(&mut chars))),
+                        None if next == '\\' => out.push(' '),
+                        None => out.push(next),
+                    }
+                }
+            }
+            '{' | '}' | '$' => {}
+            '~' => out.push('\u{a0}'),
+            '-' if chars.peek() == Some(&'-') => {
+                chars.next();
+                if chars.peek() == Some(&'-') {
+                    chars.next();
+                    out.push('—');
+                } else {
+                    out.push('–');
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Text macros defined with `\newcommand` in the preambles of a file.
+#[derive(Debug, Default, PartialEq)]
+pub struct Macros {
+    /// Replacement of each macro, by name without backslash
+    pub defs: HashMap<String, String>,
+}
+
+impl Macros {
+    /// Collects the argument-less `\newcommand`, `\renewcommand` and
+    /// `\providecommand` definitions. Anything else is ignored.
+    pub fn from_preambles(preambles: &[String]) -> Macros {
+        let mut macros = Macros::default();
+        for preamble in preambles {
+            let mut rest = preamble.as_str();
+            while let Some(start) = rest.find('\\') {
+                rest = &rest[start + 1..];
+                let command: String = rest.chars().take_while(char::is_ascii_alphabetic).collect();
+                rest = &rest[command.len()..];
+                if !["newcommand", "renewcommand", "providecommand"].contains(&command.as_str()) {
+                    continue;
+                }
+                // The name is braced, `{\\name}`, or not, `\\name`.
+                let unbraced = rest.trim_start().strip_prefix('\\').map(|r| {
+                    let name: String = r.chars().take_while(char::is_ascii_alphabetic).collect();
+                    (r[name.len()..].to_string(), name)
+                });
+                let mut chars = rest.chars().peekable();
+                let name = match &unbraced {
+                    Some((r, name)) => {
+                        chars = r.chars().peekable();
+                        name.clone()
+                    }
+                    None => argument(&mut chars)
+                        .trim()
+                        .trim_start_matches('\\')
+                        .to_string(),
+                };
+                let after_name: String = chars.clone().collect();
+                if after_name.trim_start().starts_with('[') {
+                    debug(
+                        "preamble",
+                        &format!("ignoring \\{} which takes arguments", name),
+                    );
+                    rest = &rest[rest.len() - after_name.len()..];
+                    continue;
+                }
+                let body = argument(&mut chars);
+                let remaining: String = chars.collect();
+                rest = &rest[rest.len() - remaining.len()..];
+                if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphabetic()) {
+                    debug("preamble", &format!("ignoring definition of {:?}", name));
+                    continue;
+                }
+                let body = macros.expand(&body);
+                macros.defs.insert(name, body);
+            }
+        }
+        macros
+    }
+
+    /// Replaces the known macros of `s`, with the empty group or the space
+    /// which may follow them.
+    pub fn expand(&self, s: &str) -> String {
+        if self.defs.is_empty() {
+            return s.to_string();
+        }
+        let mut out = String::new();
+        let mut rest = s;
+        while let Some(start) = rest.find('\\') {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let name: String = after
+                .chars()
+                .take_while(char::is_ascii_alphabetic)
+                .collect();
+            match self.defs.get(&name) {
+                Some(body) if !name.is_empty() => {
+                    out.push_str(body);
+                    rest = &after[name.len()..];
+                    if let Some(r) = rest.strip_prefix("{}") {
+                        rest = r;
+                    }
+                }
+                _ => {
+                    // Keep the backslash and what it escapes.
+                    let len = name
+                        .len()
+                        .max(after.chars().next().map_or(0, char::len_utf8));
+                    out.push('\\');
+                    out.push_str(&after[..len]);
+                    rest = &after[len..];
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
@@ -0,0 +1,458 @@
+//! Converts bibtex entries into front matter pages for Hugo.
+//!
+//! The parsers of the field values also stand on their own, for other
+//! tools: `fields::Pages::from_string`, `fields::parse_name_list`,
+//! `latex::decode` and `venues::infer_shortname` take any string, do no
+//! I/O, never panic, and keep their signatures across minor versions.
+
+pub mod arxiv;
+pub mod builder;
+pub mod cache;
+pub mod check;
+pub mod citation;
+pub mod citations;
+pub mod clipboard;
+pub mod config;
+pub mod conventions;
+pub mod dblp;
+pub mod diagnostics;
+pub mod diff;
+pub mod discover;
+pub mod duplicates;
+pub mod fetch;
+pub mod fields;
+pub mod formats;
+pub mod ignore;
+pub mod import;
+pub mod inspect;
+pub mod interactive;
+pub mod keys;
+pub mod latex;
+pub mod listing;
+pub mod locale;
+pub mod manifest;
+pub mod mapping;
+pub mod markup;
+pub mod merge;
+pub mod output;
+pub mod paper;
+pub mod progress;
+pub mod provenance;
+pub mod recover;
+pub mod schema;
+pub mod target;
+pub mod text;
+pub mod time;
+pub mod timings;
+pub mod venues;
+pub mod verify;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod xdata;
+pub mod yaml;
+
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use nom_bibtex::{Bibliography, Bibtex};
+
+use fields::{EntryType, Keywords};
+use latex::Macros;
+use merge::MergeOn;
+use provenance::Origins;
+
+pub use builder::OptionsBuilder;
+pub use diagnostics::{Error, Warning};
+pub use output::{LineEnding, RenderedPage};
+pub use paper::{AliasFrom, LinkSource, Options, Paper, Venue};
+pub use target::{Emit, Extension, Target};
+
+thread_local! {
+    /// Whether the reports are silenced, see `quietly`.
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` without reporting anything, for work which would repeat reports
+/// already made.
+pub fn quietly<T>(f: impl FnOnce() -> T) -> T {
+    let was = QUIET.replace(true);
+    let result = f();
+    QUIET.set(was);
+    result
+}
+
+/// See `warnings`.
+static WARNINGS: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of warnings reported so far, including the ones silenced by
+/// `quietly`.
+pub fn warnings() -> usize {
+    WARNINGS.load(Ordering::Relaxed)
+}
+
+/// Reports a problem with an entry that does not prevent its conversion.
+pub fn warn(warning: Warning) {
+    WARNINGS.fetch_add(1, Ordering::Relaxed);
+    if !QUIET.get() {
+        diagnostics::report(warning);
+    }
+}
+
+/// Reports details only useful when investigating an issue, printed when
+/// the `NEW_PAGE_DEBUG` environment variable is set.
+pub fn debug(key: &str, msg: &str) {
+    if !QUIET.get() && std::env::var_os("NEW_PAGE_DEBUG").is_some() {
+        progress::report(&format!("Debug: {}: {}", key, msg));
+    }
+}
+
+/// Reports what is done with an entry, for the record.
+pub fn info(key: &str, msg: &str) {
+    if !QUIET.get() {
+        progress::report(&format!("Info: {}: {}", key, msg));
+    }
+}
+
+/// Keyword marking the entries which are not for the website.
+pub const NOSITE: &str = "nosite";
+
+/// Counts of what happened to the entries of a bibtex file.
+#[derive(Debug, Default, PartialEq)]
+pub struct Summary {
+    pub converted: usize,
+    /// Entries whose type is skipped
+    pub skipped: usize,
+    /// Entries kept out of the website by their key, `website` tag or
+    /// `nosite` keyword, by the ignore file, or by not being selected
+    pub excluded: usize,
+    /// Entries which could not be parsed, in recovery mode, or converted,
+    /// or whose pages do not verify
+    pub failed: usize,
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} converted, {} skipped, {} excluded, {} failed",
+            self.converted, self.skipped, self.excluded, self.failed
+        )
+    }
+}
+
+/// Why an entry is kept out of the website, if it is.
+fn exclusion(b: &Bibliography, opts: &Options) -> Option<&'static str> {
+    let tags = b.tags();
+    if opts.exclude_keys.iter().any(|k| k == b.citation_key()) {
+        Some("key is excluded")
+    } else if tags.get("website").is_some_and(|w| {
+        ["false", "no", "0"]
+            .iter()
+            .any(|f| w.trim().eq_ignore_ascii_case(f))
+    }) {
+        Some("website tag is false")
+    } else if Keywords::from(tags.get("keywords"))
+        .tags
+        .iter()
+        .any(|k| k.eq_ignore_ascii_case(NOSITE))
+    {
+        Some("has the nosite keyword")
+    } else {
+        None
+    }
+}
+
+/// Parses the content of a bibtex file and converts its entries, in file
+/// order.
+pub fn convert(input: &str, opts: &Options) -> Result<Vec<Paper>, Error> {
+    convert_summarized(input, opts).map(|(papers, _)| papers)
+}
+
+/// Like `convert`, also counting the entries left out.
+pub fn convert_summarized(input: &str, opts: &Options) -> Result<(Vec<Paper>, Summary), Error> {
+    convert_with_overrides(input, "", MergeOn::Key, opts)
+}
+
+/// Like `convert_summarized`, the fields of the entries of `overrides`
+/// replacing the ones of the matching entries of `input`, see
+/// `merge::merge`. Both are parsed together, so that the string macros of
+/// either apply to the other.
+pub fn convert_with_overrides(
+    input: &str,
+    overrides: &str,
+    on: MergeOn,
+    opts: &Options,
+) -> Result<(Vec<Paper>, Summary), Error> {
+    let mut summary = Summary::default();
+    let (entries, macros, origins) = parse_merged(input, overrides, on, opts, &mut summary)?;
+    let mut papers = Vec::new();
+    // Entries of each unknown type, reported on together.
+    let mut unknown: BTreeMap<String, usize> = BTreeMap::new();
+    for b in &entries {
+        let selected = opts.select_keys.iter().any(|k| k == b.citation_key());
+        if !opts.select_keys.is_empty() && !selected {
+            summary.excluded += 1;
+            continue;
+        }
+        // Ignored entries are kept out before anything else is done, unless
+        // they are selected.
+        match opts.ignore.reason(b) {
+            Some(reason) if selected => warn(Warning::SelectedIgnored {
+                key: b.citation_key().to_string(),
+                reason,
+            }),
+            Some(reason) => {
+                info(b.citation_key(), &format!("ignored, {}", reason));
+                summary.excluded += 1;
+                continue;
+            }
+            None => {}
+        }
+        let kind = EntryType::from(b);
+        if opts
+            .skip_types
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(b.entry_type()))
+        {
+            summary.skipped += 1;
+            continue;
+        }
+        let known = kind.is_known();
+        if !known {
+            *unknown.entry(kind.kind).or_default() += 1;
+        }
+        if opts.skip_unknown_types && !known {
+            summary.skipped += 1;
+        } else if let Some(reason) = exclusion(b, opts) {
+            info(b.citation_key(), &format!("excluded, {}", reason));
+            summary.excluded += 1;
+        } else {
+            let pages: Result<Vec<Paper>, Error> = match opts.languages.is_empty() {
+                true => timings::entry(b.citation_key(), || Paper::new(b, opts, &macros))
+                    .map(|paper| vec![paper]),
+                // One page per language, the entry being reported on once.
+                false => (opts.languages.iter().enumerate())
+                    .map(|(i, lang)| {
+                        let page = || {
+                            timings::entry(b.citation_key(), || {
+                                Paper::in_language(b, opts, &macros, Some(lang))
+                            })
+                        };
+                        if i == 0 {
+                            page()
+                        } else {
+                            quietly(page)
+                        }
+                    })
+                    .collect(),
+            };
+            match pages {
+                Ok(pages) => {
+                    for mut paper in pages {
+                        paper.provenance.locate(b.citation_key(), &origins);
+                        papers.push(paper);
+                    }
+                    summary.converted += 1;
+                }
+                Err(e) => {
+                    if !QUIET.get() {
+                        diagnostics::report_error(&e);
+                    }
+                    summary.failed += 1;
+                }
+            }
+        }
+    }
+    if !unknown.is_empty() {
+        match opts.skip_unknown_types {
+            true => {
+                let counts: Vec<String> = unknown
+                    .iter()
+                    .map(|(kind, n)| format!("@{} ({})", kind, n))
+                    .collect();
+                info("entry types", &format!("left out {}", counts.join(", ")))
+            }
+            false => warn(Warning::UnknownTypes {
+                types: unknown.into_iter().collect(),
+            }),
+        }
+    }
+    Ok((papers, summary))
+}
+
+/// The entries of `input` merged with the ones of `overrides`, with the
+/// text macros of their preambles and the origins of their tags,
+/// recovering from the entries which do not parse as `opts` says and
+/// counting them in `summary`. With a `cache_dir`, an input parsed before
+/// is read from the cache; the inputs whose parsing gives warnings are not
+/// cached, so that every run gives them.
+pub(crate) fn parse_merged(
+    input: &str,
+    overrides: &str,
+    on: MergeOn,
+    opts: &Options,
+    summary: &mut Summary,
+) -> Result<(Vec<Bibliography>, Macros, Origins), Error> {
+    let Some(dir) = &opts.cache_dir else {
+        return parse_fresh(input, overrides, on, opts, summary);
+    };
+    let settings = format!("{:?} {} {}", on, opts.recover, opts.max_field_length);
+    let hash = cache::hash(&[input, overrides, &settings]);
+    let start = Instant::now();
+    if let Some(hit) = timings::time("cache", || cache::load(dir, hash)) {
+        timings::cached(hit.parse_time, start.elapsed());
+        summary.failed += hit.failed;
+        return Ok((hit.entries, hit.macros, hit.origins));
+    }
+    let failed = summary.failed;
+    // The warnings are collected to see whether there are any, then printed
+    // as they would have been; `warn` counted them already.
+    let (parsed, warnings) =
+        diagnostics::collect(|| parse_fresh(input, overrides, on, opts, summary));
+    let warned = !warnings.is_empty();
+    for warning in warnings {
+        diagnostics::report(warning);
+    }
+    let (entries, macros, origins) = parsed?;
+    if warned {
+        return Ok((entries, macros, origins));
+    }
+    let parse_time = start.elapsed();
+    let stored = timings::time("cache", || {
+        cache::store(
+            dir,
+            hash,
+            &entries,
+            &macros,
+            &origins,
+            summary.failed - failed,
+            parse_time,
+        )
+    });
+    if let Err(e) = stored {
+        warn(Warning::service("cache", format!("not written: {}", e)));
+    }
+    Ok((entries, macros, origins))
+}
+
+/// `parse_merged` without the cache.
+fn parse_fresh(
+    input: &str,
+    overrides: &str,
+    on: MergeOn,
+    opts: &Options,
+    summary: &mut Summary,
+) -> Result<(Vec<Bibliography>, Macros, Origins), Error> {
+    let mut overrides = overrides.replace("\r\n", "\n");
+    if Bibtex::raw_parse(&overrides).is_err() && opts.recover {
+        let failed;
+        (overrides, failed) = recover::recover(&overrides);
+        summary.failed += failed;
+    }
+    let overridden = Bibtex::raw_parse(&overrides)
+        .map_err(|e| Error::located(e, recover::first_failure(&overrides)))?
+        .iter()
+        .filter(|e| matches!(e, nom_bibtex::Entry::Bibliography(..)))
+        .count();
+    let mut input = input.replace("\r\n", "\n") + "\n" + &overrides;
+    let bibtex = match timings::time("parse", || Bibtex::parse(&input)) {
+        Ok(bibtex) => bibtex,
+        Err(_) if opts.recover => {
+            let failed;
+            (input, failed) = timings::time("recovery", || recover::recover(&input));
+            summary.failed += failed;
+            timings::time("parse", || Bibtex::parse(&input)).map_err(|e| Error::located(e, None))?
+        }
+        Err(e) => return Err(Error::located(e, recover::first_failure(&input))),
+    };
+    // Comments and preambles are not entries; the preambles only matter for
+    // the text macros they define.
+    for comment in bibtex.comments() {
+        debug("comment", comment.lines().next().unwrap_or_default());
+    }
+    let macros = Macros::from_preambles(bibtex.preambles());
+    let bibliographies: Vec<Bibliography> = bibtex.bibliographies().iter().map(unwrapped).collect();
+    let (base, overrides) =
+        bibliographies.split_at(bibliographies.len().saturating_sub(overridden));
+    let mut origins = Origins::default();
+    let merged = merge::merge(base, overrides, on, &mut origins);
+    let entries = xdata::resolve(merged, &mut origins)
+        .into_iter()
+        .map(|b| within_length(b, opts.max_field_length))
+        .collect();
+    Ok((entries, macros, origins))
+}
+
+/// Tags holding lists of names, whose braces around the whole value make a
+/// single name of an organization, `{Food and Drug Administration}`.
+const NAME_LISTS: [&str; 4] = ["author", "editor", "translator", "corresponding"];
+
+/// The entry with its tag values unwrapped of their surrounding braces and
+/// quotes, see `latex::unwrap_value`; the name lists only lose their quotes.
+fn unwrapped(b: &Bibliography) -> Bibliography {
+    let tags = b
+        .tags()
+        .iter()
+        .map(|(name, value)| {
+            let value = match NAME_LISTS.contains(&name.as_str()) {
+                true => latex::unquote_value(value),
+                false => latex::unwrap_value(value),
+            };
+            (name.clone(), value.to_string())
+        })
+        .collect();
+    Bibliography::new(
+        b.entry_type().to_string(),
+        b.citation_key().to_string(),
+        tags,
+    )
+}
+
+/// The entry with the tag values longer than `max` bytes cut to it, with a
+/// warning.
+fn within_length(b: Bibliography, max: usize) -> Bibliography {
+    if b.tags().values().all(|v| v.len() <= max) {
+        return b;
+    }
+    let mut tags = b.tags().clone();
+    // By field name, for the warnings to come in the same order every run.
+    let mut long: Vec<(&String, &mut String)> =
+        tags.iter_mut().filter(|(_, v)| v.len() > max).collect();
+    long.sort_by_key(|(name, _)| *name);
+    for (name, value) in long {
+        warn(Warning::FieldTooLong {
+            key: b.citation_key().to_string(),
+            field: name.clone(),
+            length: value.len(),
+            limit: max,
+        });
+        let end = (0..=max)
+            .rev()
+            .find(|i| value.is_char_boundary(*i))
+            .unwrap_or(0);
+        value.truncate(end);
+    }
+    Bibliography::new(
+        b.entry_type().to_string(),
+        b.citation_key().to_string(),
+        tags,
+    )
+}
+
+/// Converts the content of a bibtex file into the pages to write, in one
+/// call, for embedders.
+pub fn convert_str(input: &str, opts: &Options) -> Result<Vec<RenderedPage>, Error> {
+    convert(input, opts).map(|papers| output::rendered_pages(&papers, opts))
+}
+
+/// Renders pages the way they are printed on the standard output, normalized
+/// as the written ones, see `output::normalize`.
+pub fn render(papers: &[Paper], opts: &Options) -> String {
+    let pages: String = papers
+        .iter()
+        .map(|p| format!("{}\n", p.render_with(opts)))
+        .collect();
+    output::normalize(&pages, opts.line_ending)
+}
@@ -0,0 +1,186 @@
+//! One line per entry of a bibliography, to see what it holds without
+//! converting it. Entries are only read, so that incomplete ones are listed
+//! like the others.
+
+use std::ops::RangeInclusive;
+
+use nom_bibtex::Bibliography;
+use serde::Serialize;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::fields::split_names;
+use crate::latex::decode;
+use crate::merge::MergeOn;
+use crate::{parse_merged, Error, Options, Summary};
+
+/// Length of the titles in the table, in characters, beyond which they are
+/// cut.
+pub const TITLE_WIDTH: usize = 50;
+
+/// What the list shows of an entry; the missing tags are `None`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Listed {
+    pub key: String,
+    pub entry_type: String,
+    pub year: Option<i64>,
+    /// Family name of the first author, or else of the first editor
+    pub author: Option<String>,
+    pub title: Option<String>,
+}
+
+impl From<&Bibliography> for Listed {
+    fn from(b: &Bibliography) -> Listed {
+        let tags = b.tags();
+        let year = tags
+            .get("year")
+            .or(tags.get("date"))
+            .and_then(|y| y.trim().get(..4))
+            .and_then(|y| y.parse().ok());
+        let author = tags
+            .get("author")
+            .or(tags.get("editor"))
+            .and_then(|names| family_name(split_names(names).first()?));
+        Listed {
+            key: b.citation_key().to_string(),
+            entry_type: b.entry_type().to_lowercase(),
+            year,
+            author,
+            title: tags
+                .get("title")
+                .map(|t| decode(t).split_whitespace().collect::<Vec<_>>().join(" ")),
+        }
+    }
+}
+
+/// Family name of a bibtex name, `Doe, Jane` or `Jane Doe`.
+fn family_name(name: &str) -> Option<String> {
+    let name = name.trim();
+    let family = match name.split_once(',') {
+        Some((family, _)) => family,
+        None if name.starts_with('{') => name,
+        None => name.rsplit(' ').next()?,
+    };
+    let family = decode(family).trim().to_string();
+    (!family.is_empty()).then_some(family)
+}
+
+/// Entries kept in the list; the default keeps them all.
+#[derive(Clone, Debug, Default)]
+pub struct ListFilter {
+    /// Entry types kept, compared case insensitively; all when empty
+    pub types: Vec<String>,
+    /// Years kept; the undated entries are left out
+    pub years: Option<RangeInclusive<i64>>,
+    /// Text the family name of the first author contains, compared case
+    /// insensitively
+    pub author: Option<String>,
+}
+
+impl ListFilter {
+    pub fn keeps(&self, entry: &Listed) -> bool {
+        (self.types.is_empty()
+            || self
+                .types
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(&entry.entry_type)))
+            && self
+                .years
+                .as_ref()
+                .is_none_or(|years| entry.year.is_some_and(|y| years.contains(&y)))
+            && self.author.as_ref().is_none_or(|author| {
+                entry
+                    .author
+                    .as_ref()
+                    .is_some_and(|a| a.to_lowercase().contains(&author.to_lowercase()))
+            })
+    }
+}
+
+/// Order of the listed entries.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ListOrder {
+    /// As in the files
+    #[default]
+    File,
+    Key,
+    /// Oldest first, the undated entries last
+    Year,
+    Type,
+    Author,
+}
+
+/// Sorts the entries, keeping the file order of the equal ones.
+pub fn sort(entries: &mut [Listed], order: ListOrder) {
+    match order {
+        ListOrder::File => {}
+        ListOrder::Key => entries.sort_by(|a, b| a.key.cmp(&b.key)),
+        ListOrder::Year => entries.sort_by_key(|e| (e.year.is_none(), e.year)),
+        ListOrder::Type => entries.sort_by(|a, b| a.entry_type.cmp(&b.entry_type)),
+        ListOrder::Author => entries.sort_by_key(|e| {
+            (
+                e.author.is_none(),
+                e.author.as_ref().map(|a| a.to_lowercase()),
+            )
+        }),
+    }
+}
+
+/// The entries of `input` kept by `filter`, in `order`. Only the syntax of
+/// the file matters: entries missing any tag are listed too.
+pub fn list(
+    input: &str,
+    filter: &ListFilter,
+    order: ListOrder,
+    opts: &Options,
+) -> Result<Vec<Listed>, Error> {
+    let (entries, _, _) = parse_merged(input, "", MergeOn::Key, opts, &mut Summary::default())?;
+    let mut listed: Vec<Listed> = entries
+        .iter()
+        .map(Listed::from)
+        .filter(|e| filter.keeps(e))
+        .collect();
+    sort(&mut listed, order);
+    Ok(listed)
+}
+
+/// Cuts `s` to `width` characters, the last one being an ellipsis.
+fn cut(s: &str, width: usize) -> String {
+    let graphemes: Vec<&str> = s.graphemes(true).collect();
+    if graphemes.len() <= width {
+        return s.to_string();
+    }
+    graphemes[..width - 1].concat() + "…"
+}
+
+/// The entries as a table with aligned columns and a header, the missing
+/// values shown as `-` and the titles cut to `TITLE_WIDTH`.
+pub fn table(entries: &[Listed]) -> String {
+    let or_dash = |v: Option<String>| v.unwrap_or_else(|| "-".to_string());
+    let mut rows = vec![["KEY", "TYPE", "YEAR", "AUTHOR", "TITLE"].map(String::from)];
+    for e in entries {
+        rows.push([
+            e.key.clone(),
+            e.entry_type.clone(),
+            or_dash(e.year.map(|y| y.to_string())),
+            or_dash(e.author.clone()),
+            or_dash(e.title.as_ref().map(|t| cut(t, TITLE_WIDTH))),
+        ]);
+    }
+    let width = |i: usize| {
+        rows.iter()
+            .map(|r| r[i].graphemes(true).count())
+            .max()
+            .unwrap_or(0)
+    };
+    let widths: Vec<usize> = (0..4).map(width).collect();
+    let mut out = String::new();
+    for row in &rows {
+        for (cell, width) in row.iter().zip(&widths) {
+            out.push_str(cell);
+            out.push_str(&" ".repeat(width - cell.graphemes(true).count() + 2));
+        }
+        out.push_str(&row[4]);
+        out.push('\n');
+    }
+    out
+}
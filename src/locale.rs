@@ -0,0 +1,66 @@
+//! Languages of the citation strings of the list formats: the abbreviations
+//! of the months and the phrasing around the dates, from a small table of
+//! their own. The front matter, its keys and its ISO dates, is the same
+//! whatever the locale.
+
+use serde::Deserialize;
+
+/// Language of the citation strings.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    /// `Accessed: Jan. 15, 2024.`
+    #[default]
+    En,
+    /// `Consulté le 15 janv. 2024.`
+    Fr,
+    /// `Abgerufen am 15. Jan. 2024.`
+    De,
+}
+
+impl Locale {
+    /// Abbreviations of the months, from January.
+    fn months(self) -> [&'static str; 12] {
+        match self {
+            Locale::En => [
+                "Jan.", "Feb.", "Mar.", "Apr.", "May", "Jun.", "Jul.", "Aug.", "Sep.", "Oct.",
+                "Nov.", "Dec.",
+            ],
+            Locale::Fr => [
+                "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.",
+                "nov.", "déc.",
+            ],
+            Locale::De => [
+                "Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sept.", "Okt.",
+                "Nov.", "Dez.",
+            ],
+        }
+    }
+
+    /// An ISO date, `2024-01-15`, as written in the locale: `Jan. 15, 2024`,
+    /// `15 janv. 2024`, `15. Jan. 2024`.
+    pub fn short_date(self, iso: &str) -> Option<String> {
+        let mut parts = iso.split('-');
+        let year = parts.next()?;
+        let month: usize = parts.next()?.parse().ok()?;
+        let day: u32 = parts.next()?.parse().ok()?;
+        let month = self.months().get(month.checked_sub(1)?)?.to_owned();
+        Some(match self {
+            Locale::En => format!("{} {}, {}", month, day, year),
+            Locale::Fr if day == 1 => format!("1er {} {}", month, year),
+            Locale::Fr => format!("{} {} {}", day, month, year),
+            Locale::De => format!("{}. {} {}", day, month, year),
+        })
+    }
+
+    /// The sentence ending the citation of an online resource accessed on
+    /// the ISO date `iso`.
+    pub fn accessed(self, iso: &str) -> Option<String> {
+        let date = self.short_date(iso)?;
+        Some(match self {
+            Locale::En => format!("Accessed: {}.", date),
+            Locale::Fr => format!("Consulté le {}.", date),
+            Locale::De => format!("Abgerufen am {}.", date),
+        })
+    }
+}
@@ -1,359 +1,1446 @@
-use std::fmt::{Display, Error, Formatter};
+use std::io::{ErrorKind, Read};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
 
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 
-use nom_bibtex::{Bibliography, Bibtex};
+use new_page::check::check;
+use new_page::citation::sort_newest_first;
+use new_page::clipboard;
+use new_page::config::{self, Config};
+use new_page::conventions;
+use new_page::diagnostics;
+use new_page::diff::compare;
+use new_page::discover::{bib_files, Discovery};
+use new_page::duplicates;
+use new_page::fetch::Fetcher;
+use new_page::formats::{data_yaml, document, html, markdown_list};
+use new_page::ignore::{self, IgnoreList};
+use new_page::import::{entry_fragment, InputFormat};
+use new_page::inspect::inspect;
+use new_page::interactive::{self, select};
+use new_page::keys::{self, KeyPattern};
+use new_page::listing::{self, ListFilter, ListOrder};
+use new_page::locale::Locale;
+use new_page::manifest::{Manifest, Run};
+use new_page::mapping::FieldMap;
+use new_page::merge::MergeOn;
+use new_page::output::{
+    existing_pages, normalize, rendered_pages, write_file, write_file_atomic, write_rendered,
+    LineEnding,
+};
+use new_page::progress;
+use new_page::provenance::Files;
+use new_page::text::{Punctuation, TitleRules};
+use new_page::time::{current_year, timestamp, today};
+use new_page::timings;
+use new_page::venues::{self, VenueMap};
+use new_page::verify;
+use new_page::yaml::Quotes;
+use new_page::{AliasFrom, Emit, Extension, LinkSource, Options, Paper, Summary, Target, Venue};
 
-#[derive(Default)]
-struct Pages {
-    from: Option<i64>,
-    to: Option<i64>,
+#[derive(Parser, Debug)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    after_help = "Exit codes: 0 on success, 1 when warnings were reported under \
+                  --fail-on-warning or the pages differ under --diff, 2 when some entries \
+                  could not be parsed or converted, 3 when the bibtex file cannot be read or \
+                  parsed, 4 on invalid command line or configuration. Beyond these, 5 when \
+                  the output cannot be written, which none of them describes."
+)]
+struct Args {
+    /// The path of the bibtex file, or of a directory of .bib files; repeat
+    /// it to convert several files, possibly of different formats, together
+    #[arg(short, long)]
+    file_path: Vec<String>,
+
+    /// Search the subdirectories of the directories given with --file-path
+    /// for .bib files too, not only their top level
+    #[arg(long)]
+    recursive: bool,
+
+    /// Depth of the subdirectories searched with --recursive
+    #[arg(long, default_value_t = 16, requires = "recursive")]
+    max_depth: usize,
+
+    /// Name of the files and directories left out when searching the
+    /// directories given with --file-path, `*` standing for any text and `?`
+    /// for any character; repeat it for several. Instead of the hidden ones
+    /// and `node_modules`
+    #[arg(long, value_name = "PATTERN")]
+    skip_path: Vec<String>,
+
+    /// What the standard input holds, read when no file is given or for the
+    /// file `-`: a whole file, or the single entry an editor pipes
+    #[arg(long, value_enum)]
+    stdin_format: Option<StdinFormat>,
+
+    /// Convert the BibTeX entries copied to the clipboard; needs the
+    /// `clipboard` feature and, on the PATH, one of pbpaste, wl-paste, xclip,
+    /// xsel or powershell
+    #[arg(long, conflicts_with_all = ["file_path", "stdin_format"])]
+    from_clipboard: bool,
+
+    /// Format of the input files; by default told by their extension,
+    /// `.bib`, `.json` or `.ris`, or else by their content
+    #[arg(long, value_enum)]
+    input_format: Option<InputFormatArg>,
+
+    /// Hand-curated entries whose fields replace the ones of the matching
+    /// entries of the converted files; the entries matching none are
+    /// converted on their own
+    #[arg(long, value_name = "FILE")]
+    merge_overrides: Option<String>,
+
+    /// How the overriding entries are matched
+    #[arg(long, value_enum, default_value_t = MergeBy::Key)]
+    merge_on: MergeBy,
+
+    /// Name conferences after their booktitle even when an eventtitle is given
+    #[arg(long)]
+    no_eventtitle: bool,
+
+    /// Leave out the `categories` derived from the entry types
+    #[arg(long)]
+    no_categories: bool,
+
+    /// Primary venue of entries having both a journal and a booktitle;
+    /// by default the entry type decides
+    #[arg(long, value_enum)]
+    prefer_venue: Option<PreferVenue>,
+
+    /// Sources of the `www` link, the first one the entry has being taken
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "url")]
+    url_preference: Vec<LinkFrom>,
+
+    /// List the links of the entry not taken for `www` under `links`
+    #[arg(long)]
+    other_links: bool,
+
+    /// Emit `aliases` redirecting stable paths, `/doi/<doi>` and `/bib/<key>`,
+    /// to the pages
+    #[arg(long, value_enum)]
+    alias_from: Option<Alias>,
+
+    /// Number of sentences of the abstract kept in the `summary`
+    #[arg(long, default_value_t = 2)]
+    summary_sentences: usize,
+
+    /// Reading speed, in words per minute, of the `reading_time`
+    #[arg(long, default_value_t = 200)]
+    wpm: usize,
+
+    /// Character ending the names of the authors who contributed equally,
+    /// as in `Doe, Jane* and Smith, Bob*`
+    #[arg(long, default_value_t = '*')]
+    author_marker: char,
+
+    /// Cut the given names of the authors, editors and translators to their
+    /// initials, `J.-P. Sartre`
+    #[arg(long)]
+    initials: bool,
+
+    /// Write one page per entry and language, `index.fr.md`, with the title
+    /// and abstract of the language; the untranslated abstracts are in the
+    /// first language
+    #[arg(long, value_delimiter = ',')]
+    languages: Vec<String>,
+
+    /// Suffix of the tags translating the title and abstract, `{}` standing
+    /// for the language: `abstract-fr`
+    #[arg(long, default_value = "-{}")]
+    language_suffix: String,
+
+    /// Take the awards of the entries without `award` tag from the parts of
+    /// their note naming one, e.g. `Best Paper`
+    #[arg(long)]
+    detect_awards: bool,
+
+    /// Set `featured: true` on the pages of awarded works
+    #[arg(long)]
+    feature_awards: bool,
+
+    /// Write the quotes of titles as straight quotes rather than curly ones
+    #[arg(long)]
+    ascii_quotes: bool,
+
+    /// Cut the abstracts longer than this many characters at a sentence
+    /// boundary, marking them `abstract_truncated`
+    #[arg(long)]
+    max_abstract_chars: Option<usize>,
+
+    /// Cut the abstracts longer than this many words at a sentence boundary,
+    /// marking them `abstract_truncated`
+    #[arg(long)]
+    max_abstract_words: Option<usize>,
+
+    /// Marker appended to the cut abstracts
+    #[arg(long, default_value = "…")]
+    abstract_ellipsis: String,
+
+    /// Dashes and quotes of abstracts: Unicode ones, ASCII ones, or as written
+    #[arg(long, value_enum, default_value_t = AbstractPunctuation::Keep)]
+    abstract_punctuation: AbstractPunctuation,
+
+    /// Leave the HTML or JATS markup of the abstracts, `<jats:p>`, `<sub>`
+    /// or `&amp;`, as it is instead of converting it to text and Markdown
+    #[arg(long)]
+    keep_abstract_markup: bool,
+
+    /// Citation keys of entries to leave out of the website, added to the
+    /// `exclude_keys` of the configuration
+    #[arg(long, value_delimiter = ',')]
+    exclude_keys: Vec<String>,
+
+    /// Citation key of an entry to convert, leaving out the others; repeat
+    /// it to convert several. Selected entries are converted even when the
+    /// ignore file lists them, with a warning
+    #[arg(long = "key", value_name = "KEY", value_delimiter = ',')]
+    select_keys: Vec<String>,
+
+    /// File of the citation keys, key patterns (`memo-*`) and DOI prefixes
+    /// of the entries never to convert; by default the `.newpageignore`
+    /// next to each input file, if any, for the entries of that file
+    #[arg(long, value_name = "FILE")]
+    ignore_file: Option<PathBuf>,
+
+    /// Configuration file, `new_page.toml` by default when present
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Strip the `Proceedings of the 44th Annual` boilerplate and trailing
+    /// year or location from conference names taken from the booktitle,
+    /// keeping the full title as `proceedings`
+    #[arg(long)]
+    clean_proceedings: bool,
+
+    /// Write the `number` of the entries without `series` as their series
+    /// too, as versions before the `issue` field did
+    #[arg(long)]
+    legacy_series_number: bool,
+
+    /// Fail when the file does not parse as a whole, rather than converting
+    /// the entries which do
+    #[arg(long)]
+    no_recover: bool,
+
+    /// Do not parse the rendered pages back to check their front matter;
+    /// the pages which do not parse are otherwise left out
+    #[arg(long)]
+    no_verify: bool,
+
+    /// Earliest plausible publication year
+    #[arg(long, default_value_t = 1900)]
+    min_year: i64,
+
+    /// Latest plausible publication year, two years from now by default
+    #[arg(long)]
+    max_year: Option<i64>,
+
+    /// Warn about page ranges spanning more pages
+    #[arg(long, default_value_t = 2000)]
+    max_page_span: i64,
+
+    /// Refuse input files larger than this many megabytes
+    #[arg(long, value_name = "MB", default_value_t = 256)]
+    max_input_size: u64,
+
+    /// Cut the tag values longer than this many bytes, with a warning
+    #[arg(long, value_name = "BYTES", default_value_t = 1 << 20)]
+    max_field_length: usize,
+
+    /// Refuse to write more pages than this in the output directory
+    #[arg(long, default_value_t = 10_000)]
+    max_pages: usize,
+
+    /// Expand abbreviated end pages, `1153--67` to 1153–1167, when
+    /// unambiguous
+    #[arg(long)]
+    fix_pages: bool,
+
+    /// Entry types to leave out of the output, e.g. `proceedings`
+    #[arg(long, value_delimiter = ',')]
+    skip_types: Vec<String>,
+
+    /// Leave out the entries of types the tool does not know, e.g.
+    /// `@dataset`, instead of converting them generically
+    #[arg(long)]
+    skip_unknown_types: bool,
+
+    /// Record the generation time in the front matter
+    #[arg(long)]
+    stamp: bool,
+
+    /// Write one page per entry in this directory instead of printing them
+    #[arg(short, long)]
+    output_dir: Option<PathBuf>,
+
+    /// Write each page as a Hugo page bundle, `<key>/index.md`
+    #[arg(long, requires = "output_dir")]
+    bundle: bool,
+
+    /// Follow the conventions of the pages already in --output-dir where
+    /// they are clear, instead of only warning about the differences
+    #[arg(long, requires = "output_dir")]
+    adopt_conventions: bool,
+
+    /// Once all the pages of --output-dir are written, list them in this
+    /// JSON file, with their path, slug, title, year and content hash
+    #[arg(long, requires = "output_dir")]
+    manifest: Option<PathBuf>,
+
+    /// Look the entries without DOI up on dblp to fill in their DOI, URL and
+    /// pages
+    #[arg(long)]
+    enrich_dblp: bool,
+
+    /// Complete the entries having an arXiv `eprint` with the arXiv abstract,
+    /// primary category and date of the latest version
+    #[arg(long)]
+    enrich_arxiv: bool,
+
+    /// Let the enrichment replace fields which are already filled in
+    #[arg(long)]
+    enrich_overwrite: bool,
+
+    /// Add the Semantic Scholar citation count of each entry having a DOI or
+    /// an arXiv identifier
+    #[arg(long)]
+    citation_counts: bool,
+
+    /// Days during which a fetched citation count is reused
+    #[arg(long, default_value_t = 7, value_name = "DAYS")]
+    citation_counts_max_age: u64,
+
+    /// Only use the cached answers of the enrichment services
+    #[arg(long)]
+    offline: bool,
+
+    /// Directory caching the answers of the enrichment services, and the
+    /// parsed inputs with --parse-cache
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+
+    /// Cache the parsed inputs under the --cache-dir, so that converting
+    /// them again unchanged skips parsing
+    #[arg(long)]
+    parse_cache: bool,
+
+    /// Neither read nor write the cache of the parsed inputs, even with
+    /// --parse-cache
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Exit with code 1 when warnings were reported, even if the pages were
+    /// written
+    #[arg(long)]
+    fail_on_warning: bool,
+
+    /// Format of the warnings and of the parse errors on the standard
+    /// error: text, or one JSON object per line with the rule, citation
+    /// key, field and location in the input
+    #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+    message_format: ReportFormat,
+
+    /// Front matter keys left out of the pages, as `www,publisher`, on top
+    /// of the `omit` list of the configuration's `[fields]`
+    #[arg(long, value_delimiter = ',', value_name = "FIELDS")]
+    omit_fields: Vec<String>,
+
+    /// Give the entries new citation keys following this pattern of
+    /// `{author}` (first author's family name), `{year}` and `{word}`
+    /// (first significant title word), letters being appended to keep
+    /// them unique; the old and new keys are listed
+    #[arg(long, value_name = "PATTERN", value_parser = KeyPattern::parse)]
+    regen_keys: Option<KeyPattern>,
+
+    /// File the old and new keys are also written to, one `old -> new` per
+    /// line
+    #[arg(long, value_name = "FILE", requires = "regen_keys")]
+    key_map: Option<PathBuf>,
+
+    /// Report the entries whose titles are the same or nearly, such as a
+    /// preprint and its published version
+    #[arg(long)]
+    find_duplicates: bool,
+
+    /// Write the keys of the other versions of each reported entry as its
+    /// `other_versions`
+    #[arg(long)]
+    link_versions: bool,
+
+    /// Merge each arXiv preprint into the page of its published version,
+    /// found by their titles or by a `relatedversion` tag
+    #[arg(long)]
+    merge_versions: bool,
+
+    /// Similarity of titles, from 0 to 1, above which entries are reported
+    /// as duplicates
+    #[arg(long, default_value_t = duplicates::THRESHOLD, value_parser = threshold)]
+    duplicate_threshold: f64,
+
+    /// Show neither the progress of the long steps nor the warnings; the
+    /// progress is a bar on a terminal, a line every 100 entries otherwise
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Print the time taken by each step, and the slowest entries, at the
+    /// end of the run
+    #[arg(long)]
+    timings: bool,
+
+    /// Pick the entries to convert from a searchable list
+    #[arg(long)]
+    interactive: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Hugo)]
+    format: Format,
+
+    /// Write the output to this file instead of the standard output, `-`
+    /// for the standard output
+    #[arg(long, conflicts_with = "output_dir")]
+    output: Option<PathBuf>,
+
+    /// Let `--output` replace an existing file
+    #[arg(long, requires = "output")]
+    force: bool,
+
+    /// Static site generator whose conventions the pages follow
+    #[arg(long, value_enum, default_value_t = SiteTarget::Hugo)]
+    target: SiteTarget,
+
+    /// Parts of each page to produce; pages of front matter only are
+    /// written as `.yaml` files, except for Zola
+    #[arg(long, value_enum, default_value_t = EmitParts::Both)]
+    emit: EmitParts,
+
+    /// Files written with --output-dir: `md` pages, the front matter
+    /// mapping as `yaml`, or the entries as `json`
+    #[arg(long, value_enum, requires = "output_dir")]
+    extension: Option<PageExtension>,
+
+    /// Quotes of the strings of the YAML front matter, data file and venue
+    /// pages: `double`, `single`, the single quotes doubled, or `auto`, only
+    /// quoting the strings which would not read back as themselves
+    #[arg(long, value_enum, default_value_t = YamlQuotes::Double)]
+    yaml_quotes: YamlQuotes,
+
+    /// Language of the months and of the access dates of the citations of
+    /// the list formats; the front matter does not change
+    #[arg(long, value_enum, default_value_t = CitationLocale::En)]
+    locale: CitationLocale,
+
+    /// Line ending of the generated files and of the standard output, which
+    /// also lose their trailing whitespace and end with a single line ending
+    #[arg(long, value_enum, default_value_t = Newline::Lf)]
+    line_ending: Newline,
+
+    /// Report the entries of this content directory which are new, stale or
+    /// changed instead of writing anything; fails when they are out of sync
+    #[arg(long, value_name = "CONTENT_DIR", conflicts_with_all = ["output", "output_dir"])]
+    diff: Option<PathBuf>,
+
+    /// Print the JSON Schema of the structured outputs and exit
+    #[arg(long)]
+    schema: bool,
+
+    /// How `--diff` prints its report
+    #[arg(long, value_enum, default_value_t = ReportFormat::Text, requires = "diff")]
+    diff_format: ReportFormat,
+
+    /// Print the tags of the entry with this key as written and as
+    /// normalized, the values of its fields and its page, instead of
+    /// writing anything
+    #[arg(long, value_name = "KEY", conflicts_with_all = ["output", "output_dir", "diff"])]
+    print_entry: Option<String>,
+
+    /// Check the entries against the conventions switched on under
+    /// `[check]` in the configuration instead of writing anything; fails
+    /// when some entries do not follow them
+    #[arg(long, conflicts_with_all = ["output", "output_dir", "diff", "print_entry"])]
+    check: bool,
+
+    /// How `--print-entry` prints the entry
+    #[arg(long, value_enum, default_value_t = ReportFormat::Text, requires = "print_entry")]
+    print_entry_format: ReportFormat,
+
+    /// Follow each front matter key of the pages with a comment naming the
+    /// tag its value comes from, and whether the entry, the overrides, an
+    /// `@xdata` bundle or a web service gave it; the file of the entry too,
+    /// when there are several
+    #[arg(long)]
+    provenance_comments: bool,
+
+    /// Insert a heading before each group of entries of the list formats,
+    /// the undated ones last; per-entry pages are not grouped
+    #[arg(long, value_enum)]
+    group_by: Option<GroupBy>,
+
+    /// With `--format html`, produce a complete HTML page rather than a fragment
+    #[arg(long)]
+    html_standalone: bool,
+
+    /// With `--format document`, the title of the document
+    #[arg(long, default_value = "Publications")]
+    document_title: String,
+
+    /// With `--format document`, start with a table of contents
+    #[arg(long)]
+    toc: bool,
+
+    /// Also write a `<venue>/_index.md` page listing the papers of each venue
+    /// in this directory, and report which venue names were merged
+    #[arg(long, value_name = "DIR")]
+    venue_pages: Option<PathBuf>,
+
+    /// TOML file of canonical venue names and the spellings merged into
+    /// them, `PLDI = ["PLDI '22", "Proc. PLDI"]`
+    #[arg(long, requires = "venue_pages")]
+    venue_map: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
-impl Pages {
-    fn from_string(s: &String) -> Self {
-        let mut pages = s
-            .split(|c: char| !c.is_ascii_digit())
-            .filter_map(|i| i.parse::<i64>().ok());
-        let from = pages.next();
-        let to = pages.next();
-        Pages { from, to }
-    }
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print one line per entry, without converting them: key, type, year,
+    /// first author and title, a dash standing for the missing ones
+    List(ListArgs),
 }
 
-impl Display for Pages {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        let to_str: String = self.to.map(|p| p.to_string()).unwrap_or("".to_string());
-        let from_str: String = self.from.map(|p| p.to_string()).unwrap_or("".to_string());
-        write!(f, "page:\n  from: {}\n  to: {}\n", from_str, to_str)
-    }
+#[derive(clap::Args, Debug)]
+struct ListArgs {
+    /// The bibtex files
+    #[arg(required = true)]
+    files: Vec<String>,
+
+    /// Entry type to list; repeat it for several
+    #[arg(long = "type", value_name = "TYPE")]
+    types: Vec<String>,
+
+    /// Year of the entries to list, or range of years, `2019..2021`
+    #[arg(long, value_parser = year_range)]
+    year: Option<RangeInclusive<i64>>,
+
+    /// Text in the family name of the first author of the entries to list
+    #[arg(long)]
+    author: Option<String>,
+
+    /// Order of the entries
+    #[arg(long, value_enum, default_value_t = SortBy::File)]
+    sort: SortBy,
+
+    /// How the entries are printed
+    #[arg(long, value_enum, default_value_t = ListFormat::Table)]
+    format: ListFormat,
 }
 
-#[derive(Debug)]
-struct Series {
-    series: Option<i64>,
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum SortBy {
+    /// As in the files
+    File,
+    Key,
+    /// Oldest first
+    Year,
+    Type,
+    Author,
 }
 
-impl From<Option<&String>> for Series {
-    fn from(s: Option<&String>) -> Series {
-        Series {
-            series: s.map_or(None, |i| i.parse::<i64>().ok()),
+impl From<SortBy> for ListOrder {
+    fn from(s: SortBy) -> ListOrder {
+        match s {
+            SortBy::File => ListOrder::File,
+            SortBy::Key => ListOrder::Key,
+            SortBy::Year => ListOrder::Year,
+            SortBy::Type => ListOrder::Type,
+            SortBy::Author => ListOrder::Author,
         }
     }
 }
 
-impl Display for Series {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(
-            f,
-            "series: {}\n",
-            self.series
-                .clone()
-                .map(|i| i.to_string())
-                .unwrap_or("".to_string())
-        )
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum ListFormat {
+    /// Aligned columns under a header
+    Table,
+    /// An array of objects, the missing values being null
+    Json,
+}
+
+fn year_range(s: &str) -> Result<RangeInclusive<i64>, String> {
+    let (from, to) = s.split_once("..").unwrap_or((s, s));
+    match (from.trim().parse(), to.trim().parse()) {
+        (Ok(from), Ok(to)) if from <= to => Ok(from..=to),
+        _ => Err("expected a year or a range of years, 2019..2021".to_string()),
     }
 }
 
-struct Authors {
-    authors: Vec<String>,
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum Format {
+    /// One Hugo page per entry
+    Hugo,
+    /// A single Markdown list of citations, newest first
+    MarkdownList,
+    /// An HTML list of citations, newest first
+    Html,
+    /// A single YAML list of all the entries, for Hugo's `data/` directory
+    DataYaml,
+    /// A single Markdown document with a section per entry, newest first
+    Document,
 }
 
-impl Authors {
-    fn from_string(s: &String) -> Self {
-        let s = s.replace("\n", " ");
-        let authors = s
-            .split(" and ")
-            .map(str::to_string)
-            .map(|s| {
-                s.split(',')
-                    .rev()
-                    .fold("".to_string(), |mut acc, s| {
-                        acc.push(' ');
-                        acc.push_str(s);
-                        acc
-                    })
-                    .trim()
-                    .to_string()
-            })
-            .collect();
-        Authors { authors }
+impl Format {
+    /// Name of the file written in the output directory by the formats
+    /// producing a single document.
+    fn file_name(self) -> &'static str {
+        match self {
+            Format::Hugo => "publications.md",
+            Format::MarkdownList => "publications.md",
+            Format::Html => "publications.html",
+            Format::DataYaml => "publications.yaml",
+            Format::Document => "publications.md",
+        }
     }
 }
 
-impl Display for Authors {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(f, "authors:\n")?;
-        for a in &self.authors {
-            write!(f, "  - \"{}\"\n", a)?;
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum InputFormatArg {
+    Bibtex,
+    CslJson,
+    Ris,
+}
+
+impl From<InputFormatArg> for InputFormat {
+    fn from(f: InputFormatArg) -> InputFormat {
+        match f {
+            InputFormatArg::Bibtex => InputFormat::Bibtex,
+            InputFormatArg::CslJson => InputFormat::CslJson,
+            InputFormatArg::Ris => InputFormat::Ris,
         }
-        Ok(())
     }
 }
 
-struct Volume {
-    nb: Option<i64>,
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum StdinFormat {
+    /// A whole file, in the `--input-format`
+    File,
+    /// A single BibTeX entry
+    Entry,
 }
 
-impl From<Option<&String>> for Volume {
-    fn from(s: Option<&String>) -> Self {
-        Volume {
-            nb: s.map(|s| s.parse::<i64>().ok()).flatten(),
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum MergeBy {
+    /// Same citation key
+    Key,
+    /// Same DOI
+    Doi,
+}
+
+impl From<MergeBy> for MergeOn {
+    fn from(m: MergeBy) -> MergeOn {
+        match m {
+            MergeBy::Key => MergeOn::Key,
+            MergeBy::Doi => MergeOn::Doi,
         }
     }
 }
 
-impl Display for Volume {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(f, "volume: ")?;
-        match self.nb {
-            Some(n) => write!(f, "{}\n", n),
-            None => write!(f, "\n"),
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum SiteTarget {
+    Hugo,
+    Zola,
+    Jekyll,
+}
+
+impl From<SiteTarget> for Target {
+    fn from(t: SiteTarget) -> Target {
+        match t {
+            SiteTarget::Hugo => Target::Hugo,
+            SiteTarget::Zola => Target::Zola,
+            SiteTarget::Jekyll => Target::Jekyll,
         }
     }
 }
 
-struct Doi {
-    s: String,
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum EmitParts {
+    FrontMatter,
+    Body,
+    Both,
 }
 
-impl From<Option<&String>> for Doi {
-    fn from(s: Option<&String>) -> Doi {
-        Doi {
-            s: s.map(|s| s.clone()).unwrap_or(String::new()),
+impl From<EmitParts> for Emit {
+    fn from(e: EmitParts) -> Emit {
+        match e {
+            EmitParts::FrontMatter => Emit::FrontMatter,
+            EmitParts::Body => Emit::Body,
+            EmitParts::Both => Emit::Both,
         }
     }
 }
 
-impl Display for Doi {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(f, "doi: \"{}\"\n", self.s)
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum PageExtension {
+    Md,
+    Yaml,
+    Json,
+}
+
+impl From<PageExtension> for Extension {
+    fn from(e: PageExtension) -> Extension {
+        match e {
+            PageExtension::Md => Extension::Md,
+            PageExtension::Yaml => Extension::Yaml,
+            PageExtension::Json => Extension::Json,
+        }
     }
 }
 
-struct Year {
-    year: i64,
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum YamlQuotes {
+    Double,
+    Single,
+    Auto,
 }
 
-impl From<&str> for Year {
-    fn from(s: &str) -> Year {
-        Year {
-            year: s.parse::<i64>().unwrap(),
+impl From<YamlQuotes> for Quotes {
+    fn from(q: YamlQuotes) -> Quotes {
+        match q {
+            YamlQuotes::Double => Quotes::Double,
+            YamlQuotes::Single => Quotes::Single,
+            YamlQuotes::Auto => Quotes::Auto,
         }
     }
 }
 
-impl Display for Year {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(f, "year: {}\n", self.year)
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum CitationLocale {
+    En,
+    Fr,
+    De,
+}
+
+impl From<CitationLocale> for Locale {
+    fn from(l: CitationLocale) -> Locale {
+        match l {
+            CitationLocale::En => Locale::En,
+            CitationLocale::Fr => Locale::Fr,
+            CitationLocale::De => Locale::De,
+        }
     }
 }
 
-struct Title {
-    title: String,
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum Newline {
+    Lf,
+    Crlf,
 }
 
-impl From<&String> for Title {
-    fn from(s: &String) -> Title {
-        Title { title: s.clone() }
+impl From<Newline> for LineEnding {
+    fn from(n: Newline) -> LineEnding {
+        match n {
+            Newline::Lf => LineEnding::Lf,
+            Newline::Crlf => LineEnding::CrLf,
+        }
     }
 }
 
-impl Display for Title {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(f, "title: \"{}\"\n", self.title)
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum AbstractPunctuation {
+    Smart,
+    Ascii,
+    Keep,
+}
+
+impl From<AbstractPunctuation> for Punctuation {
+    fn from(p: AbstractPunctuation) -> Punctuation {
+        match p {
+            AbstractPunctuation::Smart => Punctuation::Smart,
+            AbstractPunctuation::Ascii => Punctuation::Ascii,
+            AbstractPunctuation::Keep => Punctuation::Keep,
+        }
     }
 }
 
-struct Abstract {
-    abs: String,
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum PreferVenue {
+    Journal,
+    Booktitle,
 }
 
-impl From<&String> for Abstract {
-    fn from(s: &String) -> Abstract {
-        Abstract { abs: s.clone() }
+impl From<PreferVenue> for Venue {
+    fn from(v: PreferVenue) -> Venue {
+        match v {
+            PreferVenue::Journal => Venue::Journal,
+            PreferVenue::Booktitle => Venue::Booktitle,
+        }
     }
 }
 
-impl Display for Abstract {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(f, "{}\n", self.abs)
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum LinkFrom {
+    /// The DOI resolver link
+    Doi,
+    /// The arXiv abstract page of the `eprint`
+    Arxiv,
+    /// The `url` tag
+    Url,
+}
+
+impl From<LinkFrom> for LinkSource {
+    fn from(l: LinkFrom) -> LinkSource {
+        match l {
+            LinkFrom::Doi => LinkSource::Doi,
+            LinkFrom::Arxiv => LinkSource::Arxiv,
+            LinkFrom::Url => LinkSource::Url,
+        }
     }
 }
 
-enum Place {
-    Journal(Name),
-    Conference(Name),
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum Alias {
+    Doi,
+    Key,
+    Both,
 }
 
-impl Display for Place {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        match self {
-            Place::Journal(n) => write!(f, "journal:\n  name: \"{}\"\n  shortname: \"\"\n", n),
-            Place::Conference(n) => {
-                write!(f, "conference:\n  name: \"{}\"\n  shortname: \"\"\n", n)
-            }
+impl From<Alias> for AliasFrom {
+    fn from(a: Alias) -> AliasFrom {
+        match a {
+            Alias::Doi => AliasFrom::Doi,
+            Alias::Key => AliasFrom::Key,
+            Alias::Both => AliasFrom::Both,
         }
     }
 }
 
-struct Name {
-    name: String,
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum ReportFormat {
+    Text,
+    Json,
 }
 
-impl From<&String> for Name {
-    fn from(s: &String) -> Name {
-        Name { name: s.clone() }
-    }
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum GroupBy {
+    Year,
 }
 
-impl Display for Name {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(f, "{}", self.name)
+/// Parses the --duplicate-threshold, a similarity from 0 to 1.
+fn threshold(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(t) if (0.0..=1.0).contains(&t) => Ok(t),
+        _ => Err("expected a number from 0 to 1".to_string()),
     }
 }
 
-struct Url {
-    link: String,
+/// How a run failed, each failure having its exit code; a success exits
+/// with 0. Codes 0 to 4 are the documented contract, which 5 extends for
+/// the output, so that scripts can tell a full disk from a bad input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Failure {
+    /// Warnings were reported under `--fail-on-warning`, or the pages differ
+    /// from the ones `--diff` compares with
+    Flagged = 1,
+    /// Some entries could not be parsed or converted and were left out
+    FailedEntries = 2,
+    /// The bibtex file cannot be read or parsed
+    Input = 3,
+    /// The command line or the configuration is invalid
+    Usage = 4,
+    /// The output cannot be written
+    Output = 5,
 }
 
-impl From<&String> for Url {
-    fn from(s: &String) -> Url {
-        Url { link: s.clone() }
-    }
+/// Conversion settings from the flags, completed by the configuration.
+fn options(args: &Args, config: Config) -> Result<Options, String> {
+    Options::builder()
+        .eventtitle(!args.no_eventtitle)
+        .categories(!args.no_categories)
+        .category_names(config.categories)
+        .workshop_keywords(
+            config
+                .workshop_keywords
+                .unwrap_or_else(|| vec!["workshop".to_string()]),
+        )
+        .venue_kinds(config.venue_kinds)
+        .stamp(args.stamp.then(timestamp))
+        .skip_types(args.skip_types.clone())
+        .skip_unknown_types(args.skip_unknown_types)
+        .target(args.target)
+        .emit(args.emit)
+        .extension(args.extension.map(Extension::from))
+        .yaml_quotes(Quotes::from(args.yaml_quotes))
+        .locale(Locale::from(args.locale))
+        .line_ending(LineEnding::from(args.line_ending))
+        .author_marker(args.author_marker)
+        .initials(args.initials)
+        .detect_awards(args.detect_awards)
+        .languages(args.languages.clone())
+        .language_suffix(args.language_suffix.clone())
+        .feature_awards(args.feature_awards)
+        .abstract_punctuation(args.abstract_punctuation)
+        .keep_abstract_markup(args.keep_abstract_markup)
+        .max_abstract_chars(args.max_abstract_chars)
+        .max_abstract_words(args.max_abstract_words)
+        .ellipsis(args.abstract_ellipsis.clone())
+        .bundle(args.bundle)
+        .prefer_venue(args.prefer_venue.map(Venue::from))
+        .url_preference(
+            args.url_preference
+                .iter()
+                .map(|&l| LinkSource::from(l))
+                .collect::<Vec<_>>(),
+        )
+        .other_links(args.other_links)
+        .provenance_comments(args.provenance_comments)
+        .alias_from(args.alias_from.map(AliasFrom::from))
+        .summary_sentences(args.summary_sentences)
+        .wpm(args.wpm)
+        .clean_proceedings(args.clean_proceedings)
+        .legacy_series_number(args.legacy_series_number)
+        .recover(!args.no_recover)
+        .years(args.min_year..=args.max_year.unwrap_or(current_year() + 2))
+        .max_page_span(args.max_page_span)
+        .max_field_length(args.max_field_length)
+        .cache_dir(cache_dir(args))
+        .fix_pages(args.fix_pages)
+        .title(TitleRules {
+            ascii_quotes: args.ascii_quotes || config.title.ascii_quotes,
+            ..config.title
+        })
+        .checks(config.check)
+        .fields(FieldMap {
+            omit: config
+                .fields
+                .omit
+                .into_iter()
+                .chain(args.omit_fields.iter().cloned())
+                .collect(),
+            ..config.fields
+        })
+        .exclude_keys(
+            config
+                .exclude_keys
+                .into_iter()
+                .chain(args.exclude_keys.iter().cloned())
+                .collect::<Vec<_>>(),
+        )
+        .ignore(match &args.ignore_file {
+            Some(path) => IgnoreList::load(path)?,
+            None => IgnoreList::default(),
+        })
+        .select_keys(args.select_keys.clone())
+        .build()
 }
 
-impl Display for Url {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(f, "www: \"{}\"\n", self.link)
+fn main() -> ExitCode {
+    let args = match Args::try_parse() {
+        Ok(args) => args,
+        Err(e) => {
+            let _ = e.print();
+            return if e.use_stderr() {
+                ExitCode::from(Failure::Usage as u8)
+            } else {
+                ExitCode::SUCCESS
+            };
+        }
+    };
+    if args.timings {
+        timings::enable();
+    }
+    if args.message_format == ReportFormat::Json {
+        diagnostics::print_json();
+    }
+    let result = if args.quiet {
+        new_page::quietly(|| run(&args))
+    } else {
+        progress::enable();
+        run(&args)
+    };
+    if args.timings {
+        eprint!("{}", timings::take());
+    }
+    match result {
+        Ok(()) if args.fail_on_warning && new_page::warnings() > 0 => {
+            ExitCode::from(Failure::Flagged as u8)
+        }
+        Ok(()) => ExitCode::SUCCESS,
+        Err(failure) => ExitCode::from(failure as u8),
     }
 }
 
-struct Publisher {
-    publi: Option<String>,
-}
+fn run(args: &Args) -> Result<(), Failure> {
+    if args.schema {
+        println!("{:#}", new_page::schema::schema());
+        return Ok(());
+    }
+    if let Some(Command::List(list)) = &args.command {
+        return run_list(args, list);
+    }
+    let default_config = PathBuf::from(config::DEFAULT_PATH);
+    let config = match &args.config {
+        Some(path) => Config::load(path),
+        None if default_config.exists() => Config::load(&default_config),
+        None => Ok(Config::default()),
+    }
+    .map_err(|e| {
+        eprintln!("Cannot read the configuration {}", e);
+        Failure::Usage
+    })?;
+    let inputs = input_paths(args)?;
+    let mut opts = options(args, config).map_err(|e| {
+        eprintln!("Invalid options: {}", e);
+        Failure::Usage
+    })?;
+    if let (Some(dir), Format::Hugo) = (&args.output_dir, args.format) {
+        conventions::check(dir, &mut opts, args.adopt_conventions);
+    }
+    if args.manifest.is_some() && args.format != Format::Hugo {
+        eprintln!("Invalid options: --manifest lists the pages of --format hugo");
+        return Err(Failure::Usage);
+    }
+    // Before reading and converting the entries, only picked from at the end.
+    if args.interactive {
+        interactive::check_terminal().map_err(|e| {
+            eprintln!("Cannot select entries: {}", e);
+            Failure::Usage
+        })?;
+    }
+
+    let stdin = ["-".to_string()];
+    let paths = match (inputs.as_slice(), args.stdin_format) {
+        ([], None) if args.from_clipboard => &[],
+        ([], None) => {
+            eprintln!("No file provided");
+            return Err(Failure::Usage);
+        }
+        ([], Some(_)) => &stdin[..],
+        (paths, _) => paths,
+    };
+    let mut input = String::new();
+    if args.from_clipboard {
+        input = clipboard::read()
+            .and_then(|content| clipboard::bibtex(&content))
+            .map_err(|e| {
+                eprintln!("Cannot read the clipboard: {}", e);
+                Failure::Input
+            })?;
+    }
+    // The files of the entries, when there are several to tell apart.
+    let mut files = Files::default();
+    for path in paths {
+        let content = match path.as_str() {
+            "-" => read_stdin(args)?,
+            path => read_input(path, args)?,
+        };
+        if paths.len() > 1 {
+            files.add(path, &content);
+        }
+        // The ignore file next to an input applies to its entries only.
+        let discovered = match (&args.ignore_file, path.as_str()) {
+            (None, "-") | (Some(_), _) => None,
+            (None, path) => ignore::discover(Path::new(path)),
+        };
+        if let Some(ignore) = discovered {
+            let list = IgnoreList::load(&ignore).map_err(|e| {
+                eprintln!("Invalid options: {}", e);
+                Failure::Usage
+            })?;
+            opts.ignore.scope(&content, list);
+        }
+        input.push_str(&content);
+        input.push('\n');
+    }
+    let overrides = match &args.merge_overrides {
+        Some(path) => read_input(path, args)?,
+        None => String::new(),
+    };
+    let path = match args.from_clipboard {
+        true => "the clipboard".to_string(),
+        false => args.file_path.join(", "),
+    };
 
-impl From<Option<&String>> for Publisher {
-    fn from(s: Option<&String>) -> Publisher {
-        Publisher {
-            publi: s.map(String::from),
+    if args.check {
+        let violations = check(&input, &overrides, args.merge_on.into(), &opts)
+            .map_err(|e| parse_failure(args, &path, &e))?;
+        return match violations {
+            0 => Ok(()),
+            _ => Err(Failure::Flagged),
+        };
+    }
+    if let Some(key) = &args.print_entry {
+        let inspection = inspect(&input, &overrides, args.merge_on.into(), key, &opts)
+            .map_err(|e| parse_failure(args, &path, &e))?;
+        let Some(mut inspection) = inspection else {
+            eprintln!("No entry {} in {}", key, path);
+            return Err(Failure::Usage);
+        };
+        if let Some(file) = files.get(key) {
+            inspection.provenance.in_file(file);
+        }
+        match args.print_entry_format {
+            ReportFormat::Text => print!("{}", inspection),
+            ReportFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&inspection).unwrap())
+            }
         }
+        return Ok(());
+    }
+    let (mut papers, mut summary) =
+        new_page::convert_with_overrides(&input, &overrides, args.merge_on.into(), &opts)
+            .map_err(|e| parse_failure(args, &path, &e))?;
+    files.locate(&mut papers);
+    let manifest = publish(args, &opts, papers, &mut summary)?;
+    if summary.failed > 0 {
+        return Err(Failure::FailedEntries);
+    }
+    match (manifest, &args.manifest) {
+        (Some(manifest), Some(path)) => manifest.write(path).map_err(|e| {
+            eprintln!("Cannot write {}", e);
+            Failure::Output
+        }),
+        _ => Ok(()),
     }
 }
 
-impl Display for Publisher {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(f, "publisher: ")?;
-        match &self.publi {
-            Some(n) => write!(f, "\"{}\"\n", n),
-            None => write!(f, "\n"),
+/// The input files: the ones given with --file-path, the directories among
+/// them replaced by the .bib files found in them.
+fn input_paths(args: &Args) -> Result<Vec<String>, Failure> {
+    let discovery = Discovery {
+        recursive: args.recursive,
+        max_depth: args.max_depth,
+        skip: match args.skip_path.is_empty() {
+            true => Discovery::default().skip,
+            false => args.skip_path.clone(),
+        },
+    };
+    let mut paths = Vec::new();
+    for path in &args.file_path {
+        if !Path::new(path).is_dir() {
+            paths.push(path.clone());
+            continue;
+        }
+        let files = bib_files(Path::new(path), &discovery).map_err(|e| {
+            eprintln!("Cannot read {}", e);
+            Failure::Input
+        })?;
+        if files.is_empty() {
+            eprintln!("Cannot read {}: no .bib file found", path);
+            return Err(Failure::Input);
         }
+        paths.extend(files.iter().map(|f| f.display().to_string()));
     }
+    Ok(paths)
 }
 
-struct Paper {
-    auth: Authors,
-    pages: Pages,
-    vol: Volume,
-    year: Year,
-    doi: Doi,
-    title: Title,
-    place: Place,
-    url: Url,
-    abs: Abstract,
-    series: Series,
-    publi: Publisher,
+/// Reports that the input at `path` does not parse, or that the entry the
+/// error is about does not convert, in the format of the warnings.
+fn parse_failure(args: &Args, path: &str, e: &new_page::Error) -> Failure {
+    match (args.message_format, e.key()) {
+        (ReportFormat::Text, Some(_)) => eprintln!("Cannot convert {}", e),
+        (ReportFormat::Text, None) => eprintln!("Cannot parse {}: {}", path, e),
+        (ReportFormat::Json, _) => eprintln!("{}", serde_json::to_string(e).unwrap()),
+    }
+    match e.key() {
+        Some(_) => Failure::FailedEntries,
+        None => Failure::Input,
+    }
 }
 
-impl Display for Paper {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(f, "---\n")?;
-        write!(f, "{}", self.auth)?;
-        write!(f, "{}", self.pages)?;
-        write!(f, "{}", self.vol)?;
-        write!(f, "{}", self.series)?;
-        write!(f, "{}", self.place)?;
-        write!(f, "{}", self.title)?;
-        write!(f, "{}", self.publi)?;
-        write!(f, "{}", self.year)?;
-        write!(f, "{}", self.doi)?;
-        write!(f, "{}", self.url)?; // Not accepted by hugo
-        write!(f, "---\n")?;
-        write!(f, "{}\n", self.abs)
+/// Prints the entries of the files of `list`, read as the flags say.
+fn run_list(args: &Args, list: &ListArgs) -> Result<(), Failure> {
+    let mut input = String::new();
+    for path in &list.files {
+        input.push_str(&read_input(path, args)?);
+        input.push('\n');
+    }
+    let opts = Options::builder()
+        .recover(!args.no_recover)
+        .max_field_length(args.max_field_length)
+        .build()
+        .map_err(|e| {
+            eprintln!("Invalid options: {}", e);
+            Failure::Usage
+        })?;
+    let filter = ListFilter {
+        types: list.types.clone(),
+        years: list.year.clone(),
+        author: list.author.clone(),
+    };
+    let entries = listing::list(&input, &filter, list.sort.into(), &opts)
+        .map_err(|e| parse_failure(args, &list.files.join(", "), &e))?;
+    match list.format {
+        ListFormat::Table => print!("{}", listing::table(&entries)),
+        ListFormat::Json => println!("{}", serde_json::to_string_pretty(&entries).unwrap()),
     }
+    Ok(())
 }
 
-impl From<&Bibliography> for Paper {
-    fn from(b: &Bibliography) -> Self {
-        let tags = b.tags();
-        //println!("{:#?}", tags);
-        let conf = tags
-            .get("booktitle")
-            .map(|j| Place::Conference(Name::from(j)));
-        let place = match tags
-            .get("journal")
-            .or(tags.get("journaltitle"))
-            .map(|j| Place::Journal(Name::from(j))) 
-            {
-                Some(j) => j,
-                None => conf.unwrap(),
-            };
-        let series = Series::from(tags.get("series").or(tags.get("number")));
+/// The directory caching the parsed inputs, with `--parse-cache` and
+/// without `--no-cache`.
+fn cache_dir(args: &Args) -> Option<PathBuf> {
+    (args.parse_cache && !args.no_cache).then(|| {
+        args.cache_dir
+            .clone()
+            .unwrap_or_else(Fetcher::default_cache_dir)
+            .join(new_page::cache::SUBDIRECTORY)
+    })
+}
 
-        let date = tags.get("date");
-        let year =
-        if let Some(s) = date {
-            s.split('-').next().unwrap()
-        } else {
-            tags.get("year").unwrap()
-        };
+/// The error reported when `what` is larger than `--max-input-size`.
+fn too_large(what: &str, args: &Args) -> Failure {
+    eprintln!(
+        "Cannot read {}: larger than the limit of {} MB; raise it with --max-input-size",
+        what, args.max_input_size
+    );
+    Failure::Input
+}
 
-        Paper {
-            auth: Authors::from_string(tags.get("author").unwrap()),
-            pages: tags
-                .get("pages")
-                .map(|s| Pages::from_string(s))
-                .unwrap_or(Pages::default()),
-            vol: Volume::from(tags.get("volume")),
-            doi: Doi::from(tags.get("doi")),
-            year: Year::from(year),
-            title: Title::from(tags.get("title").unwrap()),
-            place,
-            url: Url::from(tags.get("url").unwrap()),
-            abs: Abstract::from(tags.get("abstract").unwrap_or(&"".to_string())),
-            series,
-            publi: Publisher::from(tags.get("publisher")),
-        }
+/// Reads the standard input as BibTeX, as the `--stdin-format` says.
+fn read_stdin(args: &Args) -> Result<String, Failure> {
+    let limit = args.max_input_size.saturating_mul(1 << 20);
+    // One byte more than the limit tells whether it is exceeded.
+    let content =
+        std::io::read_to_string(std::io::stdin().take(limit.saturating_add(1))).map_err(|e| {
+            eprintln!("Cannot read the standard input: {}", e);
+            Failure::Input
+        })?;
+    if content.len() as u64 > limit {
+        return Err(too_large("the standard input", args));
     }
+    let content = match args.stdin_format {
+        Some(StdinFormat::Entry) => entry_fragment(&content),
+        _ => args
+            .input_format
+            .map_or(InputFormat::detect(Path::new("-"), &content), Into::into)
+            .to_bibtex(&content),
+    };
+    content.map_err(|e| {
+        eprintln!("Cannot read the standard input: {}", e);
+        Failure::Input
+    })
 }
 
-#[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
-struct Args {
-    /// The path of the bibtex file
-    #[arg(short, long)]
-    file_path: Option<String>,
+/// Reads the file at `path` as BibTeX, converting it from its format,
+/// unless it is larger than `--max-input-size`.
+fn read_input(path: &str, args: &Args) -> Result<String, Failure> {
+    let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    if size > args.max_input_size.saturating_mul(1 << 20) {
+        return Err(too_large(path, args));
+    }
+    let content = timings::time("reading", || std::fs::read_to_string(path)).map_err(|e| {
+        eprintln!("Cannot read {}: {}", path, e);
+        Failure::Input
+    })?;
+    let format = match args.input_format {
+        Some(format) => format.into(),
+        None => InputFormat::detect(Path::new(path), &content),
+    };
+    timings::time("import", || format.to_bibtex(&content)).map_err(|e| {
+        eprintln!("Cannot read {}: {}", path, e);
+        Failure::Input
+    })
 }
 
-fn main() -> Result<(), ()> {
-    let args = Args::parse();
+/// Enriches the converted papers and writes them as the command line asks,
+/// returning, with --manifest, the manifest of the written pages. The pages
+/// left out because they do not verify count as failed in `summary`, which
+/// is printed once they are known.
+fn publish(
+    args: &Args,
+    opts: &Options,
+    mut papers: Vec<Paper>,
+    summary: &mut Summary,
+) -> Result<Option<Manifest>, Failure> {
+    if let Some(pattern) = &args.regen_keys {
+        let map = keys::regenerate(&mut papers, pattern);
+        eprint!("{}", map);
+        if let Some(path) = &args.key_map {
+            write_file(path, &map.to_string()).map_err(|e| {
+                eprintln!("Cannot write {}", e);
+                Failure::Output
+            })?;
+        }
+    }
+    if args.merge_versions {
+        papers = duplicates::merge_versions(papers);
+    }
+
+    let mut fetcher = Fetcher::new(
+        args.cache_dir
+            .clone()
+            .unwrap_or_else(Fetcher::default_cache_dir),
+        args.offline,
+    );
+    if args.enrich_dblp {
+        timings::time("enrichment: dblp", || {
+            new_page::dblp::enrich(&mut papers, &mut fetcher)
+        });
+    }
+    if args.enrich_arxiv {
+        timings::time("enrichment: arxiv", || {
+            new_page::arxiv::enrich(&mut papers, &mut fetcher, args.enrich_overwrite)
+        });
+    }
+    if args.citation_counts {
+        let max_age = Duration::from_secs(args.citation_counts_max_age * 24 * 3600);
+        timings::time("enrichment: citations", || {
+            new_page::citations::enrich(&mut papers, &mut fetcher, max_age)
+        });
+    }
 
-    let path = match args.file_path {
-        Some(p) => p,
-        None => {
-            eprintln!("No file provided");
-            return Err(());
+    // Only the pages have a front matter to verify.
+    if !args.no_verify && args.format == Format::Hugo {
+        let invalid = timings::time("verification", || verify::retain_valid(&mut papers, opts));
+        summary.converted -= invalid;
+        summary.failed += invalid;
+    }
+    // A single piped entry needs no summary.
+    if args.stdin_format != Some(StdinFormat::Entry) {
+        eprintln!("{}", summary);
+    }
+
+    if args.find_duplicates || args.link_versions {
+        let groups = duplicates::find(&papers, args.duplicate_threshold);
+        eprint!(
+            "{}",
+            duplicates::Report {
+                papers: &papers,
+                groups: &groups
+            }
+        );
+        if args.link_versions {
+            duplicates::link_versions(&mut papers, &groups);
         }
-    };
-    let input = std::fs::read_to_string(path).unwrap();
+    }
+
+    if args.interactive {
+        let generated = match &args.output_dir {
+            Some(dir) => existing_pages(&papers, dir, opts.bundle),
+            None => Vec::new(),
+        };
+        papers = select(papers, &generated).map_err(|e| {
+            eprintln!("Cannot select entries: {}", e);
+            Failure::Usage
+        })?;
+    }
+
+    if let Some(dir) = &args.venue_pages {
+        let map = match &args.venue_map {
+            Some(path) => VenueMap::load(path).map_err(|e| {
+                eprintln!("Cannot read the venue map {}", e);
+                Failure::Usage
+            })?,
+            None => VenueMap::default(),
+        };
+        let groups = venues::group(&papers, &map);
+        eprint!("{}", venues::report(&groups));
+        venues::write_venue_pages(&groups, dir, opts.yaml_quotes).map_err(|e| {
+            eprintln!("Cannot write {}", e);
+            Failure::Output
+        })?;
+    }
+
+    if let Some(dir) = &args.diff {
+        let diff = compare(&papers, dir, opts).map_err(|e| {
+            eprintln!("Cannot read {}: {}", dir.display(), e);
+            Failure::Usage
+        })?;
+        match args.diff_format {
+            ReportFormat::Text => print!("{}", diff),
+            ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&diff).unwrap()),
+        }
+        return if diff.is_clean() {
+            Ok(None)
+        } else {
+            Err(Failure::Flagged)
+        };
+    }
 
-    let bibtex = Bibtex::parse(&input).unwrap();
-    let entries = bibtex.bibliographies();
+    if let (Some(dir), Format::Hugo) = (&args.output_dir, args.format) {
+        if papers.len() > args.max_pages {
+            eprintln!(
+                "Cannot write {} pages in {}: more than the limit of {}; raise it with --max-pages",
+                papers.len(),
+                dir.display(),
+                args.max_pages
+            );
+            return Err(Failure::Output);
+        }
+        let pages = timings::time("rendering", || rendered_pages(&papers, opts));
+        let manifest = args.manifest.as_ref().map(|_| {
+            let run = Run::new(&args.file_path, opts.stamp.clone());
+            Manifest::of(&papers, &pages, run)
+        });
+        return timings::time("writing", || write_rendered(&pages, dir))
+            .map(|()| manifest)
+            .map_err(|e| {
+                eprintln!("Cannot write {}", e);
+                Failure::Output
+            });
+    }
 
-    entries
-        .iter()
-        .map(Paper::from)
-        .for_each(|p| println!("{}", p));
+    let group_by_year = args.group_by == Some(GroupBy::Year);
+    let out = match args.format {
+        Format::Hugo => timings::time("rendering", || new_page::render(&papers, opts)),
+        Format::MarkdownList => {
+            sort_newest_first(&mut papers);
+            timings::time("rendering", || markdown_list(&papers, group_by_year))
+        }
+        Format::Html => {
+            sort_newest_first(&mut papers);
+            timings::time("rendering", || {
+                html(&papers, group_by_year, args.html_standalone)
+            })
+        }
+        Format::Document => {
+            sort_newest_first(&mut papers);
+            timings::time("rendering", || {
+                document(
+                    &papers,
+                    &args.document_title,
+                    &today(),
+                    args.toc,
+                    opts.yaml_quotes,
+                )
+            })
+        }
+        Format::DataYaml => {
+            match timings::time("rendering", || data_yaml(&papers, opts.yaml_quotes)) {
+                Ok(yaml) => yaml,
+                Err(e) => {
+                    eprintln!("Cannot serialize the bibliography: {}", e);
+                    return Err(Failure::Output);
+                }
+            }
+        }
+    };
+    let out = normalize(&out, opts.line_ending);
 
-    return Ok(());
+    timings::time("writing", || match (&args.output, &args.output_dir) {
+        (Some(path), _) if path.as_os_str() != "-" => write_file_atomic(path, &out, args.force)
+            .map_err(|e| {
+                if e.kind() == ErrorKind::AlreadyExists {
+                    eprintln!("Cannot write {}, use --force to replace it", e);
+                } else {
+                    eprintln!("Cannot write {}", e);
+                }
+                Failure::Output
+            }),
+        (None, Some(dir)) => write_file(&dir.join(args.format.file_name()), &out).map_err(|e| {
+            eprintln!("Cannot write {}", e);
+            Failure::Output
+        }),
+        _ => {
+            print!("{}", out);
+            Ok(())
+        }
+    })
+    .map(|()| None)
 }
@@ -0,0 +1,122 @@
+//! The manifest of a run: the page written for each entry, with its slug,
+//! title, year and the hash of its content, for the deployment scripts
+//! building redirects or sitemaps. Written as JSON once all the pages are,
+//! and only when the run succeeded, so that it always describes a complete
+//! output directory.
+
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::output::{write_file_atomic, RenderedPage};
+use crate::{cache, Paper};
+
+/// The run which wrote the pages.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Run {
+    /// Version of the crate
+    pub version: String,
+    /// Input files, as given
+    pub inputs: Vec<String>,
+    /// Time of the run, with `--stamp`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generated: Option<String>,
+}
+
+impl Run {
+    pub fn new(inputs: &[String], generated: Option<String>) -> Run {
+        Run {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            inputs: inputs.to_vec(),
+            generated,
+        }
+    }
+}
+
+/// A page written by the run.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Page {
+    pub key: String,
+    /// Path of the page relative to the output directory
+    pub path: String,
+    /// Name of the page, or of its bundle, without language nor extension
+    pub slug: String,
+    pub title: String,
+    /// None when the entry gives no year
+    pub year: Option<i64>,
+    /// FNV-1a hash of the content, in hexadecimal
+    pub hash: String,
+}
+
+/// The pages of a run, in the order they were written; the translations
+/// of an entry are as many pages of the same key.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Manifest {
+    pub run: Run,
+    pub pages: Vec<Page>,
+}
+
+/// Hash of the content of a page, as the manifest gives it.
+pub fn content_hash(content: &str) -> String {
+    format!("{:016x}", cache::hash(&[content]))
+}
+
+/// Name of the page at `path`: its bundle, `<slug>/index.md`, or its file,
+/// `<slug>.md` or `<slug>.<lang>.md`.
+fn slug(path: &str, lang: Option<&str>) -> String {
+    let path = Path::new(path);
+    let bundle = path.parent().and_then(Path::file_name);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let stem = match lang {
+        Some(lang) => stem.strip_suffix(&format!(".{}", lang)).unwrap_or(&stem),
+        None => &stem,
+    };
+    match bundle {
+        Some(bundle) if stem == "index" => bundle.to_string_lossy().into_owned(),
+        _ => stem.to_string(),
+    }
+}
+
+impl Manifest {
+    /// The manifest of the `pages` rendered for `papers`, in the same order.
+    pub fn of(papers: &[Paper], pages: &[RenderedPage], run: Run) -> Manifest {
+        let pages = papers
+            .iter()
+            .zip(pages)
+            .map(|(paper, page)| Page {
+                key: page.key.clone(),
+                path: page.filename.clone(),
+                slug: slug(&page.filename, paper.lang.as_deref()),
+                title: paper.title.title.clone(),
+                year: paper.year.dated.then_some(paper.year.year),
+                hash: content_hash(&page.content),
+            })
+            .collect();
+        Manifest { run, pages }
+    }
+
+    /// Reads the manifest of an earlier run. Errors mention the path which
+    /// could not be read.
+    pub fn load(path: &Path) -> io::Result<Manifest> {
+        let with_path =
+            |e: io::Error| io::Error::new(e.kind(), format!("{}: {}", path.display(), e));
+        let content = std::fs::read_to_string(path).map_err(with_path)?;
+        serde_json::from_str(&content).map_err(|e| with_path(e.into()))
+    }
+
+    /// Hash of the page at `path`, relative to the output directory, when
+    /// the manifest was written; an unchanged page has the same.
+    pub fn hash(&self, path: &str) -> Option<&str> {
+        self.pages
+            .iter()
+            .find(|p| p.path == path)
+            .map(|p| p.hash.as_str())
+    }
+
+    /// Writes the manifest at `path`, replacing the earlier one in one step.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self).unwrap();
+        write_file_atomic(path, &format!("{}\n", json), true)
+    }
+}
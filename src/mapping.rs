@@ -0,0 +1,145 @@
+//! Renaming and reordering of the front matter keys, for themes expecting
+//! other names than the default ones.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+/// Keys of the front matter, as they are named by default.
+pub const FIELDS: [&str; 54] = [
+    "authors",
+    "authors_equal_contribution",
+    "authors_corresponding",
+    "editors",
+    "translators",
+    "page",
+    "page_display",
+    "volume",
+    "issue",
+    "venue_detail",
+    "series",
+    "journal",
+    "conference",
+    "venue_kind",
+    "also_appeared_in",
+    "proceedings",
+    "event",
+    "title",
+    "title_missing",
+    "publisher",
+    "howpublished",
+    "patent_number",
+    "standard_number",
+    "isbn",
+    "edition",
+    "chapter",
+    "year",
+    "date",
+    "publishDate",
+    "publication_status",
+    "original_language",
+    "original_year",
+    "lastmod",
+    "accessed",
+    "citation_count",
+    "citation_count_date",
+    "doi",
+    "www",
+    "links",
+    "categories",
+    "tags",
+    "award",
+    "featured",
+    "aliases",
+    "other_versions",
+    "earlier_versions",
+    "summary",
+    "word_count",
+    "reading_time",
+    "translated",
+    "abstract_truncated",
+    "bibtex_key",
+    "entry_type",
+    "generated",
+];
+
+/// Front matter keys to rename, to move first and to leave out, under
+/// `[fields]`. The default changes nothing.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct FieldMap {
+    /// Keys written first, in this order; the others follow in their usual
+    /// order
+    pub order: Vec<String>,
+    /// Name written for each renamed key
+    pub rename: BTreeMap<String, String>,
+    /// Keys left out of the front matter
+    pub omit: Vec<String>,
+}
+
+impl FieldMap {
+    /// Rejects the keys which are not front matter keys, listing the valid
+    /// ones.
+    pub fn validate(&self) -> Result<(), String> {
+        match self
+            .order
+            .iter()
+            .chain(self.rename.keys())
+            .chain(&self.omit)
+            .find(|k| !FIELDS.contains(&k.as_str()))
+        {
+            Some(k) => Err(format!(
+                "unknown front matter field `{}`, expected one of {}",
+                k,
+                FIELDS.join(", ")
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Name written for the key `field`.
+    pub fn name<'a>(&'a self, field: &'a str) -> &'a str {
+        self.rename.get(field).map_or(field, String::as_str)
+    }
+
+    /// Renames and reorders the `(key, value)` entries of a front matter,
+    /// dropping the omitted ones.
+    pub fn arrange<T>(&self, mut entries: Vec<(String, T)>) -> Vec<(String, T)> {
+        entries.retain(|(k, _)| !self.omit.contains(k));
+        entries.sort_by_key(|(k, _)| {
+            self.order
+                .iter()
+                .position(|o| o == k)
+                .unwrap_or(self.order.len())
+        });
+        entries
+            .into_iter()
+            .map(|(k, v)| (self.name(&k).to_string(), v))
+            .collect()
+    }
+
+    /// Renames, reorders and drops the top-level keys of YAML front matter
+    /// lines, keeping the nested lines with their key.
+    pub fn arrange_yaml(&self, fields: &str) -> String {
+        let mut entries: Vec<(String, String)> = Vec::new();
+        for line in fields.lines() {
+            match entries.last_mut() {
+                Some((_, rest)) if line.starts_with(' ') => {
+                    rest.push_str(line);
+                    rest.push('\n');
+                }
+                _ => {
+                    let (key, rest) = match line.split_once(':') {
+                        Some((key, rest)) => (key, format!(":{}\n", rest)),
+                        None => (line, "\n".to_string()),
+                    };
+                    entries.push((key.to_string(), rest));
+                }
+            }
+        }
+        self.arrange(entries)
+            .into_iter()
+            .map(|(k, rest)| k + &rest)
+            .collect()
+    }
+}
@@ -0,0 +1,244 @@
+//! HTML and JATS markup in abstracts, as Crossref and some publishers give
+//! them: `<jats:p>` paragraphs, `<sub>` and `<sup>` scripts, `&amp;`
+//! entities. Turned into the text and Markdown of the pages, since the tags
+//! would show literally.
+
+/// Named entities decoded, beyond the numeric ones.
+const ENTITIES: [(&str, &str); 25] = [
+    ("amp", "&"),
+    ("lt", "<"),
+    ("gt", ">"),
+    ("quot", "\""),
+    ("apos", "'"),
+    ("nbsp", "\u{a0}"),
+    ("thinsp", "\u{2009}"),
+    ("ndash", "–"),
+    ("mdash", "—"),
+    ("hellip", "…"),
+    ("minus", "−"),
+    ("times", "×"),
+    ("plusmn", "±"),
+    ("deg", "°"),
+    ("le", "≤"),
+    ("ge", "≥"),
+    ("alpha", "α"),
+    ("beta", "β"),
+    ("gamma", "γ"),
+    ("delta", "δ"),
+    ("epsilon", "ε"),
+    ("lambda", "λ"),
+    ("mu", "μ"),
+    ("pi", "π"),
+    ("sigma", "σ"),
+];
+
+/// Characters having a subscript form, with it.
+const SUBSCRIPTS: [(char, char); 28] = [
+    ('0', '₀'),
+    ('1', '₁'),
+    ('2', '₂'),
+    ('3', '₃'),
+    ('4', '₄'),
+    ('5', '₅'),
+    ('6', '₆'),
+    ('7', '₇'),
+    ('8', '₈'),
+    ('9', '₉'),
+    ('+', '₊'),
+    ('-', '₋'),
+    ('=', '₌'),
+    ('(', '₍'),
+    (')', '₎'),
+    ('a', 'ₐ'),
+    ('e', 'ₑ'),
+    ('h', 'ₕ'),
+    ('i', 'ᵢ'),
+    ('k', 'ₖ'),
+    ('l', 'ₗ'),
+    ('m', 'ₘ'),
+    ('n', 'ₙ'),
+    ('o', 'ₒ'),
+    ('p', 'ₚ'),
+    ('s', 'ₛ'),
+    ('t', 'ₜ'),
+    ('x', 'ₓ'),
+];
+
+/// Characters having a superscript form, with it.
+const SUPERSCRIPTS: [(char, char); 17] = [
+    ('0', '⁰'),
+    ('1', '¹'),
+    ('2', '²'),
+    ('3', '³'),
+    ('4', '⁴'),
+    ('5', '⁵'),
+    ('6', '⁶'),
+    ('7', '⁷'),
+    ('8', '⁸'),
+    ('9', '⁹'),
+    ('+', '⁺'),
+    ('-', '⁻'),
+    ('−', '⁻'),
+    ('=', '⁼'),
+    ('(', '⁽'),
+    (')', '⁾'),
+    ('n', 'ⁿ'),
+];
+
+/// Tags starting a paragraph of their own.
+const BLOCKS: [&str; 8] = [
+    "p",
+    "sec",
+    "div",
+    "title",
+    "br",
+    "li",
+    "list-item",
+    "abstract",
+];
+
+/// A tag at the start of `s`, `<p>`, `</jats:sub>` or `<br/>`: its name,
+/// lowercased and without namespace, whether it closes an element, and its
+/// length.
+fn tag(s: &str) -> Option<(String, bool, usize)> {
+    let rest = s.strip_prefix('<')?;
+    let (closing, rest) = match rest.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    let inner = &rest[..rest.find('>')?];
+    let name = &inner[..inner
+        .find(|c: char| c.is_whitespace() || c == '/')
+        .unwrap_or(inner.len())];
+    let valid = name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ":-_".contains(c))
+        && !inner.contains('<');
+    let local = name.rsplit(':').next().unwrap_or_default().to_lowercase();
+    valid.then(|| (local, closing, 1 + usize::from(closing) + inner.len() + 1))
+}
+
+/// An entity at the start of `s`, `&amp;`, `&#8211;` or `&#x2013;`: its
+/// text and its length.
+fn entity(s: &str) -> Option<(String, usize)> {
+    let end = s.get(..12).unwrap_or(s).find(';')?;
+    let name = s.get(1..end)?;
+    let code = match name.strip_prefix('#') {
+        Some(n) => match n.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok(),
+            None => n.parse().ok(),
+        },
+        None => None,
+    };
+    let text = match code {
+        Some(code) => char::from_u32(code).filter(|c| *c != '\0')?.to_string(),
+        None => ENTITIES.iter().find(|(n, _)| *n == name)?.1.to_string(),
+    };
+    Some((text, end + 1))
+}
+
+/// Whether `s` holds tags or entities.
+pub fn has_markup(s: &str) -> bool {
+    s.char_indices().any(|(i, c)| match c {
+        '<' => tag(&s[i..]).is_some(),
+        '&' => entity(&s[i..]).is_some(),
+        _ => false,
+    })
+}
+
+/// `text` in the scripts of `table`, when all its characters have one, or
+/// else after `marker`, `x^(n+k)`.
+fn script(text: &str, table: &[(char, char)], marker: char) -> String {
+    let mapped: Option<String> = text
+        .chars()
+        .map(|c| table.iter().find(|(from, _)| *from == c).map(|(_, to)| *to))
+        .collect();
+    match mapped {
+        Some(mapped) => mapped,
+        None if text.chars().count() == 1 => format!("{}{}", marker, text),
+        None => format!("{}({})", marker, text),
+    }
+}
+
+/// Ends the paragraph `out` ends with, if any.
+fn paragraph_break(out: &mut String) {
+    let trimmed = out.trim_end().len();
+    out.truncate(trimmed);
+    if !out.is_empty() {
+        out.push_str("\n\n");
+    }
+}
+
+/// The content of the element `name`, written from `start` in `out`, as
+/// Markdown or Unicode.
+fn close(out: &mut String, name: &str, start: usize) {
+    let content = out.split_off(start.min(out.len()));
+    let trimmed = content.trim();
+    match name {
+        _ if trimmed.is_empty() => {}
+        "script" | "style" => {}
+        "sub" => out.push_str(&script(trimmed, &SUBSCRIPTS, '_')),
+        "sup" => out.push_str(&script(trimmed, &SUPERSCRIPTS, '^')),
+        "i" | "em" | "italic" => out.push_str(&format!("*{}*", trimmed)),
+        "b" | "strong" | "bold" => out.push_str(&format!("**{}**", trimmed)),
+        "title" if trimmed.eq_ignore_ascii_case("abstract") => {}
+        "title" => {
+            out.push_str(&format!("**{}**", trimmed));
+            paragraph_break(out);
+        }
+        _ => out.push_str(&content),
+    }
+}
+
+/// `s` without its markup: paragraphs separated by blank lines, sub- and
+/// superscripts in Unicode where they can be, italics and bold in Markdown,
+/// `<script>` and `<style>` elements dropped, the other tags removed,
+/// keeping their text, and the entities decoded. The ASCII whitespace of
+/// each paragraph is collapsed.
+pub fn strip(s: &str) -> String {
+    let mut out = String::new();
+    // Elements converted once closed, with where their content starts.
+    let mut open: Vec<(String, usize)> = Vec::new();
+    let mut i = 0;
+    while let Some(c) = s[i..].chars().next() {
+        if let Some((name, closing, len)) = (c == '<').then(|| tag(&s[i..])).flatten() {
+            if closing {
+                if let Some(at) = open.iter().rposition(|(n, _)| *n == name) {
+                    for (name, start) in open.split_off(at).into_iter().rev() {
+                        close(&mut out, &name, start);
+                    }
+                }
+            }
+            if BLOCKS.contains(&name.as_str()) {
+                // The inline elements left open end with their paragraph.
+                let inline = open
+                    .iter()
+                    .rposition(|(n, _)| BLOCKS.contains(&n.as_str()))
+                    .map_or(0, |at| at + 1);
+                for (name, start) in open.split_off(inline).into_iter().rev() {
+                    close(&mut out, &name, start);
+                }
+                paragraph_break(&mut out);
+            }
+            if !closing && !s[i..i + len].ends_with("/>") {
+                open.push((name, out.len()));
+            }
+            i += len;
+        } else if let Some((text, len)) = (c == '&').then(|| entity(&s[i..])).flatten() {
+            out.push_str(&text);
+            i += len;
+        } else {
+            out.push(c);
+            i += c.len_utf8();
+        }
+    }
+    for (name, start) in open.into_iter().rev() {
+        close(&mut out, &name, start);
+    }
+    out.split("\n\n")
+        .map(|p| p.split_ascii_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
@@ -0,0 +1,87 @@
+//! Overriding the fields of entries with the ones of a hand-curated file,
+//! for bibliographies whose canonical data is exported by a tool.
+
+use nom_bibtex::Bibliography;
+
+use crate::provenance::{Origin, Origins};
+use crate::{warn, Warning};
+
+/// How override entries are matched with the base entries.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum MergeOn {
+    /// Same citation key
+    #[default]
+    Key,
+    /// Same DOI, whatever its form
+    Doi,
+}
+
+/// DOI of an entry, without the resolver or `doi:` prefix and lowercased,
+/// so that its forms compare equal.
+fn doi(b: &Bibliography) -> Option<String> {
+    let doi = b.tags().get("doi")?.trim().to_lowercase();
+    let doi = [
+        "https://doi.org/",
+        "http://doi.org/",
+        "https://dx.doi.org/",
+        "http://dx.doi.org/",
+        "doi:",
+    ]
+    .iter()
+    .find_map(|prefix| doi.strip_prefix(prefix))
+    .unwrap_or(&doi)
+    .to_string();
+    (!doi.is_empty()).then_some(doi)
+}
+
+fn matches(base: &Bibliography, over: &Bibliography, on: MergeOn) -> bool {
+    match on {
+        MergeOn::Key => base.citation_key() == over.citation_key(),
+        MergeOn::Doi => doi(base).is_some_and(|d| Some(d) == doi(over)),
+    }
+}
+
+/// The base entries with the fields of their matching override entries
+/// replacing theirs, in order, followed by the override entries which match
+/// none, converted on their own. The overridden tags are recorded in
+/// `origins`.
+pub fn merge(
+    base: &[Bibliography],
+    overrides: &[Bibliography],
+    on: MergeOn,
+    origins: &mut Origins,
+) -> Vec<Bibliography> {
+    let mut used = vec![false; overrides.len()];
+    let mut merged = Vec::new();
+    for b in base {
+        let mut tags = b.tags().clone();
+        for (i, over) in overrides.iter().enumerate() {
+            if matches(b, over, on) {
+                used[i] = true;
+                for tag in over.tags().keys() {
+                    origins.set(b.citation_key(), tag, Origin::Override);
+                }
+                tags.extend(over.tags().clone());
+            }
+        }
+        merged.push(Bibliography::new(
+            b.entry_type().to_string(),
+            b.citation_key().to_string(),
+            tags,
+        ));
+    }
+    for (over, _) in overrides.iter().zip(used).filter(|(_, used)| !used) {
+        warn(Warning::UnmatchedOverride {
+            key: over.citation_key().to_string(),
+        });
+        for tag in over.tags().keys() {
+            origins.set(over.citation_key(), tag, Origin::Override);
+        }
+        merged.push(Bibliography::new(
+            over.entry_type().to_string(),
+            over.citation_key().to_string(),
+            over.tags().clone(),
+        ));
+    }
+    merged
+}
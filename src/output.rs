@@ -0,0 +1,220 @@
+//! Writing pages to disk.
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::target::Extension;
+use crate::{progress, warn, Options, Paper, Warning};
+
+/// Characters which are not allowed in file names on Windows or which would
+/// create subdirectories.
+const FORBIDDEN_CHARS: &str = "<>:\"/\\|?*";
+
+/// Device names reserved by Windows, whatever the extension.
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Turns a citation key into a name usable as a file name on every platform.
+pub fn sanitize_filename(key: &str) -> String {
+    let name: String = key
+        .chars()
+        .map(|c| {
+            if c.is_control() || FORBIDDEN_CHARS.contains(c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let name = name.trim_end_matches(['.', ' ']);
+    let stem = name.split('.').next().unwrap_or_default();
+    if name.is_empty() {
+        "_".to_string()
+    } else if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        format!("_{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Hands out sanitized file names, suffixing the ones which collide (case
+/// insensitively, as on Windows) in the order they are requested.
+#[derive(Default)]
+pub struct FileNames {
+    pub used: HashSet<String>,
+}
+
+impl FileNames {
+    pub fn claim(&mut self, key: &str) -> String {
+        let base = sanitize_filename(key);
+        let mut name = base.clone();
+        let mut n = 1;
+        while !self.used.insert(name.to_lowercase()) {
+            n += 1;
+            name = format!("{}-{}", base, n);
+        }
+        if n > 1 {
+            warn(Warning::FileNameCollision {
+                key: key.to_string(),
+                name: name.clone(),
+            });
+        }
+        name
+    }
+}
+
+/// Line ending of the generated files.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+/// `content` as the generated files are written: its lines ended by
+/// `ending`, whatever they were ended by, without trailing whitespace, and
+/// the file by exactly one line ending. The content of empty files stays
+/// empty.
+pub fn normalize(content: &str, ending: LineEnding) -> String {
+    let lines: Vec<&str> = content
+        .split('\n')
+        .map(|l| l.trim_end_matches(|c: char| c.is_whitespace()))
+        .collect();
+    let end = lines
+        .iter()
+        .rposition(|l| !l.is_empty())
+        .map_or(0, |i| i + 1);
+    lines[..end]
+        .iter()
+        .map(|l| format!("{}{}", l, ending.as_str()))
+        .collect()
+}
+
+/// A page ready to be written, for the embedders doing the writing.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct RenderedPage {
+    pub key: String,
+    /// Path of the page relative to the content directory
+    pub filename: String,
+    pub content: String,
+}
+
+/// Renders one page per paper, named `<name>.md` or, for page bundles,
+/// `<name>/index.md`, with the extension of the emitted parts, or the one
+/// of the options, and the language of the page, `index.fr.md`. Their
+/// content is normalized, see `normalize`.
+pub fn rendered_pages(papers: &[Paper], opts: &Options) -> Vec<RenderedPage> {
+    let mut names: HashMap<Option<&str>, FileNames> = HashMap::new();
+    papers
+        .iter()
+        .map(|p| {
+            let name = names
+                .entry(p.lang.as_deref())
+                .or_default()
+                .claim(&p.key.key);
+            let extension = opts
+                .extension
+                .map_or(opts.emit.extension(opts.target), Extension::name);
+            let extension = match &p.lang {
+                Some(lang) => format!("{}.{}", lang, extension),
+                None => extension.to_string(),
+            };
+            let path = page_path(Path::new(""), &name, opts.bundle).with_extension(extension);
+            RenderedPage {
+                key: p.key.key.clone(),
+                filename: path.to_string_lossy().into_owned(),
+                content: normalize(&p.render_file(opts), opts.line_ending),
+            }
+        })
+        .collect()
+}
+
+/// Writes the pages of `rendered_pages` in `dir`. Errors mention the path
+/// which could not be written.
+pub fn write_pages(papers: &[Paper], dir: &Path, opts: &Options) -> io::Result<()> {
+    write_rendered(&rendered_pages(papers, opts), dir)
+}
+
+/// Writes the rendered `pages` in `dir`. Errors mention the path which
+/// could not be written.
+pub fn write_rendered(pages: &[RenderedPage], dir: &Path) -> io::Result<()> {
+    progress::start("Writing", pages.len());
+    for page in pages {
+        progress::tick(&page.key);
+        write_file(&dir.join(&page.filename), &page.content)?;
+    }
+    progress::finish();
+    Ok(())
+}
+
+/// Path of the page named `name` in `dir`.
+pub fn page_path(dir: &Path, name: &str, bundle: bool) -> PathBuf {
+    if bundle {
+        dir.join(name).join("index.md")
+    } else {
+        dir.join(format!("{}.md", name))
+    }
+}
+
+/// Tells, for each paper, whether its page already exists in `dir`. Names
+/// are claimed in order, as `write_pages` does.
+pub fn existing_pages(papers: &[Paper], dir: &Path, bundle: bool) -> Vec<bool> {
+    let mut names = FileNames::default();
+    papers
+        .iter()
+        .map(|p| page_path(dir, &names.claim(&p.key.key), bundle).exists())
+        .collect()
+}
+
+/// Writes `content` to `path`, creating the parent directories. Errors
+/// mention the path which could not be written.
+pub fn write_file(path: &Path, content: &str) -> io::Result<()> {
+    path.parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(path, content))
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+}
+
+/// Replaces the content of the file at `path` in one step, writing a
+/// temporary file next to it then renaming it, so that readers never see a
+/// partial document. An existing file is only replaced when `force` is set.
+/// Errors mention the path which could not be written.
+pub fn write_file_atomic(path: &Path, content: &str, force: bool) -> io::Result<()> {
+    let with_path = |e: io::Error| io::Error::new(e.kind(), format!("{}: {}", path.display(), e));
+    if path.is_dir() {
+        return Err(with_path(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "is a directory",
+        )));
+    }
+    if path.exists() && !force {
+        return Err(with_path(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "already exists",
+        )));
+    }
+    let name = path
+        .file_name()
+        .ok_or_else(|| with_path(io::Error::new(io::ErrorKind::InvalidInput, "not a file")))?;
+    let tmp = path.with_file_name(format!(".{}.tmp", name.to_string_lossy()));
+    write_file(&tmp, content)?;
+    std::fs::rename(&tmp, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        with_path(e)
+    })
+}
@@ -0,0 +1,1014 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Error, Formatter};
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+use nom_bibtex::Bibliography;
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::check::Checks;
+use crate::fields::*;
+use crate::ignore::IgnoreList;
+use crate::latex::{decode, Macros};
+use crate::locale::Locale;
+use crate::mapping::FieldMap;
+use crate::markup;
+use crate::output::LineEnding;
+use crate::provenance::{Origin, Provenance};
+use crate::target::{Emit, Extension, Target};
+use crate::text::{
+    clean_proceedings, normalize_title, proceedings_edition, proceedings_year, punctuate, scrub,
+    summarize, truncate, Punctuation, TitleRules,
+};
+use crate::time::current_year;
+use crate::yaml::{string, Quotes, ToYaml, Yaml};
+use crate::{warn, Warning};
+
+/// A bibliography entry, rendered as a Hugo page by its `Display`
+/// implementation.
+pub struct Paper {
+    pub key: BibtexKey,
+    pub kind: EntryType,
+    pub auth: Authors,
+    pub contributions: Contributions,
+    pub editors: Authors,
+    pub translators: Authors,
+    pub pages: Pages,
+    pub vol: Volume,
+    pub issue: Issue,
+    pub year: Year,
+    pub status: PubState,
+    pub doi: Doi,
+    pub title: Title,
+    pub place: Option<Place>,
+    /// Secondary venue, for entries having both a journal and a booktitle
+    pub also: Option<Place>,
+    pub proceedings: Proceedings,
+    /// Conference or workshop, for `@inproceedings`
+    pub venue_kind: Option<VenueKind>,
+    /// Name, place and dates of the conference, for `@inproceedings`
+    pub event: Event,
+    pub url: Url,
+    /// Links to the work other than `url`
+    pub links: Vec<String>,
+    pub categories: Categories,
+    pub keywords: Keywords,
+    /// Language of the page, when pages are written per language
+    pub lang: Option<String>,
+    /// Whether the abstract is in the language of the page, when it is not
+    pub translated: Option<bool>,
+    pub awards: Awards,
+    pub aliases: Aliases,
+    /// Keys of the other versions of the work, such as its preprint
+    pub other_versions: Vec<String>,
+    /// Keys of the earlier versions merged into the page, such as its
+    /// preprint
+    pub earlier_versions: Vec<String>,
+    /// Keys of the other versions the entry names in its `relatedversion`
+    /// tag
+    pub related_versions: Vec<String>,
+    pub summary: Teaser,
+    pub reading: Reading,
+    pub eprint: Option<String>,
+    pub updated: Updated,
+    pub accessed: Accessed,
+    pub dates: Dates,
+    pub citations: Citations,
+    pub abs: Abstract,
+    pub series: Series,
+    pub publi: Publisher,
+    pub howpublished: HowPublished,
+    pub number: DocumentNumber,
+    pub isbn: Isbn,
+    pub edition: Edition,
+    pub chapter: Chapter,
+    pub original: Original,
+    pub stamp: Stamp,
+    /// Language of the citation strings; never rendered in the front matter
+    pub locale: Locale,
+    /// Tags and origins of the values, for debugging; never rendered
+    pub provenance: Provenance,
+}
+
+impl ToYaml for Paper {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        writeln!(f, "---")?;
+        if self.kind.is_standalone() {
+            if !self.auth.authors.is_empty() {
+                write!(f, "{}", Yaml(&self.auth, quotes))?;
+                write!(f, "{}", self.contributions)?;
+            }
+            if !self.editors.authors.is_empty() {
+                write!(f, "{}", Yaml(&self.editors, quotes))?;
+            }
+            if !self.translators.authors.is_empty() {
+                write!(f, "{}", Yaml(&self.translators, quotes))?;
+            }
+            write!(f, "{}", Yaml(&self.title, quotes))?;
+            write!(f, "{}", Yaml(&self.publi, quotes))?;
+            write!(f, "{}", Yaml(&self.howpublished, quotes))?;
+            write!(f, "{}", Yaml(&self.number, quotes))?;
+            write!(f, "{}", Yaml(&self.isbn, quotes))?;
+            if self.kind.has_chapters() {
+                write!(f, "{}", Yaml(&self.edition, quotes))?;
+                write!(f, "{}", Yaml(&self.chapter, quotes))?;
+            }
+            write!(f, "{}", Yaml(&self.series, quotes))?;
+        } else {
+            write!(f, "{}", Yaml(&self.auth, quotes))?;
+            write!(f, "{}", self.contributions)?;
+            if !self.translators.authors.is_empty() {
+                write!(f, "{}", Yaml(&self.translators, quotes))?;
+            }
+            write!(f, "{}", Yaml(&self.pages, quotes))?;
+            write!(f, "{}", self.vol)?;
+            write!(f, "{}", Yaml(&self.issue, quotes))?;
+            if let Some(d) = self.venue_detail() {
+                writeln!(f, "venue_detail: {}", string(d, quotes))?;
+            }
+            write!(f, "{}", Yaml(&self.series, quotes))?;
+            if let Some(place) = &self.place {
+                write!(f, "{}", Yaml(place, quotes))?;
+            }
+            if let Some(kind) = &self.venue_kind {
+                write!(f, "{}", kind)?;
+            }
+            if let Some(also) = &self.also {
+                writeln!(f, "also_appeared_in:")?;
+                for line in also.to_yaml(quotes).lines() {
+                    writeln!(f, "  {}", line)?;
+                }
+            }
+            write!(f, "{}", Yaml(&self.proceedings, quotes))?;
+            write!(f, "{}", Yaml(&self.event, quotes))?;
+            write!(f, "{}", Yaml(&self.title, quotes))?;
+            write!(f, "{}", Yaml(&self.publi, quotes))?;
+            write!(f, "{}", Yaml(&self.howpublished, quotes))?;
+            if self.kind.has_chapters() {
+                write!(f, "{}", Yaml(&self.edition, quotes))?;
+                write!(f, "{}", Yaml(&self.chapter, quotes))?;
+            }
+        }
+        write!(f, "{}", self.year)?;
+        write!(f, "{}", self.dates)?;
+        write!(f, "{}", self.status)?;
+        write!(f, "{}", Yaml(&self.original, quotes))?;
+        write!(f, "{}", Yaml(&self.updated, quotes))?;
+        write!(f, "{}", Yaml(&self.accessed, quotes))?;
+        write!(f, "{}", Yaml(&self.citations, quotes))?;
+        write!(f, "{}", Yaml(&self.doi, quotes))?;
+        write!(f, "{}", Yaml(&self.url, quotes))?; // Not accepted by hugo
+        if !self.links.is_empty() {
+            writeln!(f, "links:")?;
+            for link in &self.links {
+                writeln!(f, "  - {}", string(link, quotes))?;
+            }
+        }
+        write!(f, "{}", Yaml(&self.categories, quotes))?;
+        write!(f, "{}", Yaml(&self.keywords, quotes))?;
+        write!(f, "{}", Yaml(&self.awards, quotes))?;
+        write!(f, "{}", Yaml(&self.aliases, quotes))?;
+        if !self.other_versions.is_empty() {
+            writeln!(f, "other_versions:")?;
+            for key in &self.other_versions {
+                writeln!(f, "  - {}", string(key, quotes))?;
+            }
+        }
+        if !self.earlier_versions.is_empty() {
+            writeln!(f, "earlier_versions:")?;
+            for key in &self.earlier_versions {
+                writeln!(f, "  - {}", string(key, quotes))?;
+            }
+        }
+        write!(f, "{}", Yaml(&self.summary, quotes))?;
+        write!(f, "{}", self.reading)?;
+        if let Some(t) = self.translated {
+            writeln!(f, "translated: {}", t)?;
+        }
+        if self.abs.truncated {
+            writeln!(f, "abstract_truncated: true")?;
+        }
+        write!(f, "{}", Yaml(&self.key, quotes))?;
+        write!(f, "{}", Yaml(&self.kind, quotes))?;
+        write!(f, "{}", Yaml(&self.stamp, quotes))?;
+        writeln!(f, "---")
+    }
+}
+
+/// Same keys, in the same order, as the front matter, with the abstract
+/// under an `abstract` key.
+impl Serialize for Paper {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let mut m = s.serialize_map(None)?;
+        if self.kind.is_standalone() {
+            for names in [&self.auth, &self.editors, &self.translators] {
+                if !names.authors.is_empty() {
+                    m.serialize_entry(names.label, names)?;
+                }
+                if names.label == "authors" {
+                    self.serialize_contributions(&mut m)?;
+                }
+            }
+            m.serialize_entry("title", &self.title)?;
+            if self.title.missing {
+                m.serialize_entry("title_missing", &true)?;
+            }
+            m.serialize_entry("publisher", &self.publi)?;
+            if self.howpublished.how.is_some() {
+                m.serialize_entry("howpublished", &self.howpublished)?;
+            }
+            if let (Some(l), Some(n)) = (self.number.label, &self.number.number) {
+                m.serialize_entry(l, n)?;
+            }
+            m.serialize_entry("isbn", &self.isbn)?;
+            if self.kind.has_chapters() {
+                if self.edition.raw.is_some() {
+                    m.serialize_entry("edition", &self.edition)?;
+                }
+                if self.chapter.chapter.is_some() {
+                    m.serialize_entry("chapter", &self.chapter)?;
+                }
+            }
+            m.serialize_entry("series", &self.series)?;
+        } else {
+            m.serialize_entry("authors", &self.auth)?;
+            self.serialize_contributions(&mut m)?;
+            if !self.translators.authors.is_empty() {
+                m.serialize_entry("translators", &self.translators)?;
+            }
+            if self.pages.from.is_some() || self.pages.display.is_none() {
+                m.serialize_entry("page", &self.pages)?;
+            }
+            if let Some(d) = &self.pages.display {
+                m.serialize_entry("page_display", d)?;
+            }
+            m.serialize_entry("volume", &self.vol)?;
+            if self.issue.issue.is_some() {
+                m.serialize_entry("issue", &self.issue)?;
+            }
+            if let Some(d) = self.venue_detail() {
+                m.serialize_entry("venue_detail", &d)?;
+            }
+            m.serialize_entry("series", &self.series)?;
+            if let Some(place) = &self.place {
+                m.serialize_entry(place.label(), place)?;
+            }
+            if let Some(kind) = &self.venue_kind {
+                m.serialize_entry("venue_kind", kind)?;
+            }
+            if let Some(also) = &self.also {
+                let also: BTreeMap<_, _> = [(also.label(), also)].into();
+                m.serialize_entry("also_appeared_in", &also)?;
+            }
+            if self.proceedings.title.is_some() {
+                m.serialize_entry("proceedings", &self.proceedings)?;
+            }
+            if !self.event.is_empty() {
+                m.serialize_entry("event", &self.event)?;
+            }
+            m.serialize_entry("title", &self.title)?;
+            if self.title.missing {
+                m.serialize_entry("title_missing", &true)?;
+            }
+            m.serialize_entry("publisher", &self.publi)?;
+            if self.howpublished.how.is_some() {
+                m.serialize_entry("howpublished", &self.howpublished)?;
+            }
+            if self.kind.has_chapters() {
+                if self.edition.raw.is_some() {
+                    m.serialize_entry("edition", &self.edition)?;
+                }
+                if self.chapter.chapter.is_some() {
+                    m.serialize_entry("chapter", &self.chapter)?;
+                }
+            }
+        }
+        m.serialize_entry("year", &self.year)?;
+        m.serialize_entry("date", &self.dates.date)?;
+        if let Some(p) = &self.dates.publish {
+            m.serialize_entry("publishDate", p)?;
+        }
+        m.serialize_entry("publication_status", &self.status)?;
+        if let Some(l) = &self.original.language {
+            m.serialize_entry("original_language", l)?;
+        }
+        if let Some(y) = &self.original.year {
+            m.serialize_entry("original_year", y)?;
+        }
+        if let Some(d) = &self.updated.at {
+            m.serialize_entry("lastmod", d)?;
+        }
+        if let Some(d) = &self.accessed.at {
+            m.serialize_entry("accessed", d)?;
+        }
+        if let Some(n) = &self.citations.count {
+            m.serialize_entry("citation_count", n)?;
+            m.serialize_entry("citation_count_date", &self.citations.date)?;
+        }
+        m.serialize_entry("doi", &self.doi)?;
+        m.serialize_entry("www", &self.url)?;
+        if !self.links.is_empty() {
+            m.serialize_entry("links", &self.links)?;
+        }
+        if !self.categories.names.is_empty() {
+            m.serialize_entry("categories", &self.categories)?;
+        }
+        if !self.keywords.tags.is_empty() {
+            m.serialize_entry("tags", &self.keywords)?;
+        }
+        match self.awards.names.as_slice() {
+            [] => {}
+            [award] => m.serialize_entry("award", award)?,
+            awards => m.serialize_entry("award", awards)?,
+        }
+        if self.awards.featured {
+            m.serialize_entry("featured", &true)?;
+        }
+        if !self.aliases.paths.is_empty() {
+            m.serialize_entry("aliases", &self.aliases)?;
+        }
+        if !self.other_versions.is_empty() {
+            m.serialize_entry("other_versions", &self.other_versions)?;
+        }
+        if !self.earlier_versions.is_empty() {
+            m.serialize_entry("earlier_versions", &self.earlier_versions)?;
+        }
+        if let Some(t) = &self.summary.text {
+            m.serialize_entry("summary", t)?;
+        }
+        if let Some(words) = self.reading.words {
+            m.serialize_entry("word_count", &words)?;
+            m.serialize_entry("reading_time", &self.reading.minutes)?;
+        }
+        if let Some(t) = self.translated {
+            m.serialize_entry("translated", &t)?;
+        }
+        if self.abs.truncated {
+            m.serialize_entry("abstract_truncated", &true)?;
+        }
+        m.serialize_entry("bibtex_key", &self.key)?;
+        m.serialize_entry("entry_type", &self.kind)?;
+        if let Some(t) = &self.stamp.at {
+            m.serialize_entry("generated", t)?;
+        }
+        m.serialize_entry("abstract", &self.abs)?;
+        m.end()
+    }
+}
+
+/// The link of the page from the first source of `preference` the entry
+/// has, and the other links of the entry, the preferred ones first.
+fn choose_link(
+    preference: &[LinkSource],
+    doi: Option<&String>,
+    eprint: Option<&str>,
+    url: Url,
+) -> (Url, Vec<String>) {
+    let link = |source: &LinkSource| match source {
+        LinkSource::Doi => doi.map(|d| format!("https://doi.org/{}", d.trim())),
+        LinkSource::Arxiv => eprint.map(|e| format!("https://arxiv.org/abs/{}", e)),
+        LinkSource::Url => Some(url.link.clone()).filter(|l| !l.is_empty()),
+    };
+    let chosen = preference.iter().find_map(link).unwrap_or_default();
+    let mut links: Vec<String> = Vec::new();
+    for l in preference
+        .iter()
+        .chain(&[LinkSource::Doi, LinkSource::Arxiv, LinkSource::Url])
+        .filter_map(link)
+    {
+        if l != chosen && !links.contains(&l) {
+            links.push(l);
+        }
+    }
+    (Url { link: chosen }, links)
+}
+
+/// Tag the chosen `link` was made of, if any.
+fn link_tag(
+    link: &Url,
+    tags: &HashMap<String, String>,
+    eprint: Option<&str>,
+) -> Option<&'static str> {
+    let link = &link.link;
+    if link.is_empty() {
+        None
+    } else if tags
+        .get("doi")
+        .is_some_and(|d| *link == format!("https://doi.org/{}", d.trim()))
+    {
+        Some("doi")
+    } else if eprint.is_some_and(|e| *link == format!("https://arxiv.org/abs/{}", e)) {
+        Some("eprint")
+    } else if tags.contains_key("url") {
+        Some("url")
+    } else {
+        Some("howpublished")
+    }
+}
+
+impl Paper {
+    /// Serializes the contribution flags which are set.
+    fn serialize_contributions<M: SerializeMap>(&self, m: &mut M) -> Result<(), M::Error> {
+        for (label, flags) in [
+            ("authors_equal_contribution", &self.contributions.equal),
+            ("authors_corresponding", &self.contributions.corresponding),
+        ] {
+            if flags.contains(&true) {
+                m.serialize_entry(label, flags)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts an entry, expanding the text `macros` of the file's
+    /// preambles in its title and abstract. Fails when the entry has no
+    /// author or no venue though its type asks for them.
+    pub fn new(b: &Bibliography, opts: &Options, macros: &Macros) -> Result<Self, crate::Error> {
+        Paper::in_language(b, opts, macros, None)
+    }
+
+    /// Converts an entry for its page in `lang`, taking the title and the
+    /// abstract from the tags with the language suffix, `title-fr` and
+    /// `abstract-fr`, when the entry has them.
+    pub fn in_language(
+        b: &Bibliography,
+        opts: &Options,
+        macros: &Macros,
+        lang: Option<&str>,
+    ) -> Result<Self, crate::Error> {
+        let mut controls = Vec::new();
+        let mut scrubbed: HashMap<String, String> = b
+            .tags()
+            .iter()
+            .map(|(k, v)| {
+                let (v, found) = scrub(v);
+                if found {
+                    controls.push(k.as_str());
+                }
+                (k.clone(), v)
+            })
+            .collect();
+        if !controls.is_empty() {
+            controls.sort();
+            warn(Warning::ControlCharacters {
+                key: b.citation_key().to_string(),
+                fields: controls.iter().map(|c| c.to_string()).collect(),
+            });
+        }
+        let mut provenance = Provenance::of(&scrubbed);
+        let mut translated = None;
+        if let Some(lang) = lang {
+            let suffix = opts.language_suffix.replace("{}", lang);
+            let title_tag = format!("title{}", suffix);
+            if let Some(title) = scrubbed.get(&title_tag) {
+                scrubbed.insert("title".into(), title.clone());
+                provenance.set("title", Some(&title_tag), Origin::Entry);
+            }
+            let abstract_tag = format!("abstract{}", suffix);
+            match scrubbed.get(&abstract_tag) {
+                Some(abs) => {
+                    scrubbed.insert("abstract".into(), abs.clone());
+                    for field in ["summary", "word_count", "reading_time", "abstract"] {
+                        provenance.set(field, Some(&abstract_tag), Origin::Entry);
+                    }
+                }
+                // The abstract of the entry is in the default language.
+                None if opts.languages.first().is_some_and(|l| l != lang) => {
+                    translated = Some(false)
+                }
+                None => {}
+            }
+        }
+        let tags = &scrubbed;
+        let kind = EntryType::from(b);
+        if !kind.has_chapters() && tags.contains_key("chapter") {
+            warn(Warning::ignored(
+                b.citation_key(),
+                "chapter",
+                format!("ignoring chapter on {} entry", kind.kind),
+            ));
+        }
+        let booktitle = tags.get("booktitle");
+        let eventtitle = tags.get("eventtitle").filter(|_| opts.eventtitle);
+        let cleaned = booktitle
+            .filter(|_| opts.clean_proceedings && eventtitle.is_none())
+            .map(|b| clean_proceedings(b))
+            .filter(|c| Some(c) != booktitle);
+        let proceedings =
+            Proceedings::from(booktitle.filter(|_| eventtitle.is_some() || cleaned.is_some()));
+        let conference = eventtitle.or(cleaned.as_ref()).or(booktitle);
+        if eventtitle.is_some() {
+            provenance.set("conference", Some("eventtitle"), Origin::Entry);
+        }
+        let (event, venue_kind) = match kind.kind.as_str() {
+            "inproceedings" => (
+                Event::of(b.citation_key(), conference, tags),
+                Some(VenueKind::detect(
+                    b.citation_key(),
+                    tags,
+                    &opts.workshop_keywords,
+                    &opts.venue_kinds,
+                )),
+            ),
+            _ => (Event::default(), None),
+        };
+        let conf = conference.map(|j| {
+            let edition = booktitle.and_then(|b| proceedings_edition(b));
+            Place::Conference(Name::from(j), Location::from(tags.get("venue")), edition)
+        });
+        // The title of a special issue, with its subtitle.
+        let issue_title = tags
+            .get("issuetitle")
+            .map(|t| match tags.get("issuesubtitle") {
+                Some(sub) => format!("{}: {}", t, sub),
+                None => t.clone(),
+            });
+        let journal = tags
+            .get("journal")
+            .or(tags.get("journaltitle"))
+            .map(|j| Place::Journal(Name::from(j), issue_title));
+        let (mut place, also) = match (journal, conf) {
+            _ if kind.is_standalone() => (None, None),
+            // Whatever names a venue, best-effort.
+            (journal, conf) if !kind.is_known() => {
+                let other = ["institution", "organization"]
+                    .iter()
+                    .find_map(|t| tags.get(*t))
+                    .map(|n| Place::Other(Name::from(n)));
+                (journal.or(conf).or(other), None)
+            }
+            (Some(journal), Some(conf)) => {
+                warn(Warning::TwoVenues {
+                    key: b.citation_key().to_string(),
+                });
+                let prefer = opts.prefer_venue.unwrap_or(match kind.kind.as_str() {
+                    "inproceedings" | "conference" => Venue::Booktitle,
+                    _ => Venue::Journal,
+                });
+                match prefer {
+                    Venue::Journal => (Some(journal), Some(conf)),
+                    Venue::Booktitle => (Some(conf), Some(journal)),
+                }
+            }
+            (None, None) => {
+                return Err(crate::Error::MissingVenue {
+                    key: b.citation_key().to_string(),
+                })
+            }
+            (journal, conf) => (journal.or(conf), None),
+        };
+        if matches!(place, Some(Place::Conference(..))) && tags.contains_key("issuetitle") {
+            warn(Warning::ignored(
+                b.citation_key(),
+                "issuetitle",
+                "ignoring issuetitle on a conference entry",
+            ));
+        }
+        let series = if kind.is_standalone() {
+            Series::named(tags.get("series"))
+        } else {
+            Series::from(
+                tags.get("series")
+                    .or(tags.get("number").filter(|_| opts.legacy_series_number)),
+            )
+        };
+        // The acronym of an ACM-style series names the conference.
+        if let (Some(acronym), Some(Place::Conference(name, ..))) = (&series.acronym, &mut place) {
+            name.short.get_or_insert_with(|| acronym.clone());
+        }
+
+        let year = match tags.get("date") {
+            Some(s) => Some(s.split('-').next().unwrap()),
+            None => tags.get("year").map(String::as_str),
+        };
+        let year = match year {
+            Some(s) => Year::lenient(b.citation_key(), s),
+            None => match booktitle
+                .and_then(|b| proceedings_year(b))
+                .filter(|y| opts.years.contains(y))
+            {
+                Some(y) => Year::from_booktitle(b.citation_key(), y),
+                None => Year::missing(b.citation_key()),
+            },
+        };
+        if !opts.years.contains(&year.year) {
+            warn(Warning::invalid(
+                b.citation_key(),
+                "year",
+                format!(
+                    "year {} is outside of {}..={}",
+                    year.year,
+                    opts.years.start(),
+                    opts.years.end()
+                ),
+            ));
+        }
+
+        if year.dated && !series.is_of(year.year) {
+            warn(Warning::invalid(
+                b.citation_key(),
+                "series",
+                format!(
+                    "series \"{}\" is not of year {}",
+                    series.name.as_deref().unwrap_or_default(),
+                    year.year
+                ),
+            ));
+        }
+
+        let dates = Dates::of(b.citation_key(), &year, tags);
+
+        let title = match tags.get("title").filter(|t| !t.trim().is_empty()) {
+            Some(t) => Title::from(&normalize_title(&macros.expand(t), &opts.title)),
+            None => {
+                let title = Title::placeholder(b.citation_key());
+                warn(Warning::missing(
+                    b.citation_key(),
+                    "title",
+                    format!("no title, naming the page \"{}\"", title.title),
+                ));
+                title
+            }
+        };
+
+        let author = match tags.get("author") {
+            Some(author) => author,
+            None if kind.is_standalone() || !kind.is_known() => "",
+            None => {
+                return Err(crate::Error::MissingAuthor {
+                    key: b.citation_key().to_string(),
+                })
+            }
+        };
+        let (author, marked) = strip_markers(author, opts.author_marker);
+        let names = |label, s: &str| match opts.initials {
+            true => Authors::abbreviated(label, b.citation_key(), s),
+            false => Authors::deduplicated(label, b.citation_key(), s),
+        };
+        let auth = names("authors", &author);
+        let contributions =
+            Contributions::of(b.citation_key(), &auth, &marked, tags.get("corresponding"));
+
+        let howpublished = tags
+            .get("howpublished")
+            .map(|h| strip_url_macro(h).to_string());
+        let link = howpublished.as_ref().filter(|h| is_link(h));
+        let url = Url::checked(b.citation_key(), tags.get("url").or(link));
+        let eprint = arxiv_eprint(tags);
+        let (url, links) = choose_link(
+            &opts.url_preference,
+            tags.get("doi").filter(|d| !d.is_empty()),
+            eprint.as_deref(),
+            url,
+        );
+        let howpublished = HowPublished::from(howpublished.as_ref().filter(|h| !is_link(h)));
+        if let Some(tag) = link_tag(&url, tags, eprint.as_deref()) {
+            provenance.set("www", Some(tag), Origin::Entry);
+        }
+
+        // Decoded once, for the body and the fields derived from it.
+        let abs = tags
+            .get("abstract")
+            .map(|a| {
+                let abs = decode(&macros.expand(a));
+                let abs = match !opts.keep_abstract_markup && markup::has_markup(&abs) {
+                    true => {
+                        warn(Warning::invalid(
+                            b.citation_key(),
+                            "abstract",
+                            "holds HTML or JATS markup, converted to text; check the result",
+                        ));
+                        markup::strip(&abs)
+                    }
+                    false => abs,
+                };
+                punctuate(&abs, opts.abstract_punctuation)
+            })
+            .unwrap_or_default();
+
+        let doi = tags.get("doi").filter(|d| !d.is_empty());
+        let mut aliases = Aliases::default();
+        if let (Some(AliasFrom::Doi | AliasFrom::Both), Some(doi)) = (opts.alias_from, doi) {
+            aliases.paths.push(Aliases::doi(doi));
+        }
+        if let Some(AliasFrom::Key | AliasFrom::Both) = opts.alias_from {
+            aliases.paths.push(Aliases::key(b.citation_key()));
+        }
+
+        Ok(Paper {
+            key: BibtexKey::from(b.citation_key()),
+            auth,
+            contributions,
+            editors: names("editors", tags.get("editor").map_or("", |e| e)),
+            translators: match (opts.initials, tags.get("translator")) {
+                (true, Some(t)) => Authors::with_label("translators", &abbreviate_names(t)),
+                (_, t) => Authors::with_label("translators", t.map_or("", |t| t)),
+            },
+            pages: tags
+                .get("pages")
+                .map(|s| Pages::checked(b.citation_key(), s, opts.fix_pages, opts.max_page_span))
+                .unwrap_or_default()
+                .with_count(b.citation_key(), tags.get("numpages"), opts.max_page_span),
+            vol: Volume::from(tags.get("volume")),
+            issue: Issue {
+                issue: tags
+                    .get("issue")
+                    .or(tags.get("number").filter(|_| kind.kind == "article"))
+                    .cloned(),
+            },
+            doi: Doi::from(tags.get("doi")),
+            year,
+            status: PubState::detect(tags),
+            title,
+            place,
+            also,
+            proceedings,
+            venue_kind,
+            event,
+            url,
+            links: match opts.other_links {
+                true => links,
+                false => Vec::new(),
+            },
+            categories: match opts.categories {
+                true => Categories::of(
+                    match venue_kind {
+                        Some(VenueKind::Workshop) => "workshop",
+                        _ => &kind.kind,
+                    },
+                    &opts.category_names,
+                ),
+                false => Categories::default(),
+            },
+            keywords: Keywords::without(tags.get("keywords"), crate::NOSITE),
+            lang: lang.map(String::from),
+            translated,
+            awards: Awards::of(
+                tags.get("award")
+                    .map(|a| decode(&macros.expand(a)))
+                    .as_deref(),
+                tags.get("note")
+                    .map(|n| decode(&macros.expand(n)))
+                    .as_deref(),
+                opts.detect_awards,
+                opts.feature_awards,
+            ),
+            aliases,
+            other_versions: Vec::new(),
+            earlier_versions: Vec::new(),
+            related_versions: tags
+                .get("relatedversion")
+                .map(|r| {
+                    r.split(',')
+                        .map(str::trim)
+                        .filter(|k| !k.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            summary: Teaser {
+                text: summarize(&abs, opts.summary_sentences),
+            },
+            reading: Reading::of(&abs, opts.wpm),
+            eprint,
+            updated: Updated { at: None },
+            accessed: Accessed::checked(b.citation_key(), tags.get("urldate")),
+            dates,
+            citations: Citations::default(),
+            abs: match truncate(&abs, opts.max_abstract_chars, opts.max_abstract_words) {
+                Some(cut) => Abstract {
+                    abs: format!("{}{}", cut, opts.ellipsis),
+                    truncated: true,
+                },
+                None => Abstract::from(&abs),
+            },
+            series,
+            publi: Publisher::from(
+                tags.get("publisher")
+                    .or(tags.get("organization"))
+                    .or(tags.get("institution"))
+                    .or(tags.get("school")),
+            ),
+            howpublished,
+            number: DocumentNumber {
+                label: kind.number_label(),
+                number: tags.get("number").cloned(),
+            },
+            isbn: Isbn::from(tags.get("isbn")),
+            edition: Edition::from(tags.get("edition")),
+            chapter: Chapter::from(tags.get("chapter")),
+            original: Original {
+                language: tags.get("origlanguage").cloned(),
+                year: tags
+                    .get("origdate")
+                    .and_then(|d| d.split('-').next())
+                    .or(tags.get("origyear").map(String::as_str))
+                    .and_then(|y| y.trim().parse::<i64>().ok()),
+            },
+            stamp: Stamp {
+                at: opts.stamp.clone(),
+            },
+            locale: opts.locale,
+            kind,
+            provenance,
+        })
+    }
+}
+
+/// Conversion settings, mirroring the command line flags. Embedders may
+/// deserialize them, the missing settings taking their default value.
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Options {
+    /// Prefer `eventtitle` over `booktitle` for the conference name
+    pub eventtitle: bool,
+    /// Emit the category of the entry type under `categories`
+    pub categories: bool,
+    /// Category of the entry types, overriding the default ones
+    pub category_names: BTreeMap<String, String>,
+    /// Words of the conference names, or keywords, marking workshop papers
+    pub workshop_keywords: Vec<String>,
+    /// Kind of venue of the papers of proceedings, by citation key,
+    /// overriding the one `workshop_keywords` detect
+    pub venue_kinds: BTreeMap<String, VenueKind>,
+    /// Generation time written in every page, shared by the whole run
+    pub stamp: Option<String>,
+    /// Entry types left out of the conversion, e.g. `proceedings`
+    pub skip_types: Vec<String>,
+    /// Leave out the entries whose type is not one of the known ones,
+    /// instead of converting them generically
+    pub skip_unknown_types: bool,
+    /// Static site generator the pages are written for
+    pub target: Target,
+    /// Parts of the pages which are rendered
+    pub emit: Emit,
+    /// Content of the files written one per entry; `None` writes the pages
+    /// with the extension of their parts
+    pub extension: Option<Extension>,
+    /// Character ending the names of authors who contributed equally
+    pub author_marker: char,
+    /// Cut the given names of the authors, editors and translators to their
+    /// initials
+    pub initials: bool,
+    /// Languages of the pages, one page per entry and language; the
+    /// untranslated abstracts are in the first one
+    pub languages: Vec<String>,
+    /// Suffix of the tags holding the translations, `{}` standing for the
+    /// language
+    pub language_suffix: String,
+    /// Look for awards in the notes of the entries without `award` tag
+    pub detect_awards: bool,
+    /// Feature the pages of awarded works
+    pub feature_awards: bool,
+    /// Typography of the dashes and quotes of abstracts
+    pub abstract_punctuation: Punctuation,
+    /// Leave the HTML or JATS markup of the abstracts as it is
+    pub keep_abstract_markup: bool,
+    /// Length of the abstract body in characters, beyond which it is cut at
+    /// a sentence boundary
+    pub max_abstract_chars: Option<usize>,
+    /// Same in words
+    pub max_abstract_words: Option<usize>,
+    /// Marker ending a cut abstract
+    pub ellipsis: String,
+    /// Names, order and omissions of the front matter keys
+    pub fields: FieldMap,
+    /// Directory of the cache of the parsed inputs, not cached when `None`
+    pub cache_dir: Option<PathBuf>,
+    /// Write pages as `<key>/index.md` page bundles
+    pub bundle: bool,
+    /// Number of sentences of the abstract kept in the summary
+    pub summary_sentences: usize,
+    /// Name conferences after their proceedings title stripped of its
+    /// boilerplate, when there is no `eventtitle`
+    pub clean_proceedings: bool,
+    /// Take the `number` of the entries without `series` for their series,
+    /// as older versions did, besides their issue
+    pub legacy_series_number: bool,
+    /// Convert the entries which parse when the file as a whole does not
+    pub recover: bool,
+    /// Plausible publication years, the others are warned about
+    pub years: RangeInclusive<i64>,
+    /// Page ranges spanning more pages are warned about
+    pub max_page_span: i64,
+    /// Expand the abbreviated end pages, as in `1153--67`
+    pub fix_pages: bool,
+    /// Length in bytes of the tag values beyond which they are cut, with a
+    /// warning
+    pub max_field_length: usize,
+    /// Clean-ups applied to the titles
+    pub title: TitleRules,
+    /// Rules of `check::check`
+    pub checks: Checks,
+    /// Reading speed, in words per minute, of the `reading_time`
+    pub wpm: usize,
+    /// Identifiers the `aliases` of the pages are made of
+    pub alias_from: Option<AliasFrom>,
+    /// Citation keys of the entries kept out of the website
+    pub exclude_keys: Vec<String>,
+    /// Entries never converted, from the ignore file
+    pub ignore: IgnoreList,
+    /// Citation keys of the only entries to convert, if any; they are
+    /// converted even when the ignore file lists them
+    pub select_keys: Vec<String>,
+    /// Primary venue of entries having both a journal and a booktitle,
+    /// otherwise decided by the entry type
+    pub prefer_venue: Option<Venue>,
+    /// Sources of the `www` link, the first one the entry has being taken
+    pub url_preference: Vec<LinkSource>,
+    /// List the links not taken for `www` under `links`
+    pub other_links: bool,
+    /// Follow each front matter key with a comment naming the tag, and the
+    /// file or service, its value comes from
+    pub provenance_comments: bool,
+    /// Quotes of the strings of the YAML front matter, data file and venue pages
+    pub yaml_quotes: Quotes,
+    /// Language of the months and phrases of the citation strings
+    pub locale: Locale,
+    /// Line ending of the generated files
+    pub line_ending: LineEnding,
+}
+
+/// Identifiers from which stable redirect paths are made.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum AliasFrom {
+    Doi,
+    Key,
+    Both,
+}
+
+/// Source of the link of a page.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum LinkSource {
+    /// The DOI, through the doi.org resolver
+    Doi,
+    /// The arXiv `eprint`, as its abstract page
+    Arxiv,
+    /// The `url` tag, or a link in `howpublished`
+    Url,
+}
+
+/// Tag naming the venue of an entry.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Venue {
+    Journal,
+    Booktitle,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            eventtitle: true,
+            categories: true,
+            category_names: BTreeMap::new(),
+            workshop_keywords: vec!["workshop".to_string()],
+            venue_kinds: BTreeMap::new(),
+            stamp: None,
+            skip_types: Vec::new(),
+            skip_unknown_types: false,
+            target: Target::Hugo,
+            emit: Emit::Both,
+            extension: None,
+            author_marker: '*',
+            initials: false,
+            languages: Vec::new(),
+            language_suffix: "-{}".to_string(),
+            detect_awards: false,
+            feature_awards: false,
+            abstract_punctuation: Punctuation::Keep,
+            keep_abstract_markup: false,
+            max_abstract_chars: None,
+            max_abstract_words: None,
+            ellipsis: "…".to_string(),
+            fields: FieldMap::default(),
+            cache_dir: None,
+            bundle: false,
+            prefer_venue: None,
+            url_preference: vec![LinkSource::Url],
+            other_links: false,
+            provenance_comments: false,
+            yaml_quotes: Quotes::Double,
+            locale: Locale::En,
+            line_ending: LineEnding::Lf,
+            exclude_keys: Vec::new(),
+            ignore: IgnoreList::default(),
+            select_keys: Vec::new(),
+            alias_from: None,
+            summary_sentences: 2,
+            wpm: 200,
+            title: TitleRules::default(),
+            checks: Checks::default(),
+            clean_proceedings: false,
+            legacy_series_number: false,
+            recover: true,
+            years: 1900..=current_year() + 2,
+            max_page_span: 2000,
+            fix_pages: false,
+            max_field_length: 1 << 20,
+        }
+    }
+}
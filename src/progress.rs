@@ -0,0 +1,131 @@
+//! Progress of the long steps on the standard error: a bar redrawn in place
+//! on a terminal, a line every `EVERY` entries otherwise. Nothing is shown
+//! unless the command line enables it.
+
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Entries between two progress lines when the standard error is not a
+/// terminal.
+pub const EVERY: usize = 100;
+
+/// Width of the bar, in characters.
+const WIDTH: usize = 30;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static CURRENT: Mutex<Option<Progress>> = Mutex::new(None);
+
+/// Progress of one step over `total` entries.
+#[derive(Debug)]
+pub struct Progress {
+    label: String,
+    total: usize,
+    done: usize,
+    start: Instant,
+    /// Whether the bar is redrawn in place
+    terminal: bool,
+    /// Bar currently on the terminal
+    drawn: Option<String>,
+}
+
+impl Progress {
+    /// Progress of the step `label`, shown as a bar when on a `terminal`.
+    pub fn new(label: &str, total: usize, terminal: bool) -> Progress {
+        Progress {
+            label: label.to_string(),
+            total,
+            done: 0,
+            start: Instant::now(),
+            terminal,
+            drawn: None,
+        }
+    }
+
+    /// Counts the entry `key` as processed, returning the status to show,
+    /// if any.
+    pub fn advance(&mut self, key: &str) -> Option<String> {
+        self.done += 1;
+        if self.terminal {
+            let filled = (self.done * WIDTH).checked_div(self.total).unwrap_or(WIDTH);
+            let filled = filled.min(WIDTH);
+            Some(format!(
+                "{} [{}{}] {}/{} {}",
+                self.label,
+                "#".repeat(filled),
+                "-".repeat(WIDTH - filled),
+                self.done,
+                self.total,
+                key
+            ))
+        } else if self.done.is_multiple_of(EVERY) {
+            Some(format!("{}: {}/{}", self.label, self.done, self.total))
+        } else {
+            None
+        }
+    }
+
+    /// Closing line of the step.
+    pub fn summary(&self, elapsed: Duration) -> String {
+        format!(
+            "{}: {} entries in {:.1}s",
+            self.label,
+            self.done,
+            elapsed.as_secs_f64()
+        )
+    }
+}
+
+/// Shows the progress of the steps started from now on.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Starts showing the progress of a step over `total` entries.
+pub fn start(label: &str, total: usize) {
+    if ENABLED.load(Ordering::Relaxed) && total > 0 {
+        let terminal = io::stderr().is_terminal();
+        *lock() = Some(Progress::new(label, total, terminal));
+    }
+}
+
+/// Counts the entry `key` of the current step as processed.
+pub fn tick(key: &str) {
+    let mut current = lock();
+    if let Some(p) = current.as_mut() {
+        match p.advance(key) {
+            Some(status) if p.terminal => {
+                eprint!("\r\x1b[K{}", status);
+                let _ = io::stderr().flush();
+                p.drawn = Some(status);
+            }
+            Some(status) => eprintln!("{}", status),
+            None => {}
+        }
+    }
+}
+
+/// Ends the current step with the time it took.
+pub fn finish() {
+    if let Some(p) = lock().take() {
+        let clear = if p.terminal { "\r\x1b[K" } else { "" };
+        eprintln!("{}{}", clear, p.summary(p.start.elapsed()));
+    }
+}
+
+/// Prints a report line above the bar, which is then redrawn.
+pub(crate) fn report(line: &str) {
+    match lock().as_ref().and_then(|p| p.drawn.as_ref()) {
+        Some(bar) => {
+            eprintln!("\r\x1b[K{}", line);
+            eprint!("{}", bar);
+        }
+        None => eprintln!("{}", line),
+    }
+}
+
+/// The current step, even if a thread panicked while holding it.
+fn lock() -> std::sync::MutexGuard<'static, Option<Progress>> {
+    CURRENT.lock().unwrap_or_else(|e| e.into_inner())
+}
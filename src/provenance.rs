@@ -0,0 +1,265 @@
+//! Where the values of the pages come from: the tag each front matter key
+//! is read from, and whether the entry itself, an override, an `@xdata`
+//! bundle or a web service gave it, to find out where a wrong value came
+//! in. Kept on every page, since it costs little, but only shown by
+//! `--print-entry` and the provenance comments.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Error, Formatter};
+
+use nom_bibtex::{Bibtex, Entry};
+use serde::{Deserialize, Serialize};
+
+use crate::mapping::{FieldMap, FIELDS};
+use crate::{quietly, recover, Paper};
+
+/// What gave a tag, or a value, of an entry.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case", tag = "origin", content = "name")]
+pub enum Origin {
+    /// The entry, in the input files
+    #[default]
+    Entry,
+    /// The entry, in this input file, when several are converted together
+    File(String),
+    /// The matching entry of the overrides file
+    Override,
+    /// The `@xdata` bundle of this key
+    Xdata(String),
+    /// The web service of this name, when enriching the pages
+    Service(String),
+}
+
+/// The origins of the tags of the entries which do not come from the
+/// entries themselves, by citation key and tag.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Origins(HashMap<String, HashMap<String, Origin>>);
+
+impl Origins {
+    pub fn set(&mut self, key: &str, tag: &str, origin: Origin) {
+        self.0
+            .entry(key.to_string())
+            .or_default()
+            .insert(tag.to_string(), origin);
+    }
+
+    /// Origin of the tag `tag` of the entry `key`.
+    pub fn get(&self, key: &str, tag: &str) -> Origin {
+        self.0
+            .get(key)
+            .and_then(|tags| tags.get(tag))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Where a value of a page comes from.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Source {
+    /// Tag it is read from; none for the values services give
+    pub tag: Option<String>,
+    #[serde(flatten)]
+    pub origin: Origin,
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let tag = self.tag.as_deref().unwrap_or_default();
+        match &self.origin {
+            Origin::Entry => write!(f, "{} of the entry", tag),
+            Origin::File(file) => write!(f, "{} of the entry in {}", tag, file),
+            Origin::Override => write!(f, "{} of the overrides", tag),
+            Origin::Xdata(key) => write!(f, "{} of @xdata {}", tag, key),
+            Origin::Service(service) => write!(f, "{}", service),
+        }
+    }
+}
+
+/// Tags the front matter keys are read from, the first one the entry has
+/// being taken. The keys made of several tags, or of none, are left out;
+/// `www` depends on the preferred link, see `Paper::in_language`.
+const TAGS: [(&str, &[&str]); 36] = [
+    ("authors", &["author"]),
+    ("authors_equal_contribution", &["author"]),
+    ("authors_corresponding", &["corresponding", "author"]),
+    ("editors", &["editor"]),
+    ("translators", &["translator"]),
+    ("page", &["pages", "numpages"]),
+    ("page_display", &["pages", "numpages"]),
+    ("volume", &["volume"]),
+    ("issue", &["issue", "number"]),
+    ("series", &["series", "number"]),
+    ("journal", &["journal", "journaltitle"]),
+    ("conference", &["booktitle"]),
+    ("proceedings", &["booktitle"]),
+    ("event", &["eventtitle", "booktitle"]),
+    ("title", &["title"]),
+    (
+        "publisher",
+        &["publisher", "organization", "institution", "school"],
+    ),
+    ("howpublished", &["howpublished"]),
+    ("patent_number", &["number"]),
+    ("standard_number", &["number"]),
+    ("isbn", &["isbn"]),
+    ("edition", &["edition"]),
+    ("chapter", &["chapter"]),
+    ("year", &["date", "year", "booktitle"]),
+    ("date", &["date", "year", "booktitle"]),
+    ("publication_status", &["pubstate"]),
+    ("original_language", &["origlanguage"]),
+    ("original_year", &["origdate", "origyear"]),
+    ("accessed", &["urldate"]),
+    ("doi", &["doi"]),
+    ("tags", &["keywords"]),
+    ("award", &["award", "note"]),
+    ("featured", &["award", "note"]),
+    ("summary", &["abstract"]),
+    ("word_count", &["abstract"]),
+    ("reading_time", &["abstract"]),
+    ("abstract", &["abstract"]),
+];
+
+/// Sources of the values of a page, by front matter key, as named by
+/// default, and `abstract`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Provenance(BTreeMap<&'static str, Source>);
+
+impl Provenance {
+    /// The tags of an entry the front matter keys are read from, the blank
+    /// ones aside, all from the entry until `locate` says otherwise.
+    pub fn of(tags: &HashMap<String, String>) -> Provenance {
+        let mut provenance = Provenance::default();
+        let given = |t: &&&str| tags.get(**t).is_some_and(|v| !v.trim().is_empty());
+        for (field, names) in TAGS {
+            if let Some(tag) = names.iter().find(given) {
+                provenance.set(field, Some(tag), Origin::Entry);
+            }
+        }
+        provenance
+    }
+
+    pub fn get(&self, field: &str) -> Option<&Source> {
+        self.0.get(field)
+    }
+
+    pub fn set(&mut self, field: &'static str, tag: Option<&str>, origin: Origin) {
+        let tag = tag.map(String::from);
+        self.0.insert(field, Source { tag, origin });
+    }
+
+    /// Takes the origins of the tags of the entry `key` from `origins`.
+    pub fn locate(&mut self, key: &str, origins: &Origins) {
+        for source in self.0.values_mut() {
+            if let (Some(tag), Origin::Entry) = (&source.tag, &source.origin) {
+                source.origin = origins.get(key, tag);
+            }
+        }
+    }
+
+    /// Says that the values read from the entry come from the input file
+    /// `file`.
+    pub fn in_file(&mut self, file: &str) {
+        for source in self.0.values_mut() {
+            if source.origin == Origin::Entry {
+                source.origin = Origin::File(file.to_string());
+            }
+        }
+    }
+
+    /// Sources of the values, in the order of the front matter keys.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &Source)> {
+        FIELDS
+            .iter()
+            .chain(&["abstract"])
+            .filter_map(|f| Some((*f, self.0.get(f)?)))
+    }
+
+    /// The comment naming the source of the front matter `line` of YAML,
+    /// `key: value`, appended to it.
+    pub fn comment_yaml(&self, line: &str) -> String {
+        let key = match line.split_once(':') {
+            Some((key, _)) if !line.starts_with([' ', '-']) => key,
+            _ => return line.to_string(),
+        };
+        match self.get(key) {
+            Some(source) => format!("{} # from {}", line, source),
+            None => line.to_string(),
+        }
+    }
+
+    /// The front matter of TOML, `key = value` lines, with the sources of
+    /// the top-level and `[extra]` keys, named as `fields` says, in
+    /// comments; the tables of `[extra]` get theirs on their header.
+    pub fn comment_toml(&self, front_matter: &str, fields: &FieldMap) -> String {
+        let mut table = "";
+        let mut out = String::new();
+        for line in front_matter.lines() {
+            let name = if line.starts_with('[') {
+                table = line;
+                line.strip_prefix("[extra.")
+                    .and_then(|l| l.strip_suffix(']'))
+            } else {
+                line.split_once(" = ")
+                    .filter(|_| table.is_empty() || table == "[extra]")
+                    .map(|(name, _)| name)
+            };
+            let source = name
+                .and_then(|name| FIELDS.iter().find(|f| fields.name(f) == name))
+                .and_then(|field| self.get(field));
+            match source {
+                Some(source) => out.push_str(&format!("{} # from {}\n", line, source)),
+                None => out.push_str(&format!("{}\n", line)),
+            }
+        }
+        out
+    }
+}
+
+/// The input files of the entries, by citation key, when several files are
+/// converted together. A key in several files is taken from the first one.
+#[derive(Debug, Default)]
+pub struct Files(HashMap<String, String>);
+
+impl Files {
+    /// Records that the entries of `content`, a bibtex file, come from
+    /// `path`.
+    pub fn add(&mut self, path: &str, content: &str) {
+        for key in citation_keys(content) {
+            self.0.entry(key).or_insert_with(|| path.to_string());
+        }
+    }
+
+    /// The file the entry `key` comes from.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    /// Says which file each of `papers` comes from, see
+    /// `Provenance::in_file`.
+    pub fn locate(&self, papers: &mut [Paper]) {
+        for p in papers {
+            if let Some(file) = self.get(&p.key.key) {
+                p.provenance.in_file(file);
+            }
+        }
+    }
+}
+
+/// The citation keys of the entries of `content`, a bibtex file, recovered
+/// from syntax errors as the conversion does.
+pub(crate) fn citation_keys(content: &str) -> Vec<String> {
+    let content = content.replace("\r\n", "\n");
+    let entries = match Bibtex::raw_parse(&content) {
+        Err(_) => Bibtex::raw_parse(&quietly(|| recover::recover(&content)).0),
+        entries => entries,
+    };
+    entries
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Entry::Bibliography(_, key, _) => Some(key),
+            _ => None,
+        })
+        .collect()
+}
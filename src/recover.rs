@@ -0,0 +1,127 @@
+//! Salvaging the well-formed entries of a file which does not parse as a
+//! whole.
+
+use nom_bibtex::Bibtex;
+
+use crate::{warn, Warning};
+
+/// Splits a bibtex file before each `@` found outside of braces, so that an
+/// `@` in a field value, such as an email address, does not start a chunk.
+/// So that an unbalanced brace does not swallow the rest of the file, an
+/// `@type{` opening a line also starts a chunk. Each chunk comes with the
+/// line it starts on.
+pub fn split_entries(input: &str) -> Vec<(usize, &str)> {
+    chunks(input)
+        .into_iter()
+        .map(|(line, _, chunk)| (line, chunk))
+        .collect()
+}
+
+/// The chunks of `split_entries`, with the byte offset they start at.
+fn chunks(input: &str) -> Vec<(usize, usize, &str)> {
+    let mut chunks = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut line = 1;
+    let mut start_line = 1;
+    let mut line_start = 0;
+    for (i, c) in input.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            '\n' => {
+                line += 1;
+                line_start = i + 1;
+            }
+            '@' if depth == 0
+                || (input[line_start..i].trim().is_empty() && opens_entry(&input[i..])) =>
+            {
+                depth = 0;
+                if !input[start..i].trim().is_empty() {
+                    chunks.push((start_line, start, &input[start..i]));
+                }
+                start = i;
+                start_line = line;
+            }
+            _ => {}
+        }
+    }
+    if !input[start..].trim().is_empty() {
+        chunks.push((start_line, start, &input[start..]));
+    }
+    chunks
+}
+
+/// Whether `s` starts with `@type{` or `@type(`.
+fn opens_entry(s: &str) -> bool {
+    let rest = s[1..].trim_start_matches(|c: char| c.is_ascii_alphabetic());
+    rest.len() < s.len() - 1 && rest.trim_start().starts_with(['{', '('])
+}
+
+/// Type of a chunk, lowercased, `article` for `@Article{...`.
+fn chunk_type(chunk: &str) -> Option<String> {
+    let rest = chunk.strip_prefix('@')?;
+    let name: String = rest.chars().take_while(char::is_ascii_alphabetic).collect();
+    (!name.is_empty()).then(|| name.to_lowercase())
+}
+
+/// The text made of the chunks of `input` which parse, and the entries
+/// which do not, with their line, span and, when it can be read, key.
+///
+/// String definitions and preambles are kept in front of every chunk tried,
+/// so that entries using them still parse.
+fn triage(input: &str) -> (String, Vec<Warning>) {
+    let mut definitions = String::new();
+    let mut entries = String::new();
+    let mut unparsable = Vec::new();
+    for (line, start, chunk) in chunks(input) {
+        let Some(kind) = chunk_type(chunk).filter(|_| opens_entry(chunk)) else {
+            // Text between entries, which bibtex ignores.
+            continue;
+        };
+        if kind == "comment" {
+            continue;
+        }
+        if Bibtex::parse(&format!("{}{}", definitions, chunk)).is_ok() {
+            if kind == "string" || kind == "preamble" {
+                definitions.push_str(chunk);
+            } else {
+                entries.push_str(chunk);
+            }
+            continue;
+        }
+        let key = chunk
+            .split_once(['{', '('])
+            .and_then(|(_, r)| r.trim_start().split([',', ' ', '\n']).next())
+            .filter(|k| !k.is_empty());
+        unparsable.push(Warning::Unparsable {
+            entry_type: kind,
+            key: key.map(str::to_string),
+            line,
+            span: start..start + chunk.trim_end().len(),
+        });
+    }
+    (definitions + &entries, unparsable)
+}
+
+/// Keeps the chunks of `input` which parse, reporting the others. Returns
+/// the text made of the good chunks and the number of entries dropped.
+pub fn recover(input: &str) -> (String, usize) {
+    let (kept, unparsable) = triage(input);
+    let mut failed = 0;
+    for warning in unparsable {
+        if let Warning::Unparsable { entry_type, .. } = &warning {
+            if entry_type != "string" && entry_type != "preamble" {
+                failed += 1;
+            }
+        }
+        warn(warning);
+    }
+    (kept, failed)
+}
+
+/// The first entry of `input` which does not parse, locating the error of
+/// parsing the whole of it.
+pub fn first_failure(input: &str) -> Option<Warning> {
+    triage(input).1.into_iter().next()
+}
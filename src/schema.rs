@@ -0,0 +1,172 @@
+//! Version and JSON Schema of the structured outputs, so that the scripts
+//! consuming them can check what they read.
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::Paper;
+
+/// Version of the structured outputs, bumped whenever a key is renamed or
+/// removed or its type changes.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Name and version of the tool, as `new_page 0.1.0`.
+pub fn generator() -> String {
+    format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+}
+
+/// The papers, with the versions of the schema and of the tool.
+#[derive(Serialize)]
+pub struct Document<'a> {
+    pub schema_version: u32,
+    pub generator: String,
+    pub papers: &'a [Paper],
+}
+
+impl<'a> Document<'a> {
+    pub fn new(papers: &'a [Paper]) -> Document<'a> {
+        Document {
+            schema_version: SCHEMA_VERSION,
+            generator: generator(),
+            papers,
+        }
+    }
+}
+
+/// JSON Schema of the `Document`. The papers being serialized by hand, it
+/// is written by hand too, and must follow their `Serialize`
+/// implementation.
+pub fn schema() -> Value {
+    let string = json!({ "type": "string" });
+    let strings = json!({ "type": "array", "items": { "type": "string" } });
+    let flags = json!({ "type": "array", "items": { "type": "boolean" } });
+    let venue = json!({
+        "type": "object",
+        "properties": {
+            "name": string,
+            "shortname": string,
+            "issue_title": string,
+            "edition": { "type": "integer" },
+            "location": string,
+        },
+        "required": ["name"],
+        "additionalProperties": false,
+    });
+    let properties: Map<String, Value> = [
+        ("authors", strings.clone()),
+        ("authors_equal_contribution", flags.clone()),
+        ("authors_corresponding", flags.clone()),
+        ("editors", strings.clone()),
+        ("translators", strings.clone()),
+        (
+            "page",
+            json!({
+                "type": "object",
+                "properties": {
+                    "from": { "type": ["integer", "null"] },
+                    "to": { "type": ["integer", "null"] },
+                    "ranges": string,
+                    "count": { "type": "integer" },
+                },
+                "required": ["from", "to"],
+                "additionalProperties": false,
+            }),
+        ),
+        ("page_display", string.clone()),
+        ("volume", json!({ "type": ["integer", "null"] })),
+        ("issue", json!({ "type": ["integer", "string"] })),
+        ("venue_detail", string.clone()),
+        ("series", json!({ "type": ["integer", "string", "null"] })),
+        ("journal", venue.clone()),
+        ("conference", venue.clone()),
+        ("venue", venue.clone()),
+        ("venue_kind", json!({ "enum": ["conference", "workshop"] })),
+        (
+            "also_appeared_in",
+            json!({
+                "type": "object",
+                "properties": { "journal": venue, "conference": venue },
+                "additionalProperties": false,
+            }),
+        ),
+        ("proceedings", string.clone()),
+        (
+            "event",
+            json!({
+                "type": "object",
+                "properties": {
+                    "name": string,
+                    "location": string,
+                    "start": string,
+                    "end": string,
+                },
+                "additionalProperties": false,
+            }),
+        ),
+        ("title", string.clone()),
+        ("title_missing", json!({ "type": "boolean" })),
+        ("publisher", json!({ "type": ["string", "null"] })),
+        ("howpublished", string.clone()),
+        ("patent_number", string.clone()),
+        ("standard_number", string.clone()),
+        ("isbn", json!({ "type": ["string", "null"] })),
+        ("edition", json!({ "type": ["integer", "string", "null"] })),
+        ("chapter", json!({ "type": ["integer", "string", "null"] })),
+        ("year", json!({ "type": "integer" })),
+        ("date", string.clone()),
+        ("publishDate", string.clone()),
+        (
+            "publication_status",
+            json!({ "enum": ["published", "forthcoming", "inpress"] }),
+        ),
+        ("original_language", string.clone()),
+        ("original_year", json!({ "type": "integer" })),
+        ("lastmod", string.clone()),
+        ("accessed", string.clone()),
+        ("citation_count", json!({ "type": "integer" })),
+        ("citation_count_date", json!({ "type": ["string", "null"] })),
+        ("doi", string.clone()),
+        ("www", string.clone()),
+        ("links", strings.clone()),
+        ("categories", strings.clone()),
+        ("tags", strings.clone()),
+        (
+            "award",
+            json!({ "type": ["string", "array"], "items": string }),
+        ),
+        ("featured", json!({ "type": "boolean" })),
+        ("aliases", strings.clone()),
+        ("other_versions", strings.clone()),
+        ("earlier_versions", strings.clone()),
+        ("summary", string.clone()),
+        ("word_count", json!({ "type": "integer" })),
+        ("reading_time", json!({ "type": "integer" })),
+        ("translated", json!({ "type": "boolean" })),
+        ("abstract_truncated", json!({ "type": "boolean" })),
+        ("bibtex_key", string.clone()),
+        ("entry_type", string.clone()),
+        ("generated", string.clone()),
+        ("abstract", string.clone()),
+    ]
+    .into_iter()
+    .map(|(key, value)| (key.to_string(), value))
+    .collect();
+    let paper = json!({
+        "type": "object",
+        "properties": properties,
+        "required": ["title", "year", "date", "bibtex_key", "entry_type", "abstract"],
+        "additionalProperties": false,
+    });
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "new_page publications",
+        "type": "object",
+        "properties": {
+            "schema_version": { "const": SCHEMA_VERSION },
+            "generator": string,
+            "papers": { "type": "array", "items": paper },
+        },
+        "required": ["schema_version", "generator", "papers"],
+        "additionalProperties": false,
+    })
+}
@@ -0,0 +1,234 @@
+//! Front matter conventions of the supported static site generators. The
+//! `Paper` model is shared; only the way a page is laid out changes.
+
+use serde::Deserialize;
+use serde_yaml::Value;
+
+use crate::mapping::FieldMap;
+use crate::yaml::{Quotes, ToYaml};
+use crate::{Options, Paper};
+
+/// Static site generator the pages are written for.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Target {
+    /// YAML front matter between `---`
+    #[default]
+    Hugo,
+    /// TOML front matter between `+++`, tool params under `[extra]`
+    Zola,
+    /// YAML front matter with a `layout` and a Jekyll `date`
+    Jekyll,
+}
+
+impl Target {
+    /// Line opening and closing the front matter.
+    pub fn delimiter(self) -> &'static str {
+        match self {
+            Target::Zola => "+++",
+            Target::Hugo | Target::Jekyll => "---",
+        }
+    }
+}
+
+/// Parts of the page which are rendered.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Emit {
+    /// The whole page
+    #[default]
+    Both,
+    /// Only the front matter, delimiters included
+    FrontMatter,
+    /// Only the abstract, without front matter
+    Body,
+}
+
+impl Emit {
+    /// Extension of the files holding the rendered parts. Front matter alone
+    /// is a YAML document, except for Zola whose `+++` delimiters are not
+    /// TOML.
+    pub fn extension(self, target: Target) -> &'static str {
+        match (self, target) {
+            (Emit::FrontMatter, Target::Hugo | Target::Jekyll) => "yaml",
+            _ => "md",
+        }
+    }
+}
+
+/// Content, and extension, of the files written one per entry.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Extension {
+    /// The rendered parts of the page
+    Md,
+    /// The front matter mapping, without delimiters
+    Yaml,
+    /// The entry as JSON
+    Json,
+}
+
+impl Extension {
+    pub fn name(self) -> &'static str {
+        match self {
+            Extension::Md => "md",
+            Extension::Yaml => "yaml",
+            Extension::Json => "json",
+        }
+    }
+}
+
+impl Paper {
+    /// Publication date, `YYYY-MM-DD`.
+    pub fn date(&self) -> String {
+        self.dates.date.clone()
+    }
+
+    /// The YAML front matter of the page, delimiters included, its strings
+    /// quoted as `quotes` says; the keys are not renamed nor arranged.
+    pub fn front_matter_yaml(&self, quotes: Quotes) -> String {
+        self.to_yaml(quotes)
+    }
+
+    /// Renders the page, front matter and abstract, for the given target.
+    pub fn render(&self, target: Target) -> String {
+        self.render_with(&Options {
+            target,
+            ..Options::default()
+        })
+    }
+
+    /// Renders the page as the options ask: for their target, their parts
+    /// only, with their names, order and omissions of the front matter keys,
+    /// their quotes and the provenance comments they ask for.
+    pub fn render_with(&self, opts: &Options) -> String {
+        match opts.emit {
+            Emit::Both => match opts.target {
+                Target::Hugo | Target::Jekyll => {
+                    format!("{}{}\n", self.front_matter(opts), self.abs)
+                }
+                Target::Zola => format!("{}{}\n", self.front_matter(opts), self.abs.escaped("+++")),
+            },
+            Emit::FrontMatter => self.front_matter(opts),
+            Emit::Body => format!("{}\n", self.abs.escaped(opts.target.delimiter())),
+        }
+    }
+
+    /// Content of the file written for the entry: the rendered page, or
+    /// else what the extension of the options holds.
+    pub fn render_file(&self, opts: &Options) -> String {
+        match opts.extension {
+            None | Some(Extension::Md) => self.render_with(opts),
+            Some(Extension::Yaml) => {
+                let front_matter = self.front_matter(opts);
+                front_matter
+                    .strip_prefix("---\n")
+                    .and_then(|f| f.strip_suffix("---\n"))
+                    .unwrap_or(&front_matter)
+                    .to_string()
+            }
+            Some(Extension::Json) => serde_json::to_string_pretty(self).unwrap() + "\n",
+        }
+    }
+
+    /// Front matter of the page, delimiters included, with the comments
+    /// naming the source of each key when the options ask for them.
+    fn front_matter(&self, opts: &Options) -> String {
+        let fields = &opts.fields;
+        if opts.target == Target::Zola {
+            return match opts.provenance_comments {
+                true => self.provenance.comment_toml(&self.to_zola(fields), fields),
+                false => self.to_zola(fields),
+            };
+        }
+        let front_matter = self.front_matter_yaml(opts.yaml_quotes);
+        let lines = front_matter
+            .strip_prefix("---\n")
+            .and_then(|l| l.strip_suffix("---\n"))
+            .unwrap_or_default()
+            .to_string();
+        let lines: String = match opts.provenance_comments {
+            true => lines
+                .lines()
+                .map(|l| self.provenance.comment_yaml(l) + "\n")
+                .collect(),
+            false => lines,
+        };
+        match opts.target {
+            // Jekyll's date, with a time, comes first.
+            Target::Jekyll => format!(
+                "---\nlayout: publication\ndate: {} 00:00:00 +0000\n{}---\n",
+                self.date(),
+                fields.arrange_yaml(
+                    &lines
+                        .lines()
+                        .filter(|l| !l.starts_with("date: "))
+                        .map(|l| format!("{}\n", l))
+                        .collect::<String>()
+                )
+            ),
+            _ => format!("---\n{}---\n", fields.arrange_yaml(&lines)),
+        }
+    }
+
+    fn to_zola(&self, fields: &FieldMap) -> String {
+        let mut extra = Vec::new();
+        let mut tags = Vec::new();
+        let mut categories = Vec::new();
+        if let Ok(Value::Mapping(m)) = serde_yaml::to_value(self) {
+            for (k, v) in m {
+                match k.as_str() {
+                    Some("title") | Some("date") | Some("abstract") => {}
+                    Some("tags") => tags = self.keywords.tags.clone(),
+                    Some("categories") => categories = self.categories.names.clone(),
+                    Some(k) => {
+                        if let Some(v) = to_toml(v) {
+                            extra.push((k.to_string(), v));
+                        }
+                    }
+                    None => {}
+                }
+            }
+        }
+        let extra: toml::Table = fields.arrange(extra).into_iter().collect();
+        let mut fm = toml::Table::new();
+        fm.insert("title".into(), self.title.title.clone().into());
+        if let Ok(date) = self.date().parse::<toml::value::Datetime>() {
+            fm.insert("date".into(), toml::Value::Datetime(date));
+        }
+        fm.insert("extra".into(), toml::Value::Table(extra));
+        let mut taxonomies = toml::Table::new();
+        if !categories.is_empty() {
+            taxonomies.insert("categories".into(), categories.into());
+        }
+        if !tags.is_empty() {
+            taxonomies.insert("tags".into(), tags.into());
+        }
+        if !taxonomies.is_empty() {
+            fm.insert("taxonomies".into(), toml::Value::Table(taxonomies));
+        }
+        format!("+++\n{}+++\n", toml::to_string(&fm).unwrap_or_default())
+    }
+}
+
+/// Converts a YAML value to TOML, dropping the nulls TOML cannot represent.
+fn to_toml(v: Value) -> Option<toml::Value> {
+    match v {
+        Value::Null => None,
+        Value::Bool(b) => Some(b.into()),
+        Value::Number(n) => n
+            .as_i64()
+            .map(toml::Value::from)
+            .or_else(|| n.as_f64().map(toml::Value::from)),
+        Value::String(s) => Some(s.into()),
+        Value::Sequence(seq) => Some(toml::Value::Array(
+            seq.into_iter().filter_map(to_toml).collect(),
+        )),
+        Value::Mapping(m) => Some(toml::Value::Table(
+            m.into_iter()
+                .filter_map(|(k, v)| Some((k.as_str()?.to_string(), to_toml(v)?)))
+                .collect(),
+        )),
+        Value::Tagged(t) => to_toml(t.value),
+    }
+}
@@ -0,0 +1,588 @@
+//! Plain text helpers for titles and for the fields derived from the
+//! abstract.
+
+use serde::Deserialize;
+use unicode_normalization::UnicodeNormalization;
+
+/// Words ending with a period which do not end a sentence.
+const ABBREVIATIONS: [&str; 13] = [
+    "e.g.", "i.e.", "al.", "cf.", "vs.", "resp.", "approx.", "fig.", "figs.", "eq.", "sec.", "dr.",
+    "prof.",
+];
+
+/// Length of a summary cut in the middle of a sentence, in characters.
+pub const SUMMARY_CHARS: usize = 300;
+
+/// Whether the word ending at a period ends a sentence.
+fn ends_sentence(word: &str, next: Option<&str>) -> bool {
+    let word = word.trim_start_matches(|c: char| !c.is_alphanumeric());
+    let lowercase_next = next
+        .and_then(|n| n.chars().find(|c| c.is_alphanumeric()))
+        .is_some_and(char::is_lowercase);
+    if lowercase_next {
+        return false;
+    }
+    // Single capital letters are initials.
+    let initial = word.len() == 2 && word.starts_with(|c: char| c.is_uppercase());
+    !initial && !ABBREVIATIONS.iter().any(|a| a.eq_ignore_ascii_case(word))
+}
+
+/// Splits a text into its sentences, normalizing the spaces.
+pub fn sentences(text: &str) -> Vec<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut sentences = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for (i, w) in words.iter().enumerate() {
+        current.push(w);
+        let end = w.trim_end_matches(['"', '\'', ')', '”', '’']);
+        let stop = end.ends_with(['!', '?'])
+            || (end.ends_with('.') && ends_sentence(end, words.get(i + 1).copied()));
+        if stop {
+            sentences.push(current.join(" "));
+            current.clear();
+        }
+    }
+    if !current.is_empty() {
+        sentences.push(current.join(" "));
+    }
+    sentences
+}
+
+/// The first `n` sentences of `text`, cut at a sentence or word boundary
+/// with an ellipsis when longer than `SUMMARY_CHARS`. `None` for an empty
+/// text.
+pub fn summarize(text: &str, n: usize) -> Option<String> {
+    let sentences = sentences(text);
+    if sentences.is_empty() || n == 0 {
+        return None;
+    }
+    let summary = sentences[..n.min(sentences.len())].join(" ");
+    if summary.chars().count() <= SUMMARY_CHARS {
+        return Some(summary);
+    }
+    let mut kept = String::new();
+    for s in &sentences {
+        if kept.chars().count() + s.chars().count() + 1 > SUMMARY_CHARS {
+            break;
+        }
+        if !kept.is_empty() {
+            kept.push(' ');
+        }
+        kept.push_str(s);
+    }
+    if kept.is_empty() {
+        for w in summary.split(' ') {
+            if kept.chars().count() + w.chars().count() + 1 > SUMMARY_CHARS {
+                break;
+            }
+            if !kept.is_empty() {
+                kept.push(' ');
+            }
+            kept.push_str(w);
+        }
+        kept = kept.trim_end_matches([',', ';', ':']).to_string();
+    }
+    Some(format!("{}…", kept))
+}
+
+/// The words of `text`, an inline `$…$` math span counting as one word.
+fn words_outside_math(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut dollars = 0;
+    for w in text.split_whitespace() {
+        current.push(w);
+        dollars += w.matches('$').count();
+        if dollars % 2 == 0 {
+            words.push(current.join(" "));
+            current.clear();
+        }
+    }
+    if !current.is_empty() {
+        words.push(current.join(" "));
+    }
+    words
+}
+
+/// Cuts `text` after its last whole sentence within `max_chars` characters
+/// and `max_words` words, or after its last word within them when the first
+/// sentence is already over, never inside an inline `$…$` math span. `None`
+/// when the text is within the limits.
+pub fn truncate(text: &str, max_chars: Option<usize>, max_words: Option<usize>) -> Option<String> {
+    let fits = |s: &str| {
+        max_chars.is_none_or(|m| s.chars().count() <= m)
+            && max_words.is_none_or(|m| s.split_whitespace().count() <= m)
+    };
+    if fits(text) {
+        return None;
+    }
+    let append = |kept: &str, s: &str| {
+        if kept.is_empty() {
+            s.to_string()
+        } else {
+            format!("{} {}", kept, s)
+        }
+    };
+    let mut kept = String::new();
+    for s in sentences(text) {
+        let longer = append(&kept, &s);
+        if !fits(&longer) {
+            break;
+        }
+        kept = longer;
+    }
+    if kept.is_empty() {
+        for w in words_outside_math(text) {
+            let longer = append(&kept, &w);
+            if !fits(&longer) {
+                break;
+            }
+            kept = longer;
+        }
+        kept = kept.trim_end_matches([',', ';', ':']).to_string();
+    }
+    Some(kept)
+}
+
+/// Abbreviations which may end a title, besides the ones not ending a
+/// sentence.
+const TITLE_ABBREVIATIONS: [&str; 10] = [
+    "etc.", "vol.", "no.", "pp.", "ed.", "eds.", "inc.", "ltd.", "jr.", "st.",
+];
+
+/// Clean-ups applied to titles, each of which can be turned off for titles
+/// to be kept verbatim.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TitleRules {
+    /// Drop a trailing period, unless it ends an ellipsis or abbreviation
+    pub strip_period: bool,
+    /// Turn ``` `` '' ``` and `` ` ' `` pairs into curly quotes
+    pub quotes: bool,
+    /// Use straight quotes rather than curly ones
+    pub ascii_quotes: bool,
+    /// Collapse doubled punctuation, such as `,,` or `..`
+    pub collapse_punctuation: bool,
+}
+
+impl Default for TitleRules {
+    fn default() -> TitleRules {
+        TitleRules {
+            strip_period: true,
+            quotes: true,
+            ascii_quotes: false,
+            collapse_punctuation: true,
+        }
+    }
+}
+
+fn is_abbreviation(word: &str) -> bool {
+    let word = word.trim_start_matches(|c: char| !c.is_alphanumeric());
+    ABBREVIATIONS
+        .iter()
+        .chain(TITLE_ABBREVIATIONS.iter())
+        .any(|a| a.eq_ignore_ascii_case(word))
+}
+
+/// Removes a single trailing period, keeping ellipses and the periods of
+/// abbreviations, including numbered ones such as `Vol. 2.`.
+fn strip_period(title: &str) -> &str {
+    let Some(stripped) = title.strip_suffix('.') else {
+        return title;
+    };
+    let mut words = title.split_whitespace().rev();
+    let last = words.next().unwrap_or_default();
+    let numbered = last[..last.len() - 1].bytes().all(|b| b.is_ascii_digit())
+        && words.next().is_some_and(is_abbreviation);
+    if stripped.ends_with('.') || is_abbreviation(last) || numbered {
+        title
+    } else {
+        stripped
+    }
+}
+
+/// Replaces the LaTeX quote pairs.
+fn quotes(title: &str, ascii: bool) -> String {
+    let (double, single) = if ascii {
+        (("\"", "\""), ("'", "'"))
+    } else {
+        (("“", "”"), ("‘", "’"))
+    };
+    let title = title.replace("``", double.0).replace("''", double.1);
+    let mut out = String::new();
+    let mut open = false;
+    for c in title.chars() {
+        match c {
+            '`' => {
+                open = true;
+                out.push_str(single.0);
+            }
+            '\'' if open => {
+                open = false;
+                out.push_str(single.1);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Collapses doubled `,;:!?` and `..`, leaving ellipses alone.
+fn collapse_punctuation(title: &str) -> String {
+    let chars: Vec<char> = title.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let run = chars[i..].iter().take_while(|&&d| d == c).count();
+        if ",;:!?".contains(c) || (c == '.' && run == 2) {
+            out.push(c);
+        } else {
+            out.extend(&chars[i..i + run]);
+        }
+        i += run;
+    }
+    out
+}
+
+/// Applies the enabled `rules` to a title.
+pub fn normalize_title(title: &str, rules: &TitleRules) -> String {
+    let mut title = title.trim().to_string();
+    if rules.collapse_punctuation {
+        title = collapse_punctuation(&title);
+    }
+    if rules.quotes {
+        title = quotes(&title, rules.ascii_quotes);
+    }
+    if rules.strip_period {
+        title = strip_period(&title).to_string();
+    }
+    title
+}
+
+/// Boilerplate opening proceedings titles, longest first.
+const PROCEEDINGS_PREFIXES: [&str; 6] = [
+    "proceedings of the ",
+    "proceedings of ",
+    "proc. of the ",
+    "proc. of ",
+    "proceedings ",
+    "proc. ",
+];
+
+/// Whether `word` is an ordinal, `44th` or `Fourth`.
+fn is_ordinal(word: &str) -> bool {
+    let word = word.to_lowercase();
+    let suffix = word.trim_start_matches(|c: char| c.is_ascii_digit());
+    (suffix.len() < word.len() && ["st", "nd", "rd", "th"].contains(&suffix))
+        || crate::fields::ORDINALS.contains(&word.as_str())
+}
+
+fn is_year(word: &str) -> bool {
+    word.len() == 4 && word.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Whether a comma separated segment of a Springer title is its acronym and
+/// year, as in `CAV 2020`.
+fn is_acronym_year(segment: &str) -> bool {
+    let words: Vec<&str> = segment.split_whitespace().collect();
+    matches!(words.as_slice(), [acronym, year]
+        if is_year(year) && acronym.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()))
+}
+
+/// Shortens a proceedings title to the name of the conference, removing
+/// only recognized boilerplate: a leading `Proceedings of the`, a leading
+/// year or ordinal (with `Annual`), a trailing parenthetical holding a year
+/// or a location, and what follows the `ACRONYM YEAR` part of Springer
+/// titles.
+pub fn clean_proceedings(title: &str) -> String {
+    let mut name = title.trim();
+    let lower = name.to_lowercase();
+    if let Some(p) = PROCEEDINGS_PREFIXES.iter().find(|p| lower.starts_with(*p)) {
+        name = &name[p.len()..];
+    }
+    let mut words: Vec<&str> = name.split_whitespace().collect();
+    if words.first().is_some_and(|w| is_year(w)) {
+        words.remove(0);
+    }
+    if words.first().is_some_and(|w| is_ordinal(w)) {
+        words.remove(0);
+        if words
+            .first()
+            .is_some_and(|w| w.eq_ignore_ascii_case("annual"))
+        {
+            words.remove(0);
+        }
+    }
+    let mut name = words.join(" ");
+
+    let segments: Vec<&str> = name.split(", ").collect();
+    if let Some(i) = segments.iter().position(|s| is_acronym_year(s)) {
+        name = segments[..=i].join(", ");
+    }
+    if let Some(open) = name.rfind(" (").filter(|_| name.ends_with(')')) {
+        let inner = &name[open + 2..name.len() - 1];
+        if inner.contains(',') || inner.split_whitespace().any(is_year) {
+            name.truncate(open);
+        }
+    }
+    if name.is_empty() {
+        title.trim().to_string()
+    } else {
+        name
+    }
+}
+
+/// Spelled ordinals below twenty, from `first`.
+const SMALL_ORDINALS: [&str; 19] = [
+    "first",
+    "second",
+    "third",
+    "fourth",
+    "fifth",
+    "sixth",
+    "seventh",
+    "eighth",
+    "ninth",
+    "tenth",
+    "eleventh",
+    "twelfth",
+    "thirteenth",
+    "fourteenth",
+    "fifteenth",
+    "sixteenth",
+    "seventeenth",
+    "eighteenth",
+    "nineteenth",
+];
+
+/// Tens, from twenty, as cardinals and as ordinals.
+const TENS: [(&str, &str); 8] = [
+    ("twenty", "twentieth"),
+    ("thirty", "thirtieth"),
+    ("forty", "fortieth"),
+    ("fifty", "fiftieth"),
+    ("sixty", "sixtieth"),
+    ("seventy", "seventieth"),
+    ("eighty", "eightieth"),
+    ("ninety", "ninetieth"),
+];
+
+/// Value of an ordinal word, only when written correctly: `30th`, `21st`
+/// but not `21th`, `Fourth`, `Forty-Fourth`.
+fn ordinal_value(word: &str) -> Option<i64> {
+    let word = word.to_lowercase();
+    let digits = word.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+        let n: i64 = digits.parse().ok()?;
+        let suffix = match (n % 100, n % 10) {
+            (11..=13, _) => "th",
+            (_, 1) => "st",
+            (_, 2) => "nd",
+            (_, 3) => "rd",
+            _ => "th",
+        };
+        return (n > 0 && word[digits.len()..] == *suffix).then_some(n);
+    }
+    let small = |w: &str| SMALL_ORDINALS.iter().position(|o| *o == w);
+    if let Some(i) = small(&word) {
+        return Some(i as i64 + 1);
+    }
+    for (i, (cardinal, ordinal)) in TENS.iter().enumerate() {
+        let tens = 10 * (i as i64 + 2);
+        if word == *ordinal {
+            return Some(tens);
+        }
+        let unit = word
+            .strip_prefix(cardinal)
+            .and_then(|w| w.strip_prefix('-'))
+            .and_then(small)
+            .filter(|&u| u < 9);
+        if let Some(u) = unit {
+            return Some(tens + u as i64 + 1);
+        }
+    }
+    None
+}
+
+/// Edition of the conference of a proceedings title, from the ordinal
+/// opening it after the boilerplate and a year, as in `Proceedings of the
+/// 30th USENIX Security Symposium` or `2021 Forty-Fourth Conference`.
+pub fn proceedings_edition(title: &str) -> Option<i64> {
+    let lower = title.trim().to_lowercase();
+    let name = PROCEEDINGS_PREFIXES
+        .iter()
+        .find_map(|p| lower.strip_prefix(p))
+        .unwrap_or(&lower);
+    let mut words = name.split_whitespace().skip_while(|w| is_year(w));
+    ordinal_value(words.next()?)
+}
+
+/// Year of a proceedings title, when it holds a single one, as a word of
+/// four digits: `ICSE 2020`, `(Lyon, 2020)`, but not `ICSE2020` nor the
+/// `1950` of `1950--2000`.
+pub fn proceedings_year(title: &str) -> Option<i64> {
+    let mut years = title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| is_year(w))
+        .filter_map(|w| w.parse::<i64>().ok());
+    let year = years.next()?;
+    years.all(|y| y == year).then_some(year)
+}
+
+/// Typographic ligatures and the letters they stand for.
+const LIGATURES: [(char, &str); 7] = [
+    ('ﬀ', "ff"),
+    ('ﬁ', "fi"),
+    ('ﬂ', "fl"),
+    ('ﬃ', "ffi"),
+    ('ﬄ', "ffl"),
+    ('ﬅ', "st"),
+    ('ﬆ', "st"),
+];
+
+/// Characters which are invisible when rendered: soft hyphens, zero-width
+/// spaces and joiners, word joiners and byte order marks.
+const INVISIBLE: [char; 6] = [
+    '\u{ad}', '\u{200b}', '\u{200c}', '\u{200d}', '\u{2060}', '\u{feff}',
+];
+
+/// Whether `c` is a space other than the regular one, such as the
+/// non-breaking or thin spaces of PDFs.
+fn is_exotic_space(c: char) -> bool {
+    matches!(
+        c,
+        '\u{a0}' | '\u{1680}' | '\u{2000}'..='\u{200a}' | '\u{202f}' | '\u{205f}' | '\u{3000}'
+    )
+}
+
+/// Letters without an ASCII base letter once decomposed, with their
+/// spelling.
+const UNDECOMPOSABLE: [(char, &str); 14] = [
+    ('ß', "ss"),
+    ('ø', "o"),
+    ('Ø', "O"),
+    ('æ', "ae"),
+    ('Æ', "AE"),
+    ('œ', "oe"),
+    ('Œ', "OE"),
+    ('ł', "l"),
+    ('Ł', "L"),
+    ('đ', "d"),
+    ('Đ', "D"),
+    ('þ', "th"),
+    ('Þ', "Th"),
+    ('ı', "i"),
+];
+
+/// Spells `s` in ASCII: accents are removed, the letters of `UNDECOMPOSABLE`
+/// spelled out and the other non-ASCII characters dropped.
+pub fn transliterate(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.nfd() {
+        if c.is_ascii() {
+            out.push(c);
+        } else if let Some((_, letters)) = UNDECOMPOSABLE.iter().find(|(l, _)| *l == c) {
+            out.push_str(letters);
+        }
+    }
+    out
+}
+
+/// Cleans text pasted from PDFs: NFC normalization, ligatures spelled out,
+/// invisible characters removed and exotic spaces made regular. Control
+/// characters other than newlines and tabs are removed too; the returned
+/// flag tells whether there were any.
+pub fn scrub(s: &str) -> (String, bool) {
+    let mut controls = false;
+    let mut out = String::with_capacity(s.len());
+    for c in s.nfc() {
+        if let Some((_, letters)) = LIGATURES.iter().find(|(l, _)| *l == c) {
+            out.push_str(letters);
+        } else if INVISIBLE.contains(&c) {
+            // Dropped.
+        } else if is_exotic_space(c) {
+            out.push(' ');
+        } else if c.is_control() && c != '\n' && c != '\t' {
+            controls = true;
+        } else {
+            out.push(c);
+        }
+    }
+    (out, controls)
+}
+
+/// Typography of the dashes and quotes of abstracts.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Punctuation {
+    /// Unicode dashes and curly quotes
+    Smart,
+    /// `--`, `---` and straight quotes
+    Ascii,
+    /// As written in the entry
+    #[default]
+    Keep,
+}
+
+/// Whether a word looks like code, `snake_case` identifiers, which are
+/// left alone.
+fn is_code(word: &str) -> bool {
+    word.contains('_')
+}
+
+/// Turns the dashes and straight quotes of plain text into Unicode
+/// punctuation; a quote opens after a space or an opening bracket.
+fn smarten(text: &str, prev: &mut char, out: &mut String) {
+    for word in text.split_inclusive(char::is_whitespace) {
+        if is_code(word) {
+            out.push_str(word);
+            *prev = word.chars().last().unwrap_or(*prev);
+            continue;
+        }
+        for c in word.replace("---", "—").replace("--", "–").chars() {
+            let opening = prev.is_whitespace() || "([{—–“‘".contains(*prev);
+            match c {
+                '"' if opening => out.push('“'),
+                '"' => out.push('”'),
+                '\'' if opening => out.push('‘'),
+                '\'' => out.push('’'),
+                c => out.push(c),
+            }
+            *prev = c;
+        }
+    }
+}
+
+/// Applies a punctuation style to an abstract. The smart style leaves the
+/// `backticked` spans and the identifiers with underscores alone.
+pub fn punctuate(text: &str, style: Punctuation) -> String {
+    match style {
+        Punctuation::Keep => text.to_string(),
+        Punctuation::Ascii => text
+            .replace('—', "---")
+            .replace('–', "--")
+            .replace(['“', '”', '„'], "\"")
+            .replace(['‘', '’'], "'"),
+        Punctuation::Smart => {
+            let text = text.replace("``", "“").replace("''", "”");
+            let parts: Vec<&str> = text.split('`').collect();
+            // A backtick without its closing one does not start code.
+            let unclosed = parts.len().is_multiple_of(2);
+            let mut out = String::new();
+            let mut prev = ' ';
+            for (i, part) in parts.iter().enumerate() {
+                if i > 0 {
+                    out.push('`');
+                }
+                if i % 2 == 1 && !(unclosed && i == parts.len() - 1) {
+                    out.push_str(part);
+                    prev = '`';
+                } else {
+                    smarten(part, &mut prev, &mut out);
+                }
+            }
+            out
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Wall-clock and calendar date helpers, kept dependency free.
+
+use crate::locale::Locale;
+
+/// The current year (UTC), computed from the system clock.
+pub fn current_year() -> i64 {
+    now().0
+}
+
+/// The current UTC time as year, month, day and seconds since midnight.
+pub fn now() -> (i64, i64, i64, i64) {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    // Civil-from-days conversion, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = secs.div_euclid(86400) + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day, secs.rem_euclid(86400))
+}
+
+/// The current UTC date, `YYYY-MM-DD`.
+pub fn today() -> String {
+    let (year, month, day, _) = now();
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// The current UTC time in RFC 3339 format.
+pub fn timestamp() -> String {
+    let (year, month, day, secs) = now();
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// English month names, whose three-letter prefixes are also bibtex's month
+/// macros.
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// Month number of `12`, `dec`, `Dec.` or `December`.
+pub fn parse_month(s: &str) -> Option<u32> {
+    let s = s.trim().trim_end_matches('.').to_lowercase();
+    if let Ok(n) = s.parse::<u32>() {
+        return (1..=12).contains(&n).then_some(n);
+    }
+    MONTHS
+        .iter()
+        .position(|m| s.len() >= 3 && m.starts_with(&s))
+        .map(|i| i as u32 + 1)
+}
+
+/// A date as `YYYY-MM-DD`, the missing month or day being the first. Reads
+/// ISO dates, `2023`, `2023-06` or `2023-06-15`, and written ones, `June
+/// 2023`, `15 June 2023` or `June 15, 2023`.
+pub fn parse_date(s: &str) -> Option<String> {
+    let s = s.trim();
+    let iso: Vec<&str> = s.split('-').collect();
+    let (year, month, day) = if iso.len() <= 3 && iso[0].len() == 4 {
+        let number = |i: usize| iso.get(i).map_or(Some(1), |p| p.parse::<u32>().ok());
+        (iso[0].parse::<i64>().ok()?, number(1)?, number(2)?)
+    } else {
+        let words: Vec<&str> = s
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|w| !w.is_empty())
+            .collect();
+        let mut year = None;
+        let mut month = None;
+        let mut day = 1;
+        for w in words {
+            match w.parse::<u32>() {
+                Ok(n) if w.len() == 4 => year = Some(i64::from(n)),
+                Ok(n) => day = n,
+                Err(_) => month = Some(parse_month(w)?),
+            }
+        }
+        (year?, month.unwrap_or(1), day)
+    };
+    ((1..=12).contains(&month) && (1..=31).contains(&day))
+        .then(|| format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+/// A date as `parse_date` reads them, or written with slashes,
+/// `2024/01/15`, or `15/01/2024` and `01/15/2024` when only one of the day
+/// or the month can be over 12.
+pub fn parse_lenient_date(s: &str) -> Option<String> {
+    if let Some(date) = parse_date(s) {
+        return Some(date);
+    }
+    let parts: Vec<u32> = s
+        .trim()
+        .split(['/', '.'])
+        .map(|p| p.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    let (year, month, day) = match parts.as_slice() {
+        [y, m, d] if *y > 999 => (*y, *m, *d),
+        [a, b, y] if *y > 999 && (*a > 12 || a == b) => (*y, *b, *a),
+        [a, b, y] if *y > 999 && *b > 12 => (*y, *a, *b),
+        _ => return None,
+    };
+    ((1..=12).contains(&month) && (1..=31).contains(&day))
+        .then(|| format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+/// An ISO date, `2024-01-15`, as `Jan. 15, 2024`.
+pub fn short_date(iso: &str) -> Option<String> {
+    Locale::En.short_date(iso)
+}
@@ -0,0 +1,148 @@
+//! Wall-clock durations of the steps of a run, to tell whether parsing,
+//! conversion, enrichment or the files dominate. Nothing is measured unless
+//! the command line enables it.
+
+use std::fmt::{Display, Error, Formatter};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Conversion time beyond which an entry is listed on its own.
+pub const SLOW_ENTRY: Duration = Duration::from_millis(20);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static TIMINGS: Mutex<Timings> = Mutex::new(Timings {
+    stages: Vec::new(),
+    slow_entries: Vec::new(),
+    cache: None,
+});
+
+/// Time spent in one step, over all the times it was taken.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stage {
+    pub name: String,
+    pub total: Duration,
+    pub calls: usize,
+}
+
+/// Durations measured so far.
+#[derive(Debug, Default, PartialEq)]
+pub struct Timings {
+    /// Steps, in the order they were first taken
+    pub stages: Vec<Stage>,
+    /// Entries whose conversion took longer than `SLOW_ENTRY`, with its
+    /// duration
+    pub slow_entries: Vec<(String, Duration)>,
+    /// Time the parsing of the inputs read from the cache took when they
+    /// were cached, and time reading them took
+    pub cache: Option<(Duration, Duration)>,
+}
+
+impl Timings {
+    /// Adds `elapsed` to the step `name`.
+    pub fn record(&mut self, name: &str, elapsed: Duration) {
+        match self.stages.iter_mut().find(|s| s.name == name) {
+            Some(stage) => {
+                stage.total += elapsed;
+                stage.calls += 1;
+            }
+            None => self.stages.push(Stage {
+                name: name.to_string(),
+                total: elapsed,
+                calls: 1,
+            }),
+        }
+    }
+}
+
+/// A table of the steps, then the slow entries, slowest first.
+impl Display for Timings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let width = self.stages.iter().map(|s| s.name.len()).max().unwrap_or(0);
+        writeln!(f, "{:width$}  {:>9}  {:>6}", "Step", "Time", "Calls")?;
+        for s in &self.stages {
+            writeln!(
+                f,
+                "{:width$}  {:>8.3}s  {:>6}",
+                s.name,
+                s.total.as_secs_f64(),
+                s.calls
+            )?;
+        }
+        if let Some((parsed, loaded)) = self.cache {
+            writeln!(
+                f,
+                "Parsed from the cache in {:.3}s instead of {:.3}s, {:.1}x faster",
+                loaded.as_secs_f64(),
+                parsed.as_secs_f64(),
+                parsed.as_secs_f64() / loaded.as_secs_f64().max(1e-6)
+            )?;
+        }
+        if !self.slow_entries.is_empty() {
+            let mut slow = self.slow_entries.clone();
+            slow.sort_by_key(|(_, elapsed)| std::cmp::Reverse(*elapsed));
+            writeln!(
+                f,
+                "Entries converted in more than {}ms:",
+                SLOW_ENTRY.as_millis()
+            )?;
+            for (key, elapsed) in slow {
+                writeln!(f, "  {}: {:.3}s", key, elapsed.as_secs_f64())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Measures the steps taken from now on.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Runs `f`, adding its duration to the step `name`.
+pub fn time<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    if !enabled() {
+        return f();
+    }
+    let start = Instant::now();
+    let result = f();
+    TIMINGS.lock().unwrap().record(name, start.elapsed());
+    result
+}
+
+/// Runs `f`, converting the entry `key`, adding its duration to the
+/// `conversion` step and listing the entry when it is slow.
+pub fn entry<T>(key: &str, f: impl FnOnce() -> T) -> T {
+    if !enabled() {
+        return f();
+    }
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    let mut timings = TIMINGS.lock().unwrap();
+    timings.record("conversion", elapsed);
+    if elapsed > SLOW_ENTRY {
+        timings.slow_entries.push((key.to_string(), elapsed));
+    }
+    result
+}
+
+/// Records that an input was read from the cache in `loaded` rather than
+/// parsed, which took `parsed` when it was cached.
+pub fn cached(parsed: Duration, loaded: Duration) {
+    if enabled() {
+        let mut timings = TIMINGS.lock().unwrap();
+        let (p, l) = timings.cache.get_or_insert_default();
+        *p += parsed;
+        *l += loaded;
+    }
+}
+
+/// The durations measured so far, starting over.
+pub fn take() -> Timings {
+    std::mem::take(&mut TIMINGS.lock().unwrap())
+}
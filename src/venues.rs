@@ -0,0 +1,220 @@
+//! Pages gathering the papers of each venue, under the canonical names of a
+//! venue map.
+
+use std::collections::HashMap;
+use std::fmt::{Error, Formatter};
+use std::io;
+use std::path::Path;
+
+use crate::fields::dated_acronym;
+use crate::output::{write_file, FileNames};
+use crate::yaml::{string, Quotes, ToYaml};
+use crate::Paper;
+
+/// Canonical venue names and the spellings merged into them, read from a
+/// TOML file of `canonical = ["spelling", ...]` lines.
+#[derive(Debug, Default, PartialEq)]
+pub struct VenueMap {
+    /// Canonical name of each known spelling, by `normalize`d spelling
+    pub canonical: HashMap<String, String>,
+}
+
+impl VenueMap {
+    /// Reads the venue map at `path`. Errors mention the path.
+    pub fn load(path: &Path) -> Result<VenueMap, String> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        VenueMap::parse(&content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Reads a venue map from its TOML content.
+    pub fn parse(content: &str) -> Result<VenueMap, toml::de::Error> {
+        let table: HashMap<String, Vec<String>> = toml::from_str(content)?;
+        let mut map = VenueMap::default();
+        for (name, spellings) in table {
+            for s in spellings.iter().chain([&name]) {
+                map.canonical.insert(normalize(s), name.clone());
+            }
+        }
+        Ok(map)
+    }
+
+    /// Canonical name of `raw`, when the map knows it.
+    pub fn get(&self, raw: &str) -> Option<&str> {
+        self.canonical.get(&normalize(raw)).map(String::as_str)
+    }
+}
+
+/// Spelling of a venue name compared with the map: case and runs of
+/// whitespace do not matter.
+fn normalize(raw: &str) -> String {
+    raw.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Papers of a venue.
+#[derive(Debug, PartialEq)]
+pub struct VenueGroup {
+    /// Canonical name, or the raw name of an unmapped venue
+    pub name: String,
+    /// Whether the name comes from the venue map
+    pub mapped: bool,
+    /// Distinct raw names merged into the group, in order of appearance
+    pub spellings: Vec<String>,
+    /// Citation keys of the papers, in order of appearance
+    pub keys: Vec<String>,
+}
+
+/// Groups the papers by canonical venue, in order of first appearance. Each
+/// distinct raw name the map does not know forms a group of its own; papers
+/// without venue are left out.
+pub fn group(papers: &[Paper], map: &VenueMap) -> Vec<VenueGroup> {
+    let mut groups: Vec<VenueGroup> = Vec::new();
+    for p in papers {
+        let Some(raw) = p.place.as_ref().map(|v| v.name()) else {
+            continue;
+        };
+        let (name, mapped) = match map.get(raw) {
+            Some(name) => (name, true),
+            None => (raw, false),
+        };
+        let i = match groups
+            .iter()
+            .position(|g| g.name == name && g.mapped == mapped)
+        {
+            Some(i) => i,
+            None => {
+                groups.push(VenueGroup {
+                    name: name.to_string(),
+                    mapped,
+                    spellings: Vec::new(),
+                    keys: Vec::new(),
+                });
+                groups.len() - 1
+            }
+        };
+        let g = &mut groups[i];
+        if !g.spellings.iter().any(|s| s == raw) {
+            g.spellings.push(raw.to_string());
+        }
+        g.keys.push(p.key.key.clone());
+    }
+    groups
+}
+
+/// Turns a venue name into a URL path segment: lowercase ASCII letters and
+/// digits separated by single dashes.
+pub fn slug(name: &str) -> String {
+    let lower = name.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    if words.is_empty() {
+        "venue".to_string()
+    } else {
+        words.join("-")
+    }
+}
+
+/// Words left out of the initials of a venue name.
+const MINOR_WORDS: [&str; 12] = [
+    "a",
+    "an",
+    "and",
+    "for",
+    "in",
+    "of",
+    "on",
+    "the",
+    "to",
+    "&",
+    "proceedings",
+    "proc",
+];
+
+/// Whether `word` is an acronym, `CCS` or `EuroSys`: a single word starting
+/// with a letter, with at least two capitals.
+fn is_acronym(word: &str) -> bool {
+    word.starts_with(|c: char| c.is_ascii_alphabetic())
+        && word.chars().filter(char::is_ascii_uppercase).count() >= 2
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/&-".contains(c))
+}
+
+/// The short name of a venue, guessed from its name: the acronym it gives
+/// in parentheses, `... Security (CCS '21)`, or the name itself when it is
+/// one, or else the initials of its capitalized words, `International
+/// Conference on Machine Learning` giving `ICML`. None when fewer than two
+/// initials are found. Free of I/O, and never panics.
+pub fn infer_shortname(name: &str) -> Option<String> {
+    let name = name.trim();
+    let acronym = |s: &str| {
+        let s = s.trim();
+        match dated_acronym(s) {
+            Some((acronym, _)) => Some(acronym),
+            None => is_acronym(s).then(|| s.to_string()),
+        }
+    };
+    let given = name
+        .strip_suffix(')')
+        .and_then(|n| n.rsplit_once('('))
+        .and_then(|(_, inner)| acronym(inner));
+    if let Some(short) = given.or_else(|| acronym(name)) {
+        return Some(short);
+    }
+    let initials: String = name
+        .split(|c: char| c.is_whitespace() || c == ',' || c == ':')
+        .filter(|w| !MINOR_WORDS.iter().any(|m| w.eq_ignore_ascii_case(m)))
+        .filter(|w| !is_acronym(w))
+        .filter_map(|w| w.chars().next().filter(|c| c.is_uppercase()))
+        .collect();
+    (initials.chars().count() >= 2).then_some(initials)
+}
+
+impl ToYaml for VenueGroup {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error> {
+        writeln!(f, "---")?;
+        writeln!(f, "title: {}", string(&self.name, quotes))?;
+        writeln!(f, "papers:")?;
+        for k in &self.keys {
+            writeln!(f, "  - {}", string(k, quotes))?;
+        }
+        writeln!(f, "---")
+    }
+}
+
+/// Writes the `_index.md` page of each group in a directory named after its
+/// slug, in `dir`, the strings of their front matter quoted as `quotes`
+/// says.
+pub fn write_venue_pages(groups: &[VenueGroup], dir: &Path, quotes: Quotes) -> io::Result<()> {
+    let mut names = FileNames::default();
+    for g in groups {
+        let name = names.claim(&slug(&g.name));
+        write_file(&dir.join(name).join("_index.md"), &g.to_yaml(quotes))?;
+    }
+    Ok(())
+}
+
+/// Tells which raw names were merged into each canonical venue, then lists
+/// the unmapped names as candidates for the venue map.
+pub fn report(groups: &[VenueGroup]) -> String {
+    let mut out = String::new();
+    for g in groups.iter().filter(|g| g.mapped) {
+        out.push_str(&format!("{} ({})\n", g.name, g.keys.len()));
+        for s in &g.spellings {
+            out.push_str(&format!("  <- {}\n", s));
+        }
+    }
+    let unmapped: Vec<&VenueGroup> = groups.iter().filter(|g| !g.mapped).collect();
+    if !unmapped.is_empty() {
+        out.push_str("Unmapped:\n");
+        for g in unmapped {
+            out.push_str(&format!("  {} ({})\n", g.name, g.keys.len()));
+        }
+    }
+    out
+}
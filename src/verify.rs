@@ -0,0 +1,124 @@
+//! Checks that rendered pages parse back, so that escaping mistakes show
+//! when converting rather than when the site is built.
+
+use serde_yaml::Value;
+
+use crate::target::{Emit, Target};
+use crate::{warn, Options, Paper, Warning};
+
+/// Keys every page has, whatever the entry, each a string.
+const REQUIRED: [&str; 3] = ["title", "bibtex_key", "entry_type"];
+
+/// Keys of the people of the entry, lists when the page has them: the
+/// editors of a book may stand in for its authors, and a note may have
+/// no one.
+const PEOPLE: [&str; 3] = ["authors", "editors", "translators"];
+
+/// Checks that the front matter of `page`, rendered with `opts`, parses and
+/// has the required keys with their types, and that no line of the body
+/// could be taken for a front matter delimiter. Omitted keys are not
+/// required.
+pub fn verify(page: &str, opts: &Options) -> Result<(), String> {
+    let delimiter = opts.target.delimiter();
+    let body = match opts.emit {
+        Emit::Body => Some(page),
+        Emit::Both => between(page, &format!("{}\n", delimiter))
+            .map(|front_matter| &page[front_matter.len() + 2 * (delimiter.len() + 1)..]),
+        Emit::FrontMatter => None,
+    };
+    if body.is_some_and(|b| b.lines().any(|l| l.starts_with(delimiter))) {
+        return Err(format!("a line of the body starts with {}", delimiter));
+    }
+    if opts.emit == Emit::Body {
+        return Ok(());
+    }
+    let front_matter = match opts.target {
+        Target::Zola => {
+            let front_matter = between(page, "+++\n").ok_or("no front matter between +++")?;
+            FrontMatter::Toml(front_matter.parse().map_err(|e| format!("{}", e))?)
+        }
+        _ => {
+            let front_matter = between(page, "---\n").ok_or("no front matter between ---")?;
+            FrontMatter::Yaml(serde_yaml::from_str(front_matter).map_err(|e| format!("{}", e))?)
+        }
+    };
+    let kept = |key: &&str| !opts.fields.omit.iter().any(|o| o == key);
+    for key in REQUIRED.into_iter().filter(kept) {
+        if front_matter.shape(key, opts) != Shape::String {
+            return Err(format!("{} is missing or not a string", key));
+        }
+    }
+    for key in PEOPLE.into_iter().filter(kept) {
+        if !matches!(front_matter.shape(key, opts), Shape::Missing | Shape::List) {
+            return Err(format!("{} is not a list", key));
+        }
+    }
+    Ok(())
+}
+
+/// A parsed front matter.
+enum FrontMatter {
+    Toml(toml::Table),
+    Yaml(Value),
+}
+
+/// What a front matter holds under a key.
+#[derive(PartialEq)]
+enum Shape {
+    Missing,
+    List,
+    String,
+    Other,
+}
+
+impl FrontMatter {
+    /// What is under `key`, renamed as `opts` says; in TOML, the keys other
+    /// than the title are under `extra`.
+    fn shape(&self, key: &str, opts: &Options) -> Shape {
+        let name = opts.fields.name(key);
+        match self {
+            FrontMatter::Toml(table) => {
+                let value = match key {
+                    "title" => table.get(key),
+                    _ => table.get("extra").and_then(|e| e.get(name)),
+                };
+                match value {
+                    None => Shape::Missing,
+                    Some(toml::Value::Array(_)) => Shape::List,
+                    Some(toml::Value::String(_)) => Shape::String,
+                    Some(_) => Shape::Other,
+                }
+            }
+            FrontMatter::Yaml(map) => match map.get(name) {
+                None => Shape::Missing,
+                Some(Value::Sequence(_)) => Shape::List,
+                Some(Value::String(_)) => Shape::String,
+                Some(_) => Shape::Other,
+            },
+        }
+    }
+}
+
+/// Leaves out the papers whose page does not verify, warning about each,
+/// and returns how many were left out.
+pub fn retain_valid(papers: &mut Vec<Paper>, opts: &Options) -> usize {
+    let before = papers.len();
+    papers.retain(|p| match verify(&p.render_with(opts), opts) {
+        Ok(()) => true,
+        Err(e) => {
+            warn(Warning::InvalidPage {
+                key: p.key.key.clone(),
+                reason: e,
+            });
+            false
+        }
+    });
+    before - papers.len()
+}
+
+/// Text between the opening delimiter line starting `page` and the next one.
+fn between<'a>(page: &'a str, delimiter: &str) -> Option<&'a str> {
+    let rest = page.strip_prefix(delimiter)?;
+    let end = rest.find(&format!("\n{}", delimiter))?;
+    Some(&rest[..=end])
+}
@@ -0,0 +1,21 @@
+//! WebAssembly binding, for site builders running on Node. Only the pure
+//! conversion is exposed: the options reading or writing files, or reaching
+//! the network, do not exist in `Options` and are rejected as unknown.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{convert_str, Options};
+
+/// Converts a bibtex file with the options given as JSON, `{"target":
+/// "zola", "bundle": true}`, returning the pages as a JSON list of `key`,
+/// `filename` and `content` objects.
+#[wasm_bindgen]
+pub fn convert(bibtex: &str, options_json: &str) -> Result<String, JsError> {
+    let opts: Options = if options_json.trim().is_empty() {
+        Options::default()
+    } else {
+        serde_json::from_str(options_json)?
+    };
+    let pages = convert_str(bibtex, &opts).map_err(|e| JsError::new(&e.to_string()))?;
+    Ok(serde_json::to_string(&pages)?)
+}
@@ -0,0 +1,106 @@
+//! Biblatex `@xdata` entries, bundles of tags shared by the entries listing
+//! their keys in an `xdata` tag, as publishers or series.
+
+use std::collections::HashMap;
+
+use nom_bibtex::Bibliography;
+
+use crate::provenance::{Origin, Origins};
+use crate::{warn, Warning};
+
+/// Whether the entry is an `@xdata` bundle rather than a work.
+pub fn is_xdata(b: &Bibliography) -> bool {
+    b.entry_type().eq_ignore_ascii_case("xdata")
+}
+
+/// Keys listed in an `xdata` tag, `acm-pubs, series-lncs`.
+fn references(tags: &HashMap<String, String>) -> Vec<&str> {
+    tags.get("xdata")
+        .map(|x| {
+            x.split(',')
+                .map(str::trim)
+                .filter(|k| !k.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Tags of the `xdata` bundles, by key.
+type Bundles = HashMap<String, HashMap<String, String>>;
+
+/// The tags of `tags` other than the `xdata` references.
+fn own(tags: &HashMap<String, String>) -> impl Iterator<Item = (String, String)> + '_ {
+    tags.iter()
+        .filter(|(name, _)| *name != "xdata")
+        .map(|(name, value)| (name.clone(), value.clone()))
+}
+
+/// Tags inherited through the `xdata` references of `tags`, with the key
+/// of the bundle giving them, the later references and the bundles' own
+/// tags overriding the ones they reference. `chain` holds the keys being
+/// resolved, to stop at cycles; `key` names the entry in the warnings.
+fn inherited(
+    key: &str,
+    tags: &HashMap<String, String>,
+    bundles: &Bundles,
+    chain: &mut Vec<String>,
+) -> HashMap<String, (String, String)> {
+    let mut merged = HashMap::new();
+    for reference in references(tags) {
+        if chain.iter().any(|c| c == reference) {
+            warn(Warning::XdataCycle {
+                key: key.to_string(),
+                chain: chain
+                    .iter()
+                    .cloned()
+                    .chain([reference.to_string()])
+                    .collect(),
+            });
+            continue;
+        }
+        let Some(bundle) = bundles.get(reference) else {
+            warn(Warning::MissingXdata {
+                key: key.to_string(),
+                reference: reference.to_string(),
+            });
+            continue;
+        };
+        chain.push(reference.to_string());
+        merged.extend(inherited(key, bundle, bundles, chain));
+        chain.pop();
+        merged.extend(own(bundle).map(|(name, value)| (name, (value, reference.to_string()))));
+    }
+    merged
+}
+
+/// The entries other than `@xdata`, with the tags of the bundles they
+/// reference, possibly through other bundles, added to theirs; their own
+/// tags take precedence. Missing references and cycles are warned about
+/// and skipped; the inherited tags are recorded in `origins`.
+pub fn resolve(entries: Vec<Bibliography>, origins: &mut Origins) -> Vec<Bibliography> {
+    let bundles: Bundles = entries
+        .iter()
+        .filter(|b| is_xdata(b))
+        .map(|b| (b.citation_key().to_string(), b.tags().clone()))
+        .collect();
+    entries
+        .into_iter()
+        .filter(|b| !is_xdata(b))
+        .map(|b| {
+            if !b.tags().contains_key("xdata") {
+                return b;
+            }
+            let key = b.citation_key();
+            let mut chain = vec![key.to_string()];
+            let mut tags = HashMap::new();
+            for (name, (value, bundle)) in inherited(key, b.tags(), &bundles, &mut chain) {
+                if !b.tags().contains_key(&name) {
+                    origins.set(key, &name, Origin::Xdata(bundle));
+                }
+                tags.insert(name, value);
+            }
+            tags.extend(own(b.tags()));
+            Bibliography::new(b.entry_type().to_string(), key.to_string(), tags)
+        })
+        .collect()
+}
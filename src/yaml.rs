@@ -0,0 +1,182 @@
+//! Quoting of the strings of the YAML front matter, each value on its own:
+//! double quoted and escaped, or single quoted for sites whose linters want
+//! them so, or only quoted when they must be.
+
+use std::fmt::{Display, Error, Formatter};
+
+use serde::Deserialize;
+use serde_yaml::Value;
+
+/// How the strings of the YAML front matter are quoted.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Quotes {
+    /// `"…"`, escaped
+    #[default]
+    Double,
+    /// `'…'`, the single quotes doubled
+    Single,
+    /// Unquoted, unless the value would not read back as the same string;
+    /// then single quoted
+    Auto,
+}
+
+/// Words YAML 1.1 or 1.2 readers take for booleans or null.
+const KEYWORDS: [&str; 16] = [
+    "null", "~", "true", "false", "yes", "no", "y", "n", "on", "off", ".inf", "-.inf", "+.inf",
+    ".nan", "<<", "=",
+];
+
+/// Characters which cannot start a plain string.
+const INDICATORS: &str = "-?:,[]{}#&*!|>'\"%@`";
+
+/// Whether `value` looks like a number or a date to some YAML reader:
+/// digits, signs, separators and the letters of exponents, bases and
+/// timestamps only.
+fn numeric(value: &str) -> bool {
+    value.bytes().any(|b| b.is_ascii_digit())
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_hexdigit() || b"+-._:xXoOtTzZ".contains(&b))
+}
+
+/// Whether `c` is a control character or a line break YAML only keeps
+/// escaped, within double quotes.
+fn special(c: char) -> bool {
+    c.is_control()
+        || matches!(
+            c,
+            '\u{2028}' | '\u{2029}' | '\u{feff}' | '\u{fffe}' | '\u{ffff}'
+        )
+}
+
+/// `value` between double quotes, escaped.
+fn double_quoted(value: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            c if special(c) => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Whether `value` reads back as itself unquoted.
+pub fn is_plain(value: &str) -> bool {
+    !value.is_empty()
+        && value.trim() == value
+        && !value.starts_with(|c| INDICATORS.contains(c))
+        && !value.ends_with(':')
+        && !value.contains(": ")
+        && !value.contains(" #")
+        && !value.chars().any(special)
+        && !KEYWORDS.iter().any(|k| value.eq_ignore_ascii_case(k))
+        && !numeric(value)
+}
+
+/// `value` as a YAML string, quoted as `quotes` says; the values with
+/// control characters or line breaks are double quoted whatever `quotes`
+/// says, single quotes being unable to hold them.
+pub fn quote(value: &str, quotes: Quotes) -> String {
+    match quotes {
+        _ if value.chars().any(special) => double_quoted(value),
+        Quotes::Double => double_quoted(value),
+        Quotes::Auto if is_plain(value) => value.to_string(),
+        Quotes::Single | Quotes::Auto => format!("'{}'", value.replace('\'', "''")),
+    }
+}
+
+/// `value` as a string of the front matter, see `quote`.
+pub fn string(value: impl Display, quotes: Quotes) -> String {
+    quote(&value.to_string(), quotes)
+}
+
+/// A part of the YAML front matter, its strings quoted as the caller says.
+pub trait ToYaml {
+    fn fmt_yaml(&self, f: &mut Formatter<'_>, quotes: Quotes) -> Result<(), Error>;
+
+    /// The YAML of `self`, its strings quoted as `quotes` says.
+    fn to_yaml(&self, quotes: Quotes) -> String {
+        Yaml(self, quotes).to_string()
+    }
+}
+
+/// `self.0` rendered as YAML, its strings quoted as `self.1` says.
+pub struct Yaml<'a, T: ?Sized>(pub &'a T, pub Quotes);
+
+impl<T: ToYaml + ?Sized> Display for Yaml<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        self.0.fmt_yaml(f, self.1)
+    }
+}
+
+/// `value` as a YAML document laid out as serde_yaml does, its strings
+/// quoted as `quotes` says; the keys are only quoted when they must be.
+pub fn render(value: &Value, quotes: Quotes) -> String {
+    let mut out = String::new();
+    match scalar(value, quotes) {
+        Some(s) => out.push_str(&format!("{}\n", s)),
+        None => block(&mut out, value, 0, quotes),
+    }
+    out
+}
+
+/// `value` on one line, unless it is a non-empty sequence or mapping.
+fn scalar(value: &Value, quotes: Quotes) -> Option<String> {
+    match value {
+        Value::Null => Some("null".to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) => Some(quote(s, quotes)),
+        Value::Sequence(s) if s.is_empty() => Some("[]".to_string()),
+        Value::Mapping(m) if m.is_empty() => Some("{}".to_string()),
+        Value::Tagged(t) => scalar(&t.value, quotes),
+        Value::Sequence(_) | Value::Mapping(_) => None,
+    }
+}
+
+/// Writes the items of the sequence or mapping `value`, indented by
+/// `indent` spaces; the sequences of a mapping are not indented further.
+fn block(out: &mut String, value: &Value, indent: usize, quotes: Quotes) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Mapping(m) => {
+            for (k, v) in m {
+                let key = match k {
+                    Value::String(k) => quote(k, Quotes::Auto),
+                    k => scalar(k, Quotes::Auto).unwrap_or_default(),
+                };
+                match (scalar(v, quotes), v) {
+                    (Some(s), _) => out.push_str(&format!("{}{}: {}\n", pad, key, s)),
+                    (None, Value::Sequence(_)) => {
+                        out.push_str(&format!("{}{}:\n", pad, key));
+                        block(out, v, indent, quotes);
+                    }
+                    (None, _) => {
+                        out.push_str(&format!("{}{}:\n", pad, key));
+                        block(out, v, indent + 2, quotes);
+                    }
+                }
+            }
+        }
+        Value::Sequence(s) => {
+            for v in s {
+                match scalar(v, quotes) {
+                    Some(s) => out.push_str(&format!("{}- {}\n", pad, s)),
+                    None => {
+                        // The first line of the nested block follows the dash.
+                        let mut nested = String::new();
+                        block(&mut nested, v, indent + 2, quotes);
+                        out.push_str(&format!("{}- {}", pad, &nested[indent + 2..]));
+                    }
+                }
+            }
+        }
+        Value::Tagged(t) => block(out, &t.value, indent, quotes),
+        _ => {}
+    }
+}
@@ -0,0 +1,106 @@
+//! Tests of the one-call conversion offered to embedders.
+
+use new_page::diagnostics::collect;
+use new_page::{convert, convert_str, Options, RenderedPage, Target, Warning};
+
+#[test]
+fn pages_are_rendered_with_their_file_names() {
+    let bib = "@article{DBLP:j/x, author = {A, B}, title = {T}, journal = {J}, year = {2020}}";
+    let opts: Options = serde_json::from_str(r#"{"target": "zola", "bundle": true}"#).unwrap();
+    assert_eq!(opts.target, Target::Zola);
+    let pages = convert_str(bib, &opts).unwrap();
+    assert_eq!(pages.len(), 1);
+    let RenderedPage {
+        key,
+        filename,
+        content,
+    } = &pages[0];
+    assert_eq!(key, "DBLP:j/x");
+    assert_eq!(filename, "DBLP_j_x/index.md");
+    assert!(content.starts_with("+++\n"));
+    let strict = Options {
+        recover: false,
+        ..Options::default()
+    };
+    assert!(convert_str("@article{", &strict).is_err());
+}
+
+#[test]
+fn options_reject_unknown_settings() {
+    let opts: Options = serde_json::from_str("{}").unwrap();
+    assert_eq!(opts.summary_sentences, 2);
+    assert!(serde_json::from_str::<Options>(r#"{"output_dir": "/tmp"}"#).is_err());
+    assert!(serde_json::from_str::<Options>(r#"{"emit": "front-matter"}"#).is_ok());
+}
+
+#[test]
+fn builder_rejects_contradictory_settings() {
+    let opts = Options::builder()
+        .languages(vec!["en".to_string(), "fr".to_string()])
+        .max_abstract_words(50)
+        .build()
+        .unwrap();
+    assert_eq!(opts.max_abstract_words, Some(50));
+    assert_eq!(opts.language_suffix, Options::default().language_suffix);
+
+    let err = |b: new_page::OptionsBuilder| b.build().err().unwrap();
+    assert_eq!(
+        err(Options::builder().wpm(0usize)),
+        "the reading speed must be positive"
+    );
+    assert_eq!(
+        err(Options::builder()
+            .languages(vec!["en".to_string()])
+            .language_suffix("_fr")),
+        "the language suffix \"_fr\" has no {} standing for the language"
+    );
+    assert_eq!(
+        err(Options::builder().languages(vec!["en".to_string(), "en".to_string()])),
+        "language en is listed twice, at 2"
+    );
+    assert_eq!(
+        err(Options::builder().author_marker('a')),
+        "the author marker 'a' would be part of the names"
+    );
+}
+
+#[test]
+fn long_fields_are_cut() {
+    let bib = format!(
+        "@article{{doe, author = {{Doe, Jane}}, title = {{A Paper}}, journal = {{J}}, \
+         year = {{2021}}, abstract = {{{}}}}}",
+        "é".repeat(100)
+    );
+    let opts = Options::builder()
+        .max_field_length(51usize)
+        .build()
+        .unwrap();
+    let papers = convert(&bib, &opts).unwrap();
+    // Cut at a character boundary, below the limit.
+    assert_eq!(papers[0].abs.abs, "é".repeat(25));
+    assert!(Options::builder().max_field_length(0usize).build().is_err());
+}
+
+#[test]
+fn long_fields_warn_by_name() {
+    let long = "x".repeat(60);
+    let bib = format!(
+        "@article{{doe, author = {{Doe, Jane}}, title = {{{0}}}, journal = {{{0}}}, \
+         year = {{2021}}, abstract = {{{0}}}, note = {{{0}}}, keywords = {{{0}}}}}",
+        long
+    );
+    let opts = Options::builder()
+        .max_field_length(50usize)
+        .build()
+        .unwrap();
+    let (papers, warnings) = collect(|| convert(&bib, &opts));
+    assert_eq!(papers.unwrap().len(), 1);
+    let fields: Vec<&str> = warnings
+        .iter()
+        .filter_map(|w| match w {
+            Warning::FieldTooLong { field, .. } => Some(field.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(fields, ["abstract", "journal", "keywords", "note", "title"]);
+}
@@ -0,0 +1,127 @@
+//! Tests of the cache of the parsed inputs.
+
+mod support;
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use new_page::cache;
+use new_page::{convert, render, Options};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("new_page-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn cached_files(dir: &PathBuf) -> usize {
+    std::fs::read_dir(dir).map_or(0, |d| d.count())
+}
+
+#[test]
+fn unchanged_inputs_are_read_back() {
+    let dir = temp_dir("cache");
+    let opts = Options::builder().cache_dir(dir.clone()).build().unwrap();
+    let bib = support::synthetic_bibliography(20)
+        + "@preamble{\"\\newcommand{\\tool}{Tool}\"}\n\
+           @misc{macro, author = {Roe, R}, title = {\\tool{} Paper}, year = 2020}\n";
+    let fresh = render(&convert(&bib, &Options::default()).unwrap(), &opts);
+
+    assert_eq!(render(&convert(&bib, &opts).unwrap(), &opts), fresh);
+    assert_eq!(cached_files(&dir), 1);
+    assert_eq!(render(&convert(&bib, &opts).unwrap(), &opts), fresh);
+    assert_eq!(cached_files(&dir), 1);
+
+    // A changed input has an entry of its own.
+    let changed = bib.replace("Paper}", "Other Paper}");
+    assert!(render(&convert(&changed, &opts).unwrap(), &opts).contains("Tool Other Paper"));
+    assert_eq!(cached_files(&dir), 2);
+
+    // Entries of another format version are parsed again and replaced.
+    for entry in std::fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        let content = std::fs::read_to_string(&path).unwrap();
+        let stale = content.replacen(
+            &format!("\"version\":{}", cache::VERSION),
+            "\"version\":0",
+            1,
+        );
+        assert_ne!(stale, content);
+        std::fs::write(&path, stale.replace("Tool", "Stale")).unwrap();
+    }
+    assert_eq!(render(&convert(&bib, &opts).unwrap(), &opts), fresh);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn parse_cache_flags() {
+    let dir = temp_dir("cache-flags");
+    let bib = dir.with_extension("bib");
+    std::fs::write(&bib, support::synthetic_bibliography(50)).unwrap();
+    let run = |flags: &[&str]| -> String {
+        let out = Command::new(env!("CARGO_BIN_EXE_new_page"))
+            .arg("--file-path")
+            .arg(&bib)
+            .arg("--cache-dir")
+            .arg(&dir)
+            .args(["--quiet", "--timings", "--output", "-"])
+            .args(flags)
+            .output()
+            .unwrap();
+        assert!(out.status.success());
+        String::from_utf8(out.stderr).unwrap()
+    };
+    let cached = dir.join(cache::SUBDIRECTORY);
+
+    run(&[]);
+    assert_eq!(cached_files(&cached), 0);
+    run(&["--parse-cache", "--no-cache"]);
+    assert_eq!(cached_files(&cached), 0);
+
+    let first = run(&["--parse-cache"]);
+    assert!(!first.contains("from the cache"), "{}", first);
+    assert_eq!(cached_files(&cached), 1);
+    let second = run(&["--parse-cache"]);
+    assert!(second.contains("Parsed from the cache in "), "{}", second);
+    assert!(second.contains("x faster"), "{}", second);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    std::fs::remove_file(&bib).unwrap();
+}
+
+#[test]
+fn inputs_giving_warnings_warn_every_time() {
+    let dir = temp_dir("cache-warnings");
+    let bib = dir.with_extension("bib");
+    std::fs::write(
+        &bib,
+        "@article{long, author = {Doe, Jane}, title = {A Title Longer Than the Limit},
+          journal = {J}, year = {2021}}\n",
+    )
+    .unwrap();
+    let run = || {
+        let out = Command::new(env!("CARGO_BIN_EXE_new_page"))
+            .arg("--file-path")
+            .arg(&bib)
+            .arg("--cache-dir")
+            .arg(&dir)
+            .args([
+                "--parse-cache",
+                "--fail-on-warning",
+                "--max-field-length",
+                "20",
+            ])
+            .args(["--output", "-"])
+            .output()
+            .unwrap();
+        (out.status.code(), String::from_utf8(out.stderr).unwrap())
+    };
+    for _ in 0..2 {
+        let (code, stderr) = run();
+        assert_eq!(code, Some(1), "{}", stderr);
+        assert!(stderr.contains("Warning: long: title is "), "{}", stderr);
+    }
+    assert_eq!(cached_files(&dir.join(cache::SUBDIRECTORY)), 0);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::remove_file(&bib).unwrap();
+}
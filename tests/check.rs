@@ -0,0 +1,145 @@
+//! Tests of the house conventions checked with `--check`.
+
+use new_page::check::{check, Checks, Entry, Rule, Violation};
+use new_page::config::Config;
+use new_page::diagnostics::collect;
+use new_page::merge::MergeOn;
+use new_page::{Options, Warning};
+
+const BIB: &str = r#"
+@misc{good2020, author = {Doe, Jane}, title = {T}, year = {2020}, doi = {10.1/x},
+  abstract = {Text.}}
+@misc{Doe2021, author = {Doe, Jane}, title = {T}, year = {2021},
+  doi = {https://doi.org/10.1/y}, url = {https://example.org}}
+@misc{many2017, author = {A, A and B, B and C, C}, title = {T}, year = {2017}}
+"#;
+
+/// Rule id, key, field and message of the violations of `BIB` under `checks`.
+fn violations(checks: Checks) -> Vec<(String, String, Option<String>, String)> {
+    let opts = Options {
+        checks,
+        ..Options::default()
+    };
+    let (count, warnings) = collect(|| check(BIB, "", MergeOn::Key, &opts));
+    let found: Vec<_> = warnings
+        .iter()
+        .map(|w| {
+            (
+                w.rule().to_string(),
+                w.key().unwrap_or_default().to_string(),
+                w.field().map(String::from),
+                w.message(),
+            )
+        })
+        .collect();
+    assert_eq!(count.unwrap(), found.len());
+    found
+}
+
+fn v(
+    rule: &str,
+    key: &str,
+    field: Option<&str>,
+    message: &str,
+) -> (String, String, Option<String>, String) {
+    (
+        rule.into(),
+        key.into(),
+        field.map(String::from),
+        message.into(),
+    )
+}
+
+#[test]
+fn default_rules() {
+    assert_eq!(
+        violations(Checks::default()),
+        [
+            v("key-case", "Doe2021", None, "key is not lowercase, doe2021"),
+            v(
+                "doi-format",
+                "Doe2021",
+                Some("doi"),
+                "doi https://doi.org/10.1/y is not bare, as 10.1145/3385412"
+            ),
+            v(
+                "redundant-url",
+                "Doe2021",
+                Some("url"),
+                "has a url besides its doi"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn configured_rules() {
+    let config: Config = toml::from_str(
+        "[check]
+key-case = false
+redundant-url = false
+require-abstract-after-year = 2018
+max-authors-sanity = 2",
+    )
+    .unwrap();
+    assert_eq!(
+        violations(config.check),
+        [
+            v(
+                "doi-format",
+                "Doe2021",
+                Some("doi"),
+                "doi https://doi.org/10.1/y is not bare, as 10.1145/3385412"
+            ),
+            v(
+                "require-abstract-after-year",
+                "Doe2021",
+                Some("abstract"),
+                "has no abstract, required after 2018"
+            ),
+            v(
+                "max-authors-sanity",
+                "many2017",
+                Some("author"),
+                "lists 3 authors, more than 2"
+            ),
+        ]
+    );
+    assert!(toml::from_str::<Config>("[check]\nno-such-rule = true").is_err());
+}
+
+#[test]
+fn rules_are_pluggable() {
+    struct NoMisc;
+    impl Rule for NoMisc {
+        fn id(&self) -> &'static str {
+            "no-misc"
+        }
+        fn check(&self, entry: &Entry) -> Option<Violation> {
+            (entry.paper.kind.kind == "misc").then(|| Violation {
+                field: None,
+                message: format!("{} is a @misc", entry.tag("title").unwrap_or_default()),
+            })
+        }
+    }
+    let mut rules = Checks::default().rules();
+    rules.push(Box::new(NoMisc));
+    assert_eq!(
+        rules.iter().map(|r| r.id()).collect::<Vec<_>>(),
+        [
+            "key-case",
+            "doi-format",
+            "redundant-url",
+            "max-authors-sanity",
+            "no-misc"
+        ]
+    );
+    let warning = Warning::Convention {
+        rule: "no-misc",
+        key: "k".into(),
+        field: None,
+        message: "T is a @misc".into(),
+    };
+    assert_eq!(warning.to_string(), "k: T is a @misc");
+    assert_eq!(serde_json::to_value(&warning).unwrap()["rule"], "no-misc");
+}
@@ -0,0 +1,393 @@
+//! Tests of the exit codes of the command line.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+const CLEAN: &str = "@article{doe, author = {Doe, Jane}, title = {A Paper}, \
+                     journal = {J}, year = {2021}}\n";
+
+fn dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("new_page-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Exit code of the command line run on `bibtex` with `args`.
+fn code(name: &str, bibtex: &str, args: &[&str]) -> i32 {
+    let path = dir().join(name);
+    std::fs::write(&path, bibtex).unwrap();
+    Command::new(env!("CARGO_BIN_EXE_new_page"))
+        .current_dir(dir())
+        .arg("--file-path")
+        .arg(&path)
+        .args(["--quiet", "--output", "-"])
+        .args(args)
+        .output()
+        .unwrap()
+        .status
+        .code()
+        .unwrap()
+}
+
+#[test]
+fn success() {
+    assert_eq!(code("clean.bib", CLEAN, &[]), 0);
+    assert_eq!(code("clean-strict.bib", CLEAN, &["--fail-on-warning"]), 0);
+}
+
+#[test]
+fn warnings_fail_on_request() {
+    let bib = "@article{doe, author = {Doe, Jane}, title = {A Paper}, journal = {J}, \
+               year = {1800}}\n";
+    assert_eq!(code("old.bib", bib, &[]), 0);
+    assert_eq!(code("old-strict.bib", bib, &["--fail-on-warning"]), 1);
+}
+
+#[test]
+fn failed_entries() {
+    let bib =
+        CLEAN.to_string() + "@article{broken, title = {Unclosed}\n" + &CLEAN.replace("doe", "roe");
+    assert_eq!(code("broken.bib", &bib, &[]), 2);
+}
+
+/// Exit code and standard error of the command line converting `bibtex`.
+fn failure(name: &str, bibtex: &str) -> (Option<i32>, String) {
+    let path = dir().join(name);
+    std::fs::write(&path, bibtex).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_new_page"))
+        .current_dir(dir())
+        .arg("--file-path")
+        .arg(&path)
+        .args(["--output", "-"])
+        .output()
+        .unwrap();
+    (
+        output.status.code(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn entry_without_author() {
+    let bib =
+        CLEAN.to_string() + "@article{anonymous, title = {T}, journal = {J}, year = {2021}}\n";
+    let (code, stderr) = failure("anonymous.bib", &bib);
+    assert_eq!(code, Some(2), "{}", stderr);
+    assert!(stderr.contains("Error: anonymous: no author, leaving the entry out"));
+    assert!(stderr.contains("1 converted, 0 skipped, 0 excluded, 1 failed"));
+}
+
+#[test]
+fn entry_without_venue() {
+    let bib =
+        CLEAN.to_string() + "@article{nowhere, author = {Roe, R}, title = {T}, year = {2021}}\n";
+    let (code, stderr) = failure("nowhere.bib", &bib);
+    assert_eq!(code, Some(2), "{}", stderr);
+    assert!(stderr.contains("Error: nowhere: no journal nor booktitle, leaving the entry out"));
+    assert!(stderr.contains("1 converted, 0 skipped, 0 excluded, 1 failed"));
+}
+
+#[test]
+fn pages_which_do_not_verify_fail() {
+    let config = dir().join("verify.toml");
+    std::fs::write(&config, "[fields.rename]\nauthors = \"by: all\"\n").unwrap();
+    let path = dir().join("verify.bib");
+    std::fs::write(&path, CLEAN).unwrap();
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_new_page"))
+            .current_dir(dir())
+            .arg("--file-path")
+            .arg(&path)
+            .arg("--config")
+            .arg(&config)
+            .args(["--output", "-"])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let output = run(&[]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("0 converted, 0 skipped, 0 excluded, 1 failed"),
+        "{}",
+        stderr
+    );
+    // Only the pages have a front matter to verify.
+    let output = run(&["--format", "markdown-list"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("A Paper"));
+}
+
+#[test]
+fn unreadable_input() {
+    assert_eq!(code("strict.bib", "@article{", &["--no-recover"]), 3);
+    let missing = Command::new(env!("CARGO_BIN_EXE_new_page"))
+        .args(["--file-path", "/nonexistent/refs.bib"])
+        .output()
+        .unwrap();
+    assert_eq!(missing.status.code(), Some(3));
+}
+
+#[test]
+fn compiled_bibliography() {
+    let path = dir().join("compiled.bib");
+    std::fs::write(
+        &path,
+        "\\begin{thebibliography}{1}\n\\bibitem{doe} J. Doe.\n",
+    )
+    .unwrap();
+    let out = Command::new(env!("CARGO_BIN_EXE_new_page"))
+        .arg("--file-path")
+        .arg(&path)
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(3));
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(
+        stderr.contains("this looks like a LaTeX .bbl file"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn json_messages() {
+    let run = |name: &str, bibtex: &str, args: &[&str]| {
+        let path = dir().join(name);
+        std::fs::write(&path, bibtex).unwrap();
+        let out = Command::new(env!("CARGO_BIN_EXE_new_page"))
+            .arg("--file-path")
+            .arg(&path)
+            .args(["--output", "-", "--message-format", "json"])
+            .args(args)
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8(out.stderr).unwrap();
+        let records: Vec<serde_json::Value> = stderr
+            .lines()
+            .filter_map(|l| serde_json::from_str(l).ok())
+            .collect();
+        (out.status.code(), records)
+    };
+    let (code, records) = run("json.bib", &CLEAN.replace("2021", "soon"), &[]);
+    assert_eq!(code, Some(0));
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["rule"], "invalid-field");
+    assert_eq!(records[0]["key"], "doe");
+    assert_eq!(records[0]["field"], "year");
+
+    let broken = format!("{}@article{{bad, title = J J}}\n", CLEAN);
+    let (code, records) = run("json-broken.bib", &broken, &["--no-recover"]);
+    assert_eq!(code, Some(3));
+    assert_eq!(records[0]["severity"], "error");
+    assert_eq!(records[0]["line"], 2);
+}
+
+#[test]
+fn invalid_usage() {
+    assert_eq!(code("flag.bib", CLEAN, &["--no-such-flag"]), 4);
+    assert_eq!(
+        code(
+            "years.bib",
+            CLEAN,
+            &["--min-year", "2030", "--max-year", "2000"]
+        ),
+        4
+    );
+    assert_eq!(code("locale.bib", CLEAN, &["--locale", "es"]), 4);
+}
+
+#[test]
+fn mixed_formats() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let output = Command::new(env!("CARGO_BIN_EXE_new_page"))
+        .current_dir(dir())
+        .arg("-f")
+        .arg(fixtures.join("zotero.ris"))
+        .arg("-f")
+        .arg(fixtures.join("zotero.json"))
+        .arg("-f")
+        .arg(fixtures.join("article.bib"))
+        .args(["--quiet", "--no-verify"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let pages = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(pages.matches("bibtex_key: ").count(), 5);
+}
+
+#[test]
+fn single_entry_on_stdin() {
+    let run = |entry: &str| {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_new_page"))
+            .args([
+                "--stdin-format",
+                "entry",
+                "--emit",
+                "front-matter",
+                "--quiet",
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(entry.as_bytes())
+            .unwrap();
+        child.wait_with_output().unwrap()
+    };
+    let out = run(&format!("\n  {},\n\n", CLEAN.trim()));
+    assert_eq!(out.status.code(), Some(0));
+    let page = String::from_utf8(out.stdout).unwrap();
+    assert_eq!(page.matches("bibtex_key:").count(), 1);
+    assert!(page.starts_with("---\n"));
+    assert!(out.stderr.is_empty());
+
+    for malformed in ["@article{doe, title = {T", "not bibtex", &CLEAN.repeat(2)] {
+        let out = run(malformed);
+        assert_eq!(out.status.code(), Some(3), "{}", malformed);
+        assert!(out.stdout.is_empty());
+        let stderr = String::from_utf8(out.stderr).unwrap();
+        assert_eq!(stderr.lines().count(), 1, "{}", stderr);
+    }
+}
+
+#[test]
+fn input_limits() {
+    let large = CLEAN.to_string() + &"%".repeat(2 << 20);
+    assert_eq!(code("large.bib", &large, &["--max-input-size", "1"]), 3);
+    assert_eq!(
+        code("large-allowed.bib", &large, &["--max-input-size", "3"]),
+        0
+    );
+    assert_eq!(
+        code(
+            "long.bib",
+            CLEAN,
+            &["--max-field-length", "4", "--fail-on-warning"]
+        ),
+        1
+    );
+
+    let path = dir().join("many.bib");
+    std::fs::write(&path, CLEAN.to_string() + &CLEAN.replace("doe", "roe")).unwrap();
+    let out = dir().join("many");
+    let output = Command::new(env!("CARGO_BIN_EXE_new_page"))
+        .arg("--file-path")
+        .arg(&path)
+        .arg("--output-dir")
+        .arg(&out)
+        .args(["--max-pages", "1"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("more than the limit of 1; raise it with --max-pages"));
+    assert!(!out.join("roe.md").exists());
+}
+
+#[test]
+fn manifest_of_successful_runs() {
+    let run = |name: &str, bibtex: &str| {
+        let path = dir().join(format!("{}.bib", name));
+        std::fs::write(&path, bibtex).unwrap();
+        let out = dir().join(name);
+        let manifest = dir().join(format!("{}.json", name));
+        let output = Command::new(env!("CARGO_BIN_EXE_new_page"))
+            .arg("--file-path")
+            .arg(&path)
+            .arg("--output-dir")
+            .arg(&out)
+            .arg("--manifest")
+            .arg(&manifest)
+            .arg("--quiet")
+            .output()
+            .unwrap();
+        (output.status.code(), manifest)
+    };
+    let (code, manifest) = run("manifest", CLEAN);
+    assert_eq!(code, Some(0));
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(manifest).unwrap()).unwrap();
+    assert_eq!(json["pages"][0]["key"], "doe");
+    assert_eq!(json["pages"][0]["path"], "doe.md");
+    assert_eq!(json["pages"][0]["title"], "A Paper");
+    assert!(json["run"].get("generated").is_none());
+
+    let broken = CLEAN.to_string() + "@article{bad, title = {T}, journal = J J, year = {2021}}\n";
+    let (code, manifest) = run("manifest-failed", &broken);
+    assert_ne!(code, Some(0));
+    assert!(!manifest.exists());
+}
+
+#[test]
+fn convention_checks() {
+    let config = dir().join("check.toml");
+    std::fs::write(&config, "[check]\nkey-case = false\n").unwrap();
+    let check = |name: &str, bibtex: &str, args: &[&str]| {
+        let path = dir().join(name);
+        std::fs::write(&path, bibtex).unwrap();
+        Command::new(env!("CARGO_BIN_EXE_new_page"))
+            .arg("--file-path")
+            .arg(&path)
+            .args(["--check", "--message-format", "json"])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    assert_eq!(check("check.bib", CLEAN, &[]).status.code(), Some(0));
+    let upper = CLEAN.replace("{doe,", "{Doe,");
+    let output = check("check-upper.bib", &upper, &[]);
+    assert_eq!(output.status.code(), Some(1));
+    let record: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(record["rule"], "key-case");
+    assert_eq!(record["key"], "Doe");
+    assert!(output.stdout.is_empty());
+    let config = ["--config", config.to_str().unwrap()];
+    let output = check("check-allowed.bib", &upper, &config);
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn list_subcommand() {
+    let path = dir().join("list.bib");
+    std::fs::write(&path, CLEAN.to_string() + "@misc{bare,}\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_new_page"))
+        .arg("list")
+        .arg(&path)
+        .args(["--format", "json", "--sort", "key"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let entries: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(entries[0]["key"], "bare");
+    assert!(entries[0]["title"].is_null());
+    assert_eq!(entries[1]["author"], "Doe");
+}
+
+#[test]
+fn line_endings_of_stdout() {
+    let path = dir().join("line_endings.bib");
+    std::fs::write(&path, CLEAN).unwrap();
+    let out = Command::new(env!("CARGO_BIN_EXE_new_page"))
+        .arg("--file-path")
+        .arg(&path)
+        .args(["--quiet", "--line-ending", "crlf"])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(0));
+    let page = String::from_utf8(out.stdout).unwrap();
+    assert!(page.starts_with("---\r\nauthors:\r\n"));
+    assert!(page.ends_with("entry_type: \"article\"\r\n---\r\n"));
+    assert!(!page.replace("\r\n", "").contains(['\r', '\n']));
+    assert!(!page.contains(" \r\n"));
+    assert_eq!(code("line_endings.bib", CLEAN, &["--line-ending", "cr"]), 4);
+}
@@ -0,0 +1,106 @@
+//! Tests of the detection of the conventions of existing pages.
+
+use std::path::PathBuf;
+
+use new_page::conventions::{adopt, detect, mismatches, DateFormat};
+use new_page::{Options, Target};
+
+fn dir(name: &str, pages: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "new_page-conventions-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    for (path, content) in pages {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn hugo_pages_with_other_keys() {
+    let page = "---\ntitle: \"A\"\nauthor:\n  - \"Jane Doe\"\ndate: 2021-03-01T00:00:00Z\n---\nAbstract.\n";
+    let dir = dir(
+        "hugo",
+        &[
+            ("a.md", page),
+            ("b.md", page),
+            ("_index.md", "---\ntitle: Section\n---\n"),
+        ],
+    );
+    let found = detect(&dir);
+    assert_eq!(found.sampled, 2);
+    assert_eq!(found.toml, Some(false));
+    assert_eq!(found.target, Some(Target::Hugo));
+    assert_eq!(found.authors_key.as_deref(), Some("author"));
+    assert_eq!(found.bundle, Some(false));
+    assert_eq!(found.date, Some(DateFormat::Time));
+
+    let mut opts = Options::default();
+    let messages: Vec<(String, bool)> = mismatches(&found, &opts)
+        .into_iter()
+        .map(|m| (m.message, m.adoptable))
+        .collect();
+    assert_eq!(
+        messages,
+        [
+            (
+                "existing pages name the authors `author`; add `authors = \"author\"` under \
+                 [fields.rename] in the configuration"
+                    .to_string(),
+                true
+            ),
+            (
+                "existing pages give the time of their date, new pages only the day".to_string(),
+                false
+            ),
+        ]
+    );
+    adopt(&found, &mut opts);
+    assert_eq!(opts.fields.name("authors"), "author");
+    assert_eq!(mismatches(&found, &opts).len(), 1);
+}
+
+#[test]
+fn zola_bundles() {
+    let page = "+++\ntitle = \"A\"\ndate = 2021-03-01\n[taxonomies]\ncategories = [\"journal\"]\n[extra]\nauthors = [\"Jane Doe\"]\n+++\nAbstract.\n";
+    let dir = dir("zola", &[("a/index.md", page), ("b/index.md", page)]);
+    let found = detect(&dir);
+    assert_eq!(found.target, Some(Target::Zola));
+    assert_eq!(found.bundle, Some(true));
+    assert_eq!(found.date, Some(DateFormat::Day));
+    let mut opts = Options::default();
+    let messages: Vec<String> = mismatches(&found, &opts)
+        .into_iter()
+        .map(|m| m.message)
+        .collect();
+    assert_eq!(
+        messages,
+        [
+            "existing pages are written for Zola, with TOML front matter; pass --target zola",
+            "existing pages are page bundles; pass --bundle",
+        ]
+    );
+    adopt(&found, &mut opts);
+    assert_eq!((opts.target, opts.bundle), (Target::Zola, true));
+    assert!(mismatches(&found, &opts).is_empty());
+}
+
+#[test]
+fn disagreeing_pages_tell_nothing() {
+    let dir = dir(
+        "mixed",
+        &[
+            ("a.md", "---\nauthors: []\n---\n"),
+            ("b/index.md", "---\nauthor: []\n---\n"),
+        ],
+    );
+    let found = detect(&dir);
+    assert_eq!(found.sampled, 2);
+    assert_eq!((found.authors_key.as_deref(), found.bundle), (None, None));
+    assert!(mismatches(&found, &Options::default()).is_empty());
+    assert_eq!(detect(&dir.join("missing")).sampled, 0);
+}
@@ -0,0 +1,168 @@
+//! Tests of the errors and warnings given to embedders.
+
+use new_page::diagnostics::collect;
+use new_page::inspect::inspect;
+use new_page::merge::MergeOn;
+use new_page::{convert, convert_summarized, Error, Options, Target, Warning};
+
+const BROKEN: &str =
+    "@article{good, author = {Doe, Jane}, title = {T}, journal = {J}, year = {2021}}
+@article{bad, author = {Doe, Jane}, title = {T}, journal = J J, year = {2021}}
+";
+
+#[test]
+fn unknown_types_fixture() {
+    let bib = include_str!("fixtures/unknown_types.bib");
+    let (papers, warnings) = collect(|| convert(bib, &Options::default()));
+    assert_eq!(papers.unwrap().len(), 2);
+    assert_eq!(
+        warnings,
+        [Warning::UnknownTypes {
+            types: vec![("artwork".to_string(), 1), ("dataset".to_string(), 1)]
+        }]
+    );
+    assert_eq!(warnings[0].rule(), "unknown-types");
+    assert_eq!(warnings[0].key(), None);
+    assert_eq!(
+        warnings[0].to_string(),
+        "entry types: converted generically @artwork (1), @dataset (1)"
+    );
+}
+
+#[test]
+fn unparsable_entries_are_located() {
+    let (papers, warnings) = collect(|| convert(BROKEN, &Options::default()));
+    assert_eq!(papers.unwrap().len(), 1);
+    let [Warning::Unparsable {
+        entry_type,
+        key,
+        line,
+        span,
+    }] = &warnings[..]
+    else {
+        panic!("{:?}", warnings);
+    };
+    assert_eq!(
+        (entry_type.as_str(), key.as_deref(), *line),
+        ("article", Some("bad"), 2)
+    );
+    assert!(BROKEN[span.clone()].starts_with("@article{bad,"));
+    assert_eq!(span.end, BROKEN.trim_end().len());
+
+    let opts = Options {
+        recover: false,
+        ..Options::default()
+    };
+    let e = collect(|| convert(BROKEN, &opts)).0.err().unwrap();
+    assert!(matches!(e, Error::Parse { line: Some(2), .. }), "{:?}", e);
+    assert_eq!(e.span(), warnings[0].span());
+    assert!(e.to_string().starts_with("line 2: "));
+}
+
+#[test]
+fn entries_without_author_or_venue_fail() {
+    let bib = "@article{anonymous, title = {T}, journal = {J}, year = {2021}}
+@inproceedings{nowhere, author = {Doe, Jane}, title = {T}, year = {2021}}
+@misc{note, title = {T}, year = {2021}}
+";
+    let opts = Options::default();
+    let (papers, summary) = convert_summarized(bib, &opts).unwrap();
+    assert_eq!(papers.len(), 1);
+    assert_eq!((summary.converted, summary.failed), (1, 2));
+
+    let e = inspect(bib, "", MergeOn::Key, "anonymous", &opts).unwrap_err();
+    assert_eq!(
+        e,
+        Error::MissingAuthor {
+            key: "anonymous".to_string()
+        }
+    );
+    assert_eq!(
+        (e.rule(), e.key(), e.line()),
+        ("missing-author", Some("anonymous"), None)
+    );
+    assert_eq!(e.to_string(), "anonymous: no author, leaving the entry out");
+    let e = inspect(bib, "", MergeOn::Key, "nowhere", &opts).unwrap_err();
+    assert_eq!(
+        e,
+        Error::MissingVenue {
+            key: "nowhere".to_string()
+        }
+    );
+    assert_eq!(e.rule(), "missing-venue");
+    let json: serde_json::Value = serde_json::to_value(&e).unwrap();
+    assert_eq!(
+        (&json["severity"], &json["key"]),
+        (&"error".into(), &"nowhere".into())
+    );
+}
+
+#[test]
+fn field_defects() {
+    let bib = "@misc{k, author = {Roe, Richard and Roe, R.}, title = {T}, year = {soon},
+        xdata = {nowhere}, url = {not a link}}";
+    let (_, warnings) = collect(|| convert(bib, &Options::default()));
+    let rules: Vec<(&str, Option<&str>)> = warnings.iter().map(|w| (w.rule(), w.field())).collect();
+    assert_eq!(
+        rules,
+        [
+            ("missing-xdata", None),
+            ("invalid-field", Some("year")),
+            ("duplicate-name", Some("authors")),
+            ("invalid-field", Some("url")),
+        ],
+        "{:?}",
+        warnings
+    );
+    assert!(warnings.iter().all(|w| w.key() == Some("k")));
+    assert_eq!(
+        warnings[0],
+        Warning::MissingXdata {
+            key: "k".to_string(),
+            reference: "nowhere".to_string()
+        }
+    );
+}
+
+#[test]
+fn json_records() {
+    let (_, warnings) = collect(|| convert(BROKEN, &Options::default()));
+    let json: serde_json::Value = serde_json::to_value(&warnings[0]).unwrap();
+    assert_eq!(json["severity"], "warning");
+    assert_eq!(json["rule"], "unparsable-entry");
+    assert_eq!(json["key"], "bad");
+    assert_eq!(json["line"], 2);
+    assert_eq!(json["span"]["end"], BROKEN.trim_end().len());
+    assert_eq!(json["message"], "cannot parse this @article, skipping it");
+}
+
+#[test]
+fn untitled_entries() {
+    let bib = "@misc{doe2023fast, author = {Doe, Jane}, title = { }, year = {2023}}
+@misc{roe2021, author = {Roe, Rick}, year = {2021}}";
+    let (papers, warnings) = collect(|| convert(bib, &Options::default()));
+    let papers = papers.unwrap();
+    assert_eq!(papers[0].title.title, "Doe 2023 (fast)");
+    assert_eq!(papers[1].title.title, "Roe 2021");
+    assert!(papers[0]
+        .render(Target::Hugo)
+        .contains("title: \"Doe 2023 (fast)\"\ntitle_missing: true\n"));
+    assert_eq!(
+        serde_json::to_value(&papers[1]).unwrap()["title_missing"],
+        true
+    );
+    let missing: Vec<(&str, String)> = warnings.iter().map(|w| (w.rule(), w.to_string())).collect();
+    assert_eq!(
+        missing,
+        [
+            (
+                "missing-field",
+                "doe2023fast: no title, naming the page \"Doe 2023 (fast)\"".to_string()
+            ),
+            (
+                "missing-field",
+                "roe2021: no title, naming the page \"Roe 2021\"".to_string()
+            ),
+        ]
+    );
+}
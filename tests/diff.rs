@@ -0,0 +1,61 @@
+//! Tests of the comparison between a bibliography and generated pages.
+
+use new_page::diff::{compare, page_key};
+use new_page::output::write_pages;
+use new_page::{convert, Options, Target};
+
+const BIB: &str = r#"
+@article{doe, author = {Doe, Jane}, title = {A Paper}, journal = {J}, year = {2021}}
+@article{roe, author = {Roe, Rick}, title = {Another}, journal = {J}, year = {2020}}
+@article{poe, author = {Poe, Pat}, title = {Third}, journal = {J}, year = {2019}}
+"#;
+
+#[test]
+fn keys_are_read_from_front_matter() {
+    assert_eq!(
+        page_key("---\nbibtex_key: \"a:b\"\n---\n", "bibtex_key"),
+        Some("a:b".into())
+    );
+    assert_eq!(
+        page_key("+++\n[extra]\nbibtex_key = \"x\"\n+++\n", "bibtex_key"),
+        Some("x".into())
+    );
+    assert_eq!(
+        page_key("---\ntitle: t\n---\nbibtex_key: \"y\"\n", "bibtex_key"),
+        None
+    );
+    assert_eq!(page_key("bibtex_key: \"y\"\n", "bibtex_key"), None);
+}
+
+#[test]
+fn new_stale_and_changed() {
+    let opts = Options::default();
+    let papers = convert(BIB, &opts).unwrap();
+    let dir = std::env::temp_dir().join(format!("new_page-diff-{}", std::process::id()));
+    write_pages(&papers, &dir, &opts).unwrap();
+    assert!(compare(&papers, &dir, &opts).unwrap().is_clean());
+
+    std::fs::remove_file(dir.join("doe.md")).unwrap();
+    std::fs::write(dir.join("old.md"), "---\nbibtex_key: \"old\"\n---\n").unwrap();
+    let roe = dir.join("roe.md");
+    let edited = std::fs::read_to_string(&roe)
+        .unwrap()
+        .replace("Another", "Other");
+    std::fs::write(&roe, edited).unwrap();
+
+    let diff = compare(&papers, &dir, &opts).unwrap();
+    let zola = compare(
+        &papers,
+        &dir,
+        &Options {
+            target: Target::Zola,
+            ..opts
+        },
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(diff.new, vec!["doe"]);
+    assert_eq!(diff.stale, vec![dir.join("old.md")]);
+    assert_eq!(diff.changed, vec!["roe"]);
+    assert_eq!(zola.unwrap().changed, vec!["roe", "poe"]);
+}
@@ -0,0 +1,156 @@
+//! Tests of the search of the directories given as input.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use new_page::discover::{bib_files, Discovery};
+use new_page::provenance::Files;
+use new_page::{convert, Options};
+
+fn entry(key: &str) -> String {
+    format!(
+        "@article{{{}, author = {{Doe, Jane}}, title = {{{}}}, journal = {{J}}, year = 2021}}\n",
+        key, key
+    )
+}
+
+/// `~/papers`: a file at the top, one per project, one deeper, and files
+/// in a hidden directory and in `node_modules`.
+fn papers(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("new_page-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    for (path, key) in [
+        ("top.bib", "top"),
+        ("alpha/refs.bib", "alpha"),
+        ("beta/refs.BIB", "beta"),
+        ("beta/old/refs.bib", "old"),
+        (".git/stale.bib", "stale"),
+        ("node_modules/pkg/refs.bib", "pkg"),
+    ] {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, entry(key)).unwrap();
+    }
+    fs::write(dir.join("alpha/notes.txt"), "not bibtex").unwrap();
+    dir
+}
+
+fn relative(dir: &Path, files: Vec<PathBuf>) -> Vec<String> {
+    files
+        .iter()
+        .map(|f| f.strip_prefix(dir).unwrap().to_string_lossy().into_owned())
+        .collect()
+}
+
+#[test]
+fn directories_are_searched() {
+    let dir = papers("discover");
+    let found = |discovery: &Discovery| relative(&dir, bib_files(&dir, discovery).unwrap());
+    assert_eq!(found(&Discovery::default()), vec!["top.bib"]);
+
+    let recursive = Discovery {
+        recursive: true,
+        ..Discovery::default()
+    };
+    assert_eq!(
+        found(&recursive),
+        vec![
+            "alpha/refs.bib",
+            "beta/old/refs.bib",
+            "beta/refs.BIB",
+            "top.bib"
+        ]
+    );
+    assert_eq!(
+        found(&Discovery {
+            max_depth: 1,
+            ..recursive.clone()
+        }),
+        vec!["alpha/refs.bib", "beta/refs.BIB", "top.bib"]
+    );
+    assert_eq!(
+        found(&Discovery {
+            skip: vec!["b*".to_string(), "top.bib".to_string()],
+            ..recursive.clone()
+        }),
+        vec![
+            ".git/stale.bib",
+            "alpha/refs.bib",
+            "node_modules/pkg/refs.bib"
+        ]
+    );
+    assert!(bib_files(&dir.join("missing"), &recursive)
+        .unwrap_err()
+        .to_string()
+        .contains("missing"));
+}
+
+#[cfg(unix)]
+#[test]
+fn symbolic_link_loops_are_searched_once() {
+    let dir = papers("discover-loop");
+    std::os::unix::fs::symlink(&dir, dir.join("alpha/all")).unwrap();
+    let discovery = Discovery {
+        recursive: true,
+        ..Discovery::default()
+    };
+    assert_eq!(
+        relative(&dir, bib_files(&dir, &discovery).unwrap()).len(),
+        4
+    );
+}
+
+#[test]
+fn entries_know_their_file() {
+    let mut files = Files::default();
+    files.add("alpha/refs.bib", &entry("alpha"));
+    files.add("beta/refs.bib", &(entry("beta") + &entry("alpha")));
+    assert_eq!(files.get("alpha"), Some("alpha/refs.bib"));
+    assert_eq!(files.get("beta"), Some("beta/refs.bib"));
+    assert_eq!(files.get("gamma"), None);
+
+    let mut papers = convert(&(entry("alpha") + &entry("beta")), &Options::default()).unwrap();
+    files.locate(&mut papers);
+    let title = |i: usize| papers[i].provenance.get("title").unwrap().to_string();
+    assert_eq!(title(0), "title of the entry in alpha/refs.bib");
+    assert_eq!(title(1), "title of the entry in beta/refs.bib");
+}
+
+#[test]
+fn directories_as_input() {
+    let dir = papers("discover-cli");
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_new_page"))
+            .current_dir(&dir)
+            .args(["--quiet", "--provenance-comments", "--file-path", "."])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let page = |args| String::from_utf8(run(args).stdout).unwrap();
+    let top = page(&[]);
+    assert_eq!(top.matches("bibtex_key:").count(), 1);
+    assert!(!top.contains(" in ./top.bib"));
+
+    let all = page(&["--recursive"]);
+    assert_eq!(all.matches("bibtex_key:").count(), 4);
+    assert!(all.contains("title: \"alpha\" # from title of the entry in ./alpha/refs.bib"));
+    assert_eq!(
+        page(&["--recursive", "--max-depth", "1"])
+            .matches("bibtex_key:")
+            .count(),
+        3
+    );
+    assert_eq!(
+        page(&["--recursive", "--skip-path", "beta"])
+            .matches("bibtex_key:")
+            .count(),
+        4
+    );
+
+    fs::create_dir_all(dir.join("empty")).unwrap();
+    let empty = run(&["--file-path", "empty"]);
+    assert_eq!(empty.status.code(), Some(3));
+    assert_eq!(run(&["--max-depth", "1"]).status.code(), Some(4));
+}
@@ -0,0 +1,119 @@
+//! Tests of the detection of the versions of a same work.
+
+use new_page::diagnostics::collect;
+use new_page::duplicates::{
+    comparable, find, link_versions, merge_versions, similarity, Report, THRESHOLD,
+};
+use new_page::{convert, Options, Target, Warning};
+
+const BIB: &str = r#"
+@article{doe21, author = {Doe, Jane}, title = {Graphs and {P}roofs: A Survey},
+  journal = {Journal of Things}, year = {2021}}
+@misc{doe20arxiv, author = {Doe, Jane}, title = {Graphs and proofs -- a survey}, year = {2020}}
+@inproceedings{doe20, author = {Doe, Jane}, title = {Graph and Proofs: A Survey},
+  booktitle = {Conference}, year = {2020}}
+@article{roe, author = {Roe, Rick}, title = {Something Else Entirely}, journal = {J}, year = {2019}}
+"#;
+
+#[test]
+fn titles_compare_without_case_punctuation_or_latex() {
+    assert_eq!(
+        comparable("Graphs and {P}roofs: A Survey"),
+        "graphs and proofs a survey"
+    );
+    assert_eq!(comparable("Caf\\'e -- Bar"), "café bar");
+    assert_eq!(similarity("abc", "abc"), 1.0);
+    assert_eq!(similarity("abcd", "abce"), 0.75);
+}
+
+#[test]
+fn versions_are_grouped_and_linked() {
+    let mut papers = convert(BIB, &Options::default()).unwrap();
+    let groups = find(&papers, THRESHOLD);
+    assert_eq!(groups, vec![vec![0, 1, 2]]);
+    assert!(find(&papers, 1.0) == vec![vec![0, 1]]);
+
+    let report = Report {
+        papers: &papers,
+        groups: &groups,
+    }
+    .to_string();
+    assert!(
+        report.starts_with("Possible duplicates, group 1:\n  doe21 (2021, Journal of Things): ")
+    );
+    assert!(report.contains("  doe20arxiv (2020, no venue): "));
+
+    link_versions(&mut papers, &groups);
+    assert_eq!(papers[0].other_versions, ["doe20arxiv", "doe20"]);
+    assert!(papers[1]
+        .render(Target::Hugo)
+        .contains("other_versions:\n  - \"doe21\"\n  - \"doe20\"\n"));
+    assert!(papers[3].other_versions.is_empty());
+    assert!(!papers[3].render(Target::Hugo).contains("other_versions"));
+}
+
+#[test]
+fn preprints_merge_into_published_versions() {
+    let bib = r#"
+@inproceedings{doe22, author = {Doe, Jane}, title = {Graphs and {P}roofs},
+  booktitle = {Conference}, year = {2022}, doi = {10.1/g}}
+@misc{doe21arxiv, author = {Doe, Jane}, title = {Graphs and proofs}, year = {2021},
+  eprint = {2101.00001}, archiveprefix = {arXiv}, url = {https://example.org/draft}}
+@article{roe, author = {Roe, Rick}, title = {Things}, journal = {J}, year = {2019},
+  relatedversion = {roe-pre}}
+@misc{roe-pre, author = {Roe, Rick}, title = {Stuff}, note = {Preprint}, year = {2018},
+  url = {https://arxiv.org/abs/1801.00001}}
+"#;
+    let papers = merge_versions(convert(bib, &Options::default()).unwrap());
+    let keys: Vec<&str> = papers.iter().map(|p| p.key.key.as_str()).collect();
+    assert_eq!(keys, ["doe22", "roe"]);
+    let page = papers[0].render(Target::Hugo);
+    assert!(
+        page.contains(
+            "links:\n  - \"https://arxiv.org/abs/2101.00001\"\n  - \"https://example.org/draft\"\n"
+        ),
+        "{}",
+        page
+    );
+    assert!(page.contains("earlier_versions:\n  - \"doe21arxiv\"\n"));
+    assert_eq!(papers[0].eprint.as_deref(), Some("2101.00001"));
+    assert_eq!(papers[1].earlier_versions, ["roe-pre"]);
+    assert_eq!(papers[1].links, ["https://arxiv.org/abs/1801.00001"]);
+
+    // Without the option, nothing changes.
+    let unmerged = convert(bib, &Options::default()).unwrap();
+    assert_eq!(unmerged.len(), 4);
+    assert!(!unmerged[0]
+        .render(Target::Hugo)
+        .contains("earlier_versions"));
+}
+
+#[test]
+fn ambiguous_versions_are_left_alone() {
+    let bib = r#"
+@article{a, author = {Doe, Jane}, title = {Graphs}, journal = {J}, year = {2022}}
+@misc{b, author = {Doe, Jane}, title = {Graphs}, year = {2021}, eprint = {2101.00001}}
+@misc{c, author = {Doe, Jane}, title = {Graphs}, year = {2021}, eprint = {2101.00002}}
+@article{d, author = {Doe, Jane}, title = {Proofs}, journal = {J}, year = {2022}}
+@misc{e, author = {Doe, Jane}, title = {Proofs}, year = {2015}, eprint = {1501.00001}}
+"#;
+    let (papers, warnings) = collect(|| merge_versions(convert(bib, &Options::default()).unwrap()));
+    assert_eq!(papers.len(), 5);
+    assert_eq!(
+        warnings,
+        [
+            Warning::AmbiguousVersions {
+                keys: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+                reason: "3 candidates".to_string(),
+            },
+            Warning::AmbiguousVersions {
+                keys: vec!["d".to_string(), "e".to_string()],
+                reason: "years 2015 and 2022 are more than 2 apart".to_string(),
+            },
+        ]
+    );
+    assert_eq!(
+        warnings[1].to_string(),
+        "d, e: not merged as versions of one work, years 2015 and 2022 are more than 2 apart"
+    );
+}
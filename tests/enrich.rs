@@ -0,0 +1,130 @@
+//! Tests of the enrichment from web services, using cached answers only.
+
+use std::time::Duration;
+
+use new_page::arxiv::{self, ArxivId};
+use new_page::citations::{self, paper_id};
+use new_page::dblp::{self, normalize_title, parse_hits};
+use new_page::fetch::Fetcher;
+use new_page::{convert, Options, Paper, Target};
+
+const BIB: &str = r#"
+@inproceedings{known, author = {Doe, Jane}, title = {Graphs and {Proofs}},
+  booktitle = {Conference}, year = {2021}}
+@inproceedings{vague, author = {Roe, Rick}, title = {Some Title}, booktitle = {Conference},
+  year = {2020}, pages = {3--4}}
+"#;
+
+const ANSWER: &str = r#"{"result": {"hits": {"@total": "1", "hit": [{"info": {
+  "title": "Graphs and proofs.", "pages": "12-20", "doi": "10.1/gp",
+  "ee": "https://doi.org/10.1/gp"}}]}}}"#;
+
+const OTHER: &str = r#"{"result": {"hits": {"hit": [{"info": {
+  "title": "Some Other Title.", "doi": "10.1/other"}}]}}}"#;
+
+fn fetcher(name: &str) -> Fetcher {
+    let dir = std::env::temp_dir().join(format!("new_page-{}-{}", name, std::process::id()));
+    Fetcher::new(dir, true)
+}
+
+#[test]
+fn titles_are_normalized() {
+    assert_eq!(normalize_title("Graphs and {P}roofs."), "graphsandproofs");
+    assert_eq!(parse_hits("not json"), vec![]);
+    assert_eq!(parse_hits(ANSWER)[0].doi.as_deref(), Some("10.1/gp"));
+}
+
+#[test]
+fn dblp_fills_matching_entries_only() {
+    let mut papers = convert(BIB, &Options::default()).unwrap();
+    let mut fetcher = fetcher("dblp");
+    fetcher.store("dblp", "Graphs and Proofs Doe", ANSWER);
+    fetcher.store("dblp", "Some Title Roe", OTHER);
+    dblp::enrich(&mut papers, &mut fetcher);
+    std::fs::remove_dir_all(&fetcher.cache_dir).unwrap();
+
+    assert_eq!(papers[0].doi.s, "10.1/gp");
+    assert_eq!(papers[0].url.link, "https://doi.org/10.1/gp");
+    assert_eq!(
+        (papers[0].pages.from, papers[0].pages.to),
+        (Some(12), Some(20))
+    );
+    assert_eq!(papers[1].doi.s, "");
+    assert_eq!(papers[1].pages.from, Some(3));
+    let source = |p: &Paper, field| p.provenance.get(field).map(|s| s.to_string());
+    assert_eq!(source(&papers[0], "doi").as_deref(), Some("dblp"));
+    assert_eq!(
+        source(&papers[1], "page").as_deref(),
+        Some("pages of the entry")
+    );
+}
+
+const ATOM: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title type="html">ArXiv Query</title>
+  <entry>
+    <id>http://arxiv.org/abs/2106.01234v2</id>
+    <updated>2021-07-02T10:00:00Z</updated>
+    <title>A Preprint</title>
+    <summary>  The full
+  abstract &amp; more.
+    </summary>
+    <arxiv:primary_category xmlns:arxiv="http://arxiv.org/schemas/atom" term="cs.PL" scheme="http://arxiv.org/schemas/atom"/>
+  </entry>
+</feed>"#;
+
+#[test]
+fn arxiv_identifiers() {
+    let new = ArxivId::parse("arXiv:2106.01234v2").unwrap();
+    assert_eq!((new.id.as_str(), new.version), ("2106.01234", Some(2)));
+    assert_eq!(new.abs_url(), "https://arxiv.org/abs/2106.01234");
+    let old = ArxivId::parse("math.GT/0309136").unwrap();
+    assert_eq!((old.id.as_str(), old.version), ("math.GT/0309136", None));
+    assert_eq!(ArxivId::parse("hep-th/9901001v3").unwrap().version, Some(3));
+    assert_eq!(ArxivId::parse("see website"), None);
+    assert_eq!(ArxivId::parse("2106.123"), None);
+}
+
+#[test]
+fn arxiv_fills_empty_fields_only() {
+    let bib = r#"@misc{pre, author = {Doe, Jane}, title = {A Preprint}, year = {2021},
+      eprint = {2106.01234v2}, archivePrefix = {arXiv}, abstract = {Truncated...}}"#;
+    let mut fetcher = fetcher("arxiv");
+    fetcher.store("arxiv", "2106.01234v2", ATOM);
+
+    let mut papers = convert(bib, &Options::default()).unwrap();
+    arxiv::enrich(&mut papers, &mut fetcher, false);
+    assert_eq!(papers[0].abs.abs, "Truncated...");
+    assert_eq!(papers[0].keywords.tags, vec!["cs.PL"]);
+    assert_eq!(papers[0].updated.at.as_deref(), Some("2021-07-02"));
+    assert_eq!(papers[0].url.link, "https://arxiv.org/abs/2106.01234");
+    assert!(papers[0]
+        .render(Target::Hugo)
+        .contains("lastmod: \"2021-07-02\"\n"));
+
+    let mut papers = convert(bib, &Options::default()).unwrap();
+    arxiv::enrich(&mut papers, &mut fetcher, true);
+    std::fs::remove_dir_all(&fetcher.cache_dir).unwrap();
+    assert_eq!(papers[0].abs.abs, "The full abstract & more.");
+}
+
+#[test]
+fn citation_counts_from_cache() {
+    let bib = r#"
+@article{a, author = {Doe, Jane}, title = {T}, journal = {J}, year = {2021}, doi = {10.1/a}}
+@misc{b, author = {Doe, Jane}, title = {U}, year = {2021}, eprint = {2106.01234v2}}
+@misc{c, author = {Doe, Jane}, title = {V}, year = {2021}}
+"#;
+    let mut papers = convert(bib, &Options::default()).unwrap();
+    assert_eq!(paper_id(&papers[1]).as_deref(), Some("ARXIV:2106.01234"));
+    let mut fetcher = fetcher("citations");
+    fetcher.store("semanticscholar", "DOI:10.1/a", "42 2026-01-02");
+    citations::enrich(&mut papers, &mut fetcher, Duration::from_secs(3600));
+    std::fs::remove_dir_all(&fetcher.cache_dir).unwrap();
+
+    assert!(papers[0]
+        .render(Target::Hugo)
+        .contains("citation_count: 42\ncitation_count_date: \"2026-01-02\"\n"));
+    assert_eq!(papers[1].citations.count, None);
+    assert!(!papers[2].render(Target::Hugo).contains("citation_count"));
+}
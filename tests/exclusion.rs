@@ -0,0 +1,76 @@
+//! Tests of the entries kept out of the website.
+
+use new_page::config::Config;
+use new_page::fields::Keywords;
+use new_page::{convert_summarized, Options, Summary, Target, NOSITE};
+
+const BIB: &str = r#"
+@article{kept, author = {Doe, Jane}, title = {Kept}, journal = {J}, year = {2021},
+  keywords = {graphs, NoSite-not, proofs}}
+@article{flag, author = {Doe, Jane}, title = {Flag}, journal = {J}, year = {2021},
+  website = {False}}
+@article{word, author = {Doe, Jane}, title = {Word}, journal = {J}, year = {2021},
+  keywords = {graphs; NOSITE}}
+@article{listed, author = {Doe, Jane}, title = {Listed}, journal = {J}, year = {2021}}
+@proceedings{procs, editor = {Doe, Jane}, title = {Procs}, year = {2021}}
+"#;
+
+#[test]
+fn excluded_entries_are_counted_apart() {
+    let opts = Options {
+        exclude_keys: vec!["listed".into()],
+        skip_types: vec!["proceedings".into()],
+        ..Options::default()
+    };
+    let (papers, summary) = convert_summarized(BIB, &opts).unwrap();
+    let keys: Vec<&str> = papers.iter().map(|p| p.key.key.as_str()).collect();
+    assert_eq!(keys, vec!["kept"]);
+    assert_eq!(
+        summary,
+        Summary {
+            converted: 1,
+            skipped: 1,
+            excluded: 3,
+            failed: 0
+        }
+    );
+    assert_eq!(
+        summary.to_string(),
+        "1 converted, 1 skipped, 3 excluded, 0 failed"
+    );
+}
+
+#[test]
+fn nosite_keyword_is_not_a_tag() {
+    let keywords = Keywords::without(Some(&"graphs, NoSite".into()), NOSITE);
+    assert_eq!(keywords.tags, vec!["graphs"]);
+}
+
+#[test]
+fn config_lists_excluded_keys() {
+    let config: Config = toml::from_str("exclude_keys = [\"a\", \"b\"]").unwrap();
+    assert_eq!(config.exclude_keys, vec!["a", "b"]);
+    assert!(toml::from_str::<Config>("exclude = []").is_err());
+    assert_eq!(toml::from_str::<Config>("").unwrap(), Config::default());
+}
+
+#[test]
+fn unknown_types_are_converted_or_skipped() {
+    let bib = "@dataset{d, author = {Doe, Jane}, title = {Data}, year = 2022, institution = {Lab}}
+        @artwork{a, title = {Art}, year = 2020}
+        @article{j, author = {Roe, Richard}, title = {T}, journal = {J}, year = 2021}";
+    let (papers, summary) = convert_summarized(bib, &Options::default()).unwrap();
+    assert_eq!(summary.converted, 3);
+    assert_eq!(papers[0].place.as_ref().unwrap().label(), "venue");
+    assert!(papers[0]
+        .render(Target::Hugo)
+        .contains("venue:\n  name: \"Lab\"\n"));
+    assert!(papers[1].render(Target::Hugo).contains("authors: []\n"));
+
+    let opts = Options {
+        skip_unknown_types: true,
+        ..Options::default()
+    };
+    let (papers, summary) = convert_summarized(bib, &opts).unwrap();
+    assert_eq!((papers.len(), summary.skipped), (1, 2));
+}
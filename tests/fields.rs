@@ -0,0 +1,846 @@
+//! Targeted tests for the individual front matter fields.
+
+use new_page::fields::*;
+use new_page::output::{sanitize_filename, FileNames};
+use new_page::yaml::{Quotes, ToYaml};
+
+fn s(v: &str) -> String {
+    v.to_string()
+}
+
+#[test]
+fn pages_range() {
+    let p = Pages::from_string("123--145");
+    assert_eq!((p.from, p.to), (Some(123), Some(145)));
+    assert_eq!(p.to_yaml(Quotes::Double), "page:\n  from: 123\n  to: 145\n");
+}
+
+#[test]
+fn pages_single_and_empty() {
+    let p = Pages::from_string("7");
+    assert_eq!((p.from, p.to), (Some(7), None));
+    assert_eq!(
+        Pages::default().to_yaml(Quotes::Double),
+        "page:\n  from: \n  to: \n"
+    );
+}
+
+#[test]
+fn pages_disjoint_ranges() {
+    for s in [
+        "11--17, 23--25",
+        "11-17;23-25",
+        "11–17 ; 23–25",
+        "11 - 17,23--25",
+    ] {
+        let p = Pages::from_string(s);
+        assert_eq!((p.from, p.to), (Some(11), Some(25)), "{}", s);
+        assert_eq!(p.ranges, vec![(11, Some(17)), (23, Some(25))], "{}", s);
+        assert_eq!(
+            p.to_yaml(Quotes::Double),
+            "page:\n  from: 11\n  to: 25\n  ranges: \"11–17, 23–25\"\n"
+        );
+    }
+    let p = Pages::from_string("23--25, 11, 30");
+    assert_eq!((p.from, p.to), (Some(11), Some(30)));
+    assert_eq!(p.ranges_text().unwrap(), "23–25, 11, 30");
+    // A single range is written as before.
+    assert!(Pages::from_string("11--17,").ranges.is_empty());
+
+    let fixed = Pages::checked("k", "1153--67, 1170--75", true, 2000);
+    assert_eq!(fixed.ranges, vec![(1153, Some(1167)), (1170, Some(1175))]);
+    assert_eq!((fixed.from, fixed.to), (Some(1153), Some(1175)));
+}
+
+#[test]
+fn pages_roman_and_prefixed() {
+    assert_eq!(roman("xix"), Some(19));
+    assert_eq!(roman("MCMXCIV"), Some(1994));
+    assert_eq!(roman("xviiii"), None);
+    assert_eq!(roman("ic"), None);
+
+    for (s, display) in [("xi--xvii", "xi–xvii"), ("XI--XVII", "XI–XVII")] {
+        let p = Pages::from_string(s);
+        assert_eq!((p.from, p.to), (Some(11), Some(17)), "{}", s);
+        assert_eq!(p.display.as_deref(), Some(display), "{}", s);
+    }
+    let p = Pages::from_string("S45--S52");
+    assert_eq!((p.from, p.to), (Some(45), Some(52)));
+    assert_eq!(
+        p.to_yaml(Quotes::Double),
+        "page:\n  from: 45\n  to: 52\npage_display: \"S45–S52\"\n"
+    );
+    let p = Pages::from_string("e1234");
+    assert_eq!((p.from, p.to), (Some(1234), None));
+    assert_eq!(p.display.as_deref(), Some("e1234"));
+    let p = Pages::from_string("A1--A9, iv");
+    assert_eq!(p.ranges, vec![(1, Some(9)), (4, None)]);
+    assert_eq!(p.display.as_deref(), Some("A1–A9, iv"));
+    // Plain numbers need no display.
+    assert_eq!(Pages::from_string("12--15").display, None);
+}
+
+#[test]
+fn pages_unconvertible() {
+    let p = Pages::from_string("in press");
+    assert_eq!((p.from, p.to), (None, None));
+    assert_eq!(p.display.as_deref(), Some("in press"));
+    assert_eq!(p.to_yaml(Quotes::Double), "page_display: \"in press\"\n");
+    // Ending before they start, as the pages are given.
+    let p = Pages::from_string("45--12");
+    assert_eq!((p.from, p.to), (None, None));
+    assert_eq!(p.display.as_deref(), Some("45--12"));
+    let p = Pages::from_string("1--4, 9--7");
+    assert!(p.ranges.is_empty());
+    assert_eq!(p.display.as_deref(), Some("1--4, 9--7"));
+}
+
+#[test]
+fn abbreviated_pages() {
+    assert_eq!(expand_abbreviated_page(1153, 67), Some(1167));
+    assert_eq!(expand_abbreviated_page(153, 8), Some(158));
+    assert_eq!(expand_abbreviated_page(12345, 401), Some(12401));
+    // Ambiguous or plainly wrong: left alone.
+    assert_eq!(expand_abbreviated_page(153, 15), None);
+    assert_eq!(expand_abbreviated_page(153, 125), None);
+    assert_eq!(expand_abbreviated_page(1199, 98), None);
+    assert_eq!(expand_abbreviated_page(10, 20), None);
+
+    let fixed = Pages::checked("k", "1153--67", true, 2000);
+    assert_eq!((fixed.from, fixed.to), (Some(1153), Some(1167)));
+    let kept = Pages::checked("k", "1153--67", false, 2000);
+    assert_eq!(kept.to, Some(67));
+    let ambiguous = Pages::checked("k", "153--15", true, 2000);
+    assert_eq!(ambiguous.to, Some(15));
+    let long = Pages::checked("k", "1--5000", true, 2000);
+    assert_eq!(long.to, Some(5000));
+}
+
+#[test]
+fn series_numeric_and_named() {
+    assert_eq!(
+        Series::from(Some(&s("4"))).to_yaml(Quotes::Double),
+        "series: 4\n"
+    );
+    assert_eq!(
+        Series::from(Some(&s("LNCS"))).to_yaml(Quotes::Double),
+        "series: \n"
+    );
+    assert_eq!(
+        Series::named(Some(&s("LNCS"))).to_yaml(Quotes::Double),
+        "series: \"LNCS\"\n"
+    );
+}
+
+#[test]
+fn series_with_acronym_and_year() {
+    let ccs = Series::from(Some(&s("CCS '21")));
+    assert_eq!((ccs.acronym.as_deref(), ccs.year), (Some("CCS"), Some(21)));
+    assert_eq!(ccs.to_yaml(Quotes::Double), "series: \"CCS '21\"\n");
+    assert!(ccs.is_of(2021) && !ccs.is_of(2022));
+    let pldi = Series::from(Some(&s("PLDI 2023")));
+    assert_eq!(
+        (pldi.acronym.as_deref(), pldi.year),
+        (Some("PLDI"), Some(2023))
+    );
+    assert_eq!(dated_acronym("ESEC/FSE’21"), Some((s("ESEC/FSE"), 21)));
+    assert_eq!(dated_acronym("Lecture Notes in Computer Science"), None);
+    assert_eq!(dated_acronym("CCS 21"), None);
+    let lncs = Series::named(Some(&s("Lecture Notes in Computer Science")));
+    assert_eq!((lncs.acronym, lncs.year), (None, None));
+}
+
+#[test]
+fn series_names_the_conference() {
+    use new_page::diagnostics::collect;
+    use new_page::{convert, Options, Target};
+    let bib = "@inproceedings{a, author = {Doe, Jane}, title = {T}, booktitle = {Proceedings of the \
+               ACM Conference on Computer and Communications Security}, series = {CCS '21}, year = {2021}}
+@inproceedings{b, author = {Doe, Jane}, title = {T}, booktitle = {Programming Language Design}, \
+               series = {PLDI '23}, year = {2022}}";
+    let (papers, warnings) = collect(|| convert(bib, &Options::default()));
+    let papers = papers.unwrap();
+    let page = papers[0].render(Target::Hugo);
+    assert!(page.contains("shortname: \"CCS\"\n"), "{}", page);
+    assert!(page.contains("series: \"CCS '21\"\n"), "{}", page);
+    let json = serde_json::to_value(&papers[1]).unwrap();
+    assert_eq!(json["conference"]["shortname"], "PLDI");
+    let series: Vec<String> = warnings
+        .iter()
+        .filter(|w| w.to_string().contains("series"))
+        .map(|w| w.to_string())
+        .collect();
+    assert_eq!(series, ["b: series \"PLDI '23\" is not of year 2022"]);
+}
+
+#[test]
+fn number_is_the_issue_not_the_series() {
+    use new_page::{convert, Options, Target};
+
+    let bib = "@article{k, author = {Roe, R}, title = {T}, journal = {J}, year = {2020},
+        number = {4}}";
+    let page = convert(bib, &Options::default()).unwrap()[0].render(Target::Hugo);
+    assert!(page.contains("issue: 4\n"));
+    assert!(page.contains("series: \n"));
+    let page = convert(
+        &bib.replace("number", "series = {7}, number"),
+        &Options::default(),
+    )
+    .unwrap()[0]
+        .render(Target::Hugo);
+    assert!(page.contains("issue: 4\n"));
+    assert!(page.contains("series: 7\n"));
+
+    let legacy = Options::builder()
+        .legacy_series_number(true)
+        .build()
+        .unwrap();
+    let page = convert(bib, &legacy).unwrap()[0].render(Target::Hugo);
+    assert!(page.contains("issue: 4\n"));
+    assert!(page.contains("series: 4\n"));
+}
+
+#[test]
+fn name_list() {
+    assert_eq!(
+        parse_name_list("Doe, Jane and Smith, Bob\nand Carl Roe"),
+        vec!["Jane Doe", "Bob Smith", "Carl Roe"]
+    );
+    assert!(parse_name_list("").is_empty());
+}
+
+#[test]
+fn name_list_separators() {
+    use new_page::{convert, Options};
+
+    let authors = "Smith, John AND {Food and Drug Administration} And Anderson, Candace \
+                   and Sandy Rand";
+    assert_eq!(
+        split_names(authors),
+        vec![
+            "Smith, John",
+            "{Food and Drug Administration}",
+            "Anderson, Candace",
+            "Sandy Rand"
+        ]
+    );
+    assert_eq!(
+        parse_name_list(authors),
+        vec![
+            "John Smith",
+            "Food and Drug Administration",
+            "Candace Anderson",
+            "Sandy Rand"
+        ]
+    );
+    // A comma within braces does not separate the family name.
+    assert_eq!(
+        parse_name_list("{Ministry of Health, Spain} and Doe, J."),
+        vec!["Ministry of Health, Spain", "J. Doe"]
+    );
+    assert_eq!(
+        abbreviate_names(authors),
+        "Smith, J. and {Food and Drug Administration} and Anderson, C. and S. Rand"
+    );
+    let bib = format!(
+        "@misc{{k, author = {{{}}}, title = {{T}}, year = 2020}}",
+        authors
+    );
+    let p = &convert(&bib, &Options::default()).unwrap()[0];
+    assert_eq!(p.auth.authors.len(), 4);
+    let bib = "@misc{k, author = {{Food and Drug Administration}}, title = {T}, year = 2020}";
+    let p = &convert(bib, &Options::default()).unwrap()[0];
+    assert_eq!(p.auth.authors, vec!["Food and Drug Administration"]);
+}
+
+#[test]
+fn authors_and_editors() {
+    assert_eq!(
+        Authors::from_string("Doe, Jane").to_yaml(Quotes::Double),
+        "authors:\n  - \"Jane Doe\"\n"
+    );
+    assert_eq!(
+        Authors::with_label("editors", "Eve Editor").to_yaml(Quotes::Double),
+        "editors:\n  - \"Eve Editor\"\n"
+    );
+}
+
+#[test]
+fn duplicate_authors() {
+    let a = Authors::deduplicated("authors", "k", "Doe, Jane and Smith, Bob and Doe, Jane");
+    assert_eq!(a.authors, vec![s("Jane Doe"), s("Bob Smith")]);
+    let a = Authors::deduplicated("authors", "k", "Jane Doe and Doe, J. and Smith, Bob");
+    assert_eq!(a.authors, vec![s("Jane Doe"), s("Bob Smith")]);
+    // Same family name, different people.
+    let a = Authors::deduplicated("authors", "k", "Doe, Jane and Doe, John and Doe, J.");
+    assert_eq!(a.authors, vec![s("Jane Doe"), s("John Doe")]);
+    let a = Authors::deduplicated("authors", "k", "Doe, Jane and Doe, Robert");
+    assert_eq!(a.authors.len(), 2);
+    assert!(same_person(
+        "M{\\\"u}ller, J.-P.",
+        "Jean-Pierre M{\\\"u}ller"
+    ));
+    assert!(!same_person("Doe, Jane", "Roe, Jane"));
+}
+
+#[test]
+fn given_name_initials() {
+    assert_eq!(initials("Doe, Jane"), "Doe, J.");
+    assert_eq!(initials("Jean-Paul Sartre"), "J.-P. Sartre");
+    assert_eq!(initials("John Ronald Reuel Tolkien"), "J. R. R. Tolkien");
+    assert_eq!(initials("Tolkien, J.R.R."), "Tolkien, J. R. R.");
+    assert_eq!(initials("J. Doe"), "J. Doe");
+    assert_eq!(initials("Ludwig van Beethoven"), "L. van Beethoven");
+    assert_eq!(initials("van der Berg, Jan"), "van der Berg, J.");
+    assert_eq!(initials("Doe, Jr, John"), "Doe, Jr, J.");
+    assert_eq!(initials("\u{d6}zlem T\u{fc}rk"), "\u{d6}. T\u{fc}rk");
+    // An O followed by a combining diaeresis is a single grapheme.
+    assert_eq!(initials("O\u{308}zlem T\u{fc}rk"), "O\u{308}. T\u{fc}rk");
+    assert_eq!(initials("{\\\"O}zlem T{\\\"u}rk"), "{\\\"O}. T{\\\"u}rk");
+    assert_eq!(
+        initials("{World Health Organization}"),
+        "{World Health Organization}"
+    );
+    assert_eq!(initials("Plato"), "Plato");
+
+    // People are told apart by their full names, before they are cut.
+    let a = Authors::abbreviated("authors", "k", "Doe, Jane and Doe, John and Doe, J.");
+    assert_eq!(a.authors, vec![s("J. Doe"), s("J. Doe")]);
+    assert_eq!(
+        abbreviate_names("Sartre, Jean-Paul and\nSimone de Beauvoir"),
+        "Sartre, J.-P. and S. de Beauvoir"
+    );
+}
+
+#[test]
+fn volume() {
+    assert_eq!(Volume::from(Some(&s("42"))).to_string(), "volume: 42\n");
+    assert_eq!(Volume::from(None).to_string(), "volume: \n");
+}
+
+#[test]
+fn doi() {
+    assert_eq!(
+        Doi::from(Some(&s("10.1/x"))).to_yaml(Quotes::Double),
+        "doi: \"10.1/x\"\n"
+    );
+    assert_eq!(Doi::from(None).to_yaml(Quotes::Double), "doi: \"\"\n");
+}
+
+#[test]
+fn year() {
+    assert_eq!(Year::lenient("k", "2021").to_string(), "year: 2021\n");
+    assert!(Year::lenient("k", "to appear").year >= 2024);
+}
+
+#[test]
+fn title_and_abstract() {
+    assert_eq!(
+        Title::from(&s("T")).to_yaml(Quotes::Double),
+        "title: \"T\"\n"
+    );
+    assert_eq!(Abstract::from(&s("A.")).to_string(), "A.\n");
+}
+
+#[test]
+fn place() {
+    let j = Place::Journal(Name::from(&s("J")), None);
+    assert_eq!(
+        j.to_yaml(Quotes::Double),
+        "journal:\n  name: \"J\"\n  shortname: \"\"\n"
+    );
+    let c = Place::Conference(Name::from(&s("C")), Location::from(Some(&s("Lyon"))), None);
+    assert_eq!(
+        c.to_yaml(Quotes::Double),
+        "conference:\n  name: \"C\"\n  shortname: \"\"\n  location: \"Lyon\"\n"
+    );
+    let c = Place::Conference(Name::from(&s("C")), Location::from(None), Some(30));
+    assert_eq!(
+        c.to_yaml(Quotes::Double),
+        "conference:\n  name: \"C\"\n  shortname: \"\"\n  edition: 30\n"
+    );
+}
+
+#[test]
+fn optional_strings() {
+    assert_eq!(Proceedings::from(None).to_yaml(Quotes::Double), "");
+    assert_eq!(
+        Proceedings::from(Some(&s("P"))).to_yaml(Quotes::Double),
+        "proceedings: \"P\"\n"
+    );
+    assert_eq!(
+        Url::from(Some(&s("https://x"))).to_yaml(Quotes::Double),
+        "www: \"https://x\"\n"
+    );
+    assert_eq!(
+        Publisher::from(None).to_yaml(Quotes::Double),
+        "publisher: \n"
+    );
+    assert_eq!(
+        Isbn::from(Some(&s("978"))).to_yaml(Quotes::Double),
+        "isbn: \"978\"\n"
+    );
+    assert_eq!(HowPublished::from(None).to_yaml(Quotes::Double), "");
+}
+
+#[test]
+fn issue() {
+    let issue = |i: Option<&str>| Issue { issue: i.map(s) }.to_yaml(Quotes::Double);
+    assert_eq!(issue(Some("3")), "issue: 3\n");
+    assert_eq!(issue(Some("3-4")), "issue: \"3-4\"\n");
+    assert_eq!(issue(None), "");
+    let json = |i: &str| serde_json::to_string(&Issue { issue: Some(s(i)) }).unwrap();
+    assert_eq!(json("3"), "3");
+    assert_eq!(json("3-4"), "\"3-4\"");
+}
+
+#[test]
+fn location() {
+    assert_eq!(
+        Location::from(Some(&s("Lyon, France"))).to_yaml(Quotes::Double),
+        "  location: \"Lyon, France\"\n"
+    );
+    assert_eq!(Location::from(None).to_yaml(Quotes::Double), "");
+}
+
+#[test]
+fn document_number() {
+    let number = |label, n: Option<&str>| {
+        DocumentNumber {
+            label,
+            number: n.map(s),
+        }
+        .to_yaml(Quotes::Double)
+    };
+    assert_eq!(
+        number(Some("patent_number"), Some("US 1234")),
+        "patent_number: \"US 1234\"\n"
+    );
+    assert_eq!(number(Some("standard_number"), None), "");
+    assert_eq!(number(None, Some("1234")), "");
+}
+
+#[test]
+fn original() {
+    let original = Original {
+        language: Some(s("German")),
+        year: Some(1923),
+    };
+    assert_eq!(
+        original.to_yaml(Quotes::Double),
+        "original_language: \"German\"\noriginal_year: 1923\n"
+    );
+    let original = Original {
+        language: None,
+        year: None,
+    };
+    assert_eq!(original.to_yaml(Quotes::Double), "");
+}
+
+#[test]
+fn strings_are_escaped() {
+    let tricky = s(r#"The "best" \ 'both': #1"#);
+    let event = Event {
+        name: Some(tricky.clone()),
+        location: Some(tricky.clone()),
+        start: None,
+        end: None,
+    };
+    for yaml in [
+        Title::from(&tricky).to_yaml(Quotes::Double),
+        Issue {
+            issue: Some(tricky.clone()),
+        }
+        .to_yaml(Quotes::Double),
+        Isbn::from(Some(&tricky)).to_yaml(Quotes::Double),
+        Proceedings::from(Some(&tricky)).to_yaml(Quotes::Double),
+        DocumentNumber {
+            label: Some("patent_number"),
+            number: Some(tricky.clone()),
+        }
+        .to_yaml(Quotes::Double),
+        Original {
+            language: Some(tricky.clone()),
+            year: None,
+        }
+        .to_yaml(Quotes::Double),
+    ] {
+        let map: serde_yaml::Mapping = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(map.values().next().unwrap(), &tricky[..], "{}", yaml);
+    }
+    let map: serde_yaml::Value = serde_yaml::from_str(&event.to_yaml(Quotes::Double)).unwrap();
+    assert_eq!(map["event"]["name"], &tricky[..]);
+    assert_eq!(map["event"]["location"], &tricky[..]);
+    let place = Place::Conference(Name::from(&tricky), Location::from(Some(&tricky)), None);
+    let map: serde_yaml::Value = serde_yaml::from_str(&place.to_yaml(Quotes::Double)).unwrap();
+    assert_eq!(map["conference"]["name"], &tricky[..]);
+    assert_eq!(map["conference"]["location"], &tricky[..]);
+}
+
+#[test]
+fn url_normalization() {
+    let url = |v: &str| Url::checked("k", Some(&s(v))).link;
+    assert_eq!(url(" \\url{https://x.org/a\\_b} "), "https://x.org/a_b");
+    assert_eq!(url("https://x.org"), "https://x.org");
+    assert_eq!(url("https://x.org/"), "https://x.org/");
+    assert_eq!(url("www.x.org/p"), "https://www.x.org/p");
+    assert_eq!(url("https://x.org/a b"), "https://x.org/a");
+    assert_eq!(url("https://x.org/a; https://y.org"), "https://x.org/a");
+    assert_eq!(url("https://x.org/\"q\""), "https://x.org/%22q%22");
+    assert_eq!(url("ftp://x.org/f"), "ftp://x.org/f");
+    assert_eq!(url("see website"), "");
+    assert_eq!(url("mailto:a@b.c"), "");
+    assert_eq!(Url::checked("k", None).link, "");
+}
+
+#[test]
+fn aliases() {
+    assert_eq!(
+        Aliases::doi("10.1145/3297858.3304007"),
+        "/doi/10.1145/3297858.3304007"
+    );
+    assert_eq!(
+        Aliases::doi("10.1002/(SICI)1097<3::AID>#x"),
+        "/doi/10.1002/(SICI)1097%3C3::AID%3E%23x"
+    );
+    assert_eq!(Aliases::key("conf/x/Doe21 a"), "/bib/conf%2Fx%2FDoe21%20a");
+    let a = Aliases {
+        paths: vec![s("/doi/10.1/x"), s("/bib/x")],
+    };
+    assert_eq!(
+        a.to_yaml(Quotes::Double),
+        "aliases:\n  - \"/doi/10.1/x\"\n  - \"/bib/x\"\n"
+    );
+    assert_eq!(Aliases::default().to_yaml(Quotes::Double), "");
+}
+
+#[test]
+fn howpublished_link() {
+    assert_eq!(strip_url_macro(" \\url{https://x.org} "), "https://x.org");
+    assert!(is_link("https://x.org"));
+    assert!(!is_link("Self-published"));
+}
+
+#[test]
+fn edition() {
+    assert_eq!(parse_ordinal("2"), Some(2));
+    assert_eq!(parse_ordinal("2nd"), Some(2));
+    assert_eq!(parse_ordinal("Second edition"), Some(2));
+    assert_eq!(parse_ordinal("Revised"), None);
+    assert_eq!(
+        Edition::from(Some(&s("Third"))).to_yaml(Quotes::Double),
+        "edition: 3\n"
+    );
+    assert_eq!(
+        Edition::from(Some(&s("Revised"))).to_yaml(Quotes::Double),
+        "edition: \"Revised\"\n"
+    );
+    assert_eq!(Edition::from(None).to_yaml(Quotes::Double), "");
+}
+
+#[test]
+fn chapter() {
+    assert_eq!(
+        Chapter::from(Some(&s("5"))).to_yaml(Quotes::Double),
+        "chapter: 5\n"
+    );
+    assert_eq!(
+        Chapter::from(Some(&s("A"))).to_yaml(Quotes::Double),
+        "chapter: \"A\"\n"
+    );
+}
+
+#[test]
+fn publication_status() {
+    assert!(PubState::from_text("to appear") == Some(PubState::Forthcoming));
+    assert!(PubState::from_text("In press") == Some(PubState::InPress));
+    assert!(PubState::from_text("2021").is_none());
+    assert_eq!(
+        PubState::Published.to_string(),
+        "publication_status: published\n"
+    );
+}
+
+#[test]
+fn dblp_keys_are_sanitized() {
+    assert_eq!(
+        sanitize_filename("DBLP:conf/pldi/Doe23"),
+        "DBLP_conf_pldi_Doe23"
+    );
+    assert_eq!(sanitize_filename("a<b>c|d?e*f\\g\"h"), "a_b_c_d_e_f_g_h");
+    assert_eq!(sanitize_filename("trailing. "), "trailing");
+    assert_eq!(sanitize_filename("con"), "_con");
+    assert_eq!(sanitize_filename("LPT1.tex"), "_LPT1.tex");
+    assert_eq!(sanitize_filename("..."), "_");
+}
+
+#[test]
+fn file_name_collisions() {
+    let mut names = FileNames::default();
+    assert_eq!(names.claim("DBLP:conf/pldi/Doe23"), "DBLP_conf_pldi_Doe23");
+    assert_eq!(
+        names.claim("DBLP:conf/pldi_Doe23"),
+        "DBLP_conf_pldi_Doe23-2"
+    );
+    assert_eq!(
+        names.claim("dblp:conf/pldi/doe23"),
+        "dblp_conf_pldi_doe23-3"
+    );
+}
+
+#[test]
+fn dates() {
+    use new_page::time::{parse_date, parse_month};
+    assert_eq!(parse_month("jun"), Some(6));
+    assert_eq!(parse_month("Sept."), Some(9));
+    assert_eq!(parse_month("12"), Some(12));
+    assert_eq!(parse_month("ju"), None);
+    assert_eq!(parse_month("13"), None);
+    assert_eq!(parse_date("2023"), Some(s("2023-01-01")));
+    assert_eq!(parse_date("2023-06"), Some(s("2023-06-01")));
+    assert_eq!(parse_date("2023-06-15"), Some(s("2023-06-15")));
+    assert_eq!(parse_date("June 2023"), Some(s("2023-06-01")));
+    assert_eq!(parse_date("15 June 2023"), Some(s("2023-06-15")));
+    assert_eq!(parse_date("June 15, 2023"), Some(s("2023-06-15")));
+    assert_eq!(parse_date("2023-13-01"), None);
+    assert_eq!(parse_date("soon"), None);
+}
+
+#[test]
+fn access_dates() {
+    use new_page::time::{parse_lenient_date, short_date};
+    assert_eq!(parse_lenient_date("2024-01-15"), Some(s("2024-01-15")));
+    assert_eq!(parse_lenient_date("15/01/2024"), Some(s("2024-01-15")));
+    assert_eq!(parse_lenient_date("01/15/2024"), Some(s("2024-01-15")));
+    assert_eq!(parse_lenient_date("2024/01/15"), Some(s("2024-01-15")));
+    assert_eq!(parse_lenient_date("15.01.2024"), Some(s("2024-01-15")));
+    // Day and month cannot be told apart.
+    assert_eq!(parse_lenient_date("03/04/2024"), None);
+    assert_eq!(parse_lenient_date("yesterday"), None);
+    assert_eq!(short_date("2024-01-15"), Some(s("Jan. 15, 2024")));
+    assert_eq!(short_date("2024-05-01"), Some(s("May 1, 2024")));
+
+    let a = Accessed::checked("k", Some(&s("15/01/2024")));
+    assert_eq!(a.to_yaml(Quotes::Double), "accessed: \"2024-01-15\"\n");
+    assert_eq!(Accessed::checked("k", Some(&s("soon"))).at, None);
+    assert_eq!(Accessed::checked("k", None).to_yaml(Quotes::Double), "");
+}
+
+#[test]
+fn contribution_markers() {
+    use new_page::{convert, Options, Target};
+    let (authors, marked) = strip_markers("Doe, Jane* and Smith*, Bob and Roe, Rich", '*');
+    assert_eq!(authors, "Doe, Jane and Smith, Bob and Roe, Rich");
+    assert_eq!(marked, vec![s("Doe, Jane"), s("Smith, Bob")]);
+
+    let bib = "@article{a, author = {Doe, Jane† and Smith, Bob† and Roe, Rich}, title = {T},
+      journal = {J}, year = {2020}, corresponding = {Roe, R. and Nobody, N}}";
+    let opts = Options {
+        author_marker: '†',
+        ..Options::default()
+    };
+    let paper = &convert(bib, &opts).unwrap()[0];
+    assert_eq!(
+        paper.auth.authors,
+        vec!["Jane Doe", "Bob Smith", "Rich Roe"]
+    );
+    assert!(paper.render(Target::Hugo).contains(
+        "authors:\n  - \"Jane Doe\"\n  - \"Bob Smith\"\n  - \"Rich Roe\"\n\
+         authors_equal_contribution:\n  - true\n  - true\n  - false\n\
+         authors_corresponding:\n  - false\n  - false\n  - true\n"
+    ));
+
+    let paper = &convert(bib, &Options::default()).unwrap()[0];
+    assert!(!paper
+        .render(Target::Hugo)
+        .contains("authors_equal_contribution"));
+}
+
+#[test]
+fn awards() {
+    use new_page::{convert, Options, Target};
+    let page = |bib: &str, opts: &Options| convert(bib, opts).unwrap()[0].render(Target::Hugo);
+    let bib = r#"@inproceedings{a, author = {A, B}, title = {T}, booktitle = {C}, year = {2020},
+      award = {Distinguished Paper Award; ACM SIGPLAN Research Highlight}}"#;
+    assert!(page(bib, &Options::default()).contains(
+        "award:\n  - \"Distinguished Paper Award\"\n  - \"ACM SIGPLAN Research Highlight\"\n"
+    ));
+
+    let bib = r#"@inproceedings{a, author = {A, B}, title = {T}, booktitle = {C}, year = {2020},
+      note = {Extended version. {B}est {P}aper at {C}onf\'erence}}"#;
+    assert!(!page(bib, &Options::default()).contains("award"));
+    let opts = Options {
+        detect_awards: true,
+        feature_awards: true,
+        ..Options::default()
+    };
+    assert!(page(bib, &opts).contains("award: \"Best Paper at Conférence\"\nfeatured: true\n"));
+    assert_eq!(
+        Awards::of(None, Some("Accepted, to appear"), true, true).names,
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn pages_count() {
+    assert_eq!(Pages::from_string("123--145").page_count(), Some(23));
+    assert_eq!(Pages::from_string("7").page_count(), None);
+    assert_eq!(Pages::from_string("11--17, 23--25").page_count(), Some(10));
+    assert_eq!(Pages::from_string("11--17, 30").page_count(), Some(8));
+    assert_eq!(Pages::from_string("xi--xvii").page_count(), Some(7));
+    assert_eq!(Pages::from_string("in press").page_count(), None);
+
+    let p = Pages::from_string("123--145").with_count("k", None, 2000);
+    assert_eq!(
+        p.to_yaml(Quotes::Double),
+        "page:\n  from: 123\n  to: 145\n  count: 23\n"
+    );
+    // The numpages tag when the bounds do not give the count.
+    let numpages = s("12");
+    let p = Pages::from_string("e1234").with_count("k", Some(&numpages), 2000);
+    assert_eq!(p.count, Some(12));
+    let p = Pages::default().with_count("k", Some(&numpages), 2000);
+    assert_eq!(p.count, Some(12));
+    // Nonsensical counts are left out.
+    assert_eq!(
+        Pages::from_string("145--123")
+            .with_count("k", None, 2000)
+            .count,
+        None
+    );
+    assert_eq!(
+        Pages::from_string("1--5000")
+            .with_count("k", None, 2000)
+            .count,
+        None
+    );
+}
+
+#[test]
+fn link_preference() {
+    use new_page::{convert, LinkSource, Options, Target};
+
+    const DOI: &str = "https://doi.org/10.1/x";
+    const ARXIV: &str = "https://arxiv.org/abs/2101.00001";
+    const URL: &str = "https://publisher.example/x";
+    let opts = Options::builder()
+        .url_preference(vec![LinkSource::Doi, LinkSource::Arxiv, LinkSource::Url])
+        .other_links(true)
+        .build()
+        .unwrap();
+    // Every combination of the three sources, with the link expected and
+    // the other ones.
+    for (doi, arxiv, url, www, links) in [
+        (true, true, true, DOI, vec![ARXIV, URL]),
+        (true, true, false, DOI, vec![ARXIV]),
+        (true, false, true, DOI, vec![URL]),
+        (true, false, false, DOI, vec![]),
+        (false, true, true, ARXIV, vec![URL]),
+        (false, true, false, ARXIV, vec![]),
+        (false, false, true, URL, vec![]),
+        (false, false, false, "", vec![]),
+    ] {
+        let mut bib =
+            s("@article{k, author = {Doe, Jane}, title = {T}, journal = {J}, year = 2021");
+        if doi {
+            bib.push_str(", doi = {10.1/x}");
+        }
+        if arxiv {
+            bib.push_str(", eprint = {2101.00001}, archiveprefix = {arXiv}");
+        }
+        if url {
+            bib.push_str(", url = {https://publisher.example/x}");
+        }
+        bib.push('}');
+        let p = &convert(&bib, &opts).unwrap()[0];
+        assert_eq!(p.url.link, www, "{}", bib);
+        assert_eq!(p.links, links, "{}", bib);
+    }
+
+    let bib = "@article{k, author = {Doe, Jane}, title = {T}, journal = {J}, year = 2021,
+        doi = {10.1/x}, url = {https://publisher.example/x}}";
+    // By default, the url tag alone, without links.
+    let p = &convert(bib, &Options::default()).unwrap()[0];
+    assert_eq!((p.url.link.as_str(), p.links.len()), (URL, 0));
+    // The sources left out of the preference are only listed.
+    let opts = Options::builder()
+        .url_preference(vec![LinkSource::Arxiv])
+        .other_links(true)
+        .build()
+        .unwrap();
+    let p = &convert(bib, &opts).unwrap()[0];
+    assert_eq!(p.url.link, "");
+    assert_eq!(p.links, vec![DOI, URL]);
+    assert!(p
+        .render(Target::Hugo)
+        .contains("www: \"\"\nlinks:\n  - \"https://doi.org/10.1/x\"\n"));
+}
+
+#[test]
+fn event_block() {
+    use std::collections::HashMap;
+
+    use new_page::{convert, Options, Target};
+
+    let tags = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (s(k), s(v))).collect()
+    };
+    let only_location = Event::of("k", None, &tags(&[("address", "Lisbon, Portugal")]));
+    assert_eq!(
+        only_location.to_yaml(Quotes::Double),
+        "event:\n  location: \"Lisbon, Portugal\"\n"
+    );
+    let venue_first = tags(&[("venue", "Lisbon"), ("address", "New York")]);
+    assert_eq!(
+        Event::of("k", None, &venue_first).location.as_deref(),
+        Some("Lisbon")
+    );
+    let only_dates = Event::of("k", None, &tags(&[("eventdate", "2024-04-14/2024-04-20")]));
+    assert_eq!(
+        only_dates.to_yaml(Quotes::Double),
+        "event:\n  start: 2024-04-14\n  end: 2024-04-20\n"
+    );
+    let one_day = Event::of("k", None, &tags(&[("eventdate", "2024-04-14")]));
+    assert_eq!(
+        (one_day.start.as_deref(), one_day.end),
+        (Some("2024-04-14"), None)
+    );
+    let open = Event::of("k", None, &tags(&[("eventdate", "2024-04-14/")]));
+    assert_eq!(open.start.as_deref(), Some("2024-04-14"));
+    let nothing = Event::of("k", None, &tags(&[("eventdate", "soon")]));
+    assert!(nothing.is_empty());
+    assert_eq!(nothing.to_yaml(Quotes::Double), "");
+
+    let bib = "@inproceedings{k, author = {Roe, R}, title = {T}, year = {2024},
+        booktitle = {Proceedings of ICSE}, eventtitle = {ICSE 2024}, venue = {Lisbon},
+        eventdate = {2024-04-14/2024-04-20}}";
+    let page = convert(bib, &Options::default()).unwrap()[0].render(Target::Hugo);
+    assert!(page.contains(
+        "event:\n  name: \"ICSE 2024\"\n  location: \"Lisbon\"\n  start: 2024-04-14\n  end: 2024-04-20\n"
+    ));
+    // Only conference papers have one.
+    let bib = "@article{k, author = {Roe, R}, title = {T}, year = {2024}, journal = {J},
+        venue = {Lisbon}}";
+    assert!(!convert(bib, &Options::default()).unwrap()[0]
+        .render(Target::Hugo)
+        .contains("event:"));
+}
+
+#[test]
+fn placeholder_titles() {
+    let title = |key| Title::placeholder(key).title;
+    assert_eq!(title("doe2023fast"), "Doe 2023 (fast)");
+    assert_eq!(
+        title("smith_jones2020:deep-nets"),
+        "Smith Jones 2020 (deep nets)"
+    );
+    assert_eq!(title("placeholder"), "Placeholder");
+    assert_eq!(title("2021-42"), "2021 (42)");
+    assert_eq!(title("__"), "__");
+    let t = Title::placeholder("doe2023fast");
+    assert!(t.missing);
+    assert_eq!(
+        t.to_yaml(Quotes::Double),
+        "title: \"Doe 2023 (fast)\"\ntitle_missing: true\n"
+    );
+}
@@ -0,0 +1,296 @@
+//! Tests of the whole-bibliography output formats.
+
+use new_page::citation::{sort_newest_first, venue_detail, Style};
+use new_page::fields::Pages;
+use new_page::formats::{data_yaml, document, html, html_escape, markdown_list};
+use new_page::yaml::Quotes;
+use new_page::{convert, Options, Target};
+
+const BIB: &str = r#"
+@article{old, author = {Roe, Richard}, title = {Old Paper.}, journal = {Journal of Things},
+  year = {2019}, url = {https://example.org/old}}
+@inproceedings{new, author = {Doe, Jane and Smith, Bob}, title = {New Paper},
+  booktitle = {Conference on Stuff}, year = {2023}, doi = {10.1/new}}
+@book{nolink, author = {Writer, Will}, title = {A Book}, publisher = {Springer}, year = {2023}}
+"#;
+
+#[test]
+fn markdown_list_newest_first() {
+    let mut papers = convert(BIB, &Options::default()).unwrap();
+    sort_newest_first(&mut papers);
+    assert_eq!(
+        markdown_list(&papers, false),
+        "- Jane Doe, Bob Smith. \"New Paper.\" *Conference on Stuff*, 2023. [doi](https://doi.org/10.1/new)\n\
+         - Will Writer. \"A Book.\" Springer, 2023.\n\
+         - Richard Roe. \"Old Paper.\" *Journal of Things*, 2019. [url](https://example.org/old)\n"
+    );
+}
+
+#[test]
+fn markdown_list_grouped_by_year() {
+    let mut papers = convert(BIB, &Options::default()).unwrap();
+    sort_newest_first(&mut papers);
+    let list = markdown_list(&papers, true);
+    assert!(list.starts_with("## 2023\n\n- Jane Doe"));
+    assert!(list.contains("Springer, 2023.\n\n## 2019\n\n- Richard Roe"));
+}
+
+#[test]
+fn undated_entries_come_last() {
+    let bib = format!(
+        "@misc{{nodate, author = {{Roe, R}}, title = {{Draft}}}}\n\
+         @misc{{soon, author = {{Roe, R}}, title = {{Soon}}, year = {{to appear}}}}\n{}",
+        BIB
+    );
+    let mut papers = convert(&bib, &Options::default()).unwrap();
+    sort_newest_first(&mut papers);
+    let keys: Vec<_> = papers.iter().map(|p| p.key.key.as_str()).collect();
+    assert_eq!(keys, vec!["new", "nolink", "old", "nodate", "soon"]);
+    let list = markdown_list(&papers, true);
+    assert!(list
+        .contains("*Journal of Things*, 2019. [url](https://example.org/old)\n\n## Undated\n\n"));
+    assert_eq!(list.matches("## ").count(), 3);
+    let out = html(&papers, true, false);
+    assert!(out.contains(
+        "</ol>\n<h2>Undated</h2>\n<ol class=\"bibliography\">\n  <li data-key=\"nodate\""
+    ));
+}
+
+#[test]
+fn single_document() {
+    let bib = format!(
+        "{}@misc{{again, author = {{Roe, R}}, title = {{A Book}}, year = {{2023}},\n  abstract = {{Same title.}}}}\n",
+        BIB
+    );
+    let mut papers = convert(&bib, &Options::default()).unwrap();
+    sort_newest_first(&mut papers);
+    let doc = document(&papers, "Grant report", "2024-03-01", false, Quotes::Double);
+    assert!(doc.starts_with("---\ntitle: \"Grant report\"\ndate: 2024-03-01\n---\n\n# 2023\n\n"));
+    assert!(doc.contains(
+        "## New Paper\n\nJane Doe, Bob Smith. \"New Paper.\" *Conference on Stuff*, 2023.\n\n\
+         [doi](https://doi.org/10.1/new)\n\n## A Book\n\n"
+    ));
+    assert!(doc.contains("\"A Book.\" 2023.\n\nSame title.\n\n# 2019\n\n## Old Paper\n\n"));
+    assert!(doc.ends_with("[url](https://example.org/old)\n"));
+    assert_eq!(doc.matches("\n# ").count(), 2);
+
+    let doc = document(&papers, "Grant report", "2024-03-01", true, Quotes::Double);
+    assert!(doc.contains(
+        "---\n\n- [2023](#2023)\n  - [New Paper](#new-paper)\n  - [A Book](#a-book)\n  \
+         - [A Book](#a-book-1)\n- [2019](#2019)\n  - [Old Paper](#old-paper)\n\n# 2023\n"
+    ));
+}
+
+#[test]
+fn html_escapes_text() {
+    assert_eq!(
+        html_escape("<b>\"R&D\"</b> 'x'"),
+        "&lt;b&gt;&quot;R&amp;D&quot;&lt;/b&gt; &#39;x&#39;"
+    );
+    let bib = r#"@article{k, author = {Roe, R}, title = {A <b> & C}, journal = {J}, year = {2020},
+      abstract = {x < y & <script>alert(1)</script>}}"#;
+    // Kept, or the markup of the abstract would be converted to text.
+    let opts = Options {
+        keep_abstract_markup: true,
+        ..Options::default()
+    };
+    let out = html(&convert(bib, &opts).unwrap(), false, false);
+    assert_eq!(
+        out,
+        "<ol class=\"bibliography\">\n  <li data-key=\"k\" data-year=\"2020\" data-type=\"article\">\n    \
+         <span class=\"citation\">R Roe. &quot;A &lt;b&gt; &amp; C.&quot; <em>J</em>, 2020.</span>\n    \
+         <p class=\"abstract\">x &lt; y &amp; &lt;script&gt;alert(1)&lt;/script&gt;</p>\n  </li>\n</ol>\n"
+    );
+}
+
+#[test]
+fn html_grouped_and_standalone() {
+    let mut papers = convert(BIB, &Options::default()).unwrap();
+    sort_newest_first(&mut papers);
+    let out = html(&papers, true, true);
+    assert!(out.starts_with("<!DOCTYPE html>"));
+    assert!(out.ends_with("</body>\n</html>\n"));
+    assert_eq!(out.matches("<h2>").count(), 2);
+    assert_eq!(out.matches("<ol").count(), 2);
+    assert!(out.contains("<a href=\"https://doi.org/10.1/new\">doi</a>"));
+}
+
+#[test]
+fn data_yaml_survives_adversarial_titles() {
+    let bib = r#"@article{k, author = {Roe, R}, title = {"Quoted": {a} - b # c: 'd' | e > f}, journal = {J: K}, year = {2020},
+      abstract = {---
+Not front matter.}}"#;
+    let yaml = data_yaml(&convert(bib, &Options::default()).unwrap(), Quotes::Double).unwrap();
+    let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+    let entry = &parsed["papers"][0];
+    assert_eq!(entry["title"], "\"Quoted\": {a} - b # c: 'd' | e > f");
+    assert_eq!(entry["journal"]["name"], "J: K");
+    // The abstract is LaTeX-decoded, so the dashes are an em dash.
+    assert_eq!(entry["abstract"], "—\nNot front matter.");
+    assert_eq!(entry["year"], 2020);
+    assert!(entry["volume"].is_null());
+}
+
+#[test]
+fn data_yaml_keeps_front_matter_order() {
+    let papers = convert(BIB, &Options::default()).unwrap();
+    let yaml = data_yaml(&papers, Quotes::Double).unwrap();
+    let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+    let keys: Vec<&str> = parsed["papers"][0]
+        .as_mapping()
+        .unwrap()
+        .keys()
+        .map(|k| k.as_str().unwrap())
+        .collect();
+    assert_eq!(
+        keys,
+        [
+            "authors",
+            "page",
+            "volume",
+            "series",
+            "journal",
+            "title",
+            "publisher",
+            "year",
+            "date",
+            "publication_status",
+            "doi",
+            "www",
+            "categories",
+            "bibtex_key",
+            "entry_type",
+            "abstract"
+        ]
+    );
+}
+
+#[test]
+fn venue_detail_matrix() {
+    let pages = Pages::from_string("123--145");
+    let single = Pages::from_string("7");
+    let none = Pages::from_string("");
+    let cases = [
+        (Some(42), Some("3"), &pages, Some("42(3):123–145")),
+        (Some(42), Some("3"), &none, Some("42(3)")),
+        (Some(42), None, &pages, Some("42:123–145")),
+        (Some(42), None, &none, Some("42")),
+        (None, Some("3"), &pages, Some("(3):123–145")),
+        (None, Some("3"), &none, Some("(3)")),
+        (None, None, &pages, Some("123–145")),
+        (None, None, &none, None),
+        (Some(42), Some("3"), &single, Some("42(3):7")),
+        (None, None, &single, Some("7")),
+    ];
+    for (volume, issue, pages, expected) in cases {
+        assert_eq!(
+            venue_detail(volume, issue, pages).as_deref(),
+            expected,
+            "{:?} {:?} {:?}-{:?}",
+            volume,
+            issue,
+            pages.from,
+            pages.to
+        );
+    }
+}
+
+#[test]
+fn special_issue_citation() {
+    let bib = "@article{k, author = {Roe, Richard}, title = {T}, journaltitle = {Journal of Y},
+        issuetitle = {Special Issue on X}, date = {2023}}";
+    let p = &convert(bib, &Options::default()).unwrap()[0];
+    assert_eq!(
+        p.citation(),
+        "Richard Roe. \"T.\" Journal of Y, Special Issue on X, 2023."
+    );
+    // Ignored on conferences.
+    let bib = "@inproceedings{k, author = {Roe, Richard}, title = {T}, booktitle = {C},
+        issuetitle = {Special Issue on X}, year = {2023}}";
+    let p = &convert(bib, &Options::default()).unwrap()[0];
+    assert_eq!(p.citation(), "Richard Roe. \"T.\" C, 2023.");
+    assert!(!p.render(Target::Hugo).contains("issue_title"));
+}
+
+#[test]
+fn online_resource_citation() {
+    let bib = "@online{mdn, title = {Fetch}, organization = {Mozilla}, year = 2024,
+        url = {https://example.org}, urldate = {15/01/2024}}";
+    let p = &convert(bib, &Options::default()).unwrap()[0];
+    assert_eq!(
+        p.citation(),
+        "\"Fetch.\" Mozilla, 2024. Accessed: Jan. 15, 2024."
+    );
+    let p = &convert(&bib.replace("urldate", "note"), &Options::default()).unwrap()[0];
+    assert_eq!(p.citation(), "\"Fetch.\" Mozilla, 2024.");
+}
+
+#[test]
+fn localized_citations() {
+    let bib = "@online{mdn, title = {Fetch}, organization = {Mozilla}, year = 2024,
+        url = {https://example.org}, urldate = {2024-02-01}}
+@online{w3c, title = {Fetch}, organization = {W3C}, year = 2024, urldate = {2024-03-15}}";
+    let cited = |locale: &str| {
+        let opts: Options =
+            serde_json::from_str(&format!(r#"{{"locale": "{}"}}"#, locale)).unwrap();
+        convert(bib, &opts).unwrap()
+    };
+    let en = cited("en");
+    assert_eq!(
+        en[1].citation(),
+        "\"Fetch.\" W3C, 2024. Accessed: Mar. 15, 2024."
+    );
+    let fr = cited("fr");
+    assert_eq!(
+        fr[0].styled_citation(Style::Markdown),
+        "\"Fetch.\" Mozilla, 2024. Consulté le 1er févr. 2024."
+    );
+    assert_eq!(
+        fr[1].styled_citation(Style::Html),
+        "&quot;Fetch.&quot; W3C, 2024. Consulté le 15 mars 2024."
+    );
+    let de = cited("de");
+    assert_eq!(
+        de[1].styled_citation(Style::Plain),
+        "\"Fetch.\" W3C, 2024. Abgerufen am 15. März 2024."
+    );
+    assert!(markdown_list(&de, false).contains("Abgerufen am 1. Feb. 2024."));
+    assert!(html(&fr, false, false).contains("Consulté le 15 mars 2024."));
+    // The front matter keeps its ISO dates and English keys.
+    assert_eq!(fr[1].render(Target::Hugo), en[1].render(Target::Hugo));
+    assert_eq!(
+        serde_json::to_value(&de[1]).unwrap(),
+        serde_json::to_value(&en[1]).unwrap()
+    );
+    let e = serde_json::from_str::<Options>(r#"{"locale": "es"}"#).err();
+    let supported = "expected one of `en`, `fr`, `de`";
+    assert!(e.unwrap().to_string().contains(supported));
+}
+
+#[test]
+fn styled_venues() {
+    let bib = r#"@article{k, author = {Roe, R}, title = {Stars * and *more*}, journal = {J* & <Co>},
+      issuetitle = {Special *}, year = {2020}}"#;
+    let p = &convert(bib, &Options::default()).unwrap()[0];
+    assert_eq!(
+        p.styled_citation(Style::Plain),
+        "R Roe. \"Stars * and *more*.\" J* & <Co>, Special *, 2020."
+    );
+    assert_eq!(p.styled_citation(Style::Plain), p.citation());
+    assert_eq!(
+        p.styled_citation(Style::Markdown),
+        "R Roe. \"Stars \\* and \\*more\\*.\" *J\\* & <Co>*, Special \\*, 2020."
+    );
+    assert_eq!(
+        p.styled_citation(Style::Html),
+        "R Roe. &quot;Stars * and *more*.&quot; <em>J* &amp; &lt;Co&gt;</em>, Special *, 2020."
+    );
+    // Publishers are not venues.
+    let book = &convert(BIB, &Options::default()).unwrap()[2];
+    assert_eq!(
+        book.styled_citation(Style::Markdown),
+        "Will Writer. \"A Book.\" Springer, 2023."
+    );
+    // The front matter is left unstyled.
+    assert!(p.render(Target::Hugo).contains("name: \"J* & <Co>\""));
+}
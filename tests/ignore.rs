@@ -0,0 +1,147 @@
+//! Tests of the ignore file.
+
+use std::process::Command;
+
+use new_page::diagnostics::collect;
+use new_page::ignore::IgnoreList;
+use new_page::{convert_summarized, Options, Warning};
+
+const BIB: &str = "
+@misc{memo-2021-01, author = {Doe, Jane}, title = {Memo}, year = 2021}
+@article{internal, author = {Doe, Jane}, title = {Internal}, journal = {J}, year = 2021}
+@article{public, author = {Doe, Jane}, title = {Public}, journal = {J}, year = 2021,
+  doi = {10.1000/public}}
+@article{report, author = {Doe, Jane}, title = {Report}, journal = {J}, year = 2021,
+  doi = {10.5555/MEMO.12}}
+";
+
+const IGNORE: &str = "# Internal documents
+memo-*
+
+internal   # not for the website
+doi:10.5555/memo
+";
+
+#[test]
+fn parse_ignore_file() {
+    let list = IgnoreList::parse(IGNORE);
+    assert_eq!(list.keys, vec!["internal"]);
+    assert_eq!(list.patterns, vec!["memo-*"]);
+    assert_eq!(list.doi_prefixes, vec!["10.5555/memo"]);
+    assert_eq!(
+        IgnoreList::parse("10.1000/X\n?bc").doi_prefixes,
+        vec!["10.1000/x"]
+    );
+}
+
+#[test]
+fn ignored_entries_are_counted_as_excluded() {
+    let opts = Options {
+        ignore: IgnoreList::parse(IGNORE),
+        ..Options::default()
+    };
+    let (papers, summary) = convert_summarized(BIB, &opts).unwrap();
+    let keys: Vec<&str> = papers.iter().map(|p| p.key.key.as_str()).collect();
+    assert_eq!(keys, vec!["public"]);
+    assert_eq!((summary.converted, summary.excluded), (1, 3));
+
+    for (pattern, ignored) in [("memo-202?-*", 1), ("*-01", 1), ("*", 4), ("m*x", 0)] {
+        let opts = Options {
+            ignore: IgnoreList::parse(pattern),
+            ..Options::default()
+        };
+        let (_, summary) = convert_summarized(BIB, &opts).unwrap();
+        assert_eq!(summary.excluded, ignored, "{}", pattern);
+    }
+}
+
+#[test]
+fn selection_wins_over_the_ignore_file() {
+    let opts = Options {
+        ignore: IgnoreList::parse(IGNORE),
+        select_keys: vec!["internal".to_string(), "public".to_string()],
+        ..Options::default()
+    };
+    let (converted, warnings) = collect(|| convert_summarized(BIB, &opts));
+    let (papers, summary) = converted.unwrap();
+    let keys: Vec<&str> = papers.iter().map(|p| p.key.key.as_str()).collect();
+    assert_eq!(keys, vec!["internal", "public"]);
+    assert_eq!((summary.converted, summary.excluded), (2, 2));
+    assert_eq!(
+        warnings,
+        [Warning::SelectedIgnored {
+            key: "internal".to_string(),
+            reason: "key is in the ignore file".to_string()
+        }]
+    );
+    assert_eq!(
+        warnings[0].to_string(),
+        "internal: key is in the ignore file, converting it as it is selected"
+    );
+}
+
+#[test]
+fn ignore_file_next_to_the_input() {
+    let dir = std::env::temp_dir().join(format!("new_page-ignore-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("refs.bib"), BIB).unwrap();
+    std::fs::write(dir.join(".newpageignore"), IGNORE).unwrap();
+    let run = |args: &[&str]| {
+        let out = Command::new(env!("CARGO_BIN_EXE_new_page"))
+            .arg("--file-path")
+            .arg(dir.join("refs.bib"))
+            .args(["--quiet", "--output", "-"])
+            .args(args)
+            .output()
+            .unwrap();
+        String::from_utf8(out.stdout).unwrap()
+    };
+    let out = run(&[]);
+    assert!(out.contains("bibtex_key: \"public\""));
+    assert!(!out.contains("bibtex_key: \"internal\""));
+    // A file given explicitly replaces the discovered one.
+    std::fs::write(dir.join("other"), "public\n").unwrap();
+    let out = run(&["--ignore-file", dir.join("other").to_str().unwrap()]);
+    assert!(out.contains("bibtex_key: \"internal\""));
+    assert!(!out.contains("bibtex_key: \"public\""));
+    // A selected entry is converted whatever the ignore file says.
+    let out = run(&["--key", "memo-2021-01,public"]);
+    assert!(out.contains("bibtex_key: \"memo-2021-01\""));
+    assert!(out.contains("bibtex_key: \"public\""));
+    assert!(!out.contains("bibtex_key: \"report\""));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn ignore_file_applies_to_its_input_only() {
+    let dir = std::env::temp_dir().join(format!("new_page-ignore-scoped-{}", std::process::id()));
+    for (name, bib, ignore) in [
+        (
+            "a",
+            "@misc{a-memo, author = {Doe, Jane}, title = {A}, year = 2021}",
+            "*-memo\n",
+        ),
+        (
+            "b",
+            "@misc{b-memo, author = {Doe, Jane}, title = {B}, year = 2021}",
+            "a-report\n",
+        ),
+    ] {
+        std::fs::create_dir_all(dir.join(name)).unwrap();
+        std::fs::write(dir.join(name).join("refs.bib"), bib).unwrap();
+        std::fs::write(dir.join(name).join(".newpageignore"), ignore).unwrap();
+    }
+    let out = Command::new(env!("CARGO_BIN_EXE_new_page"))
+        .arg("--file-path")
+        .arg(dir.join("a/refs.bib"))
+        .arg("--file-path")
+        .arg(dir.join("b/refs.bib"))
+        .args(["--quiet", "--output", "-"])
+        .output()
+        .unwrap();
+    let out = String::from_utf8(out.stdout).unwrap();
+    // The pattern next to a.bib does not reach the entries of b.bib.
+    assert!(!out.contains("bibtex_key: \"a-memo\""));
+    assert!(out.contains("bibtex_key: \"b-memo\""));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
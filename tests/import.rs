@@ -0,0 +1,139 @@
+//! Tests of the reading of RIS and CSL-JSON references.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use new_page::import::{InputFormat, SNIFF_LEN};
+use new_page::{convert, Options, Target};
+
+fn fixture(name: &str) -> (PathBuf, String) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    let content = fs::read_to_string(&path).unwrap();
+    (path, content)
+}
+
+#[test]
+fn ris_records() {
+    let (path, content) = fixture("zotero.ris");
+    let format = InputFormat::detect(&path, &content);
+    assert_eq!(format, InputFormat::Ris);
+    let bibtex = format.to_bibtex(&content).unwrap();
+    let papers = convert(&bibtex, &Options::default()).unwrap();
+    assert_eq!(papers.len(), 2);
+    let page = papers[0].render(Target::Hugo);
+    assert!(page.contains("title: \"Graphs & Proofs: 100% Verified\"\n"));
+    assert!(page.contains("  - \"Jane Doe\"\n  - \"Bob Smith\"\n"));
+    assert!(page.contains("name: \"Journal of Things\""));
+    assert!(page.contains("year: 2021\n"));
+    assert!(page.contains("doi: \"10.1000/graphs\"\n"));
+    assert!(page.contains("  - \"graphs\"\n  - \"proofs\"\n"));
+    assert!(page.contains("bibtex_key: \"doe2021\"\n"));
+    assert!(page.contains("---\nWe prove things about graphs.\n"));
+    assert_eq!(papers[1].key.key, "roe2020");
+    assert!(papers[1]
+        .render(Target::Hugo)
+        .contains("entry_type: \"inproceedings\"\n"));
+}
+
+#[test]
+fn csl_items() {
+    let (path, content) = fixture("zotero.json");
+    let format = InputFormat::detect(&path, &content);
+    assert_eq!(format, InputFormat::CslJson);
+    let bibtex = format.to_bibtex(&content).unwrap();
+    let papers = convert(&bibtex, &Options::default()).unwrap();
+    assert_eq!(papers.len(), 2);
+    let page = papers[0].render(Target::Hugo);
+    assert!(page.contains("  - \"Jane Doe\"\n  - \"The Consortium\"\n"));
+    assert!(page.contains("volume: 3\n"));
+    assert!(page.contains("date: 2021-05-01\n"));
+    assert!(page.contains("bibtex_key: \"doe21\"\n"));
+    assert_eq!(papers[1].key.key, "roe2020");
+    assert!(InputFormat::CslJson.to_bibtex("42").is_err());
+}
+
+#[test]
+fn detection_from_content() {
+    let (_, ris) = fixture("zotero.ris");
+    let (_, json) = fixture("zotero.json");
+    let unknown = Path::new("refs.txt");
+    assert_eq!(InputFormat::detect(unknown, &ris), InputFormat::Ris);
+    assert_eq!(InputFormat::detect(unknown, &json), InputFormat::CslJson);
+    assert_eq!(
+        InputFormat::detect(unknown, "@article{k,}"),
+        InputFormat::Bibtex
+    );
+}
+
+#[test]
+fn misnamed_inputs() {
+    let bib = Path::new("refs.bib");
+    let (_, ris) = fixture("zotero.ris");
+    let (_, json) = fixture("zotero.json");
+    assert_eq!(InputFormat::detect(bib, &ris), InputFormat::Ris);
+    assert_eq!(
+        InputFormat::detect(bib, &format!("\u{feff}{}", json)),
+        InputFormat::CslJson
+    );
+
+    let bbl = "\\begin{thebibliography}{1}\n\n\\bibitem{doe21}\nJ.~Doe.\n\\newblock A paper.\n";
+    assert_eq!(InputFormat::detect(bib, bbl), InputFormat::Bibtex);
+    let e = InputFormat::Bibtex.to_bibtex(bbl).unwrap_err();
+    assert!(e.starts_with("this looks like a LaTeX .bbl file; new_page needs BibTeX source"));
+    let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?><xml><records><record>";
+    let e = InputFormat::Bibtex.to_bibtex(xml).unwrap_err();
+    assert!(e.contains("EndNote XML"), "{}", e);
+    assert!(e.contains("--input-format ris"), "{}", e);
+
+    // BibTeX mentioning \bibitem is still BibTeX, and only the start of a
+    // file is looked at.
+    let about = "@misc{k, title = {On \\bibitem}, author = {Doe, J}, year = 2020}";
+    assert!(InputFormat::Bibtex.to_bibtex(about).is_ok());
+    let late = format!("{}\nTY  - JOUR\n", "%".repeat(SNIFF_LEN));
+    assert_eq!(InputFormat::detect(bib, &late), InputFormat::Bibtex);
+}
+
+#[test]
+fn clipboard_content() {
+    use new_page::clipboard::bibtex;
+
+    let entry = "@article{doe, author = {Doe, Jane}, title = {T}, journal = {J}, year = 2021}";
+    assert_eq!(bibtex(&format!("\n  {}\n\n", entry)).unwrap(), entry);
+    let two = format!("{}\n{}", entry, entry.replace("doe", "roe"));
+    assert_eq!(bibtex(&two).unwrap(), two);
+    for content in [
+        "https://doi.org/10.1/x",
+        "",
+        "@article{",
+        "@comment{just a note}",
+    ] {
+        assert_eq!(
+            bibtex(content),
+            Err("clipboard does not look like a BibTeX entry".to_string()),
+            "{}",
+            content
+        );
+    }
+}
+
+#[test]
+fn clipboard_without_tools() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_new_page"))
+        .arg("--from-clipboard")
+        .env("PATH", "")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let reason = match cfg!(feature = "clipboard") {
+        true => "no clipboard available: without a display, or without pbpaste",
+        false => "built without the `clipboard` feature",
+    };
+    assert!(
+        stderr.starts_with(&format!("Cannot read the clipboard: {}", reason)),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,84 @@
+//! Tests of the view of the representations of a single entry.
+
+use new_page::inspect::inspect;
+use new_page::merge::MergeOn;
+use new_page::Options;
+
+const BIB: &str = r#"
+@string{jot = "Journal of Things"}
+@article{doe, Author = {Doe, Jane}, title = {A Paper}, journal = jot, year = {2021}}
+@article{roe, author = {Roe, Rick}, title = {Other}, journal = {J}, year = {2020}}
+"#;
+
+#[test]
+fn entry_representations() {
+    let overrides = "@article{doe, year = {2022}}";
+    let opts = Options::default();
+    let view = inspect(BIB, overrides, MergeOn::Key, "doe", &opts)
+        .unwrap()
+        .unwrap();
+    assert_eq!(view.entry_type, "article");
+    // As written, then as overridden.
+    assert_eq!(view.raw.len(), 2);
+    assert_eq!(view.raw[0][0], ("Author".into(), "{Doe, Jane}".into()));
+    assert_eq!(view.raw[0][2], ("journal".into(), "jot".into()));
+    assert_eq!(view.raw[1], vec![("year".into(), "{2022}".into())]);
+    // Lowercased, expanded and merged.
+    assert_eq!(view.tags["author"], "Doe, Jane");
+    assert_eq!(view.tags["journal"], "Journal of Things");
+    assert_eq!(view.tags["year"], "2022");
+    assert_eq!(view.fields["year"], 2022);
+    assert_eq!(view.fields["journal"]["name"], "Journal of Things");
+    assert!(view
+        .rendered
+        .starts_with("---\nauthors:\n  - \"Jane Doe\"\n"));
+
+    let text = view.to_string();
+    for section in [
+        "== Raw tags of @article{doe} ==\nAuthor = {Doe, Jane}\n",
+        "-- override --\nyear = {2022}\n",
+        "== Normalized tags ==\nauthor = Doe, Jane\n",
+        "== Fields ==\n",
+        "== Rendered ==\n---\n",
+    ] {
+        assert!(text.contains(section), "{}", text);
+    }
+    let json: serde_json::Value = serde_json::to_value(&view).unwrap();
+    assert_eq!(json["tags"]["journal"], "Journal of Things");
+
+    assert!(inspect(BIB, "", MergeOn::Key, "nobody", &opts)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn value_provenance() {
+    let bib = r#"
+@xdata{acm, publisher = {ACM}}
+@inproceedings{doe, author = {Doe, Jane}, title = {A Paper}, booktitle = {Proc. of Things},
+  xdata = {acm}, year = {2021}, doi = {10.1/x}}
+"#;
+    let overrides = "@inproceedings{doe, year = {2022}}";
+    let opts = Options::default();
+    let view = inspect(bib, overrides, MergeOn::Key, "doe", &opts)
+        .unwrap()
+        .unwrap();
+    let source = |field| view.provenance.get(field).unwrap().to_string();
+    assert_eq!(source("title"), "title of the entry");
+    assert_eq!(source("conference"), "booktitle of the entry");
+    assert_eq!(source("year"), "year of the overrides");
+    assert_eq!(source("publisher"), "publisher of @xdata acm");
+    assert_eq!(source("doi"), "doi of the entry");
+    assert!(view.provenance.get("www").is_none());
+    assert!(view.provenance.get("volume").is_none());
+    assert!(view
+        .to_string()
+        .contains("== Provenance ==\nauthors: author of the entry\n"));
+    let json: serde_json::Value = serde_json::to_value(&view).unwrap();
+    assert_eq!(
+        json["provenance"]["publisher"],
+        serde_json::json!({"tag": "publisher", "origin": "xdata", "name": "acm"})
+    );
+    // Kept out of the page.
+    assert!(!view.rendered.contains('#'));
+}
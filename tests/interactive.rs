@@ -0,0 +1,45 @@
+//! Tests of the entry selection helpers.
+
+use std::process::{Command, Stdio};
+
+use new_page::interactive::choice_label;
+use new_page::output::{existing_pages, page_path};
+use new_page::{convert, Options};
+
+const BIB: &str = r#"
+@article{doe, author = {Doe, Jane}, title = {A Paper}, journal = {J}, year = {2021}}
+@article{roe, author = {Roe, Rick}, title = {Another}, journal = {J}, year = {2020}}
+"#;
+
+#[test]
+fn generated_pages_are_marked() {
+    let papers = convert(BIB, &Options::default()).unwrap();
+    let dir = std::env::temp_dir().join(format!("new_page-interactive-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(page_path(&dir, "roe", false), "").unwrap();
+    let generated = existing_pages(&papers, &dir, false);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(generated, vec![false, true]);
+    assert_eq!(
+        choice_label(&papers[0], generated[0]),
+        "doe — 2021 — A Paper"
+    );
+    assert_eq!(
+        choice_label(&papers[1], generated[1]),
+        "roe — 2020 — Another [generated]"
+    );
+}
+
+#[test]
+fn no_terminal_fails_before_reading_the_input() {
+    let out = Command::new(env!("CARGO_BIN_EXE_new_page"))
+        .args(["--file-path", "no/such/file.bib", "--interactive"])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert_eq!(out.status.code(), Some(4), "{}", stderr);
+    assert!(stderr.contains("Cannot select entries: --interactive needs a terminal"));
+    assert!(!stderr.contains("no/such/file.bib"), "{}", stderr);
+}
@@ -0,0 +1,60 @@
+//! Tests of the regeneration of citation keys.
+
+use new_page::keys::{regenerate, KeyPattern};
+use new_page::text::transliterate;
+use new_page::{convert, Options, Target};
+
+const BIB: &str = r#"
+@article{1, author = {M{\"u}ller, J{\"o}rg}, title = {The Fast Graphs}, journal = {J}, year = {2023}}
+@article{1, author = {M{\"u}ller, J{\"o}rg}, title = {On Fast Proofs}, journal = {J}, year = {2023}}
+@article{x, author = {Ørsted, Hans and Doe, Jane}, title = {A Note}, journal = {J}, year = {2020}}
+"#;
+
+#[test]
+fn patterns() {
+    assert!(KeyPattern::parse("{author}{year}{word}").is_ok());
+    assert!(KeyPattern::parse("bib-{year}").is_ok());
+    assert_eq!(
+        KeyPattern::parse("{author}{month}").unwrap_err(),
+        "unknown placeholder {month}, expected {author}, {year} or {word}"
+    );
+    assert!(KeyPattern::parse("{author").is_err());
+    assert!(KeyPattern::parse("").is_err());
+    assert_eq!(
+        transliterate("Müller Ørsted Straße"),
+        "Muller Orsted Strasse"
+    );
+}
+
+#[test]
+fn keys_are_regenerated_uniquely() {
+    let mut papers = convert(BIB, &Options::default()).unwrap();
+    let pattern = KeyPattern::parse("{author}{year}{word}").unwrap();
+    let map = regenerate(&mut papers, &pattern);
+    let keys: Vec<&str> = papers.iter().map(|p| p.key.key.as_str()).collect();
+    assert_eq!(
+        keys,
+        ["muller2023fast", "muller2023fasta", "orsted2020note"]
+    );
+    assert!(papers[1]
+        .render(Target::Hugo)
+        .contains("bibtex_key: \"muller2023fasta\"\n"));
+    assert_eq!(
+        map.to_string(),
+        "1 -> muller2023fast\n1 -> muller2023fasta\nx -> orsted2020note\n"
+    );
+}
+
+#[test]
+fn pages_in_several_languages_share_their_key() {
+    let bib = "@article{k, author = {Doe, Jane}, title = {Graphs}, title-fr = {Graphes}, \
+               journal = {J}, year = {2021}}";
+    let opts = Options {
+        languages: vec!["en".to_string(), "fr".to_string()],
+        ..Options::default()
+    };
+    let mut papers = convert(bib, &opts).unwrap();
+    let map = regenerate(&mut papers, &KeyPattern::parse("{author}{year}").unwrap());
+    assert_eq!(map.keys, [("k".to_string(), "doe2021".to_string())]);
+    assert!(papers.iter().all(|p| p.key.key == "doe2021"));
+}
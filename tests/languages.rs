@@ -0,0 +1,59 @@
+//! Tests of the pages written once per language.
+
+use new_page::output::write_pages;
+use new_page::{convert, Options, Target};
+
+const BIB: &str = r#"
+@article{a, author = {Doe, Jane}, title = {A Paper}, journal = {J}, year = {2021},
+  abstract = {In English.}, abstract-fr = {En fran{\c{c}}ais.}, title-fr = {Un article}}
+@article{b, author = {Doe, Jane}, title = {Another}, journal = {J}, year = {2021},
+  abstract = {Only English.}}
+"#;
+
+#[test]
+fn one_page_per_language() {
+    let opts = Options {
+        languages: vec!["en".into(), "fr".into()],
+        bundle: true,
+        ..Options::default()
+    };
+    let papers = convert(BIB, &opts).unwrap();
+    let pages: Vec<_> = papers
+        .iter()
+        .map(|p| {
+            (
+                p.lang.as_deref(),
+                p.title.title.as_str(),
+                p.abs.abs.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        pages,
+        vec![
+            (Some("en"), "A Paper", "In English."),
+            (Some("fr"), "Un article", "En français."),
+            (Some("en"), "Another", "Only English."),
+            (Some("fr"), "Another", "Only English."),
+        ]
+    );
+    assert!(!papers[1].render(Target::Hugo).contains("translated"));
+    assert!(papers[3]
+        .render(Target::Hugo)
+        .contains("translated: false\n"));
+
+    let dir = std::env::temp_dir().join(format!("new_page-languages-{}", std::process::id()));
+    write_pages(&papers, &dir, &opts).unwrap();
+    let fr = std::fs::read_to_string(dir.join("a/index.fr.md")).unwrap();
+    assert!(fr.contains("title: \"Un article\"\n"));
+    assert!(dir.join("a/index.en.md").exists() && dir.join("b/index.fr.md").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let opts = Options {
+        languages: vec!["en".into(), "fr".into()],
+        language_suffix: "_{}".into(),
+        ..Options::default()
+    };
+    let papers = convert(&BIB.replace("-fr", "_fr"), &opts).unwrap();
+    assert_eq!(papers[1].title.title, "Un article");
+}
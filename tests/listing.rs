@@ -0,0 +1,80 @@
+//! Tests of the list of the entries of a bibliography.
+
+use new_page::listing::{list, table, ListFilter, ListOrder, Listed};
+use new_page::Options;
+
+const BIB: &str = r#"
+@article{doe2021, author = {Doe, Jane and Roe, R}, journal = {J}, year = {2021},
+  title = {A Rather Long Title About Things That Keeps Going On and On Forever}}
+@misc{empty,}
+@inproceedings{smith, author = {Bob {\"O}zt{\"u}rk}, title = {Short}, year = {2019}}
+@book{ed, editor = {van Berg, Jan}, title = {Edited}, date = {2020-05}}
+"#;
+
+fn keys(entries: &[Listed]) -> Vec<&str> {
+    entries.iter().map(|e| e.key.as_str()).collect()
+}
+
+#[test]
+fn incomplete_entries_are_listed() {
+    let opts = Options::default();
+    let entries = list(BIB, &ListFilter::default(), ListOrder::File, &opts).unwrap();
+    assert_eq!(keys(&entries), vec!["doe2021", "empty", "smith", "ed"]);
+    assert_eq!(
+        entries[1],
+        Listed {
+            key: "empty".into(),
+            entry_type: "misc".into(),
+            year: None,
+            author: None,
+            title: None,
+        }
+    );
+    assert_eq!(entries[2].author.as_deref(), Some("Öztürk"));
+    assert_eq!(entries[3].author.as_deref(), Some("van Berg"));
+    assert_eq!(entries[3].year, Some(2020));
+    assert_eq!(
+        table(&entries),
+        "KEY      TYPE           YEAR  AUTHOR    TITLE\n\
+         doe2021  article        2021  Doe       A Rather Long Title About Things That Keeps Going…\n\
+         empty    misc           -     -         -\n\
+         smith    inproceedings  2019  Öztürk    Short\n\
+         ed       book           2020  van Berg  Edited\n"
+    );
+}
+
+#[test]
+fn filters_and_orders() {
+    let opts = Options::default();
+    let listed = |filter: &ListFilter, order| {
+        keys(&list(BIB, filter, order, &opts).unwrap())
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>()
+    };
+    let filter = ListFilter {
+        years: Some(2019..=2020),
+        ..ListFilter::default()
+    };
+    assert_eq!(listed(&filter, ListOrder::Year), vec!["smith", "ed"]);
+    let filter = ListFilter {
+        types: vec!["Article".into(), "book".into()],
+        ..ListFilter::default()
+    };
+    assert_eq!(listed(&filter, ListOrder::Key), vec!["doe2021", "ed"]);
+    let filter = ListFilter {
+        author: Some("öz".into()),
+        ..ListFilter::default()
+    };
+    assert_eq!(listed(&filter, ListOrder::File), vec!["smith"]);
+    // Undated and anonymous entries come last.
+    let all = ListFilter::default();
+    assert_eq!(
+        listed(&all, ListOrder::Year),
+        vec!["smith", "ed", "doe2021", "empty"]
+    );
+    assert_eq!(
+        listed(&all, ListOrder::Author),
+        vec!["doe2021", "ed", "smith", "empty"]
+    );
+}
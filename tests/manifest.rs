@@ -0,0 +1,74 @@
+//! Tests of the manifest of the written pages.
+
+use new_page::manifest::{content_hash, Manifest, Run};
+use new_page::output::rendered_pages;
+use new_page::{convert, Options};
+
+const BIB: &str = r#"
+@article{doe.2021, author = {Doe, Jane}, title = {A Paper}, journal = {J}, year = {2021},
+  title-fr = {Un article}}
+@misc{roe, author = {Roe, Rick}, title = {Undated}}
+"#;
+
+#[test]
+fn pages_and_slugs() {
+    for bundle in [false, true] {
+        let opts = Options {
+            languages: vec!["en".into(), "fr".into()],
+            bundle,
+            ..Options::default()
+        };
+        let papers = convert(BIB, &opts).unwrap();
+        let pages = rendered_pages(&papers, &opts);
+        let manifest = Manifest::of(&papers, &pages, Run::new(&["pubs.bib".into()], None));
+        let listed: Vec<_> = manifest
+            .pages
+            .iter()
+            .map(|p| (p.key.as_str(), p.path.as_str(), p.slug.as_str(), p.year))
+            .collect();
+        let expected = match bundle {
+            false => [
+                ("doe.2021", "doe.2021.en.md", "doe.2021", Some(2021)),
+                ("doe.2021", "doe.2021.fr.md", "doe.2021", Some(2021)),
+                ("roe", "roe.en.md", "roe", None),
+                ("roe", "roe.fr.md", "roe", None),
+            ],
+            true => [
+                ("doe.2021", "doe.2021/index.en.md", "doe.2021", Some(2021)),
+                ("doe.2021", "doe.2021/index.fr.md", "doe.2021", Some(2021)),
+                ("roe", "roe/index.en.md", "roe", None),
+                ("roe", "roe/index.fr.md", "roe", None),
+            ],
+        };
+        assert_eq!(listed, expected);
+        assert_eq!(manifest.pages[1].title, "Un article");
+        assert_eq!(
+            manifest.hash(&pages[0].filename),
+            Some(content_hash(&pages[0].content).as_str())
+        );
+        assert_eq!(manifest.hash("missing.md"), None);
+    }
+}
+
+#[test]
+fn written_and_read_back() {
+    let dir = std::env::temp_dir().join(format!("new_page-manifest-{}", std::process::id()));
+    let path = dir.join("manifest.json");
+    let opts = Options::default();
+    let papers = convert(BIB, &opts).unwrap();
+    let pages = rendered_pages(&papers, &opts);
+    let run = Run::new(&["pubs.bib".into()], Some("2026-01-02T03:04:05Z".into()));
+    let manifest = Manifest::of(&papers, &pages, run);
+    manifest.write(&path).unwrap();
+    manifest.write(&path).unwrap();
+    assert_eq!(Manifest::load(&path).unwrap(), manifest);
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(json["run"]["inputs"][0], "pubs.bib");
+    assert_eq!(json["run"]["version"], env!("CARGO_PKG_VERSION"));
+    assert!(json["pages"][1]["year"].is_null());
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    let e = Manifest::load(&dir.join("none.json")).unwrap_err();
+    assert!(e.to_string().contains("none.json"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,73 @@
+//! Tests of the overriding of entries with a hand-curated file.
+
+use new_page::merge::MergeOn;
+use new_page::{convert, convert_with_overrides, Options, Target};
+
+const BASE: &str = r#"
+@string{jot = {Journal of Things}}
+@article{DBLP:doe21, author = {Doe, Jane}, title = {A Paper}, journal = {J. Th.},
+  year = {2021}, doi = {10.1000/Paper}, abstract = {Exported abstract.}}
+@article{DBLP:roe20, author = {Roe, Rick}, title = {Other}, journal = jot, year = {2020}}
+"#;
+
+#[test]
+fn overrides_by_key() {
+    let overrides = "@article{DBLP:doe21, journal = jot, abstract = {Curated abstract.}}";
+    let (papers, summary) =
+        convert_with_overrides(BASE, overrides, MergeOn::Key, &Options::default()).unwrap();
+    assert_eq!(summary.converted, 2);
+    let page = papers[0].render(Target::Hugo);
+    assert!(page.contains("name: \"Journal of Things\""));
+    assert!(page.contains("title: \"A Paper\"\n"));
+    assert!(page.contains("Curated abstract."));
+    assert!(!page.contains("Exported"));
+    assert!(!papers[1].render(Target::Hugo).contains("Curated"));
+}
+
+#[test]
+fn overrides_by_doi() {
+    let overrides = "@article{mine, author = {Doe, Jane}, journal = {J}, year = {2021},\
+                     doi = {https://doi.org/10.1000/paper}, title = {A Better Title}}";
+    let (papers, _) =
+        convert_with_overrides(BASE, overrides, MergeOn::Doi, &Options::default()).unwrap();
+    assert_eq!(papers.len(), 2);
+    assert_eq!(papers[0].key.key, "DBLP:doe21");
+    assert_eq!(papers[0].title.title, "A Better Title");
+
+    let (papers, _) =
+        convert_with_overrides(BASE, overrides, MergeOn::Key, &Options::default()).unwrap();
+    assert_eq!(papers.len(), 3);
+    assert_eq!(papers[0].title.title, "A Paper");
+}
+
+#[test]
+fn orphan_overrides_stand_alone() {
+    let overrides = "@misc{talk, author = {Doe, Jane}, title = {A Talk}, year = {2022}}";
+    let (papers, summary) =
+        convert_with_overrides(BASE, overrides, MergeOn::Key, &Options::default()).unwrap();
+    assert_eq!(summary.converted, 3);
+    assert_eq!(papers[2].key.key, "talk");
+    assert_eq!(papers[2].title.title, "A Talk");
+}
+
+#[test]
+fn xdata_inheritance() {
+    let bib = r#"
+@xdata{acm, publisher = {ACM}, address = {New York}}
+@xdata{pldi, xdata = {acm}, booktitle = {Programming Language Design and Implementation}}
+@xdata{loop, xdata = {loop2}, note = {Looping}}
+@xdata{loop2, xdata = {loop}}
+@inproceedings{doe21, author = {Doe, Jane}, title = {A Paper}, year = {2021},
+  xdata = {pldi, loop}, address = {Online}}
+@inproceedings{roe20, author = {Roe, Rick}, title = {Other}, year = {2020},
+  booktitle = {C}, xdata = {missing}}
+"#;
+    let papers = convert(bib, &Options::default()).unwrap();
+    let keys: Vec<_> = papers.iter().map(|p| p.key.key.as_str()).collect();
+    assert_eq!(keys, ["doe21", "roe20"]);
+    let page = papers[0].render(Target::Hugo);
+    assert!(page.contains("name: \"Programming Language Design and Implementation\""));
+    assert!(page.contains("publisher: \"ACM\""));
+    assert!(!page.contains("xdata"));
+    assert!(papers[1].render(Target::Hugo).contains("name: \"C\""));
+}
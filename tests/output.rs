@@ -0,0 +1,24 @@
+//! Tests of the single-file output.
+
+use std::io::ErrorKind;
+
+use new_page::output::write_file_atomic;
+
+#[test]
+fn output_file_is_replaced_on_request() {
+    let dir = std::env::temp_dir().join(format!("new_page-output-{}", std::process::id()));
+    let path = dir.join("nested/publications.md");
+    write_file_atomic(&path, "one", false).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "one");
+
+    let e = write_file_atomic(&path, "two", false).unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::AlreadyExists);
+    assert!(e.to_string().contains("publications.md: already exists"));
+    write_file_atomic(&path, "two", true).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "two");
+    assert_eq!(std::fs::read_dir(dir.join("nested")).unwrap().count(), 1);
+
+    let e = write_file_atomic(&dir, "x", true).unwrap_err();
+    assert!(e.to_string().ends_with("is a directory"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
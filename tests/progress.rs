@@ -0,0 +1,33 @@
+//! Tests of the progress shown during the long steps.
+
+use std::time::Duration;
+
+use new_page::progress::{Progress, EVERY};
+
+#[test]
+fn bar_on_a_terminal() {
+    let mut p = Progress::new("DBLP", 4, true);
+    assert_eq!(
+        p.advance("doe21").unwrap(),
+        format!("DBLP [{}{}] 1/4 doe21", "#".repeat(7), "-".repeat(23))
+    );
+    for key in ["a", "b"] {
+        p.advance(key);
+    }
+    assert!(p
+        .advance("d")
+        .unwrap()
+        .starts_with(&format!("DBLP [{}] 4/4", "#".repeat(30))));
+    assert_eq!(
+        p.summary(Duration::from_millis(1250)),
+        "DBLP: 4 entries in 1.2s"
+    );
+}
+
+#[test]
+fn periodic_lines_otherwise() {
+    let mut p = Progress::new("Writing", 250, false);
+    let lines: Vec<String> = (0..250).filter_map(|i| p.advance(&i.to_string())).collect();
+    assert_eq!(lines, ["Writing: 100/250", "Writing: 200/250"]);
+    assert_eq!(EVERY, 100);
+}
@@ -0,0 +1,105 @@
+//! Property tests feeding arbitrary strings to the field parsers, which must
+//! never panic whatever the bibtex file contains.
+
+use new_page::fields::*;
+use new_page::latex::decode;
+use new_page::markup::strip;
+use new_page::output::sanitize_filename;
+use new_page::venues::infer_shortname;
+use new_page::yaml::{quote, Quotes, ToYaml};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn pages_never_panic(s in "\\PC*") {
+        let p = Pages::from_string(&s);
+        let _ = p.to_yaml(Quotes::Double);
+        prop_assert!(p.from.is_some() || p.to.is_none());
+        if let (Some(from), Some(to)) = (p.from, p.to) {
+            prop_assert!(from <= to, "{} > {}", from, to);
+        }
+    }
+
+    #[test]
+    fn pages_ranges(from in 0i64..100_000, len in 0i64..1000, sep in "(-|--|–| - )") {
+        let p = Pages::from_string(&format!("{}{}{}", from, sep, from + len));
+        prop_assert_eq!(p.from, Some(from));
+        prop_assert_eq!(p.to, Some(from + len));
+        prop_assert!(p.from <= p.to);
+    }
+
+    #[test]
+    fn names_never_empty(s in "\\PC*") {
+        for name in parse_name_list(&s) {
+            prop_assert!(!name.is_empty());
+            prop_assert_eq!(name.trim(), name.as_str());
+        }
+    }
+
+    #[test]
+    fn structured_names(
+        names in prop::collection::vec(("[A-Z][a-z]{0,8}", "[A-Z][a-z]{0,8}"), 1..6)
+    ) {
+        let field = names
+            .iter()
+            .map(|(family, given)| format!("{}, {}", family, given))
+            .collect::<Vec<_>>()
+            .join(" and ");
+        let parsed = parse_name_list(&field);
+        prop_assert_eq!(parsed.len(), names.len());
+        for (p, (family, given)) in parsed.iter().zip(&names) {
+            prop_assert_eq!(p, &format!("{} {}", given, family));
+        }
+    }
+
+    #[test]
+    fn latex_never_panics(s in "\\PC*") {
+        let _ = decode(&s);
+    }
+
+    #[test]
+    fn markup_never_panics(s in "(<[a-z:/]{0,6}>|&[a-z#0-9]{0,6};|\\PC){0,40}") {
+        let text = strip(&s);
+        prop_assert!(!text.contains("\n\n\n"));
+    }
+
+    #[test]
+    fn shortnames_never_panic(s in "\\PC*") {
+        if let Some(short) = infer_shortname(&s) {
+            prop_assert!(short.chars().count() >= 2);
+        }
+    }
+
+    #[test]
+    fn year_never_panics(s in "\\PC*") {
+        let _ = Year::lenient("key", &s).to_string();
+    }
+
+    #[test]
+    fn ordinals_never_panic(s in "\\PC*") {
+        let _ = parse_ordinal(&s);
+        let _ = Edition::from(Some(&s)).to_yaml(Quotes::Double);
+    }
+
+    #[test]
+    fn url_macro_never_panics(s in "\\PC*") {
+        let _ = strip_url_macro(&s);
+    }
+
+    #[test]
+    fn sanitized_names_are_portable(s in "\\PC*") {
+        let name = sanitize_filename(&s);
+        prop_assert!(!name.is_empty());
+        prop_assert!(!name.contains(['<', '>', ':', '"', '/', '\\', '|', '?', '*']));
+        prop_assert!(!name.ends_with(['.', ' ']));
+    }
+
+    #[test]
+    fn quoted_strings_read_back(s in "\\PC*") {
+        for quotes in [Quotes::Double, Quotes::Single, Quotes::Auto] {
+            let yaml = format!("key: {}\n", quote(&s, quotes));
+            let map: serde_yaml::Mapping = serde_yaml::from_str(&yaml).unwrap();
+            prop_assert_eq!(map["key"].as_str(), Some(s.as_str()), "{}", yaml);
+        }
+    }
+}
@@ -0,0 +1,40 @@
+//! Tests of the conversion of files with malformed entries.
+
+use new_page::recover::split_entries;
+use new_page::{convert, convert_summarized, Options, Target};
+
+const BIB: &str = r#"Exported by hand, mail doe@example.org.
+@string{jt = "Journal of Things"}
+@article{good1, author = {Doe, Jane}, title = {Mail me at jane@example.org},
+  journal = jt, year = {2020}}
+@article{bad1, author = {Doe, Jane}, title = {Unbalanced, journal = {J}, year = {2020}}
+@article{good2, author = {Roe, Rick}, title = {Fine}, journal = jt, year = {2021}}
+@article{bad2 author = {Roe, Rick}, title = {No comma}, journal = {J}, year = {2021}}
+"#;
+
+#[test]
+fn splitting_is_brace_aware() {
+    let lines: Vec<usize> = split_entries(BIB).iter().map(|(l, _)| *l).collect();
+    assert_eq!(lines, vec![1, 1, 2, 3, 5, 6, 7]);
+    assert!(split_entries(BIB)[3].1.contains("jane@example.org"));
+}
+
+#[test]
+fn good_entries_survive_bad_ones() {
+    let (papers, summary) = convert_summarized(BIB, &Options::default()).unwrap();
+    let keys: Vec<&str> = papers.iter().map(|p| p.key.key.as_str()).collect();
+    assert_eq!(keys, vec!["good1", "good2"]);
+    assert_eq!((summary.converted, summary.failed), (2, 2));
+    assert!(papers[1]
+        .render(Target::Hugo)
+        .contains("name: \"Journal of Things\""));
+}
+
+#[test]
+fn recovery_can_be_turned_off() {
+    let opts = Options {
+        recover: false,
+        ..Options::default()
+    };
+    assert!(convert(BIB, &opts).is_err());
+}
@@ -0,0 +1,181 @@
+//! Tests that the structured outputs follow their published schema.
+
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use new_page::formats::data_yaml;
+use new_page::schema::{schema, SCHEMA_VERSION};
+use new_page::yaml::Quotes;
+use new_page::{convert, AliasFrom, Options};
+
+/// Checks `value` against the parts of JSON Schema `schema()` uses,
+/// returning the path of the first mismatch.
+fn validate(value: &Value, schema: &Value, path: &str) -> Result<(), String> {
+    let fail = |what: &str| Err(format!("{}: {}", path, what));
+    if let Some(expected) = schema.get("const") {
+        if value != expected {
+            return fail("unexpected constant");
+        }
+    }
+    if let Some(options) = schema["enum"].as_array() {
+        if !options.contains(value) {
+            return fail("not one of the enumerated values");
+        }
+    }
+    if !schema["type"].is_null() {
+        let kind = match value {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        };
+        let allowed = match &schema["type"] {
+            Value::Array(types) => types.iter().any(|t| t == kind),
+            t => t == kind,
+        };
+        if !allowed {
+            return fail(&format!("{} instead of {}", kind, schema["type"]));
+        }
+    }
+    match value {
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                validate(item, &schema["items"], &format!("{}[{}]", path, i))?;
+            }
+        }
+        Value::Object(map) => {
+            for key in schema["required"].as_array().into_iter().flatten() {
+                if !map.contains_key(key.as_str().unwrap()) {
+                    return fail(&format!("missing {}", key));
+                }
+            }
+            for (key, v) in map {
+                match schema["properties"].get(key) {
+                    Some(s) => validate(v, s, &format!("{}.{}", path, key))?,
+                    None if schema["additionalProperties"] == false => {
+                        return fail(&format!("unknown key {}", key))
+                    }
+                    None => {}
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn document(bib: &str, opts: &Options) -> Value {
+    let yaml = data_yaml(&convert(bib, opts).unwrap(), Quotes::Double).unwrap();
+    serde_yaml::from_str(&yaml).unwrap()
+}
+
+#[test]
+fn fixtures_follow_the_schema() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let opts = Options {
+        alias_from: Some(AliasFrom::Both),
+        stamp: Some("2024-01-01T00:00:00Z".to_string()),
+        ..Options::default()
+    };
+    for entry in fs::read_dir(fixtures).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|e| e == "bib") {
+            let bib = fs::read_to_string(&path).unwrap();
+            for opts in [&Options::default(), &opts] {
+                let doc = document(&bib, opts);
+                assert_eq!(doc["schema_version"], SCHEMA_VERSION);
+                validate(&doc, &schema(), "")
+                    .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+            }
+        }
+    }
+}
+
+/// Entries giving the keys which are left out unless set.
+const OPTIONAL: &str = r#"
+@article{a, author = {Doe*, Jane and Roe*, Richard}, title = {T}, journal = {J},
+  booktitle = {C}, year = 2021, pages = {xi--xv}, keywords = {x, y},
+  award = {Best Paper; Distinguished Paper}, pubstate = {forthcoming},
+  origlanguage = {french}, origyear = 1990, abstract = {A.}}
+@book{b, editor = {Smith, Bob}, translator = {Doe, Jane}, title = {B}, year = 2020,
+  edition = {Second}, chapter = {3}, isbn = {978-3-16-148410-0}}
+@misc{p, author = {Doe, Jane}, title = {P}, year = 2019, number = {US123}, nationality = {US}}
+"#;
+
+#[test]
+fn optional_keys_follow_the_schema() {
+    validate(&document(OPTIONAL, &Options::default()), &schema(), "").unwrap();
+}
+
+#[test]
+fn schema_keys_are_all_written() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut bibs = vec![OPTIONAL.to_string()];
+    for entry in fs::read_dir(fixtures).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|e| e == "bib") {
+            bibs.push(fs::read_to_string(&path).unwrap());
+        }
+    }
+    let opts = Options {
+        alias_from: Some(AliasFrom::Both),
+        stamp: Some("2024-01-01T00:00:00Z".to_string()),
+        ..Options::default()
+    };
+    let mut docs: Vec<Value> = bibs.iter().map(|bib| document(bib, &opts)).collect();
+    // The keys of the enrichments and of options of their own.
+    let bib = r#"
+@techreport{s, author = {Doe, Jane}, title = {S}, year = 2018, number = {ISO 8601}}
+@misc{h, author = {Doe, Jane and Roe, Rick}, title = {H}, year = 2017, howpublished = {Talk},
+  doi = {10.1/x}, url = {https://x.org}, corresponding = {Roe, Rick}, award = {Best Paper}}
+@dataset{d, author = {Doe, Jane}, title = {D}, year = 2016, institution = {Lab}}
+@misc{untitled, author = {Doe, Jane}, year = 2015, abstract = {One two three four five.}}
+"#;
+    let opts = Options {
+        feature_awards: true,
+        other_links: true,
+        max_abstract_words: Some(3),
+        languages: vec!["en".to_string(), "fr".to_string()],
+        ..Options::default()
+    };
+    let mut papers = convert(bib, &opts).unwrap();
+    papers[0].citations.count = Some(3);
+    papers[0].updated.at = Some("2024-01-01".to_string());
+    papers[0].other_versions = vec!["h".to_string()];
+    papers[0].earlier_versions = vec!["d".to_string()];
+    docs.push(serde_yaml::from_str(&data_yaml(&papers, Quotes::Double).unwrap()).unwrap());
+
+    let mut written = std::collections::BTreeSet::new();
+    for doc in &docs {
+        validate(doc, &schema(), "").unwrap();
+        for paper in doc["papers"].as_array().unwrap() {
+            written.extend(paper.as_object().unwrap().keys().cloned());
+        }
+    }
+    let schema = schema();
+    let unwritten: Vec<&String> = schema["properties"]["papers"]["items"]["properties"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .filter(|k| !written.contains(*k))
+        .collect();
+    assert!(unwritten.is_empty(), "never written: {:?}", unwritten);
+}
+
+#[test]
+fn mismatches_are_caught() {
+    let mut doc = document(
+        "@article{a, author = {Doe, Jane}, title = {T}, journal = {J}, year = 2021}",
+        &Options::default(),
+    );
+    doc["papers"][0]["year"] = Value::from("2021");
+    assert!(validate(&doc, &schema(), "").is_err());
+    doc["papers"][0]["year"] = Value::from(2021);
+    doc["papers"][0]["unknown"] = Value::from(1);
+    assert!(validate(&doc, &schema(), "").is_err());
+}
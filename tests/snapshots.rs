@@ -0,0 +1,101 @@
+//! Golden tests: every `tests/fixtures/*.bib` file is converted and compared
+//! with the `.md` file of the same name, and each of its pages is verified.
+//! Run with `UPDATE_SNAPSHOTS=1` to regenerate the expected outputs.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use new_page::output::rendered_pages;
+use new_page::verify::verify;
+use new_page::{convert, render, LineEnding, Options};
+
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn convert_file(path: &Path) -> String {
+    let input = fs::read_to_string(path).unwrap();
+    render(
+        &convert(&input, &Options::default()).unwrap(),
+        &Options::default(),
+    )
+}
+
+#[test]
+fn fixtures_match_snapshots() {
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+    let mut bibs: Vec<PathBuf> = fs::read_dir(fixtures())
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.extension().is_some_and(|e| e == "bib"))
+        .collect();
+    bibs.sort();
+    assert!(!bibs.is_empty());
+
+    for bib in bibs {
+        let opts = Options::default();
+        let papers = convert(&fs::read_to_string(&bib).unwrap(), &opts).unwrap();
+        for page in rendered_pages(&papers, &opts) {
+            let verified = verify(&page.content, &opts);
+            assert_eq!(verified, Ok(()), "{} of {}", page.filename, bib.display());
+        }
+        let actual = convert_file(&bib);
+        let snapshot = bib.with_extension("md");
+        if update {
+            fs::write(&snapshot, &actual).unwrap();
+            continue;
+        }
+        let expected = fs::read_to_string(&snapshot)
+            .unwrap_or_else(|_| panic!("missing snapshot {}", snapshot.display()));
+        assert_eq!(actual, expected, "snapshot mismatch for {}", bib.display());
+    }
+}
+
+#[test]
+fn conversion_is_reproducible() {
+    let article = convert_file(&fixtures().join("article.bib"));
+    assert_eq!(article, convert_file(&fixtures().join("article.bib")));
+    assert_eq!(article, convert_file(&fixtures().join("shuffled.bib")));
+}
+
+#[test]
+fn quote_and_brace_styles_agree() {
+    assert_eq!(
+        convert_file(&fixtures().join("dblp.bib")),
+        convert_file(&fixtures().join("mendeley_quotes.bib"))
+    );
+}
+
+#[test]
+fn crlf_input_gives_lf_output() {
+    let input = fs::read_to_string(fixtures().join("inproceedings.bib")).unwrap();
+    let crlf = input.replace('\n', "\r\n");
+    let papers = convert(&crlf, &Options::default()).unwrap();
+    assert!(!render(&papers, &Options::default()).contains('\r'));
+    assert_eq!(
+        render(&papers, &Options::default()),
+        convert_file(&fixtures().join("inproceedings.bib"))
+    );
+}
+
+#[test]
+fn line_endings() {
+    let input = fs::read_to_string(fixtures().join("crlf_abstract.bib")).unwrap();
+    let mut papers = convert(&input, &Options::default()).unwrap();
+    let lf = render(&papers, &Options::default());
+    assert!(lf.ends_with("---\nWe study line endings.\n  The abstract was pasted from a fetched page,\n  its lines ended by CRLF.\n\n  A second paragraph follows.\n"));
+    assert!(!lf.contains('\r'));
+    assert!(!lf.contains(" \n"));
+
+    // As enrichment sets it, after the conversion.
+    papers[0].abs.abs = "Fetched abstract.  \r\nSecond line.\r\n\r\n".to_string();
+    let opts = Options::builder()
+        .line_ending(LineEnding::CrLf)
+        .build()
+        .unwrap();
+    let crlf = render(&papers, &opts);
+    assert!(crlf.starts_with("---\r\nauthors:\r\n"));
+    assert!(crlf.ends_with("---\r\nFetched abstract.\r\nSecond line.\r\n"));
+    assert!(!crlf.replace("\r\n", "").contains(['\r', '\n']));
+    assert_eq!(rendered_pages(&papers, &opts)[0].content, crlf);
+}
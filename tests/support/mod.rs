@@ -0,0 +1,71 @@
+//! Helpers shared by the integration tests and the benchmarks.
+
+#![allow(dead_code)]
+
+const WORDS: [&str; 16] = [
+    "analysis",
+    "of",
+    "distributed",
+    "systems",
+    "with",
+    "formal",
+    "verification",
+    "for",
+    "scalable",
+    "memory",
+    "models",
+    "in",
+    "practice",
+    "towards",
+    "efficient",
+    "compilers",
+];
+
+const NAMES: [&str; 8] = [
+    "Doe", "Smith", "Müller", "Martin", "García", "Nguyen", "Rossi", "Kowalski",
+];
+
+fn words(seed: usize, n: usize) -> String {
+    (0..n)
+        .map(|i| WORDS[(seed * 7 + i * 13) % WORDS.len()])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Generates a bibliography of `n` entries alternating articles and
+/// conference papers, with realistic field sizes (a handful of authors, a
+/// ten-word title and a 150-word abstract).
+pub fn synthetic_bibliography(n: usize) -> String {
+    let mut bib = String::new();
+    for i in 0..n {
+        let authors = (0..3 + i % 6)
+            .map(|a| {
+                format!(
+                    "{}, {}",
+                    NAMES[(i + a) % NAMES.len()],
+                    NAMES[a % NAMES.len()]
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" and ");
+        let venue = if i % 2 == 0 {
+            format!(
+                "journal = {{Journal of {}}},\n  volume = {{{}}},",
+                words(i, 2),
+                i % 50
+            )
+        } else {
+            format!("booktitle = {{Proceedings of the {}}},", words(i, 4))
+        };
+        bib.push_str(&format!(
+            "@{kind}{{key{i},\n  author = {{{authors}}},\n  title = {{{title}}},\n  {venue}\n  year = {{{year}}},\n  pages = {{{from}--{to}}},\n  doi = {{10.1000/{i}}},\n  url = {{https://example.org/{i}}},\n  abstract = {{{abs}}}\n}}\n\n",
+            kind = if i % 2 == 0 { "article" } else { "inproceedings" },
+            title = words(i, 10),
+            year = 1990 + i % 35,
+            from = i % 500,
+            to = i % 500 + 12,
+            abs = words(i, 150),
+        ));
+    }
+    bib
+}
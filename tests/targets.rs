@@ -0,0 +1,356 @@
+//! Tests of the per-generator front matter conventions.
+
+use new_page::config::Config;
+use new_page::output::{normalize, rendered_pages, LineEnding};
+use new_page::{convert, Emit, Extension, Options, Target};
+
+const BIB: &str = r#"
+@article{doe, author = {Doe, Jane}, title = {A Paper}, journal = {Journal of Things},
+  volume = {3}, pages = {1--10}, year = {2021}, keywords = {graphs, proofs},
+  abstract = {Some abstract.}}
+"#;
+
+fn page(target: Target) -> String {
+    let papers = convert(BIB, &Options::default()).unwrap();
+    papers[0].render(target)
+}
+
+fn s(v: &str) -> String {
+    v.to_string()
+}
+
+#[test]
+fn hugo_is_the_plain_page() {
+    let papers = convert(BIB, &Options::default()).unwrap();
+    assert_eq!(page(Target::Hugo), papers[0].render(Target::Hugo));
+    assert!(page(Target::Hugo).contains("tags:\n  - \"graphs\"\n  - \"proofs\"\n"));
+}
+
+#[test]
+fn jekyll_adds_layout_and_date() {
+    let page = page(Target::Jekyll);
+    assert!(page.starts_with("---\nlayout: publication\ndate: 2021-01-01 00:00:00 +0000\n"));
+    assert!(page.contains("---\nSome abstract.\n"));
+}
+
+#[test]
+fn zola_uses_toml_with_extra_and_taxonomies() {
+    let page = page(Target::Zola);
+    assert!(page.starts_with("+++\ntitle = \"A Paper\"\ndate = 2021-01-01\n"));
+    assert!(page.ends_with("+++\nSome abstract.\n"));
+    let fm: toml::Table = page.split("+++").nth(1).unwrap().parse().unwrap();
+    assert_eq!(fm["extra"]["volume"].as_integer(), Some(3));
+    assert_eq!(
+        fm["extra"]["journal"]["name"].as_str(),
+        Some("Journal of Things")
+    );
+    assert_eq!(
+        fm["taxonomies"]["tags"].as_array().unwrap(),
+        &vec![toml::Value::from("graphs"), toml::Value::from("proofs")]
+    );
+    assert_eq!(
+        fm["taxonomies"]["categories"].as_array().unwrap(),
+        &vec![toml::Value::from("journal")]
+    );
+    assert!(fm["extra"].get("title").is_none());
+}
+
+#[test]
+fn provenance_comments() {
+    let mut opts = Options::builder()
+        .provenance_comments(true)
+        .build()
+        .unwrap();
+    opts.fields.rename.insert(s("volume"), s("vol"));
+    let papers = convert(BIB, &opts).unwrap();
+    let hugo = papers[0].render_with(&opts);
+    assert!(hugo.contains("authors: # from author of the entry\n  - \"Jane Doe\"\n"));
+    assert!(hugo.contains("vol: 3 # from volume of the entry\n"));
+    assert!(hugo.contains("bibtex_key: \"doe\"\n"));
+    assert!(hugo.contains("---\nSome abstract.\n"));
+    let fm: serde_yaml::Mapping = serde_yaml::from_str(hugo.split("---").nth(1).unwrap()).unwrap();
+    assert_eq!(fm.get("vol").and_then(|v| v.as_i64()), Some(3));
+
+    opts.target = Target::Zola;
+    let zola = papers[0].render_with(&opts);
+    assert!(zola.starts_with("+++\ntitle = \"A Paper\" # from title of the entry\n"));
+    assert!(zola.contains("vol = 3 # from volume of the entry\n"));
+    assert!(zola.contains("[extra.journal] # from journal of the entry\n"));
+    let fm: toml::Table = zola.split("+++").nth(1).unwrap().parse().unwrap();
+    assert_eq!(fm["extra"]["vol"].as_integer(), Some(3));
+
+    // Only on demand.
+    assert!(!papers[0].render(Target::Hugo).contains('#'));
+}
+
+#[test]
+fn emitted_parts() {
+    let papers = convert(BIB, &Options::default()).unwrap();
+    let render = |target, emit| {
+        papers[0].render_with(&Options {
+            target,
+            emit,
+            ..Options::default()
+        })
+    };
+    for target in [Target::Hugo, Target::Zola, Target::Jekyll] {
+        let front_matter = render(target, Emit::FrontMatter);
+        assert_eq!(render(target, Emit::Body), "Some abstract.\n");
+        let page = papers[0].render(target);
+        assert_eq!(
+            page.strip_prefix(&front_matter).unwrap().trim_end(),
+            "Some abstract."
+        );
+    }
+    let front_matter = render(Target::Hugo, Emit::FrontMatter);
+    assert!(
+        front_matter.starts_with("---\n")
+            && front_matter.ends_with("entry_type: \"article\"\n---\n")
+    );
+    assert!(render(Target::Zola, Emit::FrontMatter).ends_with("+++\n"));
+
+    assert_eq!(Emit::FrontMatter.extension(Target::Hugo), "yaml");
+    assert_eq!(Emit::FrontMatter.extension(Target::Zola), "md");
+    assert_eq!(Emit::Body.extension(Target::Hugo), "md");
+}
+
+#[test]
+fn page_extensions() {
+    let papers = convert(BIB, &Options::default()).unwrap();
+    let page = |extension| {
+        let opts = Options::builder().extension(extension).build().unwrap();
+        rendered_pages(&papers, &opts).remove(0)
+    };
+    let yaml = page(Extension::Yaml);
+    assert!(yaml.filename.ends_with(".yaml"));
+    let fm: serde_yaml::Value = serde_yaml::from_str(&yaml.content).unwrap();
+    assert_eq!(fm["entry_type"], "article");
+    assert!(yaml.content.ends_with("entry_type: \"article\"\n"));
+    assert!(!yaml.content.contains("---"));
+
+    let json = page(Extension::Json);
+    assert!(json.filename.ends_with(".json"));
+    let entry: serde_json::Value = serde_json::from_str(&json.content).unwrap();
+    assert_eq!(entry["entry_type"], "article");
+    assert_eq!(entry["abstract"], "Some abstract.");
+
+    let md = page(Extension::Md);
+    assert!(md.filename.ends_with(".md"));
+    assert_eq!(
+        md.content,
+        normalize(&papers[0].render(Target::Hugo), LineEnding::Lf)
+    );
+
+    let invalid = |extension, emit, target| {
+        Options::builder()
+            .extension(extension)
+            .emit(emit)
+            .target(target)
+            .build()
+            .err()
+            .unwrap()
+    };
+    assert_eq!(
+        invalid(Extension::Json, Emit::Body, Target::Hugo),
+        "json files hold the front matter, which the body alone leaves out"
+    );
+    assert_eq!(
+        invalid(Extension::Yaml, Emit::Both, Target::Zola),
+        "the front matter of Zola pages is TOML, not YAML"
+    );
+}
+
+#[test]
+fn renamed_and_reordered_fields() {
+    let config: Config = toml::from_str(
+        "[fields]\norder = [\"title\", \"journal\"]\n\
+         [fields.rename]\nauthors = \"author\"\njournal = \"publication\"",
+    )
+    .unwrap();
+    config.fields.validate().unwrap();
+    let opts = Options {
+        fields: config.fields,
+        ..Options::default()
+    };
+    let papers = convert(BIB, &opts).unwrap();
+    let page = papers[0].render_with(&opts);
+    assert!(page.starts_with(
+        "---\ntitle: \"A Paper\"\npublication:\n  name: \"Journal of Things\"\n  shortname: \"\"\n\
+         author:\n  - \"Jane Doe\"\npage:\n"
+    ));
+    assert!(page.ends_with("entry_type: \"article\"\n---\nSome abstract.\n\n"));
+
+    let zola = papers[0].render_with(&Options {
+        target: Target::Zola,
+        ..opts
+    });
+    assert!(zola.contains("[extra.publication]\n"));
+    assert!(zola.contains("[extra]\nauthor = [\"Jane Doe\"]\n"));
+
+    let config: Config = toml::from_str("[fields.rename]\nauthor = \"x\"").unwrap();
+    let e = config.fields.validate().unwrap_err();
+    assert!(e.starts_with("unknown front matter field `author`, expected one of authors, "));
+}
+
+#[test]
+fn omitted_fields() {
+    let config: Config = toml::from_str("[fields]\nomit = [\"www\", \"journal\"]").unwrap();
+    config.fields.validate().unwrap();
+    // The journal category would name the omitted field.
+    let mut opts = Options {
+        fields: config.fields,
+        categories: false,
+        ..Options::default()
+    };
+    let papers = convert(BIB, &opts).unwrap();
+    assert!(papers[0].render(Target::Hugo).contains("www: \"\"\n"));
+    for target in [Target::Hugo, Target::Zola, Target::Jekyll] {
+        opts.target = target;
+        let page = papers[0].render_with(&opts);
+        assert!(!page.contains("www"), "{}", page);
+        assert!(!page.contains("journal"), "{}", page);
+        assert!(page.contains("volume"));
+    }
+
+    let config: Config = toml::from_str("[fields]\nomit = [\"wwww\"]").unwrap();
+    let e = config.fields.validate().unwrap_err();
+    assert!(e.starts_with("unknown front matter field `wwww`, expected one of "));
+}
+
+#[test]
+fn categories_from_entry_types() {
+    let bib = r#"
+@article{a, author = {Doe, Jane}, title = {A}, journal = {J}, year = 2021}
+@misc{m, author = {Doe, Jane}, title = {M}, year = 2021}
+@artwork{w, author = {Doe, Jane}, title = {W}, year = 2021}
+"#;
+    let categories = |opts: &Options| -> Vec<Option<String>> {
+        convert(bib, opts)
+            .unwrap()
+            .iter()
+            .map(|p| {
+                let page = p.render(Target::Hugo);
+                page.find("categories:\n")
+                    .map(|i| page[i..].lines().nth(1).unwrap().to_string())
+            })
+            .collect()
+    };
+    // Single categories are lists too; types without a mapping are their
+    // own category.
+    assert_eq!(
+        categories(&Options::default()),
+        vec![
+            Some("  - \"journal\"".to_string()),
+            Some("  - \"misc\"".to_string()),
+            Some("  - \"artwork\"".to_string()),
+        ]
+    );
+
+    let config: Config = toml::from_str("[categories]\nmisc = \"software\"").unwrap();
+    let opts = Options::builder()
+        .category_names(config.categories)
+        .build()
+        .unwrap();
+    assert_eq!(categories(&opts)[1], Some("  - \"software\"".to_string()));
+
+    let opts = Options::builder().categories(false).build().unwrap();
+    assert_eq!(categories(&opts), vec![None, None, None]);
+}
+
+#[test]
+fn workshop_papers() {
+    let bib = r#"
+@inproceedings{w, author = {Doe, Jane}, title = {W}, year = 2021,
+  booktitle = {Proceedings of the Workshop on Testing}}
+@inproceedings{k, author = {Doe, Jane}, title = {K}, year = 2021,
+  booktitle = {Symposium on Testing}, keywords = {Workshop, tools}}
+@inproceedings{c, author = {Doe, Jane}, title = {C}, year = 2021,
+  booktitle = {Symposium on Testing}}
+@inproceedings{co, author = {Doe, Jane}, title = {Co}, year = 2021,
+  booktitle = {Conference on Software, with Co-located Workshops}, venue_kind = {conference}}
+@inproceedings{up, author = {Doe, Jane}, title = {Up}, year = 2021,
+  booktitle = {Doctoral Symposium}}
+@article{a, author = {Doe, Jane}, title = {A}, journal = {Workshop Notes}, year = 2021}
+"#;
+    let kinds = |opts: &Options| -> Vec<(Option<String>, String)> {
+        convert(bib, opts)
+            .unwrap()
+            .iter()
+            .map(|p| {
+                let page = p.render(Target::Hugo);
+                let kind = page
+                    .lines()
+                    .find_map(|l| l.strip_prefix("venue_kind: "))
+                    .map(String::from);
+                let i = page.find("categories:\n").unwrap();
+                (kind, page[i..].lines().nth(1).unwrap().to_string())
+            })
+            .collect()
+    };
+    let workshop = (Some(s("workshop")), s("  - \"workshop\""));
+    let conference = (Some(s("conference")), s("  - \"conference\""));
+    assert_eq!(
+        kinds(&Options::default()),
+        vec![
+            workshop.clone(),
+            workshop.clone(),
+            conference.clone(),
+            conference.clone(),
+            conference.clone(),
+            (None, s("  - \"journal\"")),
+        ]
+    );
+
+    // The configuration overrides the detection both ways, and its
+    // keywords replace the default one.
+    let config: Config = toml::from_str(
+        "workshop_keywords = [\"workshop\", \"doctoral symposium\"]\n\
+         [venue_kinds]\nw = \"conference\"\nc = \"workshop\"\nco = \"workshop\"",
+    )
+    .unwrap();
+    let opts = Options::builder()
+        .workshop_keywords(config.workshop_keywords.unwrap())
+        .venue_kinds(config.venue_kinds)
+        .build()
+        .unwrap();
+    let kinds = kinds(&opts);
+    assert_eq!(kinds[0], conference);
+    assert_eq!(kinds[2], workshop);
+    // The tag of the entry wins over the configuration.
+    assert_eq!(kinds[3], conference);
+    assert_eq!(kinds[4], workshop);
+}
+
+#[test]
+fn numeric_issues_are_integers() {
+    let bib = "@article{doe, author = {Doe, Jane}, title = {T}, journal = {J}, year = {2021},
+        number = {4}}
+      @article{roe, author = {Roe, Rick}, title = {T}, journal = {J}, year = {2021},
+        number = {3-4}}";
+    let papers = convert(bib, &Options::default()).unwrap();
+    let yaml: Vec<serde_yaml::Value> = papers
+        .iter()
+        .map(|p| serde_yaml::from_str(p.render(Target::Hugo).split("---").nth(1).unwrap()).unwrap())
+        .collect();
+    assert_eq!(yaml[0]["issue"].as_i64(), Some(4));
+    assert_eq!(yaml[1]["issue"].as_str(), Some("3-4"));
+    let toml: Vec<toml::Table> = papers
+        .iter()
+        .map(|p| {
+            p.render(Target::Zola)
+                .split("+++")
+                .nth(1)
+                .unwrap()
+                .parse()
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(toml[0]["extra"]["issue"].as_integer(), Some(4));
+    assert_eq!(toml[1]["extra"]["issue"].as_str(), Some("3-4"));
+    let json: Vec<serde_json::Value> = papers
+        .iter()
+        .map(|p| serde_json::to_value(p).unwrap())
+        .collect();
+    assert_eq!(json[0]["issue"], 4);
+    assert_eq!(json[1]["issue"], "3-4");
+}
@@ -0,0 +1,400 @@
+//! Tests of the LaTeX decoding and of the text derived from abstracts.
+
+use new_page::config::Config;
+use new_page::diagnostics::collect;
+use new_page::fields::Reading;
+use new_page::latex::{decode, unwrap_value, Macros};
+use new_page::markup::{has_markup, strip};
+use new_page::text::{
+    clean_proceedings, normalize_title, proceedings_edition, proceedings_year, punctuate, scrub,
+    sentences, summarize, truncate, Punctuation, TitleRules, SUMMARY_CHARS,
+};
+use new_page::{convert, Options, Target};
+
+#[test]
+fn latex_is_decoded() {
+    assert_eq!(decode(r#"M{\"u}ller, J\"{o}rg"#), "Müller, Jörg");
+    assert_eq!(
+        decode(r"{\O}stergaard \'Eric \c{c}a \v{S}koda"),
+        "Østergaard Éric ça Škoda"
+    );
+    assert_eq!(decode(r"Stra{\ss}e \'{\i}"), "Straße ı\u{301}");
+    assert_eq!(
+        decode(r"100\% \textbf{bold} \& \emph{it}"),
+        "100% bold & it"
+    );
+    assert_eq!(decode("pages 1--2 --- $x^2$"), "pages 1–2 — x^2");
+    assert_eq!(decode(r"\unknown{kept} end\"), "kept end");
+}
+
+#[test]
+fn values_are_unwrapped() {
+    assert_eq!(unwrap_value("{Some Title}"), "Some Title");
+    assert_eq!(unwrap_value("{{{Some Title}}}"), "Some Title");
+    assert_eq!(unwrap_value("\"Some Title\""), "Some Title");
+    assert_eq!(unwrap_value("{\"Some Title\"}"), "Some Title");
+    // Braces and quotes around part of the value are kept.
+    assert_eq!(unwrap_value("{A} and {B}"), "{A} and {B}");
+    assert_eq!(unwrap_value("{DNA} Sequencing"), "{DNA} Sequencing");
+    assert_eq!(unwrap_value("\"A\" or \"B\""), "\"A\" or \"B\"");
+    assert_eq!(unwrap_value(r#""M\"uller""#), r#"M\"uller"#);
+    assert_eq!(unwrap_value(r"{a\}"), r"{a\}");
+}
+
+#[test]
+fn sentences_skip_abbreviations() {
+    assert_eq!(
+        sentences("We use graphs, e.g. trees. Smith et al. showed it. Yes!  Done"),
+        vec![
+            "We use graphs, e.g. trees.",
+            "Smith et al. showed it.",
+            "Yes!",
+            "Done"
+        ]
+    );
+    assert_eq!(
+        sentences("See J. Smith. Then"),
+        vec!["See J. Smith.", "Then"]
+    );
+    assert_eq!(
+        sentences("The end etc. Next one."),
+        vec!["The end etc.", "Next one."]
+    );
+}
+
+#[test]
+fn summaries() {
+    assert_eq!(summarize("", 2), None);
+    assert_eq!(summarize("One. Two. Three.", 2).unwrap(), "One. Two.");
+    assert_eq!(
+        summarize("One. Two. Three.", 5).unwrap(),
+        "One. Two. Three."
+    );
+
+    let long = format!("Short one. {}end.", "Word ".repeat(100));
+    assert_eq!(summarize(&long, 2).unwrap(), "Short one.…");
+    let words = summarize(&"word, ".repeat(100), 1).unwrap();
+    assert!(words.ends_with("word…"));
+    assert!(words.chars().count() <= SUMMARY_CHARS + 1);
+}
+
+#[test]
+fn reading_stats() {
+    let r = Reading::of("Ünïcödé words, l'été — 42 times.", 200);
+    assert_eq!((r.words, r.minutes), (Some(5), 1));
+    let r = Reading::of(&"word ".repeat(401), 200);
+    assert_eq!((r.words, r.minutes), (Some(401), 3));
+    assert_eq!(r.to_string(), "word_count: 401\nreading_time: 3\n");
+    let r = Reading::of(" \n ", 200);
+    assert_eq!((r.words, r.to_string()), (None, String::new()));
+}
+
+#[test]
+fn titles_are_normalized() {
+    let rules = TitleRules::default();
+    let title = |t: &str| normalize_title(t, &rules);
+    assert_eq!(title("A Study of Things."), "A Study of Things");
+    assert_eq!(title("Graphs, Trees, etc."), "Graphs, Trees, etc.");
+    assert_eq!(
+        title("Collected Works, Vol. 2."),
+        "Collected Works, Vol. 2."
+    );
+    assert_eq!(title("Wait for it..."), "Wait for it...");
+    assert_eq!(title("Wait for it…"), "Wait for it…");
+    assert_eq!(
+        title("On ``Quoted'' and `single' words"),
+        "On “Quoted” and ‘single’ words"
+    );
+    assert_eq!(title("Don't panic"), "Don't panic");
+    assert_eq!(title("Lists,, and so on.."), "Lists, and so on");
+    assert_eq!(title("Really?? Yes!!"), "Really? Yes!");
+
+    let ascii = TitleRules {
+        ascii_quotes: true,
+        ..TitleRules::default()
+    };
+    assert_eq!(normalize_title("``Q'' `s'", &ascii), "\"Q\" 's'");
+
+    let verbatim = TitleRules {
+        strip_period: false,
+        quotes: false,
+        ascii_quotes: false,
+        collapse_punctuation: false,
+    };
+    assert_eq!(normalize_title("``Q'',, x.", &verbatim), "``Q'',, x.");
+}
+
+#[test]
+fn title_rules_from_config() {
+    let config: Config = toml::from_str("[title]\nstrip_period = false").unwrap();
+    assert!(!config.title.strip_period);
+    assert!(config.title.quotes);
+}
+
+#[test]
+fn proceedings_boilerplate() {
+    // ACM
+    assert_eq!(
+        clean_proceedings(
+            "Proceedings of the 44th Annual ACM SIGPLAN Symposium on Principles of Programming Languages"
+        ),
+        "ACM SIGPLAN Symposium on Principles of Programming Languages"
+    );
+    assert_eq!(
+        clean_proceedings("Proc. of the ACM on Programming Languages"),
+        "ACM on Programming Languages"
+    );
+    // IEEE
+    assert_eq!(
+        clean_proceedings("2020 IEEE Symposium on Security and Privacy (SP)"),
+        "IEEE Symposium on Security and Privacy (SP)"
+    );
+    assert_eq!(
+        clean_proceedings("Proceedings of the Fifth IEEE Conference (Lyon, France, 2020)"),
+        "IEEE Conference"
+    );
+    // USENIX
+    assert_eq!(
+        clean_proceedings("29th USENIX Security Symposium (USENIX Security 20)"),
+        "USENIX Security Symposium (USENIX Security 20)"
+    );
+    // Springer
+    assert_eq!(
+        clean_proceedings(
+            "Computer Aided Verification - 32nd International Conference, CAV 2020, \
+             Los Angeles, CA, USA, July 21-24, 2020, Proceedings, Part I"
+        ),
+        "Computer Aided Verification - 32nd International Conference, CAV 2020"
+    );
+    // Left alone
+    assert_eq!(
+        clean_proceedings("Conference on Stuff"),
+        "Conference on Stuff"
+    );
+    assert_eq!(clean_proceedings("Workshop (WS '19)"), "Workshop (WS '19)");
+    assert_eq!(clean_proceedings("Proceedings"), "Proceedings");
+}
+
+#[test]
+fn cleaned_conference_keeps_proceedings() {
+    let bib = "@inproceedings{a, author = {A, B}, title = {T}, year = {2020},
+      booktitle = {Proceedings of the 12th Conference on Stuff (Lyon, 2020)}}";
+    let opts = Options {
+        clean_proceedings: true,
+        ..Options::default()
+    };
+    let page = convert(bib, &opts).unwrap()[0].render(Target::Hugo);
+    assert!(page.contains("conference:\n  name: \"Conference on Stuff\"\n"));
+    assert!(page
+        .contains("proceedings: \"Proceedings of the 12th Conference on Stuff (Lyon, 2020)\"\n"));
+    let page = convert(bib, &Options::default()).unwrap()[0].render(Target::Hugo);
+    assert!(!page.contains("proceedings:"));
+}
+
+#[test]
+fn preamble_macros() {
+    let preambles = vec![
+        r"\newcommand{\NP}{NP} \newcommand{\noopsort}[1]{} \providecommand\tool{Tool}".to_string(),
+        r"\renewcommand{\NPC}{\NP-complete} \makeatletter \def\x{y}".to_string(),
+    ];
+    let macros = Macros::from_preambles(&preambles);
+    assert_eq!(macros.defs.len(), 3);
+    assert_eq!(macros.defs["NPC"], "NP-complete");
+    assert_eq!(
+        macros.expand(r"\NPC{} problems in \tool, \NPs \emph{x} 100\%"),
+        r"NP-complete problems in Tool, \NPs \emph{x} 100\%"
+    );
+    assert_eq!(
+        Macros::from_preambles(&["garbage {".into()]),
+        Macros::default()
+    );
+}
+
+#[test]
+fn scrubbing() {
+    assert_eq!(scrub("e\u{301}ﬁﬂ"), ("éfifl".to_string(), false));
+    assert_eq!(
+        scrub("a\u{ad}b\u{200b}c\u{feff} d\u{a0}e\u{2009}f"),
+        ("abc d e f".to_string(), false)
+    );
+    assert_eq!(scrub("a\u{7}b\nc\td"), ("ab\nc\td".to_string(), true));
+    // The non-breaking space of `~` comes later, from the LaTeX decoding.
+    assert_eq!(decode(&scrub("a~b").0), "a\u{a0}b");
+}
+
+#[test]
+fn abstract_punctuation() {
+    let smart = |t| punctuate(t, Punctuation::Smart);
+    assert_eq!(
+        smart(r#"Say "hi" -- it's 'fine' --- (really "so")."#),
+        "Say “hi” – it’s ‘fine’ — (really “so”)."
+    );
+    assert_eq!(
+        smart(r#"Set `x -- "y"` and max_pages--2 "ok""#),
+        "Set `x -- \"y\"` and max_pages--2 “ok”"
+    );
+    assert_eq!(smart("``Q'' and ` alone"), "“Q” and ` alone");
+    assert_eq!(
+        punctuate("“a” – ‘b’ — c", Punctuation::Ascii),
+        "\"a\" -- 'b' --- c"
+    );
+    assert_eq!(punctuate("\"a\" -- b", Punctuation::Keep), "\"a\" -- b");
+
+    let bib = r#"@misc{a, author = {A, B}, title = {T}, year = {2020},
+      abstract = {A "quoted" word --- and more.}}"#;
+    let opts = Options {
+        abstract_punctuation: Punctuation::Smart,
+        ..Options::default()
+    };
+    let page = convert(bib, &opts).unwrap()[0].render(Target::Hugo);
+    assert!(page.contains("summary: \"A “quoted” word — and more.\"\n"));
+    assert!(page.ends_with("---\nA “quoted” word — and more.\n\n"));
+}
+
+#[test]
+fn abstract_truncation() {
+    let text = "First one. Second one here. Third.";
+    assert_eq!(truncate(text, Some(100), Some(10)), None);
+    assert_eq!(
+        truncate(text, Some(27), None).unwrap(),
+        "First one. Second one here."
+    );
+    assert_eq!(truncate(text, Some(26), None).unwrap(), "First one.");
+    assert_eq!(truncate(text, None, Some(4)).unwrap(), "First one.");
+    // A first sentence over the limit is cut between words, keeping math
+    // spans and multi-byte characters whole.
+    assert_eq!(
+        truncate("Été à $a + b$ plus, encore", Some(13), None).unwrap(),
+        "Été à $a + b$"
+    );
+    assert_eq!(
+        truncate("Été à $a + b$ plus, encore", Some(12), None).unwrap(),
+        "Été à"
+    );
+
+    let bib = r#"@misc{a, author = {A, B}, title = {T}, year = {2020},
+      abstract = {One sentence. Two sentences. Three sentences.}}"#;
+    let opts = Options {
+        max_abstract_words: Some(4),
+        ellipsis: " […]".into(),
+        ..Options::default()
+    };
+    let page = convert(bib, &opts).unwrap()[0].render(Target::Hugo);
+    assert!(page.contains("abstract_truncated: true\nbibtex_key"));
+    assert!(page.ends_with("---\nOne sentence. Two sentences. […]\n\n"));
+    let page = convert(bib, &Options::default()).unwrap()[0].render(Target::Hugo);
+    assert!(!page.contains("abstract_truncated"));
+}
+
+#[test]
+fn proceedings_edition_and_year() {
+    let usenix = "Proceedings of the 30th USENIX Security Symposium";
+    assert_eq!(proceedings_edition(usenix), Some(30));
+    assert_eq!(
+        proceedings_edition("Forty-Fourth Annual Conference"),
+        Some(44)
+    );
+    assert_eq!(proceedings_edition("Proc. of the 21st ICSE"), Some(21));
+    assert_eq!(
+        proceedings_edition("2021 Twelfth Workshop on Types"),
+        Some(12)
+    );
+    assert_eq!(
+        proceedings_edition("Proceedings of the Twentieth SOSP"),
+        Some(20)
+    );
+    // Numbers which are not ordinals.
+    for title in [
+        "Proceedings of the 21th Workshop",
+        "Workshop on 3D Printing",
+        "Proceedings of IFIP 5G Networks",
+        "Proceedings of the 100 Years of Computing",
+        "ICSE 2020",
+        "Conference on Secondary Storage",
+        "The 11th Hour Symposium, 2nd Edition",
+    ] {
+        assert_eq!(proceedings_edition(title), None, "{}", title);
+    }
+
+    assert_eq!(proceedings_year("ICSE 2020"), Some(2020));
+    assert_eq!(
+        proceedings_year("Fifth IEEE Conference (Lyon, France, 2020)"),
+        Some(2020)
+    );
+    assert_eq!(proceedings_year("SP 2020, San Francisco, 2020"), Some(2020));
+    for title in [
+        "Proceedings of ICSE2020",
+        "Festschrift 1950--2000",
+        "Proceedings of the 30th USENIX Security Symposium",
+        "Workshop on 123 Things",
+    ] {
+        assert_eq!(proceedings_year(title), None, "{}", title);
+    }
+}
+
+#[test]
+fn year_from_booktitle() {
+    let bib = "@inproceedings{k, author = {A B}, title = {T},
+        booktitle = {Proceedings of the 30th USENIX Security Symposium (USENIX Security 2021)}}";
+    let p = &convert(bib, &Options::default()).unwrap()[0];
+    assert_eq!((p.year.year, p.year.dated), (2021, true));
+    assert!(p.render(Target::Hugo).contains("  edition: 30\n"));
+    // A year tag wins over the booktitle.
+    let p = &convert(
+        &bib.replace("title = {T}", "title = {T}, year = 2022"),
+        &Options::default(),
+    )
+    .unwrap()[0];
+    assert_eq!(p.year.year, 2022);
+}
+
+#[test]
+fn abstract_markup() {
+    assert!(has_markup("<jats:p>Text</jats:p>"));
+    assert!(has_markup("Cats &amp; dogs"));
+    assert!(has_markup("A&#x2013;B"));
+    assert!(!has_markup("x < y and y > z, AT&T; a <3"));
+    assert_eq!(
+        strip("<p>H<sub>2</sub>O at 10<sup>-3</sup>,</p>\n<p>x<sub>max</sub>, x<sub>crit</sub> and e<sup>i</sup></p>"),
+        "H₂O at 10⁻³,\n\nxₘₐₓ, x_(crit) and e^i"
+    );
+    assert_eq!(
+        strip(
+            "<jats:title>Abstract</jats:title><jats:p>An <jats:italic>open</jats:italic> \
+               <jats:bold>loop</jats:bold><unknown attr=\"1\">.</unknown></jats:p>"
+        ),
+        "An *open* **loop**."
+    );
+    assert_eq!(
+        strip("Unclosed <i>tags<br/>and <sup>2"),
+        "Unclosed *tags*\n\nand ²"
+    );
+    assert_eq!(
+        strip("&lt;b&gt; &#0; &bogus; &#8212;"),
+        "<b> &#0; &bogus; —"
+    );
+    assert_eq!(strip("x < y & <script>alert(1)</script>"), "x < y &");
+}
+
+#[test]
+fn abstract_markup_conversion() {
+    let bib = "@misc{k, author = {Doe, Jane}, title = {T}, year = {2021},
+        abstract = {<p>Cats &amp; dogs.</p><p>Birds.</p>}}";
+    let (papers, warnings) = collect(|| convert(bib, &Options::default()));
+    assert_eq!(papers.unwrap()[0].abs.abs, "Cats & dogs.\n\nBirds.");
+    let messages: Vec<String> = warnings.iter().map(|w| w.to_string()).collect();
+    assert_eq!(
+        messages,
+        ["k: holds HTML or JATS markup, converted to text; check the result"]
+    );
+    let kept = Options {
+        keep_abstract_markup: true,
+        ..Options::default()
+    };
+    let (papers, warnings) = collect(|| convert(bib, &kept));
+    assert_eq!(
+        papers.unwrap()[0].abs.abs,
+        "<p>Cats &amp; dogs.</p><p>Birds.</p>"
+    );
+    assert!(warnings.is_empty());
+}
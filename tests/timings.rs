@@ -0,0 +1,59 @@
+//! Tests of the timing of the steps of a run.
+
+use std::process::Command;
+use std::time::Duration;
+
+use new_page::timings::{self, Timings, SLOW_ENTRY};
+
+#[test]
+fn steps_add_up() {
+    let mut t = Timings::default();
+    t.record("parse", Duration::from_millis(5));
+    t.record("conversion", Duration::from_millis(1));
+    t.record("conversion", Duration::from_millis(2));
+    assert_eq!(t.stages.len(), 2);
+    assert_eq!(t.stages[1].total, Duration::from_millis(3));
+    assert_eq!(t.stages[1].calls, 2);
+    t.slow_entries = vec![
+        ("fast".to_string(), SLOW_ENTRY * 2),
+        ("giant".to_string(), SLOW_ENTRY * 10),
+    ];
+    let table = t.to_string();
+    assert!(table.starts_with("Step"));
+    assert!(table.contains("conversion     0.003s       2\n"));
+    let giant = table.find("  giant: ").unwrap();
+    assert!(giant < table.find("  fast: ").unwrap());
+}
+
+#[test]
+fn measured_once_enabled() {
+    assert_eq!(timings::time("untimed", || 1), 1);
+    assert!(timings::take().stages.is_empty());
+    timings::enable();
+    timings::time("step", || std::thread::sleep(Duration::from_millis(1)));
+    let t = timings::take();
+    assert_eq!(t.stages[0].name, "step");
+    assert!(t.stages[0].total >= Duration::from_millis(1));
+    assert!(timings::take().stages.is_empty());
+}
+
+#[test]
+fn timings_flag() {
+    let path = std::env::temp_dir().join(format!("new_page-timings-{}.bib", std::process::id()));
+    std::fs::write(
+        &path,
+        "@article{doe, author = {Doe, Jane}, title = {T}, journal = {J}, year = 2021}",
+    )
+    .unwrap();
+    let out = Command::new(env!("CARGO_BIN_EXE_new_page"))
+        .arg("--file-path")
+        .arg(&path)
+        .args(["--quiet", "--timings", "--output", "-"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    for step in ["reading", "parse", "conversion", "rendering", "writing"] {
+        assert!(stderr.contains(step), "{}", stderr);
+    }
+    std::fs::remove_file(&path).unwrap();
+}
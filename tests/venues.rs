@@ -0,0 +1,152 @@
+//! Tests of entries naming both a journal and a conference, and of the
+//! venue pages.
+
+use new_page::fields::Place;
+use new_page::venues::{group, infer_shortname, report, slug, write_venue_pages, VenueMap};
+use new_page::yaml::Quotes;
+use new_page::{convert, Options, Target, Venue};
+
+const BIB: &str = r#"
+@article{ext, author = {Doe, Jane}, title = {Extended}, journal = {Journal of Things},
+  booktitle = {Conference on Stuff}, year = {2021}}
+@inproceedings{conf, author = {Doe, Jane}, title = {Short}, journal = {Journal of Things},
+  booktitle = {Conference on Stuff}, venue = {Lyon}, year = {2020}}
+"#;
+
+fn label(p: &Option<Place>) -> Option<&'static str> {
+    p.as_ref().map(Place::label)
+}
+
+#[test]
+fn primary_venue_follows_entry_type() {
+    let papers = convert(BIB, &Options::default()).unwrap();
+    assert_eq!(label(&papers[0].place), Some("journal"));
+    assert_eq!(label(&papers[0].also), Some("conference"));
+    assert_eq!(label(&papers[1].place), Some("conference"));
+    assert_eq!(label(&papers[1].also), Some("journal"));
+
+    assert!(papers[0].render(Target::Hugo).contains(
+        "journal:\n  name: \"Journal of Things\"\n  shortname: \"\"\n\
+         also_appeared_in:\n  conference:\n    name: \"Conference on Stuff\"\n    shortname: \"\"\n"
+    ));
+    assert!(papers[1].render(Target::Hugo).contains(
+        "also_appeared_in:\n  journal:\n    name: \"Journal of Things\"\n    shortname: \"\"\n"
+    ));
+}
+
+#[test]
+fn prefer_venue_overrides_entry_type() {
+    let opts = Options {
+        prefer_venue: Some(Venue::Journal),
+        ..Options::default()
+    };
+    let papers = convert(BIB, &opts).unwrap();
+    assert_eq!(label(&papers[1].place), Some("journal"));
+    assert_eq!(label(&papers[1].also), Some("conference"));
+
+    let opts = Options {
+        prefer_venue: Some(Venue::Booktitle),
+        ..Options::default()
+    };
+    let papers = convert(BIB, &opts).unwrap();
+    assert_eq!(label(&papers[0].place), Some("conference"));
+    assert_eq!(label(&papers[0].also), Some("journal"));
+}
+
+#[test]
+fn single_venue_has_no_secondary() {
+    let bib = "@article{a, author = {A, B}, title = {T}, journal = {J}, year = {2020}}";
+    let papers = convert(bib, &Options::default()).unwrap();
+    assert!(papers[0].also.is_none());
+    assert!(!papers[0].render(Target::Hugo).contains("also_appeared_in"));
+}
+
+#[test]
+fn venues_are_merged_by_the_map() {
+    let bib = r#"
+@inproceedings{a, author = {A, B}, title = {T}, booktitle = {PLDI '22}, year = {2022}}
+@inproceedings{b, author = {A, B}, title = {T}, booktitle = {Proc.  PLDI}, year = {2021}}
+@article{c, author = {A, B}, title = {T}, journal = {Journal of Things}, year = {2020}}
+@inproceedings{d, author = {A, B}, title = {T}, booktitle = {pldi}, year = {2019}}
+@inproceedings{e, author = {A, B}, title = {T}, booktitle = {POPL 2020}, year = {2020}}
+@misc{f, author = {A, B}, title = {T}, year = {2020}}
+"#;
+    let map = VenueMap::parse("PLDI = [\"PLDI '22\", \"proc. pldi\"]").unwrap();
+    let groups = group(&convert(bib, &Options::default()).unwrap(), &map);
+    let names: Vec<_> = groups.iter().map(|g| (g.name.as_str(), g.mapped)).collect();
+    assert_eq!(
+        names,
+        vec![
+            ("PLDI", true),
+            ("Journal of Things", false),
+            ("POPL 2020", false)
+        ]
+    );
+    assert_eq!(groups[0].keys, vec!["a", "b", "d"]);
+    assert_eq!(groups[0].spellings, vec!["PLDI '22", "Proc.  PLDI", "pldi"]);
+    assert_eq!(
+        report(&groups),
+        "PLDI (3)\n  <- PLDI '22\n  <- Proc.  PLDI\n  <- pldi\n\
+         Unmapped:\n  Journal of Things (1)\n  POPL 2020 (1)\n"
+    );
+
+    let unmapped = group(
+        &convert(bib, &Options::default()).unwrap(),
+        &VenueMap::default(),
+    );
+    assert_eq!(unmapped.len(), 5);
+    assert!(unmapped.iter().all(|g| !g.mapped && g.keys.len() == 1));
+}
+
+#[test]
+fn venue_pages() {
+    assert_eq!(slug("PLDI '22"), "pldi-22");
+    assert_eq!(slug("Journal of Things"), "journal-of-things");
+    assert_eq!(slug("…"), "venue");
+
+    let bib = r#"
+@article{a, author = {A, B}, title = {T}, journal = {J "Q"}, year = {2020}}
+@article{b, author = {A, B}, title = {T}, journal = {J-Q}, year = {2020}}
+"#;
+    let groups = group(
+        &convert(bib, &Options::default()).unwrap(),
+        &VenueMap::default(),
+    );
+    let dir = std::env::temp_dir().join(format!("new_page-venues-{}", std::process::id()));
+    write_venue_pages(&groups, &dir, Quotes::Double).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.join("j-q/_index.md")).unwrap(),
+        "---\ntitle: \"J \\\"Q\\\"\"\npapers:\n  - \"a\"\n---\n"
+    );
+    assert!(dir.join("j-q-2/_index.md").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn inferred_shortnames() {
+    let short = |name: &str| infer_shortname(name);
+    assert_eq!(
+        short("Proceedings of the 2021 ACM SIGSAC Conference on Computer and Communications Security (CCS '21)").as_deref(),
+        Some("CCS")
+    );
+    assert_eq!(
+        short("Conference on Systems (EuroSys)").as_deref(),
+        Some("EuroSys")
+    );
+    assert_eq!(short(" PLDI 2023 ").as_deref(), Some("PLDI"));
+    assert_eq!(
+        short("International Conference on Machine Learning").as_deref(),
+        Some("ICML")
+    );
+    assert_eq!(
+        short("Journal of Machine Learning Research").as_deref(),
+        Some("JMLR")
+    );
+    assert_eq!(
+        short("IEEE Symposium on Security and Privacy").as_deref(),
+        Some("SSP")
+    );
+    assert_eq!(short("Nature"), None);
+    assert_eq!(short("arXiv preprint"), None);
+    assert_eq!(short(""), None);
+}
@@ -0,0 +1,112 @@
+//! Tests of the check that rendered pages parse back.
+
+use std::fs;
+use std::path::Path;
+
+use new_page::config::Config;
+use new_page::verify::{retain_valid, verify};
+use new_page::{convert, Emit, Options, Target};
+
+const BIB: &str = r#"
+@article{doe, author = {Doe, Jane}, title = {A Paper: Part {II}}, journal = {J}, year = {2021}}
+@article{quoted, author = {Roe, Rick}, title = {The "Best" Paper}, journal = {J}, year = {2020}}
+"#;
+
+#[test]
+fn pages_parse_back() {
+    let mut opts = Options::default();
+    let papers = convert(BIB, &opts).unwrap();
+    for target in [Target::Hugo, Target::Zola, Target::Jekyll] {
+        opts.target = target;
+        assert_eq!(verify(&papers[0].render_with(&opts), &opts), Ok(()));
+    }
+    opts.target = Target::Hugo;
+    assert!(verify("title: \"x\"\n", &opts).is_err());
+    let e = verify("---\ntitle: \"x\"\n---\n", &opts).unwrap_err();
+    assert_eq!(e, "bibtex_key is missing or not a string");
+    let page =
+        "---\ntitle: \"x\"\nauthors: \"Doe\"\nbibtex_key: \"k\"\nentry_type: \"misc\"\n---\n";
+    assert_eq!(
+        verify(page, &opts),
+        Err("authors is not a list".to_string())
+    );
+}
+
+#[test]
+fn pages_without_authors() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    for (fixture, pages) in [("edited_book.bib", 1), ("anonymous_misc.bib", 2)] {
+        let bib = fs::read_to_string(fixtures.join(fixture)).unwrap();
+        let mut opts = Options::default();
+        let mut papers = convert(&bib, &opts).unwrap();
+        assert_eq!(papers.len(), pages);
+        for target in [Target::Hugo, Target::Zola, Target::Jekyll] {
+            opts.target = target;
+            for paper in &papers {
+                let page = paper.render_with(&opts);
+                assert!(!page.contains("authors"), "{}", page);
+                assert_eq!(verify(&page, &opts), Ok(()), "{}", page);
+            }
+            assert_eq!(retain_valid(&mut papers, &opts), 0);
+        }
+    }
+}
+
+#[test]
+fn tricky_titles_are_escaped() {
+    let opts = Options::default();
+    let mut papers = convert(BIB, &opts).unwrap();
+    let page = papers[1].render_with(&opts);
+    assert!(
+        page.contains("title: \"The \\\"Best\\\" Paper\"\n"),
+        "{}",
+        page
+    );
+    assert_eq!(verify(&page, &opts), Ok(()));
+    assert_eq!(retain_valid(&mut papers, &opts), 0);
+    assert_eq!(papers.len(), 2);
+}
+
+#[test]
+fn renamed_and_omitted_keys() {
+    let config: Config =
+        toml::from_str("[fields]\nomit = [\"entry_type\"]\n[fields.rename]\nauthors = \"author\"")
+            .unwrap();
+    let mut opts = Options {
+        fields: config.fields,
+        ..Options::default()
+    };
+    let papers = convert(BIB, &opts).unwrap();
+    for target in [Target::Hugo, Target::Zola] {
+        opts.target = target;
+        assert_eq!(verify(&papers[0].render_with(&opts), &opts), Ok(()));
+    }
+}
+
+#[test]
+fn delimiters_in_the_abstract_are_escaped() {
+    // `-{}-{}-` escapes the LaTeX em dash, leaving a horizontal rule.
+    let bib = "@article{rule, author = {Doe, Jane}, title = {T}, journal = {J}, year = 2021,
+        abstract = {Before.\n\n-{}-{}-\n\n+++ after}}";
+    let mut opts = Options::default();
+    let papers = convert(bib, &opts).unwrap();
+    for (target, escaped) in [
+        (Target::Hugo, "\n\\---\n\n+++ after\n"),
+        (Target::Jekyll, "\n\\---\n\n+++ after\n"),
+        (Target::Zola, "\n---\n\n\\+++ after\n"),
+    ] {
+        opts.target = target;
+        let page = papers[0].render_with(&opts);
+        assert!(page.contains(escaped), "{}", page);
+        assert_eq!(verify(&page, &opts), Ok(()));
+        opts.emit = Emit::Body;
+        assert_eq!(verify(&papers[0].render_with(&opts), &opts), Ok(()));
+        opts.emit = Emit::Both;
+    }
+    opts.target = Target::Hugo;
+    let page = papers[0].render_with(&opts).replace("\\---", "---");
+    assert_eq!(
+        verify(&page, &opts),
+        Err("a line of the body starts with ---".to_string())
+    );
+}
@@ -0,0 +1,158 @@
+//! Tests of the quoting of the strings of the YAML front matter.
+
+use new_page::formats::data_yaml;
+use new_page::verify::verify;
+use new_page::yaml::{is_plain, quote, Quotes};
+use new_page::{convert, Options, Target};
+
+/// Reads `value` back as YAML.
+fn read(value: &str) -> serde_yaml::Value {
+    serde_yaml::from_str::<serde_yaml::Mapping>(&format!("key: {}\n", value)).unwrap()["key"]
+        .clone()
+}
+
+#[test]
+fn quoted_values() {
+    for (value, double, single, auto) in [
+        ("Jane Doe", "\"Jane Doe\"", "'Jane Doe'", "Jane Doe"),
+        ("O'Brien", "\"O'Brien\"", "'O''Brien'", "O'Brien"),
+        ("yes", "\"yes\"", "'yes'", "'yes'"),
+        ("No", "\"No\"", "'No'", "'No'"),
+        ("off", "\"off\"", "'off'", "'off'"),
+        ("null", "\"null\"", "'null'", "'null'"),
+        ("~", "\"~\"", "'~'", "'~'"),
+        ("True", "\"True\"", "'True'", "'True'"),
+        ("2023", "\"2023\"", "'2023'", "'2023'"),
+        ("-1.5e3", "\"-1.5e3\"", "'-1.5e3'", "'-1.5e3'"),
+        ("0x1F", "\"0x1F\"", "'0x1F'", "'0x1F'"),
+        (".inf", "\".inf\"", "'.inf'", "'.inf'"),
+        (
+            "2021-03-01",
+            "\"2021-03-01\"",
+            "'2021-03-01'",
+            "'2021-03-01'",
+        ),
+        ("3:1–10", "\"3:1–10\"", "'3:1–10'", "3:1–10"),
+        (
+            "2023 (fast)",
+            "\"2023 (fast)\"",
+            "'2023 (fast)'",
+            "2023 (fast)",
+        ),
+        ("", "\"\"", "''", "''"),
+        (" padded ", "\" padded \"", "' padded '", "' padded '"),
+        (
+            "Note: this",
+            "\"Note: this\"",
+            "'Note: this'",
+            "'Note: this'",
+        ),
+        (
+            "C# in depth",
+            "\"C# in depth\"",
+            "'C# in depth'",
+            "C# in depth",
+        ),
+        ("a #tag", "\"a #tag\"", "'a #tag'", "'a #tag'"),
+        ("@handle", "\"@handle\"", "'@handle'", "'@handle'"),
+        ("- item", "\"- item\"", "'- item'", "'- item'"),
+        ("[draft]", "\"[draft]\"", "'[draft]'", "'[draft]'"),
+        (
+            "The \"best\" O'Brien",
+            "\"The \\\"best\\\" O'Brien\"",
+            "'The \"best\" O''Brien'",
+            "The \"best\" O'Brien",
+        ),
+        (
+            "'Quoted' and \"both\"",
+            "\"'Quoted' and \\\"both\\\"\"",
+            "'''Quoted'' and \"both\"'",
+            "'''Quoted'' and \"both\"'",
+        ),
+        (
+            "back\\slash",
+            "\"back\\\\slash\"",
+            "'back\\slash'",
+            "back\\slash",
+        ),
+    ] {
+        assert_eq!(quote(value, Quotes::Double), double, "{:?}", value);
+        assert_eq!(quote(value, Quotes::Single), single, "{:?}", value);
+        assert_eq!(quote(value, Quotes::Auto), auto, "{:?}", value);
+        for quoted in [double, single, auto] {
+            assert_eq!(read(quoted), serde_yaml::Value::from(value), "{}", quoted);
+        }
+    }
+    assert!(is_plain("Journal of Things"));
+    assert!(!is_plain("ends with:"));
+}
+
+#[test]
+fn quoted_front_matter() {
+    for value in ["yes", "2023", "null", "O'Brien", "The \"best\" of 'both'"] {
+        let bib = format!(
+            "@article{{doe, author = {{Doe, Jane}}, title = {{{}}}, journal = {{{}}},
+              year = {{2021}}, keywords = {{{}}}}}",
+            value, value, value
+        );
+        for quotes in [Quotes::Double, Quotes::Single, Quotes::Auto] {
+            let opts = Options::builder().yaml_quotes(quotes).build().unwrap();
+            let papers = convert(&bib, &opts).unwrap();
+            let page = papers[0].render_with(&opts);
+            let fm: serde_yaml::Mapping =
+                serde_yaml::from_str(page.split("---\n").nth(1).unwrap()).unwrap();
+            assert_eq!(fm["title"], value, "{:?} {}", quotes, page);
+            assert_eq!(fm["journal"]["name"], value, "{:?} {}", quotes, page);
+            assert_eq!(fm["tags"][0], value, "{:?} {}", quotes, page);
+            assert!(page.contains(&format!("title: {}\n", quote(value, quotes))));
+            assert_eq!(verify(&page, &opts), Ok(()));
+        }
+    }
+}
+
+#[test]
+fn quoted_pages() {
+    let bib = "@article{doe, author = {O'Brien, Jane}, title = {Yes}, journal = {J},
+        volume = {3}, pages = {1--10}, year = {2021}, doi = {10.1/x}}";
+    for quotes in [Quotes::Single, Quotes::Auto] {
+        let opts = Options::builder().yaml_quotes(quotes).build().unwrap();
+        let papers = convert(bib, &opts).unwrap();
+        let page = papers[0].render_with(&opts);
+        let fm: serde_yaml::Mapping =
+            serde_yaml::from_str(page.split("---").nth(1).unwrap()).unwrap();
+        assert_eq!(fm["authors"][0], "Jane O'Brien");
+        assert_eq!(fm["title"], "Yes");
+        assert_eq!(fm["doi"], "10.1/x");
+        assert!(page.contains("title: 'Yes'\n"), "{}", page);
+        assert_eq!(verify(&page, &opts), Ok(()));
+    }
+    assert_eq!(
+        Options::builder()
+            .yaml_quotes(Quotes::Single)
+            .target(Target::Zola)
+            .build()
+            .err()
+            .unwrap(),
+        "YAML quotes do not apply to Zola pages, whose front matter is TOML"
+    );
+}
+
+#[test]
+fn quoted_data_file() {
+    let bib = "@article{doe, author = {O'Brien, Jane}, title = {Yes}, journal = {J},
+        year = {2021}, abstract = {Two\nlines}}";
+    let papers = convert(bib, &Options::default()).unwrap();
+    for quotes in [Quotes::Double, Quotes::Single, Quotes::Auto] {
+        let yaml = data_yaml(&papers, quotes).unwrap();
+        let doc: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+        let paper = &doc["papers"][0];
+        assert_eq!(paper["authors"][0], "Jane O'Brien", "{}", yaml);
+        assert_eq!(paper["title"], "Yes", "{}", yaml);
+        assert_eq!(paper["abstract"], "Two\nlines", "{}", yaml);
+        assert_eq!(paper["year"], 2021, "{}", yaml);
+        assert!(yaml.contains(&format!("  title: {}\n", quote("Yes", quotes))));
+        // The front matter of the page is quoted the same.
+        let fm = papers[0].front_matter_yaml(quotes);
+        assert!(fm.contains(&format!("title: {}\n", quote("Yes", quotes))));
+    }
+}